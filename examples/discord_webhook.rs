@@ -31,6 +31,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         payload_json: None,
         attachments: None,
         flags: None,
+        thread_name: None,
     };
     webhook.execute(&custom_message).await?;
 
@@ -0,0 +1,420 @@
+//! Critical-alert lifecycle.
+//!
+//! A critical condition (the rig going offline, the safety monitor turning
+//! unsafe, a failed sequence instruction, a camera download timeout) opens
+//! an alert. Until someone acknowledges it from chat, the alert is re-posted
+//! on an escalation schedule, each step pinging its own role or user. The
+//! alert closes on its own once the condition clears.
+
+use crate::chat::{ChatAction, ChatMessage};
+use crate::events::event_types;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::time::Instant;
+
+/// Per-telescope settings for critical alerts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriticalAlertConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Role or user mention on the opening post, e.g. `<@&1234>`.
+    #[serde(default)]
+    pub mention: Option<String>,
+    /// Reminders while nobody has acknowledged, by minutes since opening.
+    #[serde(default = "default_escalation")]
+    pub escalation: Vec<EscalationStep>,
+}
+
+/// One reminder on the escalation schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscalationStep {
+    pub after_minutes: u64,
+    /// Who this reminder pings; the opening mention when absent.
+    #[serde(default)]
+    pub mention: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_escalation() -> Vec<EscalationStep> {
+    [10, 30, 60]
+        .into_iter()
+        .map(|after_minutes| EscalationStep {
+            after_minutes,
+            mention: None,
+        })
+        .collect()
+}
+
+impl Default for CriticalAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mention: None,
+            escalation: default_escalation(),
+        }
+    }
+}
+
+impl CriticalAlertConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.escalation.len() > 20 {
+            return Err("critical alerts allow at most 20 escalation steps".to_string());
+        }
+        let mut previous = 0;
+        for step in &self.escalation {
+            if step.after_minutes <= previous {
+                return Err(
+                    "critical alert escalation steps must be in increasing minutes, after 0"
+                        .to_string(),
+                );
+            }
+            previous = step.after_minutes;
+        }
+        let mentions = self
+            .escalation
+            .iter()
+            .filter_map(|step| step.mention.as_deref())
+            .chain(self.mention.as_deref());
+        for mention in mentions {
+            if mention.trim().is_empty() {
+                return Err("critical alert mentions must not be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The conditions that open an alert. One alert per kind is open at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AlertKind {
+    RigOffline,
+    Unsafe,
+    SequenceFailed,
+    DownloadTimeout,
+}
+
+impl AlertKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RigOffline => "Telescope offline",
+            Self::Unsafe => "Safety monitor unsafe",
+            Self::SequenceFailed => "Sequence instruction failed",
+            Self::DownloadTimeout => "Camera download timeout",
+        }
+    }
+
+    fn slug(self) -> &'static str {
+        match self {
+            Self::RigOffline => "offline",
+            Self::Unsafe => "unsafe",
+            Self::SequenceFailed => "failed",
+            Self::DownloadTimeout => "download",
+        }
+    }
+
+    /// The alert a N.I.N.A. event opens, if it is critical.
+    pub fn for_event(event: &str) -> Option<Self> {
+        match event {
+            event_types::SEQUENCE_ENTITY_FAILED => Some(Self::SequenceFailed),
+            event_types::CAMERA_DOWNLOAD_TIMEOUT => Some(Self::DownloadTimeout),
+            _ => None,
+        }
+    }
+
+    /// Alerts a newly saved frame shows to be over.
+    pub const CLEARED_BY_IMAGING: [Self; 2] = [Self::SequenceFailed, Self::DownloadTimeout];
+}
+
+/// An alert waiting for acknowledgement or for its condition to clear.
+#[derive(Debug, Clone)]
+pub struct OpenAlert {
+    /// Unique per telescope across restarts, so an old button cannot
+    /// acknowledge a newer alert.
+    pub id: String,
+    pub kind: AlertKind,
+    /// The post that opened the alert; reminders repeat it.
+    pub message: ChatMessage,
+    pub opened: Instant,
+    pub acknowledged_by: Option<String>,
+    reminders_sent: usize,
+}
+
+impl OpenAlert {
+    /// The alert's post with its Acknowledge action and `mention`.
+    pub fn post(&self, telescope: &str, mention: Option<&str>) -> ChatMessage {
+        let mut post = self
+            .message
+            .clone()
+            .footer("🚨 Critical alert · reminders continue until acknowledged")
+            .action(ChatAction::Acknowledge {
+                telescope: telescope.to_string(),
+                alert: self.id.clone(),
+            });
+        if let Some(mention) = mention {
+            post = post.mention(mention);
+        }
+        post
+    }
+
+    /// The post again, marked as a reminder.
+    pub fn reminder(&self, telescope: &str, mention: Option<&str>, now: Instant) -> ChatMessage {
+        let minutes = now.duration_since(self.opened).as_secs() / 60;
+        let mut post = self.post(telescope, mention);
+        post.title = format!("{} · unacknowledged for {minutes}m", self.message.title);
+        post
+    }
+
+    /// How the alert ended, for the recovery post.
+    pub fn resolution(&self) -> String {
+        match &self.acknowledged_by {
+            Some(user) => format!("{} — handled by {user}", self.kind.label()),
+            None => format!("{} — cleared before anyone acknowledged", self.kind.label()),
+        }
+    }
+}
+
+/// The open alerts of one telescope.
+#[derive(Debug, Default)]
+pub struct AlertDesk {
+    open: BTreeMap<AlertKind, OpenAlert>,
+}
+
+impl AlertDesk {
+    pub fn is_open(&self, kind: AlertKind) -> bool {
+        self.open.contains_key(&kind)
+    }
+
+    /// Open an alert for `kind`, which must not already be open.
+    pub fn open(&mut self, kind: AlertKind, message: ChatMessage, now: DateTime<Utc>) -> OpenAlert {
+        let alert = OpenAlert {
+            id: format!("{}-{}", kind.slug(), now.timestamp_millis()),
+            kind,
+            message,
+            opened: Instant::now(),
+            acknowledged_by: None,
+            reminders_sent: 0,
+        };
+        self.open.insert(kind, alert.clone());
+        alert
+    }
+
+    /// Record `user` as handling alert `id`. Returns the alert when this
+    /// is its first acknowledgement.
+    pub fn acknowledge(&mut self, id: &str, user: &str) -> Option<&OpenAlert> {
+        let alert = self.open.values_mut().find(|alert| alert.id == id)?;
+        if alert.acknowledged_by.is_some() {
+            return None;
+        }
+        alert.acknowledged_by = Some(user.to_string());
+        Some(alert)
+    }
+
+    pub fn resolve(&mut self, kind: AlertKind) -> Option<OpenAlert> {
+        self.open.remove(&kind)
+    }
+
+    /// Unacknowledged alerts whose next reminder is due, each with the
+    /// step to send. Steps missed while the updater was busy collapse into
+    /// the latest one rather than arriving as a burst.
+    pub fn due_reminders(
+        &mut self,
+        config: &CriticalAlertConfig,
+        now: Instant,
+    ) -> Vec<(OpenAlert, EscalationStep)> {
+        let mut due = Vec::new();
+        for alert in self.open.values_mut() {
+            if alert.acknowledged_by.is_some() {
+                continue;
+            }
+            let elapsed = now.duration_since(alert.opened).as_secs() / 60;
+            let passed = config
+                .escalation
+                .iter()
+                .take_while(|step| step.after_minutes <= elapsed)
+                .count();
+            if passed > alert.reminders_sent {
+                alert.reminders_sent = passed;
+                due.push((alert.clone(), config.escalation[passed - 1].clone()));
+            }
+        }
+        due
+    }
+
+    /// One line per open alert for the live status, or `None` when clear.
+    pub fn summary(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .open
+            .values()
+            .map(|alert| match &alert.acknowledged_by {
+                Some(user) => format!("🚨 {} — {user} is on it", alert.kind.label()),
+                None => format!("🚨 {} — unacknowledged", alert.kind.label()),
+            })
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::Acknowledgement;
+    use crate::simulator::{FailureKind, NightPlan, ScriptedFailure};
+    use crate::test_support::{SimulatedNight, short_plan};
+    use std::time::Duration;
+
+    fn step(after_minutes: u64, mention: Option<&str>) -> EscalationStep {
+        EscalationStep {
+            after_minutes,
+            mention: mention.map(str::to_string),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reminders_escalate_until_acknowledged() {
+        let config = CriticalAlertConfig {
+            mention: Some("<@&1>".to_string()),
+            escalation: vec![step(10, None), step(30, Some("<@2>"))],
+            ..CriticalAlertConfig::default()
+        };
+        let mut desk = AlertDesk::default();
+        let alert = desk.open(
+            AlertKind::RigOffline,
+            ChatMessage::new("[scope] 🔌 Telescope offline"),
+            Utc::now(),
+        );
+        let post = alert.post("scope", config.mention.as_deref());
+        assert_eq!(post.mention.as_deref(), Some("<@&1>"));
+        assert_eq!(
+            post.actions,
+            [ChatAction::Acknowledge {
+                telescope: "scope".to_string(),
+                alert: alert.id.clone(),
+            }]
+        );
+
+        tokio::time::advance(Duration::from_secs(9 * 60)).await;
+        assert!(desk.due_reminders(&config, Instant::now()).is_empty());
+        tokio::time::advance(Duration::from_secs(60)).await;
+        let due = desk.due_reminders(&config, Instant::now());
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].1.after_minutes, 10);
+        assert!(
+            due[0]
+                .0
+                .reminder("scope", None, Instant::now())
+                .title
+                .ends_with("unacknowledged for 10m")
+        );
+        assert!(desk.due_reminders(&config, Instant::now()).is_empty());
+
+        assert!(desk.acknowledge(&alert.id, "alice").is_some());
+        assert!(desk.acknowledge(&alert.id, "bob").is_none());
+        tokio::time::advance(Duration::from_secs(30 * 60)).await;
+        assert!(desk.due_reminders(&config, Instant::now()).is_empty());
+        assert_eq!(
+            desk.summary().as_deref(),
+            Some("🚨 Telescope offline — alice is on it")
+        );
+
+        let resolved = desk.resolve(AlertKind::RigOffline).unwrap();
+        assert_eq!(
+            resolved.resolution(),
+            "Telescope offline — handled by alice"
+        );
+        assert!(desk.summary().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missed_steps_collapse_into_the_latest() {
+        let config = CriticalAlertConfig::default();
+        let mut desk = AlertDesk::default();
+        desk.open(AlertKind::Unsafe, ChatMessage::new("unsafe"), Utc::now());
+        tokio::time::advance(Duration::from_secs(45 * 60)).await;
+        let due = desk.due_reminders(&config, Instant::now());
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].1.after_minutes, 30);
+        assert!(desk.acknowledge("unsafe-0", "nobody").is_none());
+    }
+
+    #[test]
+    fn validates_the_schedule() {
+        assert!(CriticalAlertConfig::default().validate().is_ok());
+        let config: CriticalAlertConfig = serde_json::from_str(
+            r#"{"escalation": [{"after_minutes": 20}, {"after_minutes": 5}]}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+        let config = CriticalAlertConfig {
+            mention: Some(" ".to_string()),
+            ..CriticalAlertConfig::default()
+        };
+        assert!(config.validate().is_err());
+        assert_eq!(
+            AlertKind::for_event(event_types::SEQUENCE_ENTITY_FAILED),
+            Some(AlertKind::SequenceFailed)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn simulated_failure_escalates_until_acknowledged_then_resolves() {
+        let night = SimulatedNight::new(NightPlan {
+            failures: vec![ScriptedFailure {
+                at_minutes: 14.0,
+                kind: FailureKind::InstructionFailed {
+                    message: "Center failed".to_string(),
+                },
+            }],
+            ..short_plan()
+        });
+        let acknowledgements = night.manager.acknowledgement_sender();
+        let mut updater = night.updater().with_critical_alerts(CriticalAlertConfig {
+            mention: Some("<@&7>".to_string()),
+            escalation: vec![step(1, None)],
+            ..CriticalAlertConfig::default()
+        });
+        updater.initialize_baseline().await.unwrap();
+
+        // Hold back new frames so imaging does not resolve the alert yet.
+        for _ in 0..90 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            updater.poll_events().await;
+            updater.poll_alerts().await;
+        }
+        let alert = match night.chat.actions.lock().unwrap().as_slice() {
+            [ChatAction::Acknowledge { alert, .. }, ..] => alert.clone(),
+            other => panic!("no alert action in {other:?}"),
+        };
+        acknowledgements
+            .send(Acknowledgement {
+                telescope: "sim".to_string(),
+                alert,
+                user: "alice".to_string(),
+            })
+            .unwrap();
+        updater.poll_alerts().await;
+        tokio::time::advance(Duration::from_secs(120)).await;
+        updater.poll_alerts().await;
+        updater.poll_images().await;
+
+        let titles = night.chat.titles();
+        let position = |needle: &str| {
+            titles
+                .iter()
+                .position(|title| title.contains(needle))
+                .unwrap_or_else(|| panic!("no {needle} post in {titles:#?}"))
+        };
+        let reminder = position("unacknowledged for 1m");
+        let acknowledged = position("Sequence instruction failed acknowledged");
+        let resumed = position("Imaging resumed");
+        assert!(reminder < acknowledged && acknowledged < resumed);
+        assert_eq!(
+            night.chat.count("unacknowledged"),
+            1,
+            "reminders continued after acknowledgement: {titles:#?}"
+        );
+    }
+}
@@ -20,6 +20,8 @@ pub struct AutofocusData {
     pub auto_focuser_name: String,
     pub star_detector_name: String,
     pub timestamp: String,
+    /// Focuser temperature in °C; NaN when the focuser has no sensor.
+    #[serde(deserialize_with = "de_f64_tolerant")]
     pub temperature: f64,
     pub method: String,
     pub fitting: String,
@@ -161,11 +163,443 @@ impl AutofocusData {
             .map(|p| p.value)
             .min_by(|a, b| a.partial_cmp(b).unwrap())
     }
+
+    /// The fitted curves NINA reported, parsed so they can be evaluated.
+    /// Fittings NINA left empty or wrote in a form we cannot read are
+    /// skipped.
+    pub fn fitted_curves(&self) -> Vec<FittedCurve> {
+        FitKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let text = match kind {
+                    FitKind::Hyperbolic => &self.fittings.hyperbolic,
+                    FitKind::Quadratic => &self.fittings.quadratic,
+                    FitKind::Gaussian => &self.fittings.gaussian,
+                    FitKind::LeftTrend => &self.fittings.left_trend,
+                    FitKind::RightTrend => &self.fittings.right_trend,
+                };
+                let expression = Expression::parse(text)?;
+                let r_squared = match kind {
+                    FitKind::Hyperbolic => Some(self.r_squares.hyperbolic),
+                    FitKind::Quadratic => Some(self.r_squares.quadratic),
+                    FitKind::Gaussian => None,
+                    FitKind::LeftTrend => Some(self.r_squares.left_trend),
+                    FitKind::RightTrend => Some(self.r_squares.right_trend),
+                }
+                .filter(|r| r.is_finite());
+                Some(FittedCurve {
+                    kind,
+                    expression,
+                    r_squared,
+                    used: self.uses_fit(kind),
+                })
+            })
+            .collect()
+    }
+
+    /// Whether NINA's chosen fitting (e.g. "TRENDHYPERBOLIC") includes this
+    /// curve, i.e. whether it contributed to the calculated focus point.
+    pub fn uses_fit(&self, kind: FitKind) -> bool {
+        let fitting = self.fitting.to_ascii_uppercase();
+        match kind {
+            FitKind::Hyperbolic => fitting.contains("HYPERBOLIC"),
+            FitKind::Quadratic => fitting.contains("PARABOLIC") || fitting.contains("QUADRATIC"),
+            FitKind::Gaussian => {
+                fitting.contains("GAUSSIAN")
+                    || self.method.to_ascii_uppercase().contains("CONTRAST")
+            }
+            FitKind::LeftTrend | FitKind::RightTrend => fitting.contains("TREND"),
+        }
+    }
+
+    /// The point NINA solved for on a curve: the minimum of the hyperbola
+    /// or parabola, the Gaussian's peak, or where the trend lines meet.
+    pub fn extremum(&self, kind: FitKind) -> Option<&IntersectionPoint> {
+        let intersections = &self.intersections;
+        match kind {
+            FitKind::Hyperbolic => intersections.hyperbolic_minimum.as_ref(),
+            FitKind::Quadratic => intersections.quadratic_minimum.as_ref(),
+            FitKind::Gaussian => intersections.gaussian_maximum.as_ref(),
+            FitKind::LeftTrend | FitKind::RightTrend => {
+                intersections.trend_line_intersection.as_ref()
+            }
+        }
+        .filter(|point| point.position.is_finite() && point.value.is_finite())
+    }
+
+    /// A run usable for temperature trends: it found a focus point and the
+    /// focuser reported a temperature.
+    pub(crate) fn has_temperature_result(&self) -> bool {
+        self.temperature.is_finite()
+            && self.calculated_focus_point.position > 0
+            && self.final_hfr().is_some()
+    }
+}
+
+/// NINA's autofocus curve fittings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FitKind {
+    Hyperbolic,
+    Quadratic,
+    Gaussian,
+    LeftTrend,
+    RightTrend,
+}
+
+impl FitKind {
+    pub const ALL: [FitKind; 5] = [
+        FitKind::Hyperbolic,
+        FitKind::Quadratic,
+        FitKind::Gaussian,
+        FitKind::LeftTrend,
+        FitKind::RightTrend,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FitKind::Hyperbolic => "Hyperbolic",
+            FitKind::Quadratic => "Quadratic",
+            FitKind::Gaussian => "Gaussian",
+            FitKind::LeftTrend => "Left trend",
+            FitKind::RightTrend => "Right trend",
+        }
+    }
+}
+
+/// One fitted curve from an autofocus run.
+#[derive(Debug, Clone)]
+pub struct FittedCurve {
+    pub kind: FitKind,
+    expression: Expression,
+    /// Goodness of fit; NINA does not report one for the Gaussian.
+    pub r_squared: Option<f64>,
+    /// Whether the run's fitting method used this curve.
+    pub used: bool,
+}
+
+impl FittedCurve {
+    /// The curve's value at a focuser position; NaN outside its domain.
+    pub fn value_at(&self, position: f64) -> f64 {
+        self.expression.eval(position)
+    }
+}
+
+/// A parsed fitting expression in the focuser position `x`, as NINA and
+/// its focuser plugins print them: "y = 3.04 * cosh(asinh((4060.6 - x) /
+/// 82.5))", "y = 0.00017 * x^2 + -1.35 * x + 2752.8", or Hocus Focus's
+/// "5.751/132.836 * √((x - 4188.955)² + 132.836²)".
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Number(f64),
+    X,
+    Negate(Box<Expression>),
+    Binary(char, Box<Expression>, Box<Expression>),
+    Call(String, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+impl Expression {
+    fn parse(text: &str) -> Option<Self> {
+        let body = text.split_once('=').map_or(text, |(_, rhs)| rhs).trim();
+        if body.is_empty() {
+            return None;
+        }
+        let tokens = tokenize(body)?;
+        let mut parser = Parser { tokens, at: 0 };
+        let expression = parser.sum()?;
+        (parser.at == parser.tokens.len()).then_some(expression)
+    }
+
+    fn eval(&self, x: f64) -> f64 {
+        match self {
+            Expression::Number(value) => *value,
+            Expression::X => x,
+            Expression::Negate(inner) => -inner.eval(x),
+            Expression::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(x), rhs.eval(x));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(b),
+                }
+            }
+            Expression::Call(name, args) => {
+                let args: Vec<f64> = args.iter().map(|arg| arg.eval(x)).collect();
+                match (name.as_str(), args.as_slice()) {
+                    ("sqrt", [a]) => a.sqrt(),
+                    ("exp", [a]) => a.exp(),
+                    ("ln" | "log", [a]) => a.ln(),
+                    ("abs", [a]) => a.abs(),
+                    ("cosh", [a]) => a.cosh(),
+                    ("sinh", [a]) => a.sinh(),
+                    ("tanh", [a]) => a.tanh(),
+                    ("asinh", [a]) => a.asinh(),
+                    ("acosh", [a]) => a.acosh(),
+                    ("min", [a, b]) => a.min(*b),
+                    ("max", [a, b]) => a.max(*b),
+                    ("pow", [a, b]) => a.powf(*b),
+                    _ => f64::NAN,
+                }
+            }
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // An exponent only when digits follow, so "2e" stays a name.
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && matches!(chars[j], '+' | '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let literal: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(literal.parse().ok()?));
+        } else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_alphanumeric() {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            tokens.push(Token::Name(name.to_ascii_lowercase()));
+        } else if "+-*/^(),√²".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else if c == '×' || c == '·' {
+            tokens.push(Token::Symbol('*'));
+            i += 1;
+        } else {
+            return None;
+        }
+    }
+    Some(tokens)
+}
+
+/// Recursive descent over the usual precedence: sums, products, unary
+/// minus and square roots, powers, then atoms with a postfix "²".
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sum(&mut self) -> Option<Expression> {
+        let mut lhs = self.product()?;
+        loop {
+            let op = if self.eat('+') {
+                '+'
+            } else if self.eat('-') {
+                '-'
+            } else {
+                return Some(lhs);
+            };
+            lhs = Expression::Binary(op, Box::new(lhs), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Option<Expression> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                '*'
+            } else if self.eat('/') {
+                '/'
+            } else {
+                return Some(lhs);
+            };
+            lhs = Expression::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Option<Expression> {
+        if self.eat('-') {
+            Some(Expression::Negate(Box::new(self.unary()?)))
+        } else if self.eat('+') {
+            self.unary()
+        } else if self.eat('√') {
+            Some(Expression::Call("sqrt".to_string(), vec![self.unary()?]))
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Option<Expression> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            let exponent = self.unary()?;
+            return Some(Expression::Binary('^', Box::new(base), Box::new(exponent)));
+        }
+        Some(base)
+    }
+
+    fn atom(&mut self) -> Option<Expression> {
+        let mut atom = match self.tokens.get(self.at).cloned()? {
+            Token::Number(value) => {
+                self.at += 1;
+                Expression::Number(value)
+            }
+            Token::Name(name) => {
+                self.at += 1;
+                if name == "x" {
+                    Expression::X
+                } else if self.eat('(') {
+                    let mut args = vec![self.sum()?];
+                    while self.eat(',') {
+                        args.push(self.sum()?);
+                    }
+                    if !self.eat(')') {
+                        return None;
+                    }
+                    Expression::Call(name, args)
+                } else {
+                    return None;
+                }
+            }
+            Token::Symbol('(') => {
+                self.at += 1;
+                let inner = self.sum()?;
+                if !self.eat(')') {
+                    return None;
+                }
+                inner
+            }
+            Token::Symbol(_) => return None,
+        };
+        while self.eat('²') {
+            atom = Expression::Binary('^', Box::new(atom), Box::new(Expression::Number(2.0)));
+        }
+        Some(atom)
+    }
+}
+
+/// A linear focus-position-vs-temperature model estimated from a night's
+/// autofocus runs, in the form N.I.N.A.'s "Move focuser by temperature"
+/// instruction takes: position = slope × temperature + intercept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperatureCompensation {
+    /// Focuser steps per °C.
+    pub slope: f64,
+    /// Position at 0 °C, for `reference_filter`.
+    pub intercept: f64,
+    /// The filter with the most runs; other filters differ by their offsets.
+    pub reference_filter: String,
+    /// How much of the position change temperature explains.
+    pub r_squared: f64,
+    /// Runs that contributed to the fit.
+    pub runs: usize,
+}
+
+impl TemperatureCompensation {
+    /// Fewest runs worth fitting a slope to.
+    pub const MIN_RUNS: usize = 3;
+    /// Narrowest temperature range, in °C, that says anything about focus
+    /// drift rather than autofocus scatter.
+    pub const MIN_SPAN: f64 = 1.0;
+
+    /// Fit a common slope across filters. Each filter is centered on its own
+    /// mean first, so filter focus offsets do not read as temperature drift.
+    pub fn fit(runs: &[AutofocusData]) -> Option<Self> {
+        let mut by_filter: std::collections::BTreeMap<&str, Vec<(f64, f64)>> =
+            std::collections::BTreeMap::new();
+        for run in runs.iter().filter(|run| run.has_temperature_result()) {
+            by_filter
+                .entry(run.filter.as_str())
+                .or_default()
+                .push((run.temperature, run.calculated_focus_point.position as f64));
+        }
+        let (mut sxx, mut sxy, mut syy, mut count) = (0.0, 0.0, 0.0, 0);
+        let (mut lo, mut hi) = (f64::INFINITY, f64::NEG_INFINITY);
+        for points in by_filter.values().filter(|points| points.len() >= 2) {
+            let n = points.len() as f64;
+            let mean_t = points.iter().map(|p| p.0).sum::<f64>() / n;
+            let mean_p = points.iter().map(|p| p.1).sum::<f64>() / n;
+            for &(t, p) in points {
+                sxx += (t - mean_t).powi(2);
+                sxy += (t - mean_t) * (p - mean_p);
+                syy += (p - mean_p).powi(2);
+                lo = lo.min(t);
+                hi = hi.max(t);
+            }
+            count += points.len();
+        }
+        if count < Self::MIN_RUNS || hi - lo < Self::MIN_SPAN || sxx <= 0.0 {
+            return None;
+        }
+        let slope = sxy / sxx;
+        let r_squared = if syy > 0.0 {
+            sxy * sxy / (sxx * syy)
+        } else {
+            1.0
+        };
+        let (reference_filter, points) = by_filter
+            .iter()
+            .filter(|(_, points)| points.len() >= 2)
+            .max_by_key(|(_, points)| points.len())?;
+        let n = points.len() as f64;
+        let intercept = points.iter().map(|(t, p)| p - slope * t).sum::<f64>() / n;
+        Some(Self {
+            slope,
+            intercept,
+            reference_filter: reference_filter.to_string(),
+            r_squared,
+            runs: count,
+        })
+    }
+
+    /// e.g. "-12.4 steps/°C, intercept 4312 (L) · R² 0.93 over 6 runs".
+    pub fn summary(&self) -> String {
+        format!(
+            "{:+.1} steps/°C, intercept {:.0} ({}) · R² {:.2} over {} runs",
+            self.slope, self.intercept, self.reference_filter, self.r_squared, self.runs
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simulator::{FOCUS_STEPS_PER_DEGREE, NightPlan, SimulatedRigSource};
+    use crate::source::RigSource;
+    use std::time::Duration;
 
     #[test]
     fn test_parse_autofocus_response() {
@@ -312,6 +746,103 @@ mod tests {
         assert_eq!(position_change, 110);
     }
 
+    #[test]
+    fn fitted_curves_pass_through_their_reported_minima() {
+        for file in ["example_last_af.json", "example_last_af_2.json"] {
+            let json_content = std::fs::read_to_string(file).unwrap();
+            let response: AutofocusResponse = serde_json::from_str(&json_content).unwrap();
+            let af_data = &response.response;
+            let curves = af_data.fitted_curves();
+            for kind in [FitKind::Hyperbolic, FitKind::LeftTrend, FitKind::RightTrend] {
+                let curve = curves.iter().find(|c| c.kind == kind).unwrap();
+                assert!(curve.used, "{file}: {kind:?}");
+                assert!(curve.r_squared.unwrap() > 0.9);
+                let point = af_data.extremum(kind).unwrap();
+                // The first run's positions are rounded to whole steps, so
+                // steep curves land a little off the reported value.
+                let value = curve.value_at(point.position);
+                assert!(
+                    (value - point.value).abs() < 0.02,
+                    "{file}: {kind:?} {value}"
+                );
+            }
+        }
+
+        let json_content = std::fs::read_to_string("example_last_af.json").unwrap();
+        let response: AutofocusResponse = serde_json::from_str(&json_content).unwrap();
+        let curves = response.response.fitted_curves();
+        assert_eq!(curves.len(), 5);
+        let quadratic = curves
+            .iter()
+            .find(|c| c.kind == FitKind::Quadratic)
+            .unwrap();
+        assert!(!quadratic.used);
+        assert!((quadratic.value_at(4061.0) - 3.1133).abs() < 0.01);
+        let gaussian = curves.iter().find(|c| c.kind == FitKind::Gaussian).unwrap();
+        assert!(gaussian.r_squared.is_none());
+        assert!((gaussian.value_at(4171.314288473132) - 5.0314).abs() < 0.001);
+
+        // The second run leaves the quadratic and Gaussian empty.
+        let json_content = std::fs::read_to_string("example_last_af_2.json").unwrap();
+        let response: AutofocusResponse = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(response.response.fitted_curves().len(), 3);
+    }
+
+    #[test]
+    fn fitting_expressions_parse_numbers_and_reject_garbage() {
+        let curve = Expression::parse("y = 1.5E-02 * x^2 + -x / 2 + pow(2, 3)").unwrap();
+        assert!((curve.eval(10.0) - (1.5 + -5.0 + 8.0)).abs() < 1e-12);
+        assert_eq!(Expression::parse("y = -2^2").unwrap().eval(0.0), -4.0);
+        assert_eq!(Expression::parse(""), None);
+        assert_eq!(Expression::parse("y = 3 * (x"), None);
+        assert_eq!(Expression::parse("y = foo + 1"), None);
+        assert_eq!(Expression::parse("y = 1 $ 2"), None);
+        assert!(Expression::parse("y = nope(1)").unwrap().eval(0.0).is_nan());
+    }
+
+    #[test]
+    fn temperature_compensation_ignores_filter_offsets() {
+        let json_content = std::fs::read_to_string("example_last_af.json").unwrap();
+        let response: AutofocusResponse = serde_json::from_str(&json_content).unwrap();
+        let run = |filter: &str, temperature: f64, position: i32| {
+            let mut af_data = response.response.clone();
+            af_data.filter = filter.to_string();
+            af_data.temperature = temperature;
+            af_data.calculated_focus_point.position = position;
+            af_data
+        };
+        // -10 steps/°C, with OIII focusing 50 steps outward of Ha.
+        let mut runs = vec![
+            run("Ha", 20.0, 4000),
+            run("OIII", 19.0, 4060),
+            run("Ha", 18.0, 4020),
+            run("OIII", 17.0, 4080),
+            run("Ha", 16.0, 4040),
+        ];
+        let fit = TemperatureCompensation::fit(&runs).unwrap();
+        assert!((fit.slope + 10.0).abs() < 1e-9);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+        assert_eq!(fit.reference_filter, "Ha");
+        assert!((fit.intercept - 4200.0).abs() < 1e-9);
+        assert_eq!(fit.runs, 5);
+        assert!(
+            fit.summary()
+                .starts_with("-10.0 steps/°C, intercept 4200 (Ha)")
+        );
+
+        // Runs without a temperature do not count, and too few runs or too
+        // little temperature change give no estimate.
+        runs.push(run("Ha", f64::NAN, 9999));
+        assert_eq!(TemperatureCompensation::fit(&runs).unwrap().runs, 5);
+        assert_eq!(TemperatureCompensation::fit(&runs[..2]), None);
+        let flat = vec![
+            run("Ha", 20.0, 4000),
+            run("Ha", 20.2, 4010),
+            run("Ha", 20.4, 3990),
+        ];
+        assert_eq!(TemperatureCompensation::fit(&flat), None);
+    }
+
     #[test]
     fn native_nina_decimal_focus_positions_are_accepted() {
         let json_content = std::fs::read_to_string("example_last_af.json").unwrap();
@@ -326,4 +857,50 @@ mod tests {
         assert_eq!(response.response.calculated_focus_point.position, 4068);
         assert_eq!(response.response.measure_points[0].position, 3992);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn simulated_runs_track_the_cooling_night() {
+        // One filter change per stage refocuses as the air cools.
+        let plan = NightPlan {
+            filters: vec!["L".to_string(); 8],
+            frames_per_filter: 6,
+            exposure_seconds: 300.0,
+            meridian_flip_after_minutes: 600.0,
+            ..NightPlan::default()
+        };
+        let source = SimulatedRigSource::new(plan).with_time_scale(60.0);
+        let mut runs: Vec<AutofocusData> = Vec::new();
+        while !source.finished() {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            if let Ok(autofocus) = source.get_last_autofocus().await
+                && runs
+                    .last()
+                    .is_none_or(|last| last.timestamp != autofocus.response.timestamp)
+            {
+                runs.push(autofocus.response);
+            }
+        }
+        assert_eq!(runs.len(), 8);
+        // Every run's fitted hyperbola is readable and bottoms out at the
+        // reported minimum.
+        for run in &runs {
+            let curve = run
+                .fitted_curves()
+                .into_iter()
+                .find(|curve| curve.kind == FitKind::Hyperbolic)
+                .unwrap();
+            let minimum = run.intersections.hyperbolic_minimum.as_ref().unwrap();
+            assert!((curve.value_at(minimum.position) - minimum.value).abs() < 0.01);
+        }
+
+        let fit = TemperatureCompensation::fit(&runs).unwrap();
+        assert_eq!(fit.runs, 8);
+        assert!(
+            (fit.slope - FOCUS_STEPS_PER_DEGREE).abs() < 6.0,
+            "slope {}",
+            fit.slope
+        );
+        let png = crate::charts::render_autofocus_temperature_png(&runs).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
 }
@@ -5,16 +5,41 @@
 //!   signed correction-pulse bars on the right axis, dither markers,
 //!   and an RMS summary in the title;
 //! * the Direct autofocus run — measured HFR
-//!   points with error bars plus initial/calculated position markers.
+//!   points with error bars, NINA's fitted curves with their minima and
+//!   R², and initial/calculated position markers;
+//! * the night's autofocus runs — focus position and HFR against
+//!   temperature, with the temperature-compensation slope;
+//! * the end-of-night session report — integration per filter and the
+//!   night's HFR, star count and guiding RMS per light frame;
+//! * the night's frame trends — HFR, star count, median ADU and sensor
+//!   temperature against the clock, with filter changes, autofocus runs,
+//!   dithers and meridian flips marked;
+//! * a target's integration progress — acquired hours per filter stacked
+//!   against what the sequence plans;
+//! * the mount's position on an all-sky polar plot — the meridian, the
+//!   site's local horizon, the target's track through the night with hour
+//!   ticks, and the meridian flip;
+//! * the RA guiding error's amplitude spectrum against period, with the
+//!   dominant periodic error marked.
 //!
 //! Text uses an embedded Liberation Sans (SIL OFL, see
 //! `assets/LiberationSans-LICENSE`) via plotters' `ab_glyph` backend, so
 //! rendering needs no system font libraries on any release target.
 
-use crate::autofocus::AutofocusData;
+use crate::autofocus::{AutofocusData, FitKind, TemperatureCompensation};
+use crate::ephemeris::{Equatorial, Night, Site, TrackPoint, clock, countdown, horizontal, track};
+use crate::events::Event;
+use crate::guide_analysis::GuidingAnalysis;
 use crate::guider::GuideStepsHistory;
+use crate::horizon::HorizonProfile;
+use crate::images::ImageHistoryResponse;
+use crate::mount::MountInfoResponse;
+use crate::progress::IntegrationProgress;
+use crate::session_report::{MarkerKind, ReportFrame, SessionReport};
+use chrono::{DateTime, DurationRound, Utc};
 use plotters::prelude::*;
 use plotters::style::register_font;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
 use std::sync::Once;
 use thiserror::Error;
 
@@ -29,6 +54,50 @@ const DEC_COLOR: RGBColor = RGBColor(232, 77, 77);
 const DITHER_COLOR: RGBColor = RGBColor(160, 160, 90);
 const HFR_COLOR: RGBColor = RGBColor(96, 189, 232);
 const FOCUS_COLOR: RGBColor = RGBColor(96, 209, 122);
+/// Per-filter series colors, assigned in filter name order.
+const FILTER_COLORS: [RGBColor; 7] = [
+    RGBColor(96, 189, 232),
+    RGBColor(232, 77, 77),
+    RGBColor(96, 209, 122),
+    RGBColor(77, 139, 232),
+    RGBColor(232, 180, 77),
+    RGBColor(190, 120, 232),
+    RGBColor(200, 204, 210),
+];
+
+const REPORT_HEIGHT: u32 = 680;
+const TEMPERATURE_HEIGHT: u32 = 640;
+const TRENDS_HEIGHT: u32 = 820;
+const PROGRESS_ROW_HEIGHT: u32 = 56;
+const SKY_HEIGHT: u32 = 760;
+const SKY_COLOR: RGBColor = RGBColor(31, 35, 44);
+const OBSTRUCTION_COLOR: RGBColor = RGBColor(74, 96, 62);
+const MERIDIAN_COLOR: RGBColor = RGBColor(232, 170, 77);
+
+/// Dithers happen every frame or two, so they stay faint and thin.
+fn marker_style(kind: MarkerKind) -> ShapeStyle {
+    match kind {
+        MarkerKind::Dither => DITHER_COLOR.mix(0.35).stroke_width(1),
+        MarkerKind::FilterChange => TEXT.mix(0.6).stroke_width(2),
+        MarkerKind::Autofocus => FOCUS_COLOR.stroke_width(2),
+        MarkerKind::MeridianFlip => RGBColor(232, 140, 60).stroke_width(2),
+    }
+}
+
+/// Samples per fitted autofocus curve.
+const CURVE_SAMPLES: usize = 200;
+
+fn fit_color(kind: FitKind) -> RGBColor {
+    match kind {
+        FitKind::Hyperbolic => RGBColor(232, 180, 77),
+        FitKind::Quadratic => RGBColor(190, 120, 232),
+        FitKind::Gaussian => RGBColor(232, 77, 77),
+        FitKind::LeftTrend | FitKind::RightTrend => RGBColor(200, 204, 210),
+    }
+}
+
+/// Reads one per-frame quality metric for a session report panel.
+type FrameMetric = fn(&ReportFrame) -> Option<f64>;
 
 #[derive(Debug, Error)]
 pub enum ChartError {
@@ -246,8 +315,10 @@ pub fn render_guider_graph_png(history: &GuideStepsHistory) -> Result<Vec<u8>, C
 }
 
 /// Render an autofocus run to PNG bytes: measured HFR vs focuser position
-/// with error bars, a connecting line, and vertical markers for the
-/// initial and calculated focus positions. Fails when fewer than two
+/// with error bars, a connecting line, NINA's fitted curves with a cross at
+/// each curve's minimum (R² in the legend), and vertical markers for the
+/// initial and calculated focus positions. The curves the run's fitting
+/// used are drawn solid, the others faint. Fails when fewer than two
 /// finite measurement points are present.
 pub fn render_autofocus_graph_png(af: &AutofocusData) -> Result<Vec<u8>, ChartError> {
     let points: Vec<(f64, f64, f64)> = af
@@ -274,15 +345,28 @@ pub fn render_autofocus_graph_png(af: &AutofocusData) -> Result<Vec<u8>, ChartEr
         .fold(initial_pos.max(final_pos), f64::max);
     let x_pad = ((x_hi - x_lo) * 0.05).max(1.0);
 
+    let curves = af.fitted_curves();
+    let extrema: Vec<(FitKind, f64, f64)> = curves
+        .iter()
+        .filter(|curve| curve.kind != FitKind::RightTrend)
+        .filter_map(|curve| {
+            af.extremum(curve.kind)
+                .map(|point| (curve.kind, point.position, point.value))
+        })
+        .filter(|&(_, x, _)| x >= x_lo && x <= x_hi)
+        .collect();
+
     let y_lo = points
         .iter()
         .map(|(_, v, e)| v - e)
+        .chain(extrema.iter().map(|e| e.2))
         .fold(f64::INFINITY, f64::min);
     let y_hi = points
         .iter()
         .map(|(_, v, e)| v + e)
         .fold(f64::NEG_INFINITY, f64::max);
     let y_pad = ((y_hi - y_lo) * 0.1).max(0.1);
+    let (y_min, y_max) = (y_lo - y_pad, y_hi + y_pad);
 
     let hfr_change = match (af.initial_hfr(), af.final_hfr()) {
         (Some(before), Some(after)) => format!("HFR {:.2} → {:.2}", before, after),
@@ -302,10 +386,7 @@ pub fn render_autofocus_graph_png(af: &AutofocusData) -> Result<Vec<u8>, ChartEr
             .margin(12)
             .x_label_area_size(36)
             .y_label_area_size(52)
-            .build_cartesian_2d(
-                (x_lo - x_pad)..(x_hi + x_pad),
-                (y_lo - y_pad)..(y_hi + y_pad),
-            )
+            .build_cartesian_2d((x_lo - x_pad)..(x_hi + x_pad), y_min..y_max)
             .map_err(|e| ChartError::Render(e.to_string()))?;
 
         chart
@@ -326,7 +407,7 @@ pub fn render_autofocus_graph_png(af: &AutofocusData) -> Result<Vec<u8>, ChartEr
         ] {
             chart
                 .draw_series(std::iter::once(PathElement::new(
-                    vec![(pos, y_lo - y_pad), (pos, y_hi + y_pad)],
+                    vec![(pos, y_min), (pos, y_max)],
                     color.mix(0.7).stroke_width(2),
                 )))
                 .map_err(|e| ChartError::Render(e.to_string()))?
@@ -336,6 +417,66 @@ pub fn render_autofocus_graph_png(af: &AutofocusData) -> Result<Vec<u8>, ChartEr
                 });
         }
 
+        // Fitted curves, clipped to the plot. Trend lines stop where they
+        // meet so the V reads as NINA draws it.
+        let meet = af.extremum(FitKind::LeftTrend).map(|p| p.position);
+        for curve in &curves {
+            let (from, to) = match (curve.kind, meet) {
+                (FitKind::LeftTrend, Some(meet)) => (x_lo - x_pad, meet),
+                (FitKind::RightTrend, Some(meet)) => (meet, x_hi + x_pad),
+                _ => (x_lo - x_pad, x_hi + x_pad),
+            };
+            let samples: Vec<f64> = (0..=CURVE_SAMPLES)
+                .map(|i| {
+                    let x = from + (to - from) * i as f64 / CURVE_SAMPLES as f64;
+                    Some(curve.value_at(x))
+                        .filter(|y| *y >= y_min && *y <= y_max)
+                        .unwrap_or(f64::NAN)
+                })
+                .collect();
+            let color = fit_color(curve.kind);
+            let style = if curve.used {
+                color.stroke_width(2)
+            } else {
+                color.mix(0.45).stroke_width(1)
+            };
+            let x_at = |i: usize| from + (to - from) * i as f64 / CURVE_SAMPLES as f64;
+            let runs = contiguous_finite_runs(&samples);
+            for (n, run) in runs.iter().enumerate() {
+                let series = chart
+                    .draw_series(LineSeries::new(
+                        run.iter().map(|&(i, y)| (x_at(i), y)),
+                        style,
+                    ))
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+                // One legend entry per curve, and one for the trend pair.
+                if n == 0 && curve.kind != FitKind::RightTrend {
+                    let right = curves
+                        .iter()
+                        .find(|c| c.kind == FitKind::RightTrend)
+                        .and_then(|c| c.r_squared);
+                    let label = match (curve.kind, curve.r_squared, right) {
+                        (FitKind::LeftTrend, Some(l), Some(r)) => {
+                            format!("Trend lines  R² {l:.3} / {r:.3}")
+                        }
+                        (FitKind::LeftTrend, ..) => "Trend lines".to_string(),
+                        (kind, Some(r), _) => format!("{}  R² {r:.3}", kind.label()),
+                        (kind, None, _) => kind.label().to_string(),
+                    };
+                    series
+                        .label(label)
+                        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 16, y)], style));
+                }
+            }
+        }
+        chart
+            .draw_series(
+                extrema
+                    .iter()
+                    .map(|&(kind, x, y)| Cross::new((x, y), 6, fit_color(kind).stroke_width(2))),
+            )
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
         // Error bars
         let cap = x_pad * 0.3;
         for &(x, v, e) in &points {
@@ -391,6 +532,992 @@ pub fn render_autofocus_graph_png(af: &AutofocusData) -> Result<Vec<u8>, ChartEr
     encode_png(&buffer, WIDTH, HEIGHT)
 }
 
+/// Render a night's autofocus runs against focuser temperature: the
+/// calculated focus position above, with each filter's line at the fitted
+/// temperature-compensation slope, and the focused HFR below. Runs are
+/// colored by filter. Fails when fewer than two runs have a temperature
+/// and a focus point.
+pub fn render_autofocus_temperature_png(runs: &[AutofocusData]) -> Result<Vec<u8>, ChartError> {
+    let compensation = TemperatureCompensation::fit(runs);
+    let runs: Vec<&AutofocusData> = runs
+        .iter()
+        .filter(|run| run.has_temperature_result())
+        .collect();
+    if runs.len() < 2 {
+        return Err(ChartError::NotEnoughData(runs.len()));
+    }
+    ensure_font();
+
+    let mut filters: Vec<&str> = runs.iter().map(|run| run.filter.as_str()).collect();
+    filters.sort_unstable();
+    filters.dedup();
+    let color_of = |filter: &str| {
+        let index = filters.iter().position(|f| *f == filter).unwrap_or(0);
+        FILTER_COLORS[index % FILTER_COLORS.len()]
+    };
+
+    let range = |values: &mut dyn Iterator<Item = f64>, min_pad: f64| {
+        let (lo, hi) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+        let pad = ((hi - lo) * 0.1).max(min_pad);
+        (lo - pad)..(hi + pad)
+    };
+    let temperatures = range(&mut runs.iter().map(|run| run.temperature), 0.5);
+    let positions = range(
+        &mut runs
+            .iter()
+            .map(|run| run.calculated_focus_point.position as f64),
+        5.0,
+    );
+    let hfrs = range(&mut runs.iter().filter_map(|run| run.final_hfr()), 0.1);
+
+    let title = match &compensation {
+        Some(fit) => format!(
+            "Focus vs temperature  —  {:+.1} steps/°C  (R² {:.2}, {} runs)",
+            fit.slope, fit.r_squared, fit.runs
+        ),
+        None => format!("Focus vs temperature  —  {} runs", runs.len()),
+    };
+
+    let mut buffer = vec![0u8; (WIDTH * TEMPERATURE_HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, TEMPERATURE_HEIGHT))
+            .into_drawing_area();
+        root.fill(&BACKGROUND)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let root = root
+            .titled(&title, ("sans-serif", 20).into_font().color(&TEXT))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let panels = root.split_evenly((2, 1));
+
+        let mut focus = ChartBuilder::on(&panels[0])
+            .caption(
+                "Focus position",
+                ("sans-serif", 15).into_font().color(&TEXT),
+            )
+            .margin(10)
+            .x_label_area_size(28)
+            .y_label_area_size(52)
+            .build_cartesian_2d(temperatures.clone(), positions)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        focus
+            .configure_mesh()
+            .bold_line_style(GRID.mix(0.8))
+            .light_line_style(GRID.mix(0.3))
+            .axis_style(GRID)
+            .label_style(("sans-serif", 13).into_font().color(&TEXT))
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        for filter in &filters {
+            let color = color_of(filter);
+            let points: Vec<(f64, f64)> = runs
+                .iter()
+                .filter(|run| run.filter == *filter)
+                .map(|run| (run.temperature, run.calculated_focus_point.position as f64))
+                .collect();
+            if let Some(fit) = &compensation {
+                // The shared slope through this filter's own mean.
+                let n = points.len() as f64;
+                let intercept = points.iter().map(|(t, p)| p - fit.slope * t).sum::<f64>() / n;
+                focus
+                    .draw_series(LineSeries::new(
+                        [temperatures.start, temperatures.end]
+                            .map(|t| (t, fit.slope * t + intercept)),
+                        color.mix(0.6).stroke_width(1),
+                    ))
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+            }
+            focus
+                .draw_series(
+                    points
+                        .iter()
+                        .map(|&point| Circle::new(point, 4, color.filled())),
+                )
+                .map_err(|e| ChartError::Render(e.to_string()))?
+                .label(*filter)
+                .legend(move |(x, y)| Circle::new((x + 8, y), 4, color.filled()));
+        }
+        focus
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .background_style(BACKGROUND.mix(0.8))
+            .border_style(GRID)
+            .label_font(("sans-serif", 13).into_font().color(&TEXT))
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        let mut hfr = ChartBuilder::on(&panels[1])
+            .caption("HFR", ("sans-serif", 15).into_font().color(&TEXT))
+            .margin(10)
+            .x_label_area_size(28)
+            .y_label_area_size(52)
+            .build_cartesian_2d(temperatures, hfrs)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        hfr.configure_mesh()
+            .bold_line_style(GRID.mix(0.8))
+            .light_line_style(GRID.mix(0.3))
+            .axis_style(GRID)
+            .label_style(("sans-serif", 13).into_font().color(&TEXT))
+            .x_desc("Temperature (°C)")
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        hfr.draw_series(runs.iter().filter_map(|run| {
+            run.final_hfr()
+                .map(|v| Circle::new((run.temperature, v), 4, color_of(&run.filter).filled()))
+        }))
+        .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        root.present()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+    }
+
+    encode_png(&buffer, WIDTH, TEMPERATURE_HEIGHT)
+}
+
+/// Render the session report as four panels: light integration per
+/// filter, then HFR, star count and guiding RMS over the night, each frame
+/// colored by filter. Fails when the night has no light frames.
+pub fn render_session_report_png(report: &SessionReport) -> Result<Vec<u8>, ChartError> {
+    if report.timeline.is_empty() {
+        return Err(ChartError::NotEnoughData(0));
+    }
+    ensure_font();
+
+    let totals = report.integration_by_filter();
+    let filters: Vec<&str> = totals.keys().copied().collect();
+    let color_of = |filter: &str| {
+        let index = filters.iter().position(|f| *f == filter).unwrap_or(0);
+        FILTER_COLORS[index % FILTER_COLORS.len()]
+    };
+    let span = report
+        .timeline
+        .iter()
+        .map(|frame| frame.minutes)
+        .fold(1.0_f64, f64::max);
+
+    let mut buffer = vec![0u8; (WIDTH * REPORT_HEIGHT * 3) as usize];
+    {
+        let root =
+            BitMapBackend::with_buffer(&mut buffer, (WIDTH, REPORT_HEIGHT)).into_drawing_area();
+        root.fill(&BACKGROUND)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let title = match report.night {
+            Some(night) => format!("Session  —  night of {}", night.format("%Y-%m-%d")),
+            None => "Session".to_string(),
+        };
+        let root = root
+            .titled(&title, ("sans-serif", 20).into_font().color(&TEXT))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let panels = root.split_evenly((2, 2));
+
+        // Integration per filter, in minutes.
+        let max_minutes = totals.values().fold(1.0_f64, |max, s| max.max(s / 60.0));
+        let mut bars = ChartBuilder::on(&panels[0])
+            .caption(
+                "Integration (min)",
+                ("sans-serif", 15).into_font().color(&TEXT),
+            )
+            .margin(10)
+            .x_label_area_size(28)
+            .y_label_area_size(44)
+            .build_cartesian_2d(0f64..filters.len() as f64, 0f64..max_minutes * 1.15)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        bars.configure_mesh()
+            .disable_x_mesh()
+            .bold_line_style(GRID.mix(0.8))
+            .light_line_style(GRID.mix(0.3))
+            .axis_style(GRID)
+            .label_style(("sans-serif", 13).into_font().color(&TEXT))
+            .x_labels(filters.len() * 2 + 1)
+            .x_label_formatter(&|x| {
+                let index = x.floor() as usize;
+                if (x - index as f64 - 0.5).abs() < 0.01 {
+                    filters
+                        .get(index)
+                        .map(|f| f.to_string())
+                        .unwrap_or_default()
+                } else {
+                    String::new()
+                }
+            })
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        bars.draw_series(filters.iter().enumerate().map(|(i, filter)| {
+            let minutes = totals[filter] / 60.0;
+            Rectangle::new(
+                [(i as f64 + 0.15, 0.0), (i as f64 + 0.85, minutes)],
+                color_of(filter).mix(0.8).filled(),
+            )
+        }))
+        .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        let metrics: [(&str, FrameMetric); 3] = [
+            ("HFR", |frame| frame.hfr),
+            ("Stars", |frame| frame.stars),
+            ("Guiding RMS (″)", |frame| frame.rms),
+        ];
+        for (panel, (label, value)) in panels[1..].iter().zip(metrics) {
+            let points: Vec<(f64, f64, &str)> = report
+                .timeline
+                .iter()
+                .filter_map(|frame| {
+                    value(frame)
+                        .filter(|v| v.is_finite())
+                        .map(|v| (frame.minutes, v, frame.filter.as_str()))
+                })
+                .collect();
+            let (lo, hi) = points
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                    (lo.min(p.1), hi.max(p.1))
+                });
+            let (lo, hi) = if lo.is_finite() && hi > lo {
+                let pad = (hi - lo) * 0.1;
+                (lo - pad, hi + pad)
+            } else if lo.is_finite() {
+                (lo - 1.0, lo + 1.0)
+            } else {
+                (0.0, 1.0)
+            };
+            let mut chart = ChartBuilder::on(panel)
+                .caption(label, ("sans-serif", 15).into_font().color(&TEXT))
+                .margin(10)
+                .x_label_area_size(28)
+                .y_label_area_size(44)
+                .build_cartesian_2d(0f64..span, lo..hi)
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+            chart
+                .configure_mesh()
+                .bold_line_style(GRID.mix(0.8))
+                .light_line_style(GRID.mix(0.3))
+                .axis_style(GRID)
+                .label_style(("sans-serif", 13).into_font().color(&TEXT))
+                .x_desc("Minutes")
+                .draw()
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+            chart
+                .draw_series(
+                    points
+                        .iter()
+                        .map(|&(x, y, filter)| Circle::new((x, y), 3, color_of(filter).filled())),
+                )
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+
+        root.present()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+    }
+
+    encode_png(&buffer, WIDTH, REPORT_HEIGHT)
+}
+
+/// Render the latest night's light frames as four stacked trends against
+/// the clock — HFR, star count, median ADU and sensor temperature — each
+/// frame colored by filter, with vertical markers for the filter changes,
+/// autofocus runs, dithers and meridian flips in the event history. Fails
+/// when the night has fewer than two light frames.
+pub fn render_session_trends_png(
+    images: &ImageHistoryResponse,
+    events: &[Event],
+) -> Result<Vec<u8>, ChartError> {
+    let report = SessionReport::build(images, events, None);
+    if report.timeline.len() < 2 {
+        return Err(ChartError::NotEnoughData(report.timeline.len()));
+    }
+    ensure_font();
+
+    let filters: Vec<&str> = report.integration_by_filter().into_keys().collect();
+    let color_of = |filter: &str| {
+        let index = filters.iter().position(|f| *f == filter).unwrap_or(0);
+        FILTER_COLORS[index % FILTER_COLORS.len()]
+    };
+    let span = report
+        .timeline
+        .iter()
+        .map(|frame| frame.minutes)
+        .fold(1.0_f64, f64::max);
+    let start = report.start;
+    let clock = |minutes: &f64| match start {
+        Some(start) => (start + chrono::Duration::seconds((minutes * 60.0) as i64))
+            .format("%H:%M")
+            .to_string(),
+        None => format!("{minutes:.0}"),
+    };
+    let mut kinds: Vec<MarkerKind> = report.markers.iter().map(|m| m.kind).collect();
+    kinds.sort();
+    kinds.dedup();
+
+    let mut buffer = vec![0u8; (WIDTH * TRENDS_HEIGHT * 3) as usize];
+    {
+        let root =
+            BitMapBackend::with_buffer(&mut buffer, (WIDTH, TRENDS_HEIGHT)).into_drawing_area();
+        root.fill(&BACKGROUND)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let title = match report.night {
+            Some(night) => format!("Frame trends  —  night of {}", night.format("%Y-%m-%d")),
+            None => "Frame trends".to_string(),
+        };
+        let root = root
+            .titled(&title, ("sans-serif", 20).into_font().color(&TEXT))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let panels = root.split_evenly((4, 1));
+
+        let metrics: [(&str, FrameMetric); 4] = [
+            ("HFR", |frame| frame.hfr),
+            ("Stars", |frame| frame.stars),
+            ("Median (ADU)", |frame| frame.median),
+            ("Sensor (°C)", |frame| frame.temperature),
+        ];
+        for (index, (panel, (label, value))) in panels.iter().zip(metrics).enumerate() {
+            let points: Vec<(f64, f64, &str)> = report
+                .timeline
+                .iter()
+                .filter_map(|frame| {
+                    value(frame)
+                        .filter(|v| v.is_finite())
+                        .map(|v| (frame.minutes, v, frame.filter.as_str()))
+                })
+                .collect();
+            let (lo, hi) = points
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                    (lo.min(p.1), hi.max(p.1))
+                });
+            let (lo, hi) = if lo.is_finite() && hi > lo {
+                let pad = (hi - lo) * 0.1;
+                (lo - pad, hi + pad)
+            } else if lo.is_finite() {
+                (lo - 1.0, lo + 1.0)
+            } else {
+                (0.0, 1.0)
+            };
+            let last = index == metrics.len() - 1;
+            let mut chart = ChartBuilder::on(panel)
+                .caption(label, ("sans-serif", 15).into_font().color(&TEXT))
+                .margin(8)
+                .x_label_area_size(if last { 28 } else { 18 })
+                .y_label_area_size(52)
+                .build_cartesian_2d(0f64..span, lo..hi)
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+            chart
+                .configure_mesh()
+                .bold_line_style(GRID.mix(0.8))
+                .light_line_style(GRID.mix(0.3))
+                .axis_style(GRID)
+                .label_style(("sans-serif", 13).into_font().color(&TEXT))
+                .x_label_formatter(&clock)
+                .draw()
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+
+            // Markers first so the frames draw on top of them; `kinds` is
+            // sorted so dithers go underneath the rest.
+            for &kind in &kinds {
+                let style = marker_style(kind);
+                let series = chart
+                    .draw_series(
+                        report
+                            .markers
+                            .iter()
+                            .filter(|marker| marker.kind == kind)
+                            .map(|marker| {
+                                PathElement::new(
+                                    vec![(marker.minutes, lo), (marker.minutes, hi)],
+                                    style,
+                                )
+                            }),
+                    )
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+                if index == 0 {
+                    series
+                        .label(kind.label())
+                        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 16, y)], style));
+                }
+            }
+            for filter in &filters {
+                let color = color_of(filter);
+                let series = chart
+                    .draw_series(
+                        points
+                            .iter()
+                            .filter(|p| p.2 == *filter)
+                            .map(|&(x, y, _)| Circle::new((x, y), 3, color.filled())),
+                    )
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+                if index == 0 {
+                    series
+                        .label(*filter)
+                        .legend(move |(x, y)| Circle::new((x + 8, y), 4, color.filled()));
+                }
+            }
+            if index == 0 {
+                chart
+                    .configure_series_labels()
+                    .position(SeriesLabelPosition::UpperRight)
+                    .background_style(BACKGROUND.mix(0.8))
+                    .border_style(GRID)
+                    .label_font(("sans-serif", 12).into_font().color(&TEXT))
+                    .draw()
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+            }
+        }
+
+        root.present()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+    }
+
+    encode_png(&buffer, WIDTH, TRENDS_HEIGHT)
+}
+
+/// Render a target's integration progress as one horizontal bar per
+/// filter: acquired hours solid, the rest of the planned total faint, and
+/// "acquired / planned" at the end of each bar. Filters without a plan
+/// show the acquired part alone. Fails when there is nothing to show.
+pub fn render_integration_progress_png(
+    progress: &IntegrationProgress,
+) -> Result<Vec<u8>, ChartError> {
+    if progress.is_empty() {
+        return Err(ChartError::NotEnoughData(0));
+    }
+    ensure_font();
+
+    let rows = progress.filters.len();
+    let height = 110 + PROGRESS_ROW_HEIGHT * rows as u32;
+    let hours = |seconds: f64| seconds / 3600.0;
+    let longest = progress
+        .filters
+        .iter()
+        .map(|f| hours(f.seconds.max(f.planned_seconds.unwrap_or(0.0))))
+        .fold(0.25_f64, f64::max);
+    let label_of = |index: usize| {
+        let f = &progress.filters[index];
+        let acquired = format!("{:.1}h", hours(f.seconds));
+        match (f.planned_seconds, f.fraction()) {
+            (Some(planned), Some(fraction)) => format!(
+                "{acquired} / {:.1}h  ({:.0}%)",
+                hours(planned),
+                fraction * 100.0
+            ),
+            _ => format!("{acquired}  ({} frames)", f.frames),
+        }
+    };
+    let title = match progress.planned_seconds() {
+        Some(planned) => format!(
+            "{}  —  {:.1}h acquired · {:.1}h planned",
+            progress.target,
+            hours(progress.seconds()),
+            hours(planned)
+        ),
+        None => format!(
+            "{}  —  {:.1}h acquired",
+            progress.target,
+            hours(progress.seconds())
+        ),
+    };
+
+    let mut buffer = vec![0u8; (WIDTH * height * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, height)).into_drawing_area();
+        root.fill(&BACKGROUND)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        // Leave room right of the longest bar for its label.
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&title, ("sans-serif", 20).into_font().color(&TEXT))
+            .margin(12)
+            .x_label_area_size(32)
+            .y_label_area_size(64)
+            .build_cartesian_2d(0f64..longest * 1.45, 0f64..rows as f64)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .bold_line_style(GRID.mix(0.8))
+            .light_line_style(GRID.mix(0.3))
+            .axis_style(GRID)
+            .label_style(("sans-serif", 14).into_font().color(&TEXT))
+            .x_desc("Hours")
+            .y_labels(rows * 2 + 1)
+            .y_label_formatter(&|y| {
+                // Rows run top to bottom in filter order.
+                let index = y.floor() as usize;
+                if (y - index as f64 - 0.5).abs() < 0.01 && index < rows {
+                    progress.filters[rows - 1 - index].filter.clone()
+                } else {
+                    String::new()
+                }
+            })
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        for (index, f) in progress.filters.iter().enumerate() {
+            let color = FILTER_COLORS[index % FILTER_COLORS.len()];
+            let y = (rows - 1 - index) as f64;
+            let (top, bottom) = (y + 0.2, y + 0.8);
+            let acquired = hours(f.seconds);
+            let planned = f.planned_seconds.map(hours);
+            if let Some(planned) = planned.filter(|planned| *planned > acquired) {
+                chart
+                    .draw_series(std::iter::once(Rectangle::new(
+                        [(acquired, top), (planned, bottom)],
+                        color.mix(0.22).filled(),
+                    )))
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+                chart
+                    .draw_series(std::iter::once(Rectangle::new(
+                        [(acquired, top), (planned, bottom)],
+                        color.mix(0.6).stroke_width(1),
+                    )))
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+            }
+            chart
+                .draw_series(std::iter::once(Rectangle::new(
+                    [(0.0, top), (acquired, bottom)],
+                    color.mix(0.9).filled(),
+                )))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+            let end = acquired.max(planned.unwrap_or(0.0));
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    label_of(index),
+                    (end + longest * 0.02, y + 0.62),
+                    ("sans-serif", 14).into_font().color(&TEXT),
+                )))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+
+        root.present()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+    }
+
+    encode_png(&buffer, WIDTH, height)
+}
+
+/// Render the mount's position on an all-sky polar plot: zenith at the
+/// center, the horizon at the rim, north up and east left as seen looking
+/// up. Adds the meridian, `horizon`'s obstructed sky, and — when the mount
+/// knows its site — the track of `target` (or of wherever a tracking mount
+/// points) through the night with hour ticks and the meridian flip. Fails
+/// when the mount is disconnected or reports no alt/az.
+pub fn render_sky_position_png(
+    mount: &MountInfoResponse,
+    target: Option<(&str, &Equatorial)>,
+    horizon: Option<&HorizonProfile>,
+    now: DateTime<Utc>,
+) -> Result<Vec<u8>, ChartError> {
+    let info = &mount.response;
+    if !info.connected || !info.altitude.is_finite() || !info.azimuth.is_finite() {
+        return Err(ChartError::NotEnoughData(0));
+    }
+    ensure_font();
+
+    // Zenith distance as radius, azimuth north through east, east on the
+    // left.
+    let project = |altitude: f64, azimuth: f64| {
+        let r = 90.0 - altitude.clamp(-5.0, 90.0);
+        let azimuth = azimuth.to_radians();
+        (-r * azimuth.sin(), r * azimuth.cos())
+    };
+    let ring = |radius: f64| -> Vec<(f64, f64)> {
+        (0..=360)
+            .step_by(2)
+            .map(|azimuth| project(90.0 - radius, f64::from(azimuth)))
+            .collect()
+    };
+    let clears = |point: &TrackPoint| {
+        point.altitude > 0.0 && horizon.is_none_or(|h| h.clears(point.altitude, point.azimuth))
+    };
+
+    let site = Site::from_mount(mount);
+    let mount_position = (info.tracking_enabled
+        && !info.at_park
+        && info.right_ascension.is_finite()
+        && info.declination.is_finite())
+    .then_some(Equatorial {
+        ra_hours: info.right_ascension,
+        dec_degrees: info.declination,
+    });
+    let followed = target.or(mount_position.as_ref().map(|position| ("Mount", position)));
+    let flip_at = (info.time_to_meridian_flip.is_finite()
+        && info.time_to_meridian_flip > 0.0
+        && info.time_to_meridian_flip < 24.0)
+        .then(|| now + chrono::Duration::seconds((info.time_to_meridian_flip * 3600.0) as i64));
+    let (start, end) = site
+        .and_then(|site| Night::around(&site, now).span())
+        .map(|(dusk, dawn)| (dusk.min(now), dawn.max(now)))
+        .unwrap_or((
+            now - chrono::Duration::hours(6),
+            now + chrono::Duration::hours(6),
+        ));
+    let path = match (site, followed) {
+        (Some(site), Some((_, position))) => {
+            track(&site, position, start, end, chrono::Duration::minutes(5))
+        }
+        _ => Vec::new(),
+    };
+    // The first time after now the target drops behind the horizon.
+    let blocked_at = path
+        .windows(2)
+        .find(|pair| pair[1].at >= now && clears(&pair[0]) && !clears(&pair[1]))
+        .map(|pair| pair[1].at);
+
+    let title = match followed {
+        Some((name, _)) => format!(
+            "{name}  —  Alt {:.1}° · Az {:.1}°",
+            info.altitude, info.azimuth
+        ),
+        None => format!(
+            "Mount  —  Alt {:.1}° · Az {:.1}°",
+            info.altitude, info.azimuth
+        ),
+    };
+    let mut notes = Vec::new();
+    match info.side_of_pier.trim_start_matches("pier") {
+        "" | "Unknown" => {}
+        side => notes.push(format!("Pier {side}")),
+    }
+    if let Some(flip_at) = flip_at {
+        notes.push(format!(
+            "Flip {} ({})",
+            countdown(now, flip_at),
+            clock(flip_at)
+        ));
+    }
+    if let Some(horizon) = horizon {
+        notes.push(format!(
+            "{:+.0}° above horizon",
+            info.altitude - horizon.altitude_at(info.azimuth)
+        ));
+    }
+    if let Some(blocked_at) = blocked_at {
+        notes.push(format!(
+            "Behind horizon {} ({})",
+            countdown(now, blocked_at),
+            clock(blocked_at)
+        ));
+    }
+
+    let mut buffer = vec![0u8; (WIDTH * SKY_HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, SKY_HEIGHT)).into_drawing_area();
+        root.fill(&BACKGROUND)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        let area = root
+            .titled(&title, ("sans-serif", 20).into_font().color(&TEXT))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        // Equal scales on both axes keep the circles round.
+        let margin = 16;
+        let (width, height) = area.dim_in_pixel();
+        let (width, height) = (
+            f64::from(width - 2 * margin),
+            f64::from(height - 2 * margin),
+        );
+        let y_range = 100.0;
+        let x_range = y_range * width / height;
+        let unit = height / (2.0 * y_range);
+        let mut chart = ChartBuilder::on(&area)
+            .margin(margin)
+            .build_cartesian_2d(-x_range..x_range, -y_range..y_range)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        chart
+            .draw_series(std::iter::once(Polygon::new(
+                ring(90.0),
+                SKY_COLOR.filled(),
+            )))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        for azimuth in (0..360).step_by(45) {
+            chart
+                .draw_series(LineSeries::new(
+                    [(0.0, 0.0), project(0.0, f64::from(azimuth))],
+                    GRID.mix(0.5),
+                ))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+        for altitude in [30.0, 60.0] {
+            chart
+                .draw_series(LineSeries::new(ring(90.0 - altitude), GRID.mix(0.8)))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    format!("{altitude:.0}°"),
+                    project(altitude, 200.0),
+                    ("sans-serif", 12).into_font().color(&TEXT.mix(0.7)),
+                )))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+
+        if let Some(horizon) = horizon {
+            let profile: Vec<(f64, f64)> = (0..=360)
+                .step_by(2)
+                .map(|azimuth| {
+                    let azimuth = f64::from(azimuth);
+                    project(horizon.altitude_at(azimuth).max(0.0), azimuth)
+                })
+                .collect();
+            let mut obstruction = ring(90.0);
+            obstruction.extend(profile.iter().rev());
+            chart
+                .draw_series(std::iter::once(Polygon::new(
+                    obstruction,
+                    OBSTRUCTION_COLOR.mix(0.75).filled(),
+                )))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+            chart
+                .draw_series(LineSeries::new(profile, OBSTRUCTION_COLOR.stroke_width(2)))
+                .map_err(|e| ChartError::Render(e.to_string()))?
+                .label("Local horizon")
+                .legend(|(x, y)| {
+                    Rectangle::new([(x, y - 5), (x + 16, y + 5)], OBSTRUCTION_COLOR.filled())
+                });
+        }
+        chart
+            .draw_series(LineSeries::new(ring(90.0), GRID.stroke_width(2)))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        for (label, azimuth) in [("N", 0.0), ("E", 90.0), ("S", 180.0), ("W", 270.0)] {
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    label,
+                    project(-5.0, azimuth),
+                    ("sans-serif", 16)
+                        .into_font()
+                        .color(&TEXT)
+                        .pos(Pos::new(HPos::Center, VPos::Center)),
+                )))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+
+        chart
+            .draw_series(LineSeries::new(
+                [project(0.0, 0.0), project(0.0, 180.0)],
+                MERIDIAN_COLOR.mix(0.8).stroke_width(2),
+            ))
+            .map_err(|e| ChartError::Render(e.to_string()))?
+            .label("Meridian")
+            .legend(|(x, y)| {
+                PathElement::new([(x, y), (x + 16, y)], MERIDIAN_COLOR.stroke_width(2))
+            });
+
+        if let (Some(site), Some((name, position))) = (site, followed) {
+            // Split the track into clear and obstructed runs, sharing the
+            // point where it changes so the line stays continuous.
+            let mut runs: Vec<(bool, Vec<(f64, f64)>)> = Vec::new();
+            for point in path.iter().filter(|point| point.altitude > 0.0) {
+                let clear = clears(point);
+                let xy = project(point.altitude, point.azimuth);
+                match runs.last_mut() {
+                    Some((state, points)) if *state == clear => points.push(xy),
+                    Some((_, points)) => {
+                        let joint = *points.last().expect("runs are never empty");
+                        runs.push((clear, vec![joint, xy]));
+                    }
+                    None => runs.push((clear, vec![xy])),
+                }
+            }
+            let mut labelled = false;
+            for (clear, points) in runs {
+                let style = if clear {
+                    HFR_COLOR.stroke_width(2)
+                } else {
+                    DEC_COLOR.mix(0.6).stroke_width(2)
+                };
+                let series = chart
+                    .draw_series(LineSeries::new(points, style))
+                    .map_err(|e| ChartError::Render(e.to_string()))?;
+                if clear && !labelled {
+                    labelled = true;
+                    series.label(format!("{name} tonight")).legend(|(x, y)| {
+                        PathElement::new([(x, y), (x + 16, y)], HFR_COLOR.stroke_width(2))
+                    });
+                }
+            }
+
+            // Ticks on the local whole hours.
+            let mut hour = start
+                .with_timezone(&chrono::Local)
+                .duration_trunc(chrono::Duration::hours(1))
+                .map_err(|e| ChartError::Render(e.to_string()))?
+                .with_timezone(&Utc)
+                + chrono::Duration::hours(1);
+            while hour <= end {
+                let (altitude, azimuth) = horizontal(&site, position, hour);
+                if altitude > 0.0 {
+                    let (x, y) = project(altitude, azimuth);
+                    chart
+                        .draw_series([EmptyElement::at((x, y))
+                            + Circle::new((0, 0), 3, TEXT.filled())
+                            + Text::new(
+                                hour.with_timezone(&chrono::Local).format("%H").to_string(),
+                                (5, -14),
+                                ("sans-serif", 12).into_font().color(&TEXT),
+                            )])
+                        .map_err(|e| ChartError::Render(e.to_string()))?;
+                }
+                hour += chrono::Duration::hours(1);
+            }
+
+            if let Some(flip_at) = flip_at.filter(|at| (start..=end).contains(at)) {
+                let (altitude, azimuth) = horizontal(&site, position, flip_at);
+                if altitude > 0.0 {
+                    chart
+                        .draw_series([EmptyElement::at(project(altitude, azimuth))
+                            + Cross::new((0, 0), 7, MERIDIAN_COLOR.stroke_width(3))
+                            + Text::new(
+                                format!("Flip {}", clock(flip_at)),
+                                (9, 4),
+                                ("sans-serif", 13).into_font().color(&MERIDIAN_COLOR),
+                            )])
+                        .map_err(|e| ChartError::Render(e.to_string()))?;
+                }
+            }
+        }
+
+        chart
+            .draw_series(std::iter::once(Circle::new(
+                project(info.altitude, info.azimuth),
+                8,
+                FOCUS_COLOR.filled(),
+            )))
+            .map_err(|e| ChartError::Render(e.to_string()))?
+            .label(format!(
+                "Mount  Alt {:.0}° · Az {:.0}°",
+                info.altitude, info.azimuth
+            ))
+            .legend(|(x, y)| Circle::new((x + 8, y), 5, FOCUS_COLOR.filled()));
+        chart
+            .draw_series(std::iter::once(Circle::new(
+                project(info.altitude, info.azimuth),
+                8,
+                TEXT.stroke_width(2),
+            )))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        let line = 20.0 / unit;
+        for (index, note) in notes.iter().enumerate() {
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    note.clone(),
+                    (x_range, y_range - index as f64 * line),
+                    ("sans-serif", 15)
+                        .into_font()
+                        .color(&TEXT)
+                        .pos(Pos::new(HPos::Right, VPos::Top)),
+                )))
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(BACKGROUND.mix(0.85))
+            .border_style(GRID)
+            .label_font(("sans-serif", 14).into_font().color(&TEXT))
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        root.present()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+    }
+
+    encode_png(&buffer, WIDTH, SKY_HEIGHT)
+}
+
+/// Render the RA error's amplitude against period on a log axis, the
+/// dominant periodic error marked. Fails when the history was too short
+/// for a spectrum.
+pub fn render_guiding_spectrum_png(analysis: &GuidingAnalysis) -> Result<Vec<u8>, ChartError> {
+    if analysis.spectrum.len() < 2 {
+        return Err(ChartError::NotEnoughData(analysis.steps));
+    }
+    ensure_font();
+
+    // Ascending period for the log axis.
+    let bins: Vec<(f64, f64)> = analysis
+        .spectrum
+        .iter()
+        .rev()
+        .map(|bin| (bin.period_seconds, bin.amplitude))
+        .filter(|(period, amplitude)| period.is_finite() && amplitude.is_finite())
+        .collect();
+    let shortest = bins.first().map_or(1.0, |bin| bin.0);
+    let longest = bins.last().map_or(2.0, |bin| bin.0).max(shortest * 2.0);
+    let peak = bins.iter().map(|bin| bin.1).fold(0.0_f64, f64::max);
+    let top = if peak > 0.0 { peak * 1.25 } else { 1.0 };
+    let title = format!(
+        "RA error spectrum  —  RMS RA {} · Dec {}",
+        analysis.amount(analysis.ra_rms),
+        analysis.amount(analysis.dec_rms)
+    );
+
+    let mut buffer = vec![0u8; (WIDTH * HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, HEIGHT)).into_drawing_area();
+        root.fill(&BACKGROUND)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(&title, ("sans-serif", 20).into_font().color(&TEXT))
+            .margin(12)
+            .x_label_area_size(36)
+            .y_label_area_size(56)
+            .build_cartesian_2d((shortest..longest).log_scale(), 0f64..top)
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        chart
+            .configure_mesh()
+            .bold_line_style(GRID.mix(0.8))
+            .light_line_style(GRID.mix(0.3))
+            .axis_style(GRID)
+            .label_style(("sans-serif", 14).into_font().color(&TEXT))
+            .x_desc(format!(
+                "Period (s, at {:.1} s per guide step)",
+                analysis.cadence_seconds
+            ))
+            .y_desc(format!("Amplitude ({})", analysis.unit))
+            .x_label_formatter(&|period| format!("{period:.0}"))
+            .draw()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        let mut area = bins.clone();
+        if let (Some(first), Some(last)) = (bins.first(), bins.last()) {
+            area.extend([(last.0, 0.0), (first.0, 0.0)]);
+        }
+        chart
+            .draw_series(std::iter::once(Polygon::new(
+                area,
+                RA_COLOR.mix(0.25).filled(),
+            )))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+        chart
+            .draw_series(LineSeries::new(
+                bins.iter().copied(),
+                RA_COLOR.stroke_width(2),
+            ))
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+
+        if let Some(pe) = &analysis.periodic_error {
+            chart
+                .draw_series([EmptyElement::at((pe.period_seconds, pe.amplitude))
+                    + Cross::new((0, 0), 7, DEC_COLOR.stroke_width(3))
+                    + Text::new(
+                        format!(
+                            "±{} every {:.0} s",
+                            analysis.amount(pe.amplitude),
+                            pe.period_seconds
+                        ),
+                        (10, -20),
+                        ("sans-serif", 15).into_font().color(&TEXT),
+                    )])
+                .map_err(|e| ChartError::Render(e.to_string()))?;
+        }
+
+        root.present()
+            .map_err(|e| ChartError::Render(e.to_string()))?;
+    }
+
+    encode_png(&buffer, WIDTH, HEIGHT)
+}
+
 /// Split a series into runs of consecutive finite samples, keeping the
 /// original indices so gaps stay gaps on the x axis.
 fn contiguous_finite_runs(values: &[f64]) -> Vec<Vec<(usize, f64)>> {
@@ -409,7 +1536,7 @@ fn contiguous_finite_runs(values: &[f64]) -> Vec<Vec<(usize, f64)>> {
     runs
 }
 
-fn encode_png(rgb: &[u8], width: u32, height: u32) -> Result<Vec<u8>, ChartError> {
+pub(crate) fn encode_png(rgb: &[u8], width: u32, height: u32) -> Result<Vec<u8>, ChartError> {
     let mut png = Vec::new();
     {
         let mut encoder = png::Encoder::new(&mut png, width, height);
@@ -547,6 +1674,140 @@ mod tests {
         assert!((after - 2.90813054456021).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_render_autofocus_graph_with_asymmetric_fit() {
+        // Hocus Focus writes its hyperbola with "√" and "²", and leaves the
+        // quadratic and Gaussian empty.
+        let json = std::fs::read_to_string("example_last_af_2.json").unwrap();
+        let parsed: crate::autofocus::AutofocusResponse = serde_json::from_str(&json).unwrap();
+        let png = render_autofocus_graph_png(&parsed.response).unwrap();
+        assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+    }
+
+    #[test]
+    fn test_render_autofocus_temperature() {
+        let run = |filter: &str, temperature: f64, position: i32| {
+            let mut af = sample_autofocus();
+            af.filter = filter.to_string();
+            af.temperature = temperature;
+            af.calculated_focus_point.position = position;
+            af
+        };
+        let runs = vec![
+            run("Ha", 14.0, 4100),
+            run("OIII", 13.0, 4150),
+            run("Ha", 12.0, 4120),
+            run("Ha", 10.5, 4135),
+        ];
+        let png = render_autofocus_temperature_png(&runs).unwrap();
+        assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+        assert!(png.len() > 1000);
+
+        // Two runs draw without a slope; one is not a trend.
+        assert!(render_autofocus_temperature_png(&runs[..2]).is_ok());
+        assert!(matches!(
+            render_autofocus_temperature_png(&runs[..1]),
+            Err(ChartError::NotEnoughData(1))
+        ));
+    }
+
+    #[test]
+    fn test_render_session_report() {
+        let images = crate::test_support::sample_night();
+        let report = SessionReport::build(&images, &[], Some("M31"));
+        let png = render_session_report_png(&report).unwrap();
+        assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+
+        assert!(matches!(
+            render_session_report_png(&SessionReport::default()),
+            Err(ChartError::NotEnoughData(0))
+        ));
+    }
+
+    #[test]
+    fn test_render_session_trends() {
+        let images = crate::test_support::sample_night();
+        let events = crate::test_support::sample_events();
+        let png = render_session_trends_png(&images, &events).unwrap();
+        assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+        assert!(png.len() > 1000);
+
+        let mut single = images;
+        single.response.truncate(4);
+        assert!(matches!(
+            render_session_trends_png(&single, &events),
+            Err(ChartError::NotEnoughData(1))
+        ));
+    }
+
+    #[test]
+    fn test_render_integration_progress() {
+        let images = crate::test_support::sample_night();
+        let sequence: crate::sequence::SequenceResponse =
+            serde_json::from_str(&std::fs::read_to_string("example_sequence_2.json").unwrap())
+                .unwrap();
+        let progress = IntegrationProgress::build("North American", &images, &[], Some(&sequence));
+        let png = render_integration_progress_png(&progress).unwrap();
+        assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+
+        assert!(matches!(
+            render_integration_progress_png(&IntegrationProgress::default()),
+            Err(ChartError::NotEnoughData(0))
+        ));
+    }
+
+    #[test]
+    fn test_render_sky_position() {
+        let mut mount: MountInfoResponse = serde_json::from_str(
+            &std::fs::read_to_string("example_equipment_mount_info.json").unwrap(),
+        )
+        .unwrap();
+        let horizon = HorizonProfile::parse("0 15\n90 40\n180 10\n270 30\n").unwrap();
+        let target = Equatorial {
+            ra_hours: 20.69,
+            dec_degrees: 45.28,
+        };
+        let now = chrono::DateTime::parse_from_rfc3339("2025-08-09T07:20:08Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        for (target, horizon) in [(Some(("Sadr", &target)), Some(&horizon)), (None, None)] {
+            let png = render_sky_position_png(&mount, target, horizon, now).unwrap();
+            assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+        }
+
+        mount.response.connected = false;
+        assert!(matches!(
+            render_sky_position_png(&mount, None, None, now),
+            Err(ChartError::NotEnoughData(0))
+        ));
+    }
+
+    #[test]
+    fn test_render_guiding_spectrum() {
+        let steps: Vec<_> = (0..128)
+            .map(|i| {
+                let mut step = sample_history().guide_steps[0].clone();
+                step.ra_distance_raw_display = (i as f64 / 32.0 * std::f64::consts::TAU).sin();
+                step.dec_distance_raw_display = 0.1;
+                step.dither = serde_json::json!("NO");
+                step
+            })
+            .collect();
+        let mut history = sample_history();
+        history.guide_steps = steps;
+        let analysis = GuidingAnalysis::of(&history, 2.0).unwrap();
+        assert!(analysis.periodic_error.is_some());
+        let png = render_guiding_spectrum_png(&analysis).unwrap();
+        assert_eq!(&png[..4], &[0x89, b'P', b'N', b'G']);
+
+        history.guide_steps.truncate(8);
+        let short = GuidingAnalysis::of(&history, 2.0).unwrap();
+        assert!(matches!(
+            render_guiding_spectrum_png(&short),
+            Err(ChartError::NotEnoughData(8))
+        ));
+    }
+
     #[test]
     fn test_contiguous_finite_runs() {
         let runs = contiguous_finite_runs(&[1.0, 2.0, f64::NAN, 3.0]);
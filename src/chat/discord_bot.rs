@@ -9,14 +9,29 @@
 //!
 //! Read-only slash commands (Phase 1):
 //!   /status, /sequence, /target, /mount, /filter, /focus, /guider,
-//!   /events, /last-image.
+//!   /events, /last-image, /report.
+//!
+//! Outbound posts may carry action buttons: "Run autofocus" on an
+//! image-quality alert is authorized and confirmed like the matching write
+//! command, and "Acknowledge" on a critical alert is open to anyone who can see the
+//! telescope.
 
 use super::rig_resolver::{CommandContext, RigResolver};
-use super::status_state::{StatusMessage, StatusState};
-use super::{ChatAttachment, ChatMessage, ChatService, ChatTarget, DiscordBotConfig};
+use super::status_state::{StatusMessage, StatusState, session_thread_key};
+use super::{
+    Acknowledgement, AcknowledgementSender, ChatAction, ChatAttachment, ChatMessage, ChatService,
+    ChatTarget, DiscordBotConfig,
+};
+use crate::ephemeris::{
+    DEFAULT_ALTITUDE_LIMIT, Equatorial, MoonInfo, Night, Site, Visibility, twilight_summary,
+};
 use crate::error::ChatError;
+use crate::guide_analysis::{DEFAULT_GUIDE_CADENCE_SECONDS, GuidingAnalysis};
 use crate::sequence::{SequenceOperation, SequenceOperationKind};
-use crate::source::{RigCommand, SharedRigSource};
+use crate::source::{
+    DeviceState, FocuserTarget, RigCapabilities, RigCommand, RigCommandKind, RigDevice,
+    SharedRigSource, TrackingMode,
+};
 use async_trait::async_trait;
 use poise::serenity_prelude::{self as serenity, CreateAttachment, CreateMessage};
 use std::path::PathBuf;
@@ -29,6 +44,8 @@ use tokio::sync::Mutex;
 /// (static config) and the hub (database-backed tenancy).
 pub struct BotData {
     pub resolver: Arc<dyn RigResolver>,
+    /// Where Acknowledge clicks go; every chat updater listens.
+    pub acknowledgements: AcknowledgementSender,
 }
 
 pub type BotError = Box<dyn std::error::Error + Send + Sync>;
@@ -51,6 +68,13 @@ pub struct DiscordBotService {
     live_status: bool,
 }
 
+/// One channel a post goes to, with the telescope's open session thread
+/// there and its state key.
+struct Destination {
+    channel: serenity::ChannelId,
+    thread: Option<(serenity::ChannelId, String)>,
+}
+
 impl DiscordBotService {
     pub fn new(
         http: Arc<serenity::Http>,
@@ -82,15 +106,45 @@ impl DiscordBotService {
         channels.into_iter().map(serenity::ChannelId::new).collect()
     }
 
+    /// Where this target's posts go: each channel, or the telescope's
+    /// session thread in it when the target uses threads and one is open.
+    async fn destinations(&self, target: &ChatTarget) -> Vec<Destination> {
+        let channels = self.resolve_channels(target);
+        let Some(telescope) = &target.session_thread else {
+            return channels
+                .into_iter()
+                .map(|channel| Destination {
+                    channel,
+                    thread: None,
+                })
+                .collect();
+        };
+        let state = self.status_state.lock().await;
+        channels
+            .into_iter()
+            .map(|channel| {
+                let key = session_thread_key(telescope, channel.get());
+                Destination {
+                    channel,
+                    thread: state
+                        .session_thread(&key)
+                        .map(|thread| (serenity::ChannelId::new(thread), key)),
+                }
+            })
+            .collect()
+    }
+
     /// Send one prepared payload to every destination. A single broken
     /// channel is logged and skipped; the send fails only when every
-    /// destination failed.
+    /// destination failed. A session thread that rejects the post (deleted,
+    /// or locked by a moderator) is forgotten and the post goes to its
+    /// channel instead.
     async fn fan_out(
         &self,
-        channels: Vec<serenity::ChannelId>,
+        destinations: Vec<Destination>,
         build: impl Fn() -> CreateMessage,
     ) -> Result<(), ChatError> {
-        if channels.is_empty() {
+        if destinations.is_empty() {
             return Err(ChatError::Discord {
                 message: "No Discord channel available (no default and no telescope override)"
                     .to_string(),
@@ -98,7 +152,23 @@ impl DiscordBotService {
         }
         let mut delivered = 0;
         let mut last_error = None;
-        for channel in channels {
+        for Destination { channel, thread } in destinations {
+            if let Some((thread, key)) = thread {
+                match thread.send_message(&self.http, build()).await {
+                    Ok(_) => {
+                        delivered += 1;
+                        continue;
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: post to session thread {thread} failed: {e} — posting to channel {channel}"
+                        );
+                        let mut state = self.status_state.lock().await;
+                        state.remove_session_thread(&key);
+                        self.persist(&state);
+                    }
+                }
+            }
             match channel.send_message(&self.http, build()).await {
                 Ok(_) => delivered += 1,
                 Err(e) => {
@@ -115,6 +185,32 @@ impl DiscordBotService {
         }
     }
 
+    /// The embed, plus any mention as message content so it notifies and
+    /// any actions as buttons.
+    fn build_message(message: &ChatMessage) -> CreateMessage {
+        let mut payload = CreateMessage::new().embed(Self::build_embed(message));
+        if let Some(mention) = &message.mention {
+            payload = payload.content(mention);
+        }
+        // Discord rejects component IDs over 100 characters; a telescope
+        // name that long just goes without the button.
+        let buttons: Vec<serenity::CreateButton> = message
+            .actions
+            .iter()
+            .map(|action| (action, action.custom_id()))
+            .filter(|(_, id)| id.len() <= 100)
+            .map(|(action, id)| {
+                serenity::CreateButton::new(id)
+                    .label(action.label())
+                    .style(serenity::ButtonStyle::Primary)
+            })
+            .collect();
+        if !buttons.is_empty() {
+            payload = payload.components(vec![serenity::CreateActionRow::Buttons(buttons)]);
+        }
+        payload
+    }
+
     fn build_embed(message: &ChatMessage) -> serenity::CreateEmbed {
         let mut embed = serenity::CreateEmbed::new().title(&message.title);
         if let Some(color) = message.color {
@@ -142,8 +238,8 @@ impl ChatService for DiscordBotService {
         message: &ChatMessage,
         target: &ChatTarget,
     ) -> Result<(), ChatError> {
-        self.fan_out(self.resolve_channels(target), || {
-            CreateMessage::new().embed(Self::build_embed(message))
+        self.fan_out(self.destinations(target).await, || {
+            Self::build_message(message)
         })
         .await
     }
@@ -155,9 +251,8 @@ impl ChatService for DiscordBotService {
         image_data: &[u8],
         filename: &str,
     ) -> Result<(), ChatError> {
-        self.fan_out(self.resolve_channels(target), || {
-            CreateMessage::new()
-                .embed(Self::build_embed(message))
+        self.fan_out(self.destinations(target).await, || {
+            Self::build_message(message)
                 .add_file(CreateAttachment::bytes(image_data.to_vec(), filename))
         })
         .await
@@ -172,8 +267,8 @@ impl ChatService for DiscordBotService {
         if attachments.is_empty() {
             return self.send_message(message, target).await;
         }
-        self.fan_out(self.resolve_channels(target), || {
-            let mut payload = CreateMessage::new().embed(Self::build_embed(message));
+        self.fan_out(self.destinations(target).await, || {
+            let mut payload = Self::build_message(message);
             for attachment in attachments {
                 payload = payload.add_file(CreateAttachment::bytes(
                     attachment.data.clone(),
@@ -235,6 +330,51 @@ impl ChatService for DiscordBotService {
             _ => Ok(()),
         }
     }
+
+    /// Open a thread in every channel of the target from a pointer post.
+    /// A channel where that fails drops its old thread, so the new
+    /// session's posts land in the channel rather than last night's thread.
+    async fn start_session_thread(
+        &self,
+        target: &ChatTarget,
+        name: &str,
+        pointer: &ChatMessage,
+    ) -> Result<(), ChatError> {
+        let Some(telescope) = &target.session_thread else {
+            return Ok(());
+        };
+        let channels = self.resolve_channels(target);
+        if channels.is_empty() {
+            return Err(ChatError::Discord {
+                message: "No Discord channel available for a session thread".to_string(),
+            });
+        }
+        // Discord caps thread names at 100 characters.
+        let name: String = name.chars().take(100).collect();
+        let mut opened = 0;
+        let mut last_error = None;
+        for channel in channels {
+            let key = session_thread_key(telescope, channel.get());
+            let thread = self.open_session_thread_in(channel, &name, pointer).await;
+            let mut state = self.status_state.lock().await;
+            match thread {
+                Ok(thread) => {
+                    state.set_session_thread(&key, thread.get());
+                    opened += 1;
+                }
+                Err(e) => {
+                    eprintln!("Warning: session thread in channel {channel} failed: {e}");
+                    state.remove_session_thread(&key);
+                    last_error = Some(e);
+                }
+            }
+            self.persist(&state);
+        }
+        match (opened, last_error) {
+            (0, Some(e)) => Err(e),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl DiscordBotService {
@@ -304,13 +444,41 @@ impl DiscordBotService {
                 message_id: posted.id.get(),
             },
         );
+        self.persist(&state);
+        Ok(())
+    }
+
+    /// Post `pointer` in the channel and open a thread from it.
+    async fn open_session_thread_in(
+        &self,
+        channel: serenity::ChannelId,
+        name: &str,
+        pointer: &ChatMessage,
+    ) -> Result<serenity::ChannelId, ChatError> {
+        let posted = channel
+            .send_message(&self.http, Self::build_message(pointer))
+            .await
+            .map_err(|e| ChatError::Discord {
+                message: format!("session thread pointer failed: {e}"),
+            })?;
+        let thread = serenity::CreateThread::new(name)
+            .auto_archive_duration(serenity::AutoArchiveDuration::OneDay);
+        channel
+            .create_thread_from_message(&self.http, posted.id, thread)
+            .await
+            .map(|thread| thread.id)
+            .map_err(|e| ChatError::Discord {
+                message: format!("create_thread failed: {e}"),
+            })
+    }
+
+    fn persist(&self, state: &StatusState) {
         if let Err(e) = state.save(&self.state_file) {
             eprintln!(
                 "Warning: failed to persist status state to {}: {e}",
                 self.state_file.display()
             );
         }
-        Ok(())
     }
 }
 
@@ -320,26 +488,24 @@ impl DiscordBotService {
 /// framework + Phase 1 commands, spawns the gateway loop, and returns the
 /// service handle (which holds `Arc<Http>`) plus the join handle for the
 /// gateway task. The join handle is kept by the caller so the service stays
-/// alive for the life of the process.
+/// alive for the life of the process. `status_state` is loaded from
+/// `bot_config.state_file` and may be shared with the webhook service.
 pub async fn run_bot(
     bot_config: &DiscordBotConfig,
     resolver: Arc<dyn RigResolver>,
+    acknowledgements: AcknowledgementSender,
+    status_state: Arc<Mutex<StatusState>>,
 ) -> Result<(DiscordBotService, tokio::task::JoinHandle<()>), ChatError> {
     let token = bot_config.token.clone();
     let default_channel_id = bot_config.default_channel_id;
     let state_file = PathBuf::from(&bot_config.state_file);
-    let status_state = StatusState::load(&state_file).unwrap_or_else(|e| {
-        eprintln!(
-            "Warning: could not load status state from {}: {e} — starting fresh",
-            state_file.display()
-        );
-        StatusState::default()
-    });
-    let status_state = Arc::new(Mutex::new(status_state));
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
             commands: phase1_commands(),
+            event_handler: |ctx, event, _framework, data| {
+                Box::pin(handle_gateway_event(ctx, event, data))
+            },
             ..Default::default()
         })
         .setup(move |ctx, ready, framework| {
@@ -353,7 +519,10 @@ pub async fn run_bot(
                 poise::builtins::register_globally(ctx, &framework.options().commands)
                     .await
                     .map_err(|e| -> BotError { Box::new(e) })?;
-                Ok(BotData { resolver })
+                Ok(BotData {
+                    resolver,
+                    acknowledgements,
+                })
             })
         })
         .build();
@@ -397,12 +566,16 @@ pub async fn run_bot(
         "status",
         "sequence",
         "target",
+        "night",
         "mount",
         "filter",
         "focus",
         "guider",
         "events",
         "last_image",
+        "report",
+        "trends",
+        "progress",
         // Write (ACL-gated; destructive ones require confirmation)
         "park",
         "unpark",
@@ -416,6 +589,20 @@ pub async fn run_bot(
         "abort_capture",
         "stop_sequence",
         "start_sequence",
+        "slew",
+        "tracking",
+        "stop_slew",
+        "focuser_move",
+        "focuser_tempcomp",
+        "rotator_move",
+        "snapshot",
+        "dome_shutter",
+        "dome_park",
+        "dome_slave",
+        "flat_light",
+        "flat_brightness",
+        "flat_cover",
+        "switch",
     )
 )]
 async fn chatstronomy(_ctx: Context<'_>) -> Result<(), BotError> {
@@ -456,6 +643,213 @@ async fn command_context(ctx: Context<'_>) -> CommandContext {
     }
 }
 
+/// Buttons on outbound posts arrive as component interactions rather than
+/// commands. The confirm prompts' own buttons are collected by the command
+/// that posted them and are ignored here.
+async fn handle_gateway_event(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    data: &BotData,
+) -> Result<(), BotError> {
+    let serenity::FullEvent::InteractionCreate {
+        interaction: serenity::Interaction::Component(component),
+    } = event
+    else {
+        return Ok(());
+    };
+    let Some(action) = ChatAction::parse(&component.data.custom_id) else {
+        return Ok(());
+    };
+    // Acknowledge first: the rig round trip can outlast Discord's 3s window.
+    component
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::Defer(
+                serenity::CreateInteractionResponseMessage::new().ephemeral(true),
+            ),
+        )
+        .await?;
+    let invocation = component_context(ctx, component);
+    let action = action.with_telescope_from(&data.resolver.telescope_names(&invocation));
+    // Actions that actuate the rig are authorized before the prompt, so
+    // nobody confirms a click that would be refused, and again when they run.
+    let confirmed = match action.confirmation() {
+        Some(prompt) => match authorize_action(data, &invocation, &action) {
+            Err(msg) => {
+                component
+                    .create_followup(
+                        &ctx.http,
+                        serenity::CreateInteractionResponseFollowup::new()
+                            .content(msg)
+                            .ephemeral(true),
+                    )
+                    .await?;
+                return Ok(());
+            }
+            Ok(()) => {
+                if !confirm_click(ctx, component, &prompt).await? {
+                    return Ok(());
+                }
+                true
+            }
+        },
+        None => false,
+    };
+    let user = component
+        .user
+        .global_name
+        .clone()
+        .unwrap_or_else(|| component.user.name.clone());
+    let content = run_action(data, &invocation, &user, action, confirmed).await;
+    component
+        .create_followup(
+            &ctx.http,
+            serenity::CreateInteractionResponseFollowup::new()
+                .content(content)
+                .ephemeral(true),
+        )
+        .await?;
+    Ok(())
+}
+
+/// [`command_context`] for a button click.
+fn component_context(
+    ctx: &serenity::Context,
+    component: &serenity::ComponentInteraction,
+) -> CommandContext {
+    let (role_ids, member_manages) = match &component.member {
+        Some(member) => {
+            let manages = member.permissions.is_some_and(|permissions| {
+                permissions.administrator() || permissions.manage_guild()
+            });
+            (member.roles.iter().map(|r| r.get()).collect(), manages)
+        }
+        None => (Vec::new(), false),
+    };
+    let is_owner = component
+        .guild_id
+        .and_then(|guild_id| ctx.cache.guild(guild_id).map(|guild| guild.owner_id))
+        .is_some_and(|owner_id| owner_id == component.user.id);
+    CommandContext {
+        guild_id: component.guild_id.map(|g| g.get()),
+        channel_id: component.channel_id.get(),
+        user_id: component.user.id.get(),
+        role_ids,
+        manages_guild: member_manages || is_owner,
+    }
+}
+
+/// Authorize and carry out a clicked action, returning the reply line.
+/// Actions with a [`ChatAction::confirmation`] run only once `confirmed`.
+async fn run_action(
+    data: &BotData,
+    invocation: &CommandContext,
+    user: &str,
+    action: ChatAction,
+    confirmed: bool,
+) -> String {
+    if action.confirmation().is_some() && !confirmed {
+        return "❎ Not confirmed — no action taken.".to_string();
+    }
+    match action {
+        ChatAction::StartAutofocus { telescope } => {
+            run_button_command(
+                data,
+                invocation,
+                &telescope,
+                "Start autofocus",
+                RigCommand::StartAutofocus,
+            )
+            .await
+        }
+        ChatAction::Acknowledge { telescope, alert } => {
+            acknowledge(data, invocation, user, telescope, alert)
+        }
+    }
+}
+
+/// Whether the clicker may run `action` at all, checked before asking them
+/// to confirm it. The error is the reply line.
+fn authorize_action(
+    data: &BotData,
+    invocation: &CommandContext,
+    action: &ChatAction,
+) -> Result<(), String> {
+    match action {
+        ChatAction::StartAutofocus { telescope } => resolve_button_write(
+            data,
+            invocation,
+            telescope,
+            "Start autofocus",
+            RigCommandKind::StartAutofocus,
+        )
+        .map(|_| ()),
+        ChatAction::Acknowledge { .. } => Ok(()),
+    }
+}
+
+/// Record the clicking user as handling an alert. Anyone who can see the
+/// telescope here may acknowledge; it does not actuate the rig.
+fn acknowledge(
+    data: &BotData,
+    invocation: &CommandContext,
+    user: &str,
+    telescope: String,
+    alert: String,
+) -> String {
+    let name = match data.resolver.resolve(invocation, Some(&telescope)) {
+        Ok((name, _)) => name,
+        Err(msg) => return format!("❌ {msg}"),
+    };
+    let acknowledgement = Acknowledgement {
+        telescope: name.clone(),
+        alert,
+        user: user.to_string(),
+    };
+    match data.acknowledgements.send(acknowledgement) {
+        Ok(_) => format!("✋ [{name}] Acknowledged; reminders stop and the channel is told."),
+        Err(_) => format!("❌ [{name}] No monitor is running to take the acknowledgement."),
+    }
+}
+
+/// [`resolve_write_or_reply`] for a button: the error is the reply line.
+fn resolve_button_write(
+    data: &BotData,
+    invocation: &CommandContext,
+    telescope: &str,
+    label: &str,
+    kind: RigCommandKind,
+) -> Result<(String, SharedRigSource), String> {
+    let (name, client) = data
+        .resolver
+        .resolve_for_write(invocation, Some(telescope))
+        .map_err(|msg| format!("❌ {msg}"))?;
+    if let Some(reason) = unsupported_reason(&client.capabilities(), kind) {
+        return Err(format!("❌ [{name}] {label} {reason}"));
+    }
+    Ok((name, client))
+}
+
+/// [`run_command`] for a button: authorize the click, then run `command`.
+async fn run_button_command(
+    data: &BotData,
+    invocation: &CommandContext,
+    telescope: &str,
+    label: &str,
+    command: RigCommand,
+) -> String {
+    let (name, client) =
+        match resolve_button_write(data, invocation, telescope, label, command.kind()) {
+            Ok(resolved) => resolved,
+            Err(msg) => return msg,
+        };
+    match client.execute_command(command).await {
+        Ok(resp) if resp.success => format!("✅ [{name}] {label}: {}", resp.summary()),
+        Ok(resp) => format!("❌ [{name}] {label}: {}", resp.summary()),
+        Err(e) => format!("❌ [{name}] {label} failed: {e}"),
+    }
+}
+
 /// Shorthand for "resolve telescope, send an ephemeral error to the user if
 /// it fails."
 async fn resolve_or_reply<'a>(
@@ -477,21 +871,28 @@ async fn resolve_or_reply<'a>(
     }
 }
 
-/// Resolve the telescope, then check write authorization for it. Sends an
-/// ephemeral error on either failure. Write commands call this instead of
-/// `resolve_or_reply`.
+/// Resolve the telescope, then check write authorization for it and that
+/// the rig negotiated `kind`. Sends an ephemeral error on any failure. Write
+/// commands call this instead of `resolve_or_reply`.
 async fn resolve_write_or_reply<'a>(
     ctx: Context<'a>,
     telescope: Option<String>,
+    kind: RigCommandKind,
 ) -> Result<(String, SharedRigSource), BotError> {
     let invocation = command_context(ctx).await;
     // One resolver call so the authorization decision provably applies to
     // the rig the command will actuate.
-    match ctx
+    let resolved = ctx
         .data()
         .resolver
         .resolve_for_write(&invocation, telescope.as_deref())
-    {
+        .and_then(
+            |(name, client)| match unsupported_reason(&client.capabilities(), kind) {
+                None => Ok((name, client)),
+                Some(reason) => Err(format!("[{name}] `{}` {reason}", command_label(kind))),
+            },
+        );
+    match resolved {
         Ok(v) => Ok(v),
         Err(msg) => {
             ctx.send(
@@ -505,6 +906,94 @@ async fn resolve_write_or_reply<'a>(
     }
 }
 
+/// The subcommand that sends a command of this kind.
+fn command_label(kind: RigCommandKind) -> &'static str {
+    match kind {
+        RigCommandKind::UnparkMount => "unpark",
+        RigCommandKind::HomeMount => "home",
+        RigCommandKind::ChangeFilter => "change-filter",
+        RigCommandKind::StartGuiding => "guider-start",
+        RigCommandKind::StopGuiding => "guider-stop",
+        RigCommandKind::CoolCamera => "cool",
+        RigCommandKind::WarmCamera => "warm",
+        RigCommandKind::StartAutofocus => "autofocus",
+        RigCommandKind::CancelAutofocus => "autofocus cancel",
+        RigCommandKind::ParkMount => "park",
+        RigCommandKind::AbortExposure => "abort-capture",
+        RigCommandKind::StopSequence => "stop-sequence",
+        RigCommandKind::StartSequence => "start-sequence",
+        RigCommandKind::SlewToCoordinates => "slew",
+        RigCommandKind::SetTracking => "tracking",
+        RigCommandKind::StopSlew => "stop-slew",
+        RigCommandKind::MoveFocuser => "focuser-move",
+        RigCommandKind::SetFocuserTempComp => "focuser-tempcomp",
+        RigCommandKind::MoveRotator => "rotator-move",
+        RigCommandKind::CaptureSnapshot => "snapshot",
+        RigCommandKind::OpenDomeShutter => "dome-shutter open",
+        RigCommandKind::CloseDomeShutter => "dome-shutter close",
+        RigCommandKind::ParkDome => "dome-park",
+        RigCommandKind::SetDomeSlaving => "dome-slave",
+        RigCommandKind::SetFlatLight => "flat-light",
+        RigCommandKind::SetFlatBrightness => "flat-brightness",
+        RigCommandKind::OpenFlatCover => "flat-cover open",
+        RigCommandKind::CloseFlatCover => "flat-cover close",
+        RigCommandKind::SetSwitch => "switch",
+    }
+}
+
+/// Why a rig cannot take a command of this kind, or `None` if it can.
+fn unsupported_reason(capabilities: &RigCapabilities, kind: RigCommandKind) -> Option<String> {
+    if capabilities.supports_command(kind) {
+        return None;
+    }
+    Some(if !capabilities.commands {
+        "is unavailable: this rig connection is read-only".to_string()
+    } else if let Some(device) = kind
+        .device()
+        .filter(|device| capabilities.devices.get(*device) == DeviceState::Absent)
+    {
+        format!(
+            "is unavailable: the profile has no {}",
+            device.label().to_lowercase()
+        )
+    } else {
+        "is not supported by this rig's plugin version".to_string()
+    })
+}
+
+/// What the rig can be told to do, grouped by device, for `/status`.
+fn abilities_summary(capabilities: &RigCapabilities) -> String {
+    if !capabilities.commands {
+        return "Read-only connection".to_string();
+    }
+    let devices = RigDevice::ALL.into_iter().map(Some).chain([None]);
+    let lines: Vec<String> = devices
+        .filter_map(|device| {
+            let commands: Vec<&str> = RigCommandKind::ALL
+                .into_iter()
+                .filter(|kind| kind.device() == device && capabilities.supports_command(*kind))
+                .map(command_label)
+                .collect();
+            let (label, state) = match device {
+                Some(device) => (device.label(), capabilities.devices.get(device)),
+                None => ("Sequence", DeviceState::Unknown),
+            };
+            let state = match state {
+                DeviceState::Unknown => "",
+                DeviceState::Absent => return None,
+                DeviceState::Disconnected => " (disconnected)",
+                DeviceState::Connected => " (connected)",
+            };
+            (!commands.is_empty()).then(|| format!("{label}{state}: {}", commands.join(", ")))
+        })
+        .collect();
+    if lines.is_empty() {
+        "No commands negotiated".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
 /// One-page summary embed: target + mount + sequence + filter.
 #[poise::command(slash_command)]
 async fn status(
@@ -592,6 +1081,47 @@ async fn status(
         embed = embed.field("Filter", format!("{} (ID: {})", sel.name, sel.id), true);
     }
 
+    if let Ok(dome) = client.get_dome_info().await
+        && dome.success
+        && dome.response.connected
+    {
+        embed = embed.field("Dome", dome.response.summary(), true);
+    }
+
+    if let Ok(panel) = client.get_flat_device_info().await
+        && panel.success
+        && panel.response.connected
+    {
+        embed = embed.field("Flat panel", panel.response.summary(), true);
+    }
+
+    if let Ok(switches) = client.get_switch_info().await
+        && switches.success
+        && switches.response.connected
+        && let Some(heaters) = switches.response.dew_heater_summary()
+    {
+        embed = embed.field("Dew heaters", heaters, true);
+    }
+
+    if let Ok(weather) = client.get_weather_info().await
+        && weather.success
+        && weather.response.connected
+    {
+        embed = embed.field("Weather", weather.response.summary(), true);
+    }
+
+    if let Ok(safety) = client.get_safety_info().await
+        && let Some(safe) = safety.is_safe()
+    {
+        embed = embed.field("Safety", if safe { "Safe" } else { "Unsafe" }, true);
+    }
+
+    embed = embed.field(
+        "Abilities",
+        abilities_summary(&client.capabilities()),
+        false,
+    );
+
     ctx.send(poise::CreateReply::default().embed(embed)).await?;
     Ok(())
 }
@@ -751,10 +1281,52 @@ fn short_duration(duration: chrono::Duration) -> String {
     }
 }
 
+/// The latest Target Scheduler target: name, coordinates, project and
+/// rotation.
+type TsTarget = (
+    String,
+    Option<crate::events::TargetCoordinates>,
+    Option<String>,
+    Option<f64>,
+);
+
+async fn latest_ts_target(client: &SharedRigSource) -> Option<TsTarget> {
+    let events = client.get_event_history().await.ok()?;
+    events.response.iter().rev().find_map(|ev| {
+        if let Some(crate::events::EventDetails::TargetStart {
+            target_name,
+            coordinates,
+            project_name,
+            rotation,
+            ..
+        }) = &ev.details
+        {
+            Some((
+                target_name.clone(),
+                coordinates.clone(),
+                project_name.clone(),
+                *rotation,
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// The mount's site, when it reports one.
+async fn site_of(client: &SharedRigSource) -> Option<Site> {
+    let mount = client.get_mount_info().await.ok()?;
+    Site::from_mount(&mount)
+}
+
 #[poise::command(slash_command)]
 async fn target(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
+    #[description = "Altitude limit in degrees (default 30)"]
+    #[min = 0]
+    #[max = 89]
+    min_altitude: Option<f64>,
 ) -> Result<(), BotError> {
     let (name, client) = match resolve_or_reply(ctx, telescope).await {
         Ok(v) => v,
@@ -766,28 +1338,11 @@ async fn target(
         crate::sequence::extract_current_target(&seq).unwrap_or_else(|| "(none)".to_string());
 
     // Look for the latest TS-TARGETSTART event for richer coords.
-    let events = client.get_event_history().await.ok();
-    let ts_target = events.as_ref().and_then(|e| {
-        e.response.iter().rev().find_map(|ev| {
-            if let Some(crate::events::EventDetails::TargetStart {
-                target_name,
-                coordinates,
-                project_name,
-                rotation,
-                ..
-            }) = &ev.details
-            {
-                Some((
-                    target_name.clone(),
-                    coordinates.clone(),
-                    project_name.clone(),
-                    *rotation,
-                ))
-            } else {
-                None
-            }
-        })
-    });
+    let ts_target = latest_ts_target(&client).await;
+    let position = ts_target
+        .as_ref()
+        .and_then(|(_, coords, ..)| coords.as_ref())
+        .and_then(Equatorial::from_target);
 
     let mut embed = serenity::CreateEmbed::new().title(format!("[{name}] Target"));
     if let Some((tname, coords, project, rot)) = ts_target {
@@ -807,6 +1362,84 @@ async fn target(
     } else {
         embed = embed.field("Sequence target", active_target, false);
     }
+    if let Some(position) = position
+        && let Some(site) = site_of(&client).await
+    {
+        let now = chrono::Utc::now();
+        let limit = min_altitude.unwrap_or(DEFAULT_ALTITUDE_LIMIT);
+        embed = embed
+            .field(
+                "Visibility",
+                Visibility::at(&site, &position, limit, now).summary(now),
+                false,
+            )
+            .field(
+                "Moon",
+                MoonInfo::at(&site, now, Some(&position)).summary(),
+                false,
+            );
+    }
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Tonight's twilight times, the moon, and the current target's window.
+#[poise::command(slash_command)]
+async fn night(
+    ctx: Context<'_>,
+    #[description = "Telescope name"] telescope: Option<String>,
+    #[description = "Altitude limit in degrees (default 30)"]
+    #[min = 0]
+    #[max = 89]
+    min_altitude: Option<f64>,
+) -> Result<(), BotError> {
+    let (name, client) = match resolve_or_reply(ctx, telescope).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer().await?;
+    let Some(site) = site_of(&client).await else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("The mount reports no site location; set it in N.I.N.A.'s options.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    let now = chrono::Utc::now();
+    let target = latest_ts_target(&client)
+        .await
+        .and_then(|(tname, coords, ..)| {
+            coords
+                .as_ref()
+                .and_then(Equatorial::from_target)
+                .map(|position| (tname, position))
+        });
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("[{name}] Tonight"))
+        .field("Twilight", Night::around(&site, now).summary(), false);
+    if let Some(next) = twilight_summary(&site, now) {
+        embed = embed.field("Next", next, false);
+    }
+    embed = embed.field(
+        "Moon",
+        MoonInfo::at(&site, now, target.as_ref().map(|(_, position)| position)).summary(),
+        false,
+    );
+    if let Some((tname, position)) = &target {
+        let limit = min_altitude.unwrap_or(DEFAULT_ALTITUDE_LIMIT);
+        embed = embed.field(
+            tname.clone(),
+            Visibility::at(&site, position, limit, now).summary(now),
+            false,
+        );
+    }
+    embed = embed.footer(serenity::CreateEmbedFooter::new(format!(
+        "Site {:.3}°, {:.3}° · {:.0} m",
+        site.latitude, site.longitude, site.elevation
+    )));
     ctx.send(poise::CreateReply::default().embed(embed)).await?;
     Ok(())
 }
@@ -827,7 +1460,7 @@ async fn mount(
     let (alt, az) = mount.get_alt_az();
     let flip = mount.get_time_to_meridian_flip_string();
 
-    let embed = serenity::CreateEmbed::new()
+    let mut embed = serenity::CreateEmbed::new()
         .title(format!("[{name}] Mount"))
         .field(
             "Status",
@@ -842,7 +1475,28 @@ async fn mount(
         .field("Pier side", mount.get_side_of_pier().to_string(), true)
         .field("Sidereal time", &m.sidereal_time_string, true)
         .field("Time to flip", flip, true);
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+
+    // The sky chart follows the Target Scheduler's target when there is
+    // one, otherwise wherever the mount is tracking.
+    let target = latest_ts_target(&client)
+        .await
+        .and_then(|(target, coordinates, _, _)| {
+            Some((target, Equatorial::from_target(coordinates.as_ref()?)?))
+        });
+    let horizon = ctx.data().resolver.horizon(&name);
+    let mut reply = poise::CreateReply::default();
+    if let Ok(png) = crate::charts::render_sky_position_png(
+        &mount,
+        target
+            .as_ref()
+            .map(|(target, position)| (target.as_str(), position)),
+        horizon.as_ref(),
+        chrono::Utc::now(),
+    ) {
+        embed = embed.image("attachment://sky_position.png");
+        reply = reply.attachment(CreateAttachment::bytes(png, "sky_position.png"));
+    }
+    ctx.send(reply.embed(embed)).await?;
     Ok(())
 }
 
@@ -921,8 +1575,16 @@ async fn focus(
     Ok(())
 }
 
-#[poise::command(slash_command)]
-async fn guider(
+/// Guider state and guiding analysis
+#[poise::command(slash_command, subcommands("guider_status", "guider_analyze"))]
+async fn guider(_ctx: Context<'_>) -> Result<(), BotError> {
+    // Parent never runs directly when subcommands are defined.
+    Ok(())
+}
+
+/// Guider state, RMS and the recent guiding graph
+#[poise::command(slash_command, rename = "status")]
+async fn guider_status(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
@@ -969,10 +1631,14 @@ async fn guider(
     Ok(())
 }
 
-#[poise::command(slash_command)]
-async fn events(
+/// Periodic error, Dec backlash and dither settling from the guide history
+#[poise::command(slash_command, rename = "analyze")]
+async fn guider_analyze(
     ctx: Context<'_>,
-    #[description = "Number of events to show (default 10)"] count: Option<u32>,
+    #[description = "Seconds per guide step, exposure included (default 2)"]
+    #[min = 0.1]
+    #[max = 60]
+    cadence: Option<f64>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
     let (name, client) = match resolve_or_reply(ctx, telescope).await {
@@ -980,19 +1646,62 @@ async fn events(
         Err(_) => return Ok(()),
     };
     ctx.defer().await?;
-    let history = client.get_event_history().await?;
-    let count = count.unwrap_or(10).min(25) as usize;
-    let events: Vec<&crate::events::Event> = history.response.iter().rev().take(count).collect();
-    let lines: Vec<String> = events
-        .iter()
-        .rev()
-        .map(|e| format!("`{}` {}", e.time, e.event))
-        .collect();
-    let embed = serenity::CreateEmbed::new()
-        .title(format!("[{name}] Last {count} events"))
-        .description(if lines.is_empty() {
-            "(no events)".to_string()
-        } else {
+    let cadence = cadence.unwrap_or(DEFAULT_GUIDE_CADENCE_SECONDS);
+    let graph = client.get_guider_graph().await?;
+    let Some(analysis) = GuidingAnalysis::of(&graph.response, cadence) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(format!("[{name}] Not enough guide steps to analyze yet.")),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("[{name}] Guiding analysis"))
+        .field("RMS", analysis.rms_summary(), false)
+        .field("Periodic error", analysis.periodic_error_summary(), false)
+        .field("Dec backlash", analysis.backlash_summary(), false)
+        .field("Dither settling", analysis.settle_summary(), false)
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "{} steps · {:.1} s per step assumed · {:.0} min of history",
+            analysis.steps,
+            analysis.cadence_seconds,
+            analysis.span_seconds() / 60.0
+        )));
+    let mut reply = poise::CreateReply::default();
+    if let Ok(png) = crate::charts::render_guiding_spectrum_png(&analysis) {
+        embed = embed.image("attachment://guiding_spectrum.png");
+        reply = reply.attachment(CreateAttachment::bytes(png, "guiding_spectrum.png"));
+    }
+    ctx.send(reply.embed(embed)).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command)]
+async fn events(
+    ctx: Context<'_>,
+    #[description = "Number of events to show (default 10)"] count: Option<u32>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) = match resolve_or_reply(ctx, telescope).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer().await?;
+    let history = client.get_event_history().await?;
+    let count = count.unwrap_or(10).min(25) as usize;
+    let events: Vec<&crate::events::Event> = history.response.iter().rev().take(count).collect();
+    let lines: Vec<String> = events
+        .iter()
+        .rev()
+        .map(|e| format!("`{}` {}", e.time, e.event))
+        .collect();
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("[{name}] Last {count} events"))
+        .description(if lines.is_empty() {
+            "(no events)".to_string()
+        } else {
             lines.join("\n")
         });
     ctx.send(poise::CreateReply::default().embed(embed)).await?;
@@ -1052,26 +1761,140 @@ async fn last_image(
     Ok(())
 }
 
+/// Summarize the latest night's imaging session.
+#[poise::command(slash_command)]
+async fn report(
+    ctx: Context<'_>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) = match resolve_or_reply(ctx, telescope).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer().await?;
+    let images = client.get_all_image_history().await?;
+    let events = client.get_event_history().await?;
+    let report = crate::session_report::SessionReport::build(&images, &events.response, None);
+    if report.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("No images in history.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut embed =
+        DiscordBotService::build_embed(&report.chat_message(&format!("[{name}] Session report")));
+    let mut reply = poise::CreateReply::default();
+    if let Ok(png) = crate::charts::render_session_report_png(&report) {
+        embed = embed.image("attachment://session_report.png");
+        reply = reply.attachment(CreateAttachment::bytes(png, "session_report.png"));
+    }
+    ctx.send(reply.embed(embed)).await?;
+    Ok(())
+}
+
+/// Chart the latest night's HFR, stars, median and sensor temperature.
+#[poise::command(slash_command)]
+async fn trends(
+    ctx: Context<'_>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) = match resolve_or_reply(ctx, telescope).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer().await?;
+    let images = client.get_all_image_history().await?;
+    let events = client.get_event_history().await?;
+    let png = match crate::charts::render_session_trends_png(&images, &events.response) {
+        Ok(png) => png,
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("No trends to chart: {e}."))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let embed = serenity::CreateEmbed::new()
+        .title(format!("[{name}] Frame trends"))
+        .image("attachment://session_trends.png");
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .attachment(CreateAttachment::bytes(png, "session_trends.png")),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Integration per filter on the current target against the sequence's plan.
+#[poise::command(slash_command)]
+async fn progress(
+    ctx: Context<'_>,
+    #[description = "Telescope name"] telescope: Option<String>,
+    #[description = "Target name (default: the current target)"] target: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) = match resolve_or_reply(ctx, telescope).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer().await?;
+    let sequence = client.get_sequence().await.ok();
+    let target = match target {
+        Some(target) => Some(target),
+        None => match latest_ts_target(&client).await {
+            Some((target, ..)) => Some(target),
+            None => sequence
+                .as_ref()
+                .and_then(crate::sequence::extract_current_target),
+        },
+    };
+    let Some(target) = target else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("No current target; name one with the `target` option.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    let images = client.get_all_image_history().await?;
+    let events = client.get_event_history().await?;
+    let progress = crate::progress::IntegrationProgress::build(
+        &target,
+        &images,
+        &events.response,
+        sequence.as_ref(),
+    );
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("[{name}] Progress · {target}"))
+        .description(progress.summary());
+    let mut reply = poise::CreateReply::default();
+    if let Ok(png) = crate::charts::render_integration_progress_png(&progress) {
+        embed = embed.image("attachment://progress.png");
+        reply = reply.attachment(CreateAttachment::bytes(png, "progress.png"));
+    }
+    ctx.send(reply.embed(embed)).await?;
+    Ok(())
+}
+
 // ---------- Phase 3: write commands (ACL-gated) ----------
 
 /// Post a Confirm / Cancel button pair and wait for the invoker to click.
 /// Returns `Ok(true)` on Confirm, `Ok(false)` on Cancel or 30s timeout.
 async fn confirm_destructive(ctx: Context<'_>, action: &str) -> Result<bool, BotError> {
-    let prompt =
-        format!("⚠️ Confirm **{action}**?\nThis is a destructive operation — you have 30 seconds.");
-    let row = serenity::CreateActionRow::Buttons(vec![
-        serenity::CreateButton::new("chatstronomy-confirm")
-            .label("Confirm")
-            .style(serenity::ButtonStyle::Danger),
-        serenity::CreateButton::new("chatstronomy-cancel")
-            .label("Cancel")
-            .style(serenity::ButtonStyle::Secondary),
-    ]);
     let handle = ctx
         .send(
             poise::CreateReply::default()
-                .content(prompt)
-                .components(vec![row])
+                .content(confirm_prompt(action))
+                .components(vec![confirm_buttons()])
                 .ephemeral(true),
         )
         .await?;
@@ -1080,14 +1903,10 @@ async fn confirm_destructive(ctx: Context<'_>, action: &str) -> Result<bool, Bot
     let interaction = message
         .await_component_interaction(ctx.serenity_context().shard.clone())
         .author_id(ctx.author().id)
-        .timeout(std::time::Duration::from_secs(30))
+        .timeout(CONFIRM_TIMEOUT)
         .await;
-
-    let (confirmed, response_text) = match interaction.as_ref().map(|i| i.data.custom_id.as_str()) {
-        Some("chatstronomy-confirm") => (true, "✅ Confirmed, running command…"),
-        Some("chatstronomy-cancel") => (false, "❎ Cancelled."),
-        _ => (false, "⏱️ Timed out — no action taken."),
-    };
+    let (confirmed, response_text) =
+        confirm_outcome(interaction.as_ref().map(|i| i.data.custom_id.as_str()));
 
     // Acknowledge the interaction (or just edit the original message if
     // the user didn't click anything).
@@ -1115,6 +1934,83 @@ async fn confirm_destructive(ctx: Context<'_>, action: &str) -> Result<bool, Bot
     Ok(confirmed)
 }
 
+/// [`confirm_destructive`] for a button click, as a follow-up to the
+/// click's deferred response.
+async fn confirm_click(
+    ctx: &serenity::Context,
+    component: &serenity::ComponentInteraction,
+    action: &str,
+) -> Result<bool, BotError> {
+    let message = component
+        .create_followup(
+            &ctx.http,
+            serenity::CreateInteractionResponseFollowup::new()
+                .content(confirm_prompt(action))
+                .components(vec![confirm_buttons()])
+                .ephemeral(true),
+        )
+        .await?;
+
+    let interaction = message
+        .await_component_interaction(ctx.shard.clone())
+        .author_id(component.user.id)
+        .timeout(CONFIRM_TIMEOUT)
+        .await;
+    let (confirmed, response_text) =
+        confirm_outcome(interaction.as_ref().map(|i| i.data.custom_id.as_str()));
+
+    if let Some(i) = interaction {
+        let _ = i
+            .create_response(
+                &ctx.http,
+                serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .content(response_text)
+                        .components(vec![]),
+                ),
+            )
+            .await;
+    } else {
+        let _ = component
+            .edit_followup(
+                &ctx.http,
+                message.id,
+                serenity::CreateInteractionResponseFollowup::new()
+                    .content(response_text)
+                    .components(vec![]),
+            )
+            .await;
+    }
+    Ok(confirmed)
+}
+
+const CONFIRM_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+fn confirm_prompt(action: &str) -> String {
+    format!("⚠️ Confirm **{action}**?\nThis is a destructive operation — you have 30 seconds.")
+}
+
+fn confirm_buttons() -> serenity::CreateActionRow {
+    serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new("chatstronomy-confirm")
+            .label("Confirm")
+            .style(serenity::ButtonStyle::Danger),
+        serenity::CreateButton::new("chatstronomy-cancel")
+            .label("Cancel")
+            .style(serenity::ButtonStyle::Secondary),
+    ])
+}
+
+/// Whether the clicked prompt button confirms, and the text replacing the
+/// prompt. No click means the prompt timed out.
+fn confirm_outcome(custom_id: Option<&str>) -> (bool, &'static str) {
+    match custom_id {
+        Some("chatstronomy-confirm") => (true, "✅ Confirmed, running command…"),
+        Some("chatstronomy-cancel") => (false, "❎ Cancelled."),
+        _ => (false, "⏱️ Timed out — no action taken."),
+    }
+}
+
 /// Issue a typed rig command and reply with a status line. Used by all
 /// write commands once authorization (and any confirmation) has passed.
 async fn run_command(
@@ -1124,21 +2020,38 @@ async fn run_command(
     label: &str,
     command: RigCommand,
 ) -> Result<(), BotError> {
-    if !client.capabilities().commands {
+    run_command_reporting(ctx, telescope, client, label, command, None).await
+}
+
+/// [`run_command`], then on success wait for `read_back` to come to rest and
+/// add its final state to the reply.
+async fn run_command_reporting(
+    ctx: Context<'_>,
+    telescope: &str,
+    client: &SharedRigSource,
+    label: &str,
+    command: RigCommand,
+    read_back: Option<ReadBack>,
+) -> Result<(), BotError> {
+    if let Some(reason) = unsupported_reason(&client.capabilities(), command.kind()) {
         ctx.send(
             poise::CreateReply::default()
                 .ephemeral(true)
-                .content(format!(
-                    "❌ [{telescope}] {label} is not supported by this rig connection"
-                )),
+                .content(format!("❌ [{telescope}] {label} {reason}")),
         )
         .await?;
         return Ok(());
     }
     let result = client.execute_command(command).await;
     let reply = match result {
-        Ok(resp) if resp.success => poise::CreateReply::default()
-            .content(format!("✅ [{telescope}] {label}: {}", resp.summary())),
+        Ok(resp) if resp.success => {
+            let mut content = format!("✅ [{telescope}] {label}: {}", resp.summary());
+            if let Some(device) = read_back {
+                content.push('\n');
+                content.push_str(&device.settle(client).await);
+            }
+            poise::CreateReply::default().content(content)
+        }
         Ok(resp) => poise::CreateReply::default()
             .ephemeral(true)
             .content(format!("❌ [{telescope}] {label}: {}", resp.summary())),
@@ -1150,6 +2063,91 @@ async fn run_command(
     Ok(())
 }
 
+/// A device whose final position is read back after a positioning command.
+#[derive(Debug, Clone, Copy)]
+enum ReadBack {
+    Focuser,
+    Rotator,
+    Dome,
+    FlatDevice,
+    Switch { index: u32 },
+}
+
+impl ReadBack {
+    const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+    const SETTLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+    /// Poll the device until it stops moving (or the timeout passes) and
+    /// describe where it ended up.
+    async fn settle(self, client: &SharedRigSource) -> String {
+        let deadline = tokio::time::Instant::now() + Self::SETTLE_TIMEOUT;
+        loop {
+            tokio::time::sleep(Self::POLL_INTERVAL).await;
+            let (moving, position) = match self {
+                Self::Focuser => match client.get_focuser_info().await {
+                    Ok(info) => {
+                        let focuser = info.response;
+                        let comp = if focuser.temp_comp {
+                            ", temp comp on"
+                        } else {
+                            ""
+                        };
+                        (
+                            focuser.is_moving || focuser.is_settling,
+                            format!("Focuser at {} steps{comp}", focuser.position),
+                        )
+                    }
+                    Err(e) => return format!("Couldn't read the focuser back: {e}"),
+                },
+                Self::Rotator => match client.get_rotator_info().await {
+                    Ok(info) => (
+                        info.response.is_moving,
+                        format!(
+                            "Rotator at {:.1}° (mechanical {:.1}°)",
+                            info.response.position, info.response.mechanical_position
+                        ),
+                    ),
+                    Err(e) => return format!("Couldn't read the rotator back: {e}"),
+                },
+                Self::Dome => match client.get_dome_info().await {
+                    Ok(info) => {
+                        let dome = info.response;
+                        let shutter = dome.shutter_label();
+                        (
+                            dome.slewing || shutter.ends_with("ing"),
+                            format!("Dome: {}", dome.summary()),
+                        )
+                    }
+                    Err(e) => return format!("Couldn't read the dome back: {e}"),
+                },
+                Self::FlatDevice => match client.get_flat_device_info().await {
+                    Ok(info) => (
+                        info.response.cover_state == "NeitherOpenNorClosed",
+                        format!("Flat panel: {}", info.response.summary()),
+                    ),
+                    Err(e) => return format!("Couldn't read the flat panel back: {e}"),
+                },
+                Self::Switch { index } => match client.get_switch_info().await {
+                    Ok(info) => match info.response.get(index) {
+                        Some((switch, _)) => (
+                            false,
+                            format!("{}: {}", switch.name, switch.display_value()),
+                        ),
+                        None => return format!("Switch #{index} is no longer reported"),
+                    },
+                    Err(e) => return format!("Couldn't read the switch back: {e}"),
+                },
+            };
+            if !moving {
+                return position;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return format!("{position}, still moving");
+            }
+        }
+    }
+}
+
 // --- Non-destructive (ACL only) ---
 
 /// Unpark the mount.
@@ -1158,10 +2156,11 @@ async fn unpark(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::UnparkMount).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
     run_command(ctx, &name, &client, "Unpark mount", RigCommand::UnparkMount).await
 }
@@ -1172,10 +2171,11 @@ async fn home(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::HomeMount).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
     run_command(ctx, &name, &client, "Home mount", RigCommand::HomeMount).await
 }
@@ -1188,60 +2188,53 @@ async fn change_filter(
     #[description = "Filter name (e.g. L, R, G, B, HA)"] filter: String,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::ChangeFilter).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
 
-    let info = match client.get_filterwheel_info().await {
-        Ok(i) => i,
+    let (filter_id, filter_name) = match find_filter(&client, &filter).await {
+        Ok(v) => v,
         Err(e) => {
             ctx.send(
                 poise::CreateReply::default()
                     .ephemeral(true)
-                    .content(format!("❌ [{name}] couldn't fetch filterwheel info: {e}")),
+                    .content(format!("❌ [{name}] {e}")),
             )
             .await?;
             return Ok(());
         }
     };
 
-    let target = info
-        .response
-        .available_filters
-        .iter()
-        .find(|f| f.name.eq_ignore_ascii_case(&filter));
-    let Some(target) = target else {
-        let known: Vec<&str> = info
-            .response
-            .available_filters
-            .iter()
-            .map(|f| f.name.as_str())
-            .collect();
-        ctx.send(
-            poise::CreateReply::default()
-                .ephemeral(true)
-                .content(format!(
-                    "❌ [{name}] no filter '{filter}'. Known: {known:?}"
-                )),
-        )
-        .await?;
-        return Ok(());
-    };
-
     run_command(
         ctx,
         &name,
         &client,
-        &format!("Change filter → {} (ID {})", target.name, target.id),
-        RigCommand::ChangeFilter {
-            filter_id: target.id,
-        },
+        &format!("Change filter → {filter_name} (ID {filter_id})"),
+        RigCommand::ChangeFilter { filter_id },
     )
     .await
 }
 
+/// Resolve a filter name to its wheel id through the live filter-wheel
+/// snapshot.
+async fn find_filter(client: &SharedRigSource, filter: &str) -> Result<(i32, String), String> {
+    let info = client
+        .get_filterwheel_info()
+        .await
+        .map_err(|e| format!("couldn't fetch filterwheel info: {e}"))?;
+    let filters = &info.response.available_filters;
+    match filters.iter().find(|f| f.name.eq_ignore_ascii_case(filter)) {
+        Some(target) => Ok((target.id, target.name.clone())),
+        None => {
+            let known: Vec<&str> = filters.iter().map(|f| f.name.as_str()).collect();
+            Err(format!("no filter '{filter}'. Known: {known:?}"))
+        }
+    }
+}
+
 /// Start guiding (without calibration).
 #[poise::command(slash_command, rename = "guider-start")]
 async fn guider_start(
@@ -1249,10 +2242,11 @@ async fn guider_start(
     #[description = "Run calibration first"] calibrate: Option<bool>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::StartGuiding).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
     run_command(
         ctx,
@@ -1272,10 +2266,11 @@ async fn guider_stop(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::StopGuiding).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
     run_command(ctx, &name, &client, "Stop guiding", RigCommand::StopGuiding).await
 }
@@ -1288,10 +2283,11 @@ async fn cool(
     #[description = "Minutes to ramp down (default 10)"] minutes: Option<f64>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::CoolCamera).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
     run_command(
         ctx,
@@ -1316,10 +2312,11 @@ async fn warm(
     #[description = "Minutes to warm (default 10)"] minutes: Option<f64>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::WarmCamera).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     ctx.defer().await?;
     run_command(
         ctx,
@@ -1342,11 +2339,16 @@ async fn autofocus(
     #[description = "Cancel a running autofocus"] cancel: Option<bool>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
+    let cancel = cancel.unwrap_or(false);
+    let kind = if cancel {
+        RigCommandKind::CancelAutofocus
+    } else {
+        RigCommandKind::StartAutofocus
+    };
+    let (name, client) = match resolve_write_or_reply(ctx, telescope, kind).await {
         Ok(v) => v,
         Err(_) => return Ok(()),
     };
-    let cancel = cancel.unwrap_or(false);
     if !cancel && !confirm_destructive(ctx, &format!("autofocus run on {name}")).await? {
         return Ok(());
     }
@@ -1375,10 +2377,11 @@ async fn park(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::ParkMount).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     if !confirm_destructive(ctx, &format!("park {name}")).await? {
         return Ok(());
     }
@@ -1391,10 +2394,11 @@ async fn abort_capture(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::AbortExposure).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     if !confirm_destructive(ctx, &format!("abort capture on {name}")).await? {
         return Ok(());
     }
@@ -1414,10 +2418,11 @@ async fn stop_sequence(
     ctx: Context<'_>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::StopSequence).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     if !confirm_destructive(ctx, &format!("stop sequence on {name}")).await? {
         return Ok(());
     }
@@ -1438,10 +2443,11 @@ async fn start_sequence(
     #[description = "Skip pre-run validation"] skip_validation: Option<bool>,
     #[description = "Telescope name"] telescope: Option<String>,
 ) -> Result<(), BotError> {
-    let (name, client) = match resolve_write_or_reply(ctx, telescope).await {
-        Ok(v) => v,
-        Err(_) => return Ok(()),
-    };
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::StartSequence).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
     if !confirm_destructive(ctx, &format!("start sequence on {name}")).await? {
         return Ok(());
     }
@@ -1456,3 +2462,826 @@ async fn start_sequence(
     )
     .await
 }
+
+// --- Mount motion ---
+
+/// Tracking rates offered by `/chatstronomy tracking`.
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+enum TrackingRateChoice {
+    Sidereal,
+    Lunar,
+    Solar,
+    King,
+}
+
+impl From<TrackingRateChoice> for TrackingMode {
+    fn from(choice: TrackingRateChoice) -> Self {
+        match choice {
+            TrackingRateChoice::Sidereal => TrackingMode::Sidereal,
+            TrackingRateChoice::Lunar => TrackingMode::Lunar,
+            TrackingRateChoice::Solar => TrackingMode::Solar,
+            TrackingRateChoice::King => TrackingMode::King,
+        }
+    }
+}
+
+/// Reply with `refusal` and return `false`, or return `true` when there is
+/// none. Used by the live-state checks of positioning commands.
+async fn accept_or_reply(
+    ctx: Context<'_>,
+    telescope: &str,
+    refusal: Option<String>,
+) -> Result<bool, BotError> {
+    let Some(refusal) = refusal else {
+        return Ok(true);
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .ephemeral(true)
+            .content(format!("❌ [{telescope}] {refusal}")),
+    )
+    .await?;
+    Ok(false)
+}
+
+/// Fetch the live mount state and check `command` against it. Replies with
+/// the reason and returns `false` when the mount cannot accept the command.
+///
+/// Commands check once before their confirmation prompt, so a parked mount
+/// is refused without asking, and again after it, since the mount may have
+/// changed while the prompt was open.
+async fn mount_accepts(
+    ctx: Context<'_>,
+    telescope: &str,
+    client: &SharedRigSource,
+    command: &RigCommand,
+) -> Result<bool, BotError> {
+    let refusal = match client.get_mount_info().await {
+        Ok(mount) => mount.check_command(command).err(),
+        Err(e) => Some(format!("couldn't fetch mount info: {e}")),
+    };
+    accept_or_reply(ctx, telescope, refusal).await
+}
+
+/// Slew to J2000 coordinates, optionally centering with a plate solve
+/// (requires confirmation).
+#[poise::command(slash_command)]
+async fn slew(
+    ctx: Context<'_>,
+    #[description = "Right ascension in hours (5.588 or 05:35:17)"] ra: String,
+    #[description = "Declination in degrees (-5.39 or -05:23:28)"] dec: String,
+    #[description = "Plate-solve and center after the slew"] center: Option<bool>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SlewToCoordinates).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let coordinates = crate::mount::parse_ra_hours(&ra)
+        .and_then(|ra_hours| Ok((ra_hours, crate::mount::parse_dec_degrees(&dec)?)));
+    let (ra_hours, dec_degrees) = match coordinates {
+        Ok(v) => v,
+        Err(e) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .ephemeral(true)
+                    .content(format!("❌ [{name}] {e}")),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let center = center.unwrap_or(false);
+    let verb = if center { "Slew and center" } else { "Slew" };
+    let label = format!("{verb} to RA {ra_hours:.4} h, Dec {dec_degrees:+.4}°");
+    let command = RigCommand::SlewToCoordinates {
+        ra_hours,
+        dec_degrees,
+        center,
+    };
+    ctx.defer_ephemeral().await?;
+    if !mount_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !mount_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command(ctx, &name, &client, &label, command).await
+}
+
+/// Turn mount tracking on or off (requires confirmation).
+#[poise::command(slash_command)]
+async fn tracking(
+    ctx: Context<'_>,
+    #[description = "Track (true) or stop tracking (false)"] enabled: bool,
+    #[description = "Tracking rate (default: keep the current rate)"] rate: Option<
+        TrackingRateChoice,
+    >,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SetTracking).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let rate = rate.map(TrackingMode::from);
+    let label = match (enabled, rate) {
+        (true, Some(rate)) => format!("Track at {} rate", rate.nina_name()),
+        (true, None) => "Start tracking".to_string(),
+        (false, _) => "Stop tracking".to_string(),
+    };
+    let command = RigCommand::SetTracking { enabled, rate };
+    ctx.defer_ephemeral().await?;
+    if !mount_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !mount_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command(ctx, &name, &client, &label, command).await
+}
+
+/// Abort a running slew. Not confirmation-gated: stopping motion must never
+/// wait behind a prompt.
+#[poise::command(slash_command, rename = "stop-slew")]
+async fn stop_slew(
+    ctx: Context<'_>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::StopSlew).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    ctx.defer().await?;
+    run_command(ctx, &name, &client, "Stop slew", RigCommand::StopSlew).await
+}
+
+// --- Focuser and rotator positioning ---
+
+/// Move the focuser to an absolute position or by a number of steps.
+#[poise::command(slash_command, rename = "focuser-move")]
+async fn focuser_move(
+    ctx: Context<'_>,
+    #[description = "Absolute position in steps"] position: Option<i32>,
+    #[description = "Relative move in steps (negative moves inward)"] steps: Option<i32>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::MoveFocuser).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let (target, label) = match (position, steps) {
+        (Some(position), None) => (
+            FocuserTarget::Position(position),
+            format!("Move focuser to {position}"),
+        ),
+        (None, Some(steps)) => (
+            FocuserTarget::RelativeSteps(steps),
+            format!("Move focuser by {steps:+} steps"),
+        ),
+        _ => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .ephemeral(true)
+                    .content("❌ Give exactly one of `position` or `steps`."),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    ctx.defer().await?;
+    let command = RigCommand::MoveFocuser { target };
+    let refusal = match client.get_focuser_info().await {
+        Ok(info) => info.check_command(&command).err(),
+        Err(e) => Some(format!("couldn't fetch focuser info: {e}")),
+    };
+    if !accept_or_reply(ctx, &name, refusal).await? {
+        return Ok(());
+    }
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        &label,
+        command,
+        Some(ReadBack::Focuser),
+    )
+    .await
+}
+
+/// Turn focuser temperature compensation on or off.
+#[poise::command(slash_command, rename = "focuser-tempcomp")]
+async fn focuser_tempcomp(
+    ctx: Context<'_>,
+    #[description = "Compensate (true) or hold position (false)"] enabled: bool,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SetFocuserTempComp).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    ctx.defer().await?;
+    let command = RigCommand::SetFocuserTempComp { enabled };
+    let refusal = match client.get_focuser_info().await {
+        Ok(info) => info.check_command(&command).err(),
+        Err(e) => Some(format!("couldn't fetch focuser info: {e}")),
+    };
+    if !accept_or_reply(ctx, &name, refusal).await? {
+        return Ok(());
+    }
+    let label = if enabled {
+        "Enable temperature compensation"
+    } else {
+        "Disable temperature compensation"
+    };
+    run_command_reporting(ctx, &name, &client, label, command, Some(ReadBack::Focuser)).await
+}
+
+/// Rotate to a sky position angle (or a mechanical angle).
+#[poise::command(slash_command, rename = "rotator-move")]
+async fn rotator_move(
+    ctx: Context<'_>,
+    #[description = "Angle in degrees (0–360)"] angle: f64,
+    #[description = "Treat the angle as mechanical instead of sky position angle"]
+    mechanical: Option<bool>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::MoveRotator).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    ctx.defer().await?;
+    let mechanical = mechanical.unwrap_or(false);
+    let command = RigCommand::MoveRotator {
+        position_angle: angle,
+        mechanical,
+    };
+    let refusal = match client.get_rotator_info().await {
+        Ok(info) => info.check_command(&command).err(),
+        Err(e) => Some(format!("couldn't fetch rotator info: {e}")),
+    };
+    if !accept_or_reply(ctx, &name, refusal).await? {
+        return Ok(());
+    }
+    let kind = if mechanical { "mechanical" } else { "sky PA" };
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        &format!("Rotate to {kind} {angle:.1}°"),
+        command,
+        Some(ReadBack::Rotator),
+    )
+    .await
+}
+
+// --- Snapshot ---
+
+/// Longest exposure `/chatstronomy snapshot` accepts, in seconds.
+const SNAPSHOT_MAX_SECONDS: f64 = 600.0;
+/// How long past the exposure to wait for the image to be saved.
+const SNAPSHOT_SAVE_GRACE: std::time::Duration = std::time::Duration::from_secs(120);
+/// Allowance for the rig's clock running behind ours when matching the
+/// snapshot's date against when the command was sent.
+const SNAPSHOT_CLOCK_SKEW_SECONDS: i64 = 30;
+
+/// Take and post one exposure outside the sequence, e.g. to check for clouds.
+#[poise::command(slash_command)]
+async fn snapshot(
+    ctx: Context<'_>,
+    #[description = "Exposure in seconds"] exposure: f64,
+    #[description = "Filter name (default: current filter)"] filter: Option<String>,
+    #[description = "Camera gain (default: current gain)"] gain: Option<i32>,
+    #[description = "Binning, 1–4 (default: current binning)"] binning: Option<u8>,
+    #[description = "Capture even while a sequence is running"] force: Option<bool>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::CaptureSnapshot).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let invalid = if !(exposure > 0.0 && exposure <= SNAPSHOT_MAX_SECONDS) {
+        Some(format!(
+            "exposure must be between 0 and {SNAPSHOT_MAX_SECONDS:.0} s"
+        ))
+    } else if binning.is_some_and(|binning| !(1..=4).contains(&binning)) {
+        Some("binning must be 1–4".to_string())
+    } else {
+        None
+    };
+    if !accept_or_reply(ctx, &name, invalid).await? {
+        return Ok(());
+    }
+    let force = force.unwrap_or(false);
+    if force {
+        if !confirm_destructive(ctx, &format!("snapshot on {name} during its sequence")).await? {
+            return Ok(());
+        }
+    } else {
+        ctx.defer().await?;
+        let refusal = match client.get_sequence().await {
+            Ok(sequence) if sequence.is_running() => {
+                Some("a sequence is running; pass `force: true` to capture anyway".to_string())
+            }
+            Ok(_) => None,
+            Err(e) => Some(format!("couldn't check the sequence: {e}")),
+        };
+        if !accept_or_reply(ctx, &name, refusal).await? {
+            return Ok(());
+        }
+    }
+    let filter_id = match filter {
+        Some(filter) => match find_filter(&client, &filter).await {
+            Ok((id, _)) => Some(id),
+            Err(e) => {
+                accept_or_reply(ctx, &name, Some(e)).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    // The snapshot is the first new SNAPSHOT entry dated after the command
+    // went out; a running sequence can save frames alongside it.
+    let known = match client.get_all_image_history().await {
+        Ok(images) => images.response.len(),
+        Err(e) => {
+            accept_or_reply(
+                ctx,
+                &name,
+                Some(format!("couldn't read image history: {e}")),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let command = RigCommand::CaptureSnapshot {
+        exposure_seconds: exposure,
+        filter_id,
+        gain,
+        binning,
+    };
+    let sent_at = chrono::Local::now().fixed_offset()
+        - chrono::Duration::seconds(SNAPSHOT_CLOCK_SKEW_SECONDS);
+    let refusal = match client.execute_command(command).await {
+        Ok(resp) if resp.success => None,
+        Ok(resp) => Some(format!("snapshot: {}", resp.summary())),
+        Err(e) => Some(format!("snapshot failed: {e}")),
+    };
+    if !accept_or_reply(ctx, &name, refusal).await? {
+        return Ok(());
+    }
+
+    let deadline = tokio::time::Instant::now()
+        + std::time::Duration::from_secs_f64(exposure)
+        + SNAPSHOT_SAVE_GRACE;
+    let (index, image) = loop {
+        if let Ok(images) = client.get_all_image_history().await
+            && let Some((index, image)) = images.find_snapshot_since(known, sent_at)
+        {
+            break (index, image.clone());
+        }
+        if tokio::time::Instant::now() >= deadline {
+            accept_or_reply(
+                ctx,
+                &name,
+                Some("the snapshot was taken but its image never appeared".to_string()),
+            )
+            .await?;
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    };
+
+    let mut embed = serenity::CreateEmbed::new()
+        .title(format!("[{name}] Snapshot"))
+        .field("Filter", &image.filter, true)
+        .field("Exposure", format!("{:.1}s", image.exposure_time), true)
+        .field("Gain", image.gain.to_string(), true)
+        .field("HFR", format!("{:.2}", image.hfr), true)
+        .field("Stars", image.stars.to_string(), true)
+        .field("Median", format!("{:.0}", image.median), true)
+        .field("Temperature", format!("{:.1}°C", image.temperature), true);
+    let mut reply = poise::CreateReply::default();
+    if let Ok(thumbnail) = client.get_thumbnail(index as u32).await {
+        let filename = format!("snapshot_{index}.jpg");
+        embed = embed.image(format!("attachment://{filename}"));
+        reply = reply.attachment(CreateAttachment::bytes(thumbnail.data, filename));
+    }
+    ctx.send(reply.embed(embed)).await?;
+    Ok(())
+}
+
+// --- Dome and flat panel ---
+
+/// Open or close, for `/chatstronomy dome-shutter` and `flat-cover`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+enum OpenCloseChoice {
+    Open,
+    Close,
+}
+
+/// Fetch the live dome state and check `command` against it. A close goes
+/// out even when the dome can't be read: it is the weather response, and
+/// the rig refuses it anyway if the dome is really gone.
+async fn dome_accepts(
+    ctx: Context<'_>,
+    telescope: &str,
+    client: &SharedRigSource,
+    command: &RigCommand,
+) -> Result<bool, BotError> {
+    let refusal = match client.get_dome_info().await {
+        Ok(dome) => dome.check_command(command).err(),
+        Err(_) if *command == RigCommand::CloseDomeShutter => None,
+        Err(e) => Some(format!("couldn't fetch dome info: {e}")),
+    };
+    accept_or_reply(ctx, telescope, refusal).await
+}
+
+/// Fetch the live flat panel state and check `command` against it.
+async fn flat_device_accepts(
+    ctx: Context<'_>,
+    telescope: &str,
+    client: &SharedRigSource,
+    command: &RigCommand,
+) -> Result<bool, BotError> {
+    let refusal = match client.get_flat_device_info().await {
+        Ok(panel) => panel.check_command(command).err(),
+        Err(e) => Some(format!("couldn't fetch flat panel info: {e}")),
+    };
+    accept_or_reply(ctx, telescope, refusal).await
+}
+
+/// Open or close the dome shutter or roof (requires confirmation).
+#[poise::command(slash_command, rename = "dome-shutter")]
+async fn dome_shutter(
+    ctx: Context<'_>,
+    #[description = "Open or close the shutter"] action: OpenCloseChoice,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (command, label) = match action {
+        OpenCloseChoice::Open => (RigCommand::OpenDomeShutter, "Open dome shutter"),
+        OpenCloseChoice::Close => (RigCommand::CloseDomeShutter, "Close dome shutter"),
+    };
+    let (name, client) = match resolve_write_or_reply(ctx, telescope, command.kind()).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer_ephemeral().await?;
+    if !dome_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !dome_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command_reporting(ctx, &name, &client, label, command, Some(ReadBack::Dome)).await
+}
+
+/// Park the dome (requires confirmation).
+#[poise::command(slash_command, rename = "dome-park")]
+async fn dome_park(
+    ctx: Context<'_>,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::ParkDome).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let command = RigCommand::ParkDome;
+    ctx.defer_ephemeral().await?;
+    if !dome_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("park the dome on {name}")).await?
+        || !dome_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        "Park dome",
+        command,
+        Some(ReadBack::Dome),
+    )
+    .await
+}
+
+/// Slave the dome to the mount, or stop following it (requires confirmation).
+#[poise::command(slash_command, rename = "dome-slave")]
+async fn dome_slave(
+    ctx: Context<'_>,
+    #[description = "Follow the mount (true) or stop following (false)"] enabled: bool,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SetDomeSlaving).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let label = if enabled {
+        "Slave dome to the mount"
+    } else {
+        "Stop dome slaving"
+    };
+    let command = RigCommand::SetDomeSlaving { enabled };
+    ctx.defer_ephemeral().await?;
+    if !dome_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !dome_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command(ctx, &name, &client, label, command).await
+}
+
+/// Turn the flat panel light on or off (requires confirmation).
+#[poise::command(slash_command, rename = "flat-light")]
+async fn flat_light(
+    ctx: Context<'_>,
+    #[description = "Light on (true) or off (false)"] on: bool,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SetFlatLight).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let label = if on {
+        "Turn flat panel on"
+    } else {
+        "Turn flat panel off"
+    };
+    let command = RigCommand::SetFlatLight { on };
+    ctx.defer_ephemeral().await?;
+    if !flat_device_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !flat_device_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        label,
+        command,
+        Some(ReadBack::FlatDevice),
+    )
+    .await
+}
+
+/// Set the flat panel brightness (requires confirmation).
+#[poise::command(slash_command, rename = "flat-brightness")]
+async fn flat_brightness(
+    ctx: Context<'_>,
+    #[description = "Brightness within the panel's range"] brightness: i32,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SetFlatBrightness).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    let label = format!("Set flat panel brightness to {brightness}");
+    let command = RigCommand::SetFlatBrightness { brightness };
+    ctx.defer_ephemeral().await?;
+    if !flat_device_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !flat_device_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        &label,
+        command,
+        Some(ReadBack::FlatDevice),
+    )
+    .await
+}
+
+/// Open or close the flat panel cover (requires confirmation).
+#[poise::command(slash_command, rename = "flat-cover")]
+async fn flat_cover(
+    ctx: Context<'_>,
+    #[description = "Open or close the cover"] action: OpenCloseChoice,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (command, label) = match action {
+        OpenCloseChoice::Open => (RigCommand::OpenFlatCover, "Open flat panel cover"),
+        OpenCloseChoice::Close => (RigCommand::CloseFlatCover, "Close flat panel cover"),
+    };
+    let (name, client) = match resolve_write_or_reply(ctx, telescope, command.kind()).await {
+        Ok(v) => v,
+        Err(_) => return Ok(()),
+    };
+    ctx.defer_ephemeral().await?;
+    if !flat_device_accepts(ctx, &name, &client, &command).await?
+        || !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || !flat_device_accepts(ctx, &name, &client, &command).await?
+    {
+        return Ok(());
+    }
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        label,
+        command,
+        Some(ReadBack::FlatDevice),
+    )
+    .await
+}
+
+// --- Switch hub ---
+
+/// A string option the user already filled in, read while autocompleting
+/// another option of the same subcommand.
+fn filled_option(ctx: Context<'_>, name: &str) -> Option<String> {
+    fn find(options: Vec<serenity::ResolvedOption<'_>>, name: &str) -> Option<String> {
+        options.into_iter().find_map(|option| match option.value {
+            serenity::ResolvedValue::SubCommand(nested)
+            | serenity::ResolvedValue::SubCommandGroup(nested) => find(nested, name),
+            serenity::ResolvedValue::String(value) if option.name == name => {
+                Some(value.to_string())
+            }
+            _ => None,
+        })
+    }
+    let poise::Context::Application(ctx) = ctx else {
+        return None;
+    };
+    find(ctx.interaction.data.options(), name)
+}
+
+/// Offer the writable ports of the telescope being commanded, with their
+/// current values. Discord shows at most 25 choices.
+async fn autocomplete_switch(ctx: Context<'_>, partial: &str) -> Vec<serenity::AutocompleteChoice> {
+    let telescope = filled_option(ctx, "telescope");
+    let invocation = command_context(ctx).await;
+    let Ok((_, client)) = ctx
+        .data()
+        .resolver
+        .resolve(&invocation, telescope.as_deref())
+    else {
+        return Vec::new();
+    };
+    let Ok(info) = client.get_switch_info().await else {
+        return Vec::new();
+    };
+    let partial = partial.to_ascii_lowercase();
+    info.response
+        .writable_switches
+        .iter()
+        .filter(|switch| {
+            switch.id.to_string().starts_with(&partial)
+                || switch.name.to_ascii_lowercase().contains(&partial)
+        })
+        .take(25)
+        .map(|switch| {
+            serenity::AutocompleteChoice::new(
+                format!(
+                    "#{} {} ({})",
+                    switch.id,
+                    switch.name,
+                    switch.display_value()
+                ),
+                switch.id,
+            )
+        })
+        .collect()
+}
+
+/// Fetch the live switch hub and check `command` against it. Returns the
+/// port's name when the hub accepts the command; otherwise replies with the
+/// reason and returns `None`.
+async fn switch_accepts(
+    ctx: Context<'_>,
+    telescope: &str,
+    client: &SharedRigSource,
+    command: &RigCommand,
+) -> Result<Option<String>, BotError> {
+    let (refusal, port_name) = match client.get_switch_info().await {
+        Ok(info) => {
+            let port_name = match command {
+                RigCommand::SetSwitch { index, .. } => info
+                    .response
+                    .get(*index)
+                    .map(|(switch, _)| switch.name.clone()),
+                _ => None,
+            };
+            (info.check_command(command).err(), port_name)
+        }
+        Err(e) => (Some(format!("couldn't fetch switch info: {e}")), None),
+    };
+    Ok(accept_or_reply(ctx, telescope, refusal)
+        .await?
+        .then(|| port_name.unwrap_or_else(|| "switch".to_string())))
+}
+
+/// Set a switch hub port such as a dew heater or power outlet (requires
+/// confirmation).
+#[poise::command(slash_command)]
+async fn switch(
+    ctx: Context<'_>,
+    #[description = "Switch port"]
+    #[autocomplete = "autocomplete_switch"]
+    port: u32,
+    #[description = "Value within the port's range; 0 or 1 for on/off ports"] value: f64,
+    #[description = "Telescope name"] telescope: Option<String>,
+) -> Result<(), BotError> {
+    let (name, client) =
+        match resolve_write_or_reply(ctx, telescope, RigCommandKind::SetSwitch).await {
+            Ok(v) => v,
+            Err(_) => return Ok(()),
+        };
+    ctx.defer_ephemeral().await?;
+    let command = RigCommand::SetSwitch { index: port, value };
+    let Some(port_name) = switch_accepts(ctx, &name, &client, &command).await? else {
+        return Ok(());
+    };
+    let label = format!("Set {port_name} (#{port}) to {value}");
+    if !confirm_destructive(ctx, &format!("{} on {name}", label.to_lowercase())).await?
+        || switch_accepts(ctx, &name, &client, &command)
+            .await?
+            .is_none()
+    {
+        return Ok(());
+    }
+    run_command_reporting(
+        ctx,
+        &name,
+        &client,
+        &label,
+        command,
+        Some(ReadBack::Switch { index: port }),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::StaticRigResolver;
+    use crate::events::event_types;
+    use crate::simulator::{NightPlan, SimulatedRigSource};
+    use std::collections::{HashMap, HashSet};
+
+    #[tokio::test(start_paused = true)]
+    async fn autofocus_button_runs_only_once_confirmed() {
+        let source: SharedRigSource = Arc::new(SimulatedRigSource::new(NightPlan::default()));
+        source
+            .execute_command(RigCommand::StopSequence)
+            .await
+            .unwrap();
+        let data = BotData {
+            resolver: Arc::new(StaticRigResolver {
+                rig_sources: HashMap::from([("c925".to_string(), source.clone())]),
+                channel_to_telescope: HashMap::new(),
+                write_acl: HashSet::from([7]),
+                horizons: HashMap::new(),
+            }),
+            acknowledgements: tokio::sync::broadcast::channel(1).0,
+        };
+        let invocation = CommandContext {
+            user_id: 7,
+            ..CommandContext::default()
+        };
+        let action = ChatAction::StartAutofocus {
+            telescope: "c925".to_string(),
+        };
+        let autofocus_started = || async {
+            source
+                .get_event_history()
+                .await
+                .unwrap()
+                .response
+                .iter()
+                .any(|event| event.event == event_types::AUTOFOCUS_STARTING)
+        };
+
+        assert!(authorize_action(&data, &invocation, &action).is_ok());
+        let reply = run_action(&data, &invocation, "alice", action.clone(), false).await;
+        assert!(reply.starts_with('❎'), "got: {reply}");
+        assert!(!autofocus_started().await);
+
+        let reply = run_action(&data, &invocation, "alice", action.clone(), true).await;
+        assert!(reply.starts_with('✅'), "got: {reply}");
+        assert!(autofocus_started().await);
+
+        // Someone outside the write ACL is refused before any prompt.
+        let stranger = CommandContext {
+            user_id: 8,
+            ..CommandContext::default()
+        };
+        assert!(authorize_action(&data, &stranger, &action).is_err());
+    }
+}
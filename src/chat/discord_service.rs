@@ -1,22 +1,94 @@
+use super::status_state::{StatusState, session_thread_key};
 use super::{ChatAttachment, ChatMessage, ChatService, ChatTarget};
-use crate::discord::{DiscordWebhook, Embed, colors};
+use crate::discord::{DiscordError, DiscordWebhook, Embed, colors};
 use crate::error::ChatError;
 use async_trait::async_trait;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 /// Discord chat service. Holds an optional default webhook URL; per-telescope
 /// `ChatTarget::discord_webhook_url` overrides it. A new `DiscordWebhook` is
 /// constructed per send so each telescope can route to a different channel.
 pub struct DiscordChatService {
     default_webhook_url: Option<String>,
+    /// Where session threads are remembered, per telescope and webhook.
+    /// Without it, webhook posts never go into threads.
+    status_state: Option<(Arc<Mutex<StatusState>>, PathBuf)>,
 }
 
 impl DiscordChatService {
     pub fn new(default_webhook_url: Option<String>) -> Self {
         Self {
             default_webhook_url,
+            status_state: None,
         }
     }
 
+    /// Remember session threads in `status_state`, saved to `state_file`.
+    pub fn with_status_state(
+        mut self,
+        status_state: Arc<Mutex<StatusState>>,
+        state_file: PathBuf,
+    ) -> Self {
+        self.status_state = Some((status_state, state_file));
+        self
+    }
+
+    /// The state key and open thread for a target using session threads.
+    async fn session_thread(
+        &self,
+        target: &ChatTarget,
+        webhook: &DiscordWebhook,
+    ) -> Option<(String, u64)> {
+        let (state, _) = self.status_state.as_ref()?;
+        let key = session_thread_key(target.session_thread.as_deref()?, webhook.webhook_id()?);
+        let thread = state.lock().await.session_thread(&key)?;
+        Some((key, thread))
+    }
+
+    /// Record (or with `None`, forget) a session thread and save.
+    async fn remember_thread(&self, key: &str, thread: Option<u64>) {
+        let Some((state, state_file)) = &self.status_state else {
+            return;
+        };
+        let mut state = state.lock().await;
+        match thread {
+            Some(thread) => state.set_session_thread(key, thread),
+            None => state.remove_session_thread(key),
+        }
+        if let Err(e) = state.save(state_file) {
+            eprintln!(
+                "Warning: failed to persist status state to {}: {e}",
+                state_file.display()
+            );
+        }
+    }
+
+    /// Run `send` against the target's webhook, inside the telescope's
+    /// session thread when one is open. A thread that rejects the post
+    /// (deleted, or locked) is forgotten and the post goes to the channel.
+    async fn deliver<F, Fut>(&self, target: &ChatTarget, send: F) -> Result<(), ChatError>
+    where
+        F: Fn(DiscordWebhook) -> Fut,
+        Fut: Future<Output = Result<(), DiscordError>>,
+    {
+        let webhook = self.build_webhook(target)?;
+        if let Some((key, thread)) = self.session_thread(target, &webhook).await {
+            match send(webhook.clone().in_thread(thread)).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    eprintln!("Warning: post to session thread {thread} failed: {e}");
+                    self.remember_thread(&key, None).await;
+                }
+            }
+        }
+        send(webhook).await.map_err(|e| ChatError::Discord {
+            message: e.to_string(),
+        })
+    }
+
     fn resolve_url<'a>(&'a self, target: &'a ChatTarget) -> Option<&'a str> {
         target
             .discord_webhook_url
@@ -76,15 +148,12 @@ impl ChatService for DiscordChatService {
         message: &ChatMessage,
         target: &ChatTarget,
     ) -> Result<(), ChatError> {
-        let webhook = self.build_webhook(target)?;
-        let embed = Self::build_embed(message);
-        webhook
-            .execute_with_embed(None, embed)
-            .await
-            .map_err(|e| ChatError::Discord {
-                message: e.to_string(),
-            })?;
-        Ok(())
+        self.deliver(target, |webhook| async move {
+            webhook
+                .execute_with_embed(message.mention.as_deref(), Self::build_embed(message))
+                .await
+        })
+        .await
     }
 
     async fn send_message_with_image(
@@ -94,18 +163,21 @@ impl ChatService for DiscordChatService {
         image_data: &[u8],
         filename: &str,
     ) -> Result<(), ChatError> {
-        let webhook = self.build_webhook(target)?;
-        // Referencing the uploaded image from the embed makes Discord render
-        // the captured-frame preview at full embed width instead of as a small
-        // generic attachment tile.
-        let embed = Self::with_first_image(Self::build_embed(message), [filename]);
-        webhook
-            .execute_with_file(None, Some(embed), image_data, filename)
-            .await
-            .map_err(|e| ChatError::Discord {
-                message: e.to_string(),
-            })?;
-        Ok(())
+        self.deliver(target, |webhook| async move {
+            // Referencing the uploaded image from the embed makes Discord
+            // render the captured-frame preview at full embed width instead
+            // of as a small generic attachment tile.
+            let embed = Self::with_first_image(Self::build_embed(message), [filename]);
+            webhook
+                .execute_with_file(
+                    message.mention.as_deref(),
+                    Some(embed),
+                    image_data,
+                    filename,
+                )
+                .await
+        })
+        .await
     }
 
     async fn send_message_with_attachments(
@@ -117,27 +189,26 @@ impl ChatService for DiscordChatService {
         if attachments.is_empty() {
             return self.send_message(message, target).await;
         }
-        let webhook = self.build_webhook(target)?;
-        // Referencing the first uploaded image from the embed gives captured
-        // frames a full-width Discord preview. Remaining graph attachments are
-        // still uploaded and shown normally.
-        let embed = Self::with_first_image(
-            Self::build_embed(message),
-            attachments
-                .iter()
-                .map(|attachment| attachment.filename.as_str()),
-        );
         let files: Vec<(&[u8], &str)> = attachments
             .iter()
             .map(|a| (a.data.as_slice(), a.filename.as_str()))
             .collect();
-        webhook
-            .execute_with_files(None, Some(embed), &files)
-            .await
-            .map_err(|e| ChatError::Discord {
-                message: e.to_string(),
-            })?;
-        Ok(())
+        let files = files.as_slice();
+        self.deliver(target, |webhook| async move {
+            // Referencing the first uploaded image from the embed gives
+            // captured frames a full-width Discord preview. Remaining graph
+            // attachments are still uploaded and shown normally.
+            let embed = Self::with_first_image(
+                Self::build_embed(message),
+                attachments
+                    .iter()
+                    .map(|attachment| attachment.filename.as_str()),
+            );
+            webhook
+                .execute_with_files(message.mention.as_deref(), Some(embed), files)
+                .await
+        })
+        .await
     }
 
     fn service_name(&self) -> &'static str {
@@ -152,6 +223,46 @@ impl ChatService for DiscordChatService {
         }
         self.resolve_url(target).is_some()
     }
+
+    /// Webhooks cannot open threads in text channels, so this only works
+    /// when the webhook belongs to a forum channel: each session becomes a
+    /// forum post, started by `pointer`. Elsewhere Discord rejects it and
+    /// posts stay in the channel.
+    async fn start_session_thread(
+        &self,
+        target: &ChatTarget,
+        name: &str,
+        pointer: &ChatMessage,
+    ) -> Result<(), ChatError> {
+        let (Some(telescope), Some(_)) = (&target.session_thread, &self.status_state) else {
+            return Ok(());
+        };
+        let webhook = self.build_webhook(target)?;
+        let Some(webhook_id) = webhook.webhook_id() else {
+            return Err(ChatError::Discord {
+                message: "webhook URL has no webhook ID".to_string(),
+            });
+        };
+        let key = session_thread_key(telescope, webhook_id);
+        // Discord caps thread names at 100 characters.
+        let name: String = name.chars().take(100).collect();
+        match webhook
+            .start_forum_thread(&name, Self::build_embed(pointer))
+            .await
+        {
+            Ok(thread) => {
+                self.remember_thread(&key, Some(thread)).await;
+                Ok(())
+            }
+            Err(e) => {
+                // The previous session's thread is over either way.
+                self.remember_thread(&key, None).await;
+                Err(ChatError::Discord {
+                    message: format!("forum post failed: {e}"),
+                })
+            }
+        }
+    }
 }
 
 #[cfg(test)]
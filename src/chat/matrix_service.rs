@@ -1,19 +1,33 @@
-use super::{ChatAttachment, ChatMessage, ChatService, ChatTarget};
+use super::{
+    Acknowledgement, AcknowledgementSender, ChatAction, ChatAttachment, ChatMessage, ChatService,
+    ChatTarget,
+};
 use crate::error::ChatError;
 use async_trait::async_trait;
 use matrix_sdk::{
     Client, EncryptionState, Room,
     config::SyncSettings,
-    ruma::{OwnedRoomId, events::room::message::RoomMessageEventContent},
+    ruma::{
+        OwnedEventId, OwnedRoomId,
+        events::{reaction::OriginalSyncReactionEvent, room::message::RoomMessageEventContent},
+    },
 };
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use url::Url;
 
+/// Alert posts remembered for reaction acknowledgements. Alerts are rare;
+/// anything older than this is long resolved.
+const MAX_ALERT_POSTS: usize = 64;
+
 /// Matrix chat service. Holds one logged-in `Client` shared across every
 /// telescope; per-telescope `ChatTarget::matrix_room_id` selects which room
 /// each post lands in, falling back to `default_room_id`.
 pub struct MatrixChatService {
     client: Client,
     default_room_id: Option<OwnedRoomId>,
+    /// Posted alerts by event ID, so a reaction can acknowledge them.
+    alert_posts: Arc<Mutex<VecDeque<(OwnedEventId, Acknowledgement)>>>,
 }
 
 impl MatrixChatService {
@@ -109,9 +123,41 @@ impl MatrixChatService {
         Ok(Self {
             client,
             default_room_id,
+            alert_posts: Arc::new(Mutex::new(VecDeque::new())),
         })
     }
 
+    /// Treat any reaction to an alert post as acknowledging it, reported
+    /// with the reacting user's Matrix ID.
+    pub fn with_acknowledgements(self, acknowledgements: AcknowledgementSender) -> Self {
+        let alert_posts = self.alert_posts.clone();
+        let own_user = self.client.user_id().map(ToOwned::to_owned);
+        self.client
+            .add_event_handler(move |event: OriginalSyncReactionEvent| {
+                let alert_posts = alert_posts.clone();
+                let acknowledgements = acknowledgements.clone();
+                let own_user = own_user.clone();
+                async move {
+                    if own_user.as_ref() == Some(&event.sender) {
+                        return;
+                    }
+                    let acknowledgement = alert_posts
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .iter()
+                        .find(|(id, _)| *id == event.content.relates_to.event_id)
+                        .map(|(_, alert)| Acknowledgement {
+                            user: event.sender.to_string(),
+                            ..alert.clone()
+                        });
+                    if let Some(acknowledgement) = acknowledgement {
+                        let _ = acknowledgements.send(acknowledgement);
+                    }
+                }
+            });
+        self
+    }
+
     fn resolve_room_id(&self, target: &ChatTarget) -> Option<OwnedRoomId> {
         if let Some(s) = &target.matrix_room_id {
             // Per-telescope override
@@ -137,8 +183,40 @@ impl MatrixChatService {
             })
     }
 
+    /// Post the message text, remembering it if it carries an alert.
+    async fn send_notice(&self, room: &Room, message: &ChatMessage) -> Result<(), ChatError> {
+        let content = RoomMessageEventContent::notice_markdown(Self::format_message(message));
+        let sent = room
+            .send(content)
+            .await
+            .map_err(|e| ChatError::MessageSend {
+                service_name: "Matrix".to_string(),
+                reason: e.to_string(),
+            })?;
+        let mut alert_posts = self.alert_posts.lock().unwrap_or_else(|e| e.into_inner());
+        for action in &message.actions {
+            if let ChatAction::Acknowledge { telescope, alert } = action {
+                alert_posts.push_back((
+                    sent.response.event_id.clone(),
+                    Acknowledgement {
+                        telescope: telescope.clone(),
+                        alert: alert.clone(),
+                        user: String::new(),
+                    },
+                ));
+            }
+        }
+        while alert_posts.len() > MAX_ALERT_POSTS {
+            alert_posts.pop_front();
+        }
+        Ok(())
+    }
+
     fn format_message(message: &ChatMessage) -> String {
-        let mut formatted = format!("**{}**\n\n", message.title);
+        let mut formatted = match &message.mention {
+            Some(mention) => format!("{mention} **{}**\n\n", message.title),
+            None => format!("**{}**\n\n", message.title),
+        };
         if !message.fields.is_empty() {
             for field in &message.fields {
                 formatted.push_str(&format!("**{}**: {}\n", field.name, field.value));
@@ -148,6 +226,13 @@ impl MatrixChatService {
         if let Some(footer) = &message.footer {
             formatted.push_str(&format!("_{}_", footer));
         }
+        if message
+            .actions
+            .iter()
+            .any(|action| matches!(action, ChatAction::Acknowledge { .. }))
+        {
+            formatted.push_str("\n\nReact to this message to acknowledge.");
+        }
         formatted
     }
 }
@@ -160,13 +245,7 @@ impl ChatService for MatrixChatService {
         target: &ChatTarget,
     ) -> Result<(), ChatError> {
         let room = self.get_room(target).await?;
-        let content = RoomMessageEventContent::notice_markdown(Self::format_message(message));
-        room.send(content)
-            .await
-            .map_err(|e| ChatError::MessageSend {
-                service_name: "Matrix".to_string(),
-                reason: e.to_string(),
-            })?;
+        self.send_notice(&room, message).await?;
         Ok(())
     }
 
@@ -179,13 +258,7 @@ impl ChatService for MatrixChatService {
     ) -> Result<(), ChatError> {
         let room = self.get_room(target).await?;
 
-        let content = RoomMessageEventContent::notice_markdown(Self::format_message(message));
-        room.send(content)
-            .await
-            .map_err(|e| ChatError::MessageSend {
-                service_name: "Matrix".to_string(),
-                reason: e.to_string(),
-            })?;
+        self.send_notice(&room, message).await?;
 
         let mime_type = if filename.ends_with(".jpg") || filename.ends_with(".jpeg") {
             "image/jpeg"
@@ -220,13 +293,7 @@ impl ChatService for MatrixChatService {
         }
         let room = self.get_room(target).await?;
 
-        let content = RoomMessageEventContent::notice_markdown(Self::format_message(message));
-        room.send(content)
-            .await
-            .map_err(|e| ChatError::MessageSend {
-                service_name: "Matrix".to_string(),
-                reason: e.to_string(),
-            })?;
+        self.send_notice(&room, message).await?;
 
         for attachment in attachments {
             let mime_type = if attachment.filename.ends_with(".png") {
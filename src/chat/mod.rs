@@ -14,6 +14,7 @@ use crate::error::ChatError;
 use crate::source::SharedRigSource;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
 /// Represents a field in a chat message
 #[derive(Debug, Clone)]
@@ -31,6 +32,10 @@ pub struct ChatMessage {
     pub fields: Vec<ChatField>,
     pub footer: Option<String>,
     pub timestamp: Option<String>,
+    /// Posted as plain text alongside the embed so it actually notifies.
+    pub mention: Option<String>,
+    /// Buttons offered under the message where the service supports them.
+    pub actions: Vec<ChatAction>,
 }
 
 impl ChatMessage {
@@ -41,6 +46,8 @@ impl ChatMessage {
             fields: Vec::new(),
             footer: None,
             timestamp: Some(chrono::Utc::now().to_rfc3339()),
+            mention: None,
+            actions: Vec::new(),
         }
     }
 
@@ -62,8 +69,123 @@ impl ChatMessage {
         self.footer = Some(text.to_string());
         self
     }
+
+    pub fn mention(mut self, mention: &str) -> Self {
+        self.mention = Some(mention.to_string());
+        self
+    }
+
+    pub fn action(mut self, action: ChatAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+}
+
+/// A one-click follow-up attached to a notification. Clicking runs through
+/// the same authorization as the equivalent slash command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatAction {
+    /// Start an autofocus run on the named telescope.
+    StartAutofocus { telescope: String },
+    /// Take ownership of an open critical alert.
+    Acknowledge { telescope: String, alert: String },
 }
 
+impl ChatAction {
+    const PREFIX: &'static str = "chatstronomy-action:";
+    /// Discord rejects longer component IDs.
+    const MAX_CUSTOM_ID: usize = 100;
+
+    /// Button text.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::StartAutofocus { .. } => "Run autofocus",
+            Self::Acknowledge { .. } => "Acknowledge",
+        }
+    }
+
+    /// What the clicker confirms before the action runs, for actions that
+    /// actuate the rig. Worded as the equivalent slash command words it.
+    pub fn confirmation(&self) -> Option<String> {
+        match self {
+            Self::StartAutofocus { telescope } => Some(format!("autofocus run on {telescope}")),
+            Self::Acknowledge { .. } => None,
+        }
+    }
+
+    /// The component ID the action round-trips through. A telescope name
+    /// that would push it past Discord's 100-character limit is replaced by
+    /// a short key; [`ChatAction::with_telescope_from`] turns it back.
+    pub fn custom_id(&self) -> String {
+        let (head, telescope) = match self {
+            Self::StartAutofocus { telescope } => {
+                (format!("{}autofocus:", Self::PREFIX), telescope)
+            }
+            Self::Acknowledge { telescope, alert } => {
+                (format!("{}ack:{alert}:", Self::PREFIX), telescope)
+            }
+        };
+        if head.len() + telescope.len() <= Self::MAX_CUSTOM_ID {
+            format!("{head}{telescope}")
+        } else {
+            format!("{head}{}", telescope_key(telescope))
+        }
+    }
+
+    /// Resolve a parsed action's telescope key against the `names` the
+    /// clicker can reach. Actions that carry a name come back unchanged.
+    pub fn with_telescope_from(mut self, names: &[String]) -> Self {
+        let (Self::StartAutofocus { telescope } | Self::Acknowledge { telescope, .. }) = &mut self;
+        if telescope.starts_with(TELESCOPE_KEY_MARKER)
+            && let Some(name) = names.iter().find(|name| telescope_key(name) == *telescope)
+        {
+            *telescope = name.clone();
+        }
+        self
+    }
+
+    /// Parse a component ID produced by [`ChatAction::custom_id`].
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        let (action, telescope) = custom_id.strip_prefix(Self::PREFIX)?.split_once(':')?;
+        match action {
+            "autofocus" if !telescope.is_empty() => Some(Self::StartAutofocus {
+                telescope: telescope.to_string(),
+            }),
+            "ack" => {
+                let (alert, telescope) = telescope.split_once(':')?;
+                (!alert.is_empty() && !telescope.is_empty()).then(|| Self::Acknowledge {
+                    telescope: telescope.to_string(),
+                    alert: alert.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+const TELESCOPE_KEY_MARKER: char = '~';
+
+/// A fixed-length stand-in for a telescope name in a component ID: 64-bit
+/// FNV-1a, stable across restarts so buttons on old posts keep working.
+fn telescope_key(name: &str) -> String {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{TELESCOPE_KEY_MARKER}{hash:016x}")
+}
+
+/// Someone in chat taking ownership of an open critical alert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acknowledgement {
+    pub telescope: String,
+    pub alert: String,
+    /// Who acknowledged, as the chat service names them.
+    pub user: String,
+}
+
+/// Carries acknowledgements from the chat services to every updater.
+pub type AcknowledgementSender = broadcast::Sender<Acknowledgement>;
+
 /// Per-telescope routing overrides. Each field, when `Some`, redirects this
 /// telescope's posts away from the shared default destination configured on
 /// the corresponding `ChatService`.
@@ -80,6 +202,10 @@ pub struct ChatTarget {
     /// and cross-server destinations on the hub). The bot fans out to
     /// `discord_channel_id` plus all of these, deduplicated.
     pub discord_channel_ids: Vec<u64>,
+    /// When set, posts go into this telescope's current session thread in
+    /// each destination rather than the channel itself. Keyed by telescope
+    /// so rigs sharing a channel keep threads of their own.
+    pub session_thread: Option<String>,
 }
 
 #[cfg(test)]
@@ -89,10 +215,9 @@ mod chat_target_tests {
     #[test]
     fn all_discord_channels_dedupes_and_merges() {
         let target = ChatTarget {
-            discord_webhook_url: None,
-            matrix_room_id: None,
             discord_channel_id: Some(1),
             discord_channel_ids: vec![2, 1, 3],
+            ..ChatTarget::default()
         };
         assert_eq!(target.all_discord_channels(), vec![1, 2, 3]);
         // The hub's targets carry only the list; it must still count as a
@@ -103,6 +228,54 @@ mod chat_target_tests {
         };
         assert_eq!(list_only.all_discord_channels(), vec![7]);
     }
+
+    #[test]
+    fn actions_round_trip_through_component_ids() {
+        let action = ChatAction::StartAutofocus {
+            telescope: "Scope: West".to_string(),
+        };
+        assert_eq!(ChatAction::parse(&action.custom_id()), Some(action));
+        assert_eq!(ChatAction::parse("chatstronomy-confirm"), None);
+        assert_eq!(ChatAction::parse("chatstronomy-action:autofocus:"), None);
+        let ack = ChatAction::Acknowledge {
+            telescope: "Scope: West".to_string(),
+            alert: "offline-1760000000000".to_string(),
+        };
+        assert_eq!(ChatAction::parse(&ack.custom_id()), Some(ack));
+    }
+
+    #[test]
+    fn long_telescope_names_fit_the_component_id_limit() {
+        let long = format!("Observatory {} Dome", "West Pier ".repeat(12));
+        let names = vec!["c925".to_string(), long.clone()];
+        for action in [
+            ChatAction::StartAutofocus {
+                telescope: long.clone(),
+            },
+            ChatAction::Acknowledge {
+                telescope: long.clone(),
+                alert: "safety-unsafe-1760000000000".to_string(),
+            },
+        ] {
+            let custom_id = action.custom_id();
+            assert!(custom_id.len() <= 100, "{} chars", custom_id.len());
+            let parsed = ChatAction::parse(&custom_id).unwrap();
+            assert_ne!(parsed, action);
+            assert_eq!(parsed.with_telescope_from(&names), action);
+        }
+
+        // Names that fit stay readable and need no lookup.
+        let short = ChatAction::StartAutofocus {
+            telescope: "c925".to_string(),
+        };
+        assert_eq!(short.custom_id(), "chatstronomy-action:autofocus:c925");
+        assert_eq!(
+            ChatAction::parse(&short.custom_id())
+                .unwrap()
+                .with_telescope_from(&[]),
+            short
+        );
+    }
 }
 
 impl ChatTarget {
@@ -201,7 +374,7 @@ pub struct DiscordBotConfig {
     pub write_acl: Vec<u64>,
 }
 
-fn default_state_file() -> String {
+pub(crate) fn default_state_file() -> String {
     "./chatstronomy-state.json".to_string()
 }
 
@@ -219,15 +392,24 @@ pub struct TelescopeChatOverrides {
     /// channel; the webhook path is ignored for it.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub discord_channel_id: Option<u64>,
+    /// Open a Discord thread for each sequence and Target Scheduler target
+    /// and post the session's notifications into it. Through the bot, a
+    /// one-line pointer in the channel starts the thread; a webhook can
+    /// only open threads as posts in a forum channel.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub session_threads: bool,
 }
 
 impl TelescopeChatOverrides {
+    /// The telescope's destination. Session threads are switched on
+    /// per notification route by the updater, not here.
     pub fn to_chat_target(&self) -> ChatTarget {
         ChatTarget {
             discord_webhook_url: self.discord_webhook_url.clone(),
             matrix_room_id: self.matrix_room_id.clone(),
             discord_channel_id: self.discord_channel_id,
             discord_channel_ids: Vec::new(),
+            session_thread: None,
         }
     }
 }
@@ -300,21 +482,46 @@ pub trait ChatService: Send + Sync {
     fn supports_status_upsert(&self) -> bool {
         false
     }
+
+    /// Open a new session thread named `name` for a target with
+    /// `session_thread` set, announcing it with `pointer`. Later posts to
+    /// the target go into the thread. Default implementation is a no-op
+    /// for services without threads (Matrix).
+    async fn start_session_thread(
+        &self,
+        _target: &ChatTarget,
+        _name: &str,
+        _pointer: &ChatMessage,
+    ) -> Result<(), ChatError> {
+        Ok(())
+    }
 }
 
 /// Chat service manager. One instance is shared across all telescopes; the
 /// `ChatTarget` passed to each send selects the per-telescope destination.
 pub struct ChatServiceManager {
     services: Vec<Box<dyn ChatService>>,
+    acknowledgements: AcknowledgementSender,
 }
 
 impl ChatServiceManager {
     pub fn new() -> Self {
         Self {
             services: Vec::new(),
+            acknowledgements: broadcast::channel(64).0,
         }
     }
 
+    /// Hand to services that take acknowledgements from their users.
+    pub fn acknowledgement_sender(&self) -> AcknowledgementSender {
+        self.acknowledgements.clone()
+    }
+
+    /// Acknowledgements from every service, for one updater.
+    pub fn subscribe_acknowledgements(&self) -> broadcast::Receiver<Acknowledgement> {
+        self.acknowledgements.subscribe()
+    }
+
     pub fn add_service(&mut self, service: Box<dyn ChatService>) {
         self.services.push(service);
     }
@@ -345,6 +552,28 @@ impl ChatServiceManager {
             .any(|s| s.supports_status_upsert() && s.can_route(target))
     }
 
+    /// Open a session thread on every service that can reach the target.
+    /// A service that fails keeps posting to the channel itself.
+    pub async fn start_session_thread(
+        &self,
+        target: &ChatTarget,
+        name: &str,
+        pointer: &ChatMessage,
+    ) {
+        for service in &self.services {
+            if !service.can_route(target) {
+                continue;
+            }
+            if let Err(e) = service.start_session_thread(target, name, pointer).await {
+                eprintln!(
+                    "Failed to open session thread on {}: {}",
+                    service.service_name(),
+                    e
+                );
+            }
+        }
+    }
+
     pub async fn send_message(&self, message: &ChatMessage, target: &ChatTarget) {
         for service in &self.services {
             if !service.can_route(target) {
@@ -5,6 +5,7 @@
 //! command set serves both a self-hosted bot (static config maps) and the
 //! hub (database-backed, per-guild tenancy, live rig connections).
 
+use crate::horizon::HorizonProfile;
 use crate::source::SharedRigSource;
 use std::collections::{HashMap, HashSet};
 
@@ -32,6 +33,10 @@ pub trait RigResolver: Send + Sync {
         override_name: Option<&str>,
     ) -> Result<(String, SharedRigSource), String>;
 
+    /// Every telescope this invocation can name, for turning a clicked
+    /// button's telescope key back into its name.
+    fn telescope_names(&self, invocation: &CommandContext) -> Vec<String>;
+
     /// May this user run write commands against this telescope? The error is
     /// a user-facing message.
     fn write_allowed(&self, invocation: &CommandContext, telescope: &str) -> Result<(), String>;
@@ -50,6 +55,12 @@ pub trait RigResolver: Send + Sync {
         self.write_allowed(invocation, &resolved.0)?;
         Ok(resolved)
     }
+
+    /// The telescope's configured local horizon, for charts. None by
+    /// default.
+    fn horizon(&self, _telescope: &str) -> Option<HorizonProfile> {
+        None
+    }
 }
 
 /// Config-file-backed resolver used by the self-hosted bot: fixed telescope
@@ -61,6 +72,8 @@ pub struct StaticRigResolver {
     pub channel_to_telescope: HashMap<u64, String>,
     /// Discord user IDs allowed to invoke write commands.
     pub write_acl: HashSet<u64>,
+    /// Telescope name -> local horizon, for those that configure one.
+    pub horizons: HashMap<String, HorizonProfile>,
 }
 
 impl StaticRigResolver {
@@ -104,6 +117,10 @@ impl RigResolver for StaticRigResolver {
         ))
     }
 
+    fn telescope_names(&self, _invocation: &CommandContext) -> Vec<String> {
+        self.rig_sources.keys().cloned().collect()
+    }
+
     fn write_allowed(&self, invocation: &CommandContext, _telescope: &str) -> Result<(), String> {
         if self.write_acl.contains(&invocation.user_id) {
             return Ok(());
@@ -114,6 +131,10 @@ impl RigResolver for StaticRigResolver {
             invocation.user_id
         ))
     }
+
+    fn horizon(&self, telescope: &str) -> Option<HorizonProfile> {
+        self.horizons.get(telescope).cloned()
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +223,31 @@ mod tests {
         ) -> crate::source::RigSourceResult<crate::focuser::FocuserInfoResponse> {
             unused()
         }
+        async fn get_dome_info(
+            &self,
+        ) -> crate::source::RigSourceResult<crate::dome::DomeInfoResponse> {
+            unused()
+        }
+        async fn get_flat_device_info(
+            &self,
+        ) -> crate::source::RigSourceResult<crate::flat_device::FlatDeviceInfoResponse> {
+            unused()
+        }
+        async fn get_weather_info(
+            &self,
+        ) -> crate::source::RigSourceResult<crate::weather::WeatherInfoResponse> {
+            unused()
+        }
+        async fn get_safety_info(
+            &self,
+        ) -> crate::source::RigSourceResult<crate::safety::SafetyInfoResponse> {
+            unused()
+        }
+        async fn get_switch_info(
+            &self,
+        ) -> crate::source::RigSourceResult<crate::switch::SwitchInfoResponse> {
+            unused()
+        }
         async fn execute_command(
             &self,
             _: RigCommand,
@@ -216,6 +262,7 @@ mod tests {
             rig_sources: HashMap::from([("c925".to_string(), source)]),
             channel_to_telescope: HashMap::from([(42, "c925".to_string())]),
             write_acl: HashSet::from([7]),
+            horizons: HashMap::new(),
         }
     }
 
@@ -6,6 +6,9 @@
 //! with fresh posts. Stored at `chat.discord_bot.state_file` (default
 //! `./chatstronomy-state.json`).
 //!
+//! The same file remembers each telescope's current session thread per
+//! destination, so a restart mid-session keeps posting into that thread.
+//!
 //! Atomic writes: serialize to a tempfile alongside the target, then
 //! rename in place. A crash during write leaves the previous valid file
 //! intact.
@@ -15,6 +18,8 @@ use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct StatusMessage {
@@ -27,6 +32,15 @@ pub struct StatusState {
     /// telescope name -> live status message reference
     #[serde(default)]
     pub status_messages: HashMap<String, StatusMessage>,
+    /// "telescope@destination" -> the telescope's open session thread there
+    #[serde(default)]
+    pub session_threads: HashMap<String, u64>,
+}
+
+/// Key for one telescope's session thread in one destination (a channel
+/// ID, or a webhook ID).
+pub fn session_thread_key(telescope: &str, destination: u64) -> String {
+    format!("{telescope}@{destination}")
 }
 
 impl StatusState {
@@ -40,6 +54,19 @@ impl StatusState {
         }
     }
 
+    /// Load state to share between the services that persist to `path`.
+    /// An unreadable file is logged and replaced with fresh state.
+    pub fn load_shared(path: &Path) -> Arc<Mutex<Self>> {
+        let state = Self::load(path).unwrap_or_else(|e| {
+            eprintln!(
+                "Warning: could not load status state from {}: {e} — starting fresh",
+                path.display()
+            );
+            Self::default()
+        });
+        Arc::new(Mutex::new(state))
+    }
+
     /// Atomic save: write to `<path>.tmp` then rename to `path`. The
     /// rename is atomic on POSIX and on Windows for files in the same
     /// directory.
@@ -67,6 +94,18 @@ impl StatusState {
     pub fn remove(&mut self, telescope: &str) {
         self.status_messages.remove(telescope);
     }
+
+    pub fn session_thread(&self, key: &str) -> Option<u64> {
+        self.session_threads.get(key).copied()
+    }
+
+    pub fn set_session_thread(&mut self, key: &str, thread_id: u64) {
+        self.session_threads.insert(key.to_string(), thread_id);
+    }
+
+    pub fn remove_session_thread(&mut self, key: &str) {
+        self.session_threads.remove(key);
+    }
 }
 
 #[cfg(test)]
@@ -126,4 +165,24 @@ mod tests {
         state.remove("a");
         assert!(state.get("a").is_none());
     }
+
+    #[test]
+    fn test_session_threads_roundtrip_and_default() {
+        let p = tmp_path("threads");
+        let mut state = StatusState::default();
+        let key = session_thread_key("c925", 111);
+        state.set_session_thread(&key, 555);
+        state.save(&p).unwrap();
+        let loaded = StatusState::load(&p).unwrap();
+        assert_eq!(loaded.session_thread(&key), Some(555));
+        assert_eq!(
+            loaded.session_thread(&session_thread_key("c925", 222)),
+            None
+        );
+        let _ = fs::remove_file(&p);
+
+        // Files written before session threads existed still load.
+        let legacy: StatusState = serde_json::from_str(r#"{"status_messages": {}}"#).unwrap();
+        assert!(legacy.session_threads.is_empty());
+    }
 }
@@ -1,19 +1,37 @@
-use crate::autofocus::AutofocusResponse;
+use crate::alerts::{AlertDesk, AlertKind, CriticalAlertConfig};
+use crate::autofocus::{AutofocusData, AutofocusResponse, TemperatureCompensation};
 use crate::camera::CameraInfo;
-use crate::chat::{ChatAttachment, ChatField, ChatMessage, ChatServiceManager, ChatTarget};
+use crate::chat::{
+    Acknowledgement, ChatAction, ChatAttachment, ChatField, ChatMessage, ChatServiceManager,
+    ChatTarget,
+};
+use crate::checkpoint::CheckpointStore;
+use crate::coalescing::{Coalescer, CoalescingConfig, Held};
 use crate::discord::colors;
+use crate::ephemeris::{
+    DEFAULT_ALTITUDE_LIMIT, Equatorial, MoonInfo, Site, Visibility, twilight_summary,
+};
 use crate::events::{Event, EventDetails, FilterInfo, TargetCoordinates, event_types};
+use crate::horizon::HorizonProfile;
+use crate::image_quality::{ImageQualityConfig, QualityFinding, QualityMonitor};
 use crate::images::ImageMetadata;
+use crate::notification_rules::{Notice, NotificationRoute, NotificationRules, RuleAction};
+use crate::progress::IntegrationProgress;
 use crate::sequence::{
     SequenceOperation, SequenceOperationKind, SequenceResponse, extract_current_target,
     extract_current_target_with_delivery, extract_meridian_flip_time, extract_sequence_operations,
     meridian_flip_time_formatted_with_clock,
 };
-use crate::source::SharedRigSource;
-use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
-use std::collections::{BTreeMap, HashMap};
-use std::sync::Arc;
+use crate::session_report::SessionReport;
+use crate::source::{DeviceState, RigCommandKind, RigDevice, RigUpdate, SharedRigSource};
+use crate::timestamps::parse_nina_timestamp;
+use crate::weather::{ConditionAlert, WeatherAlertConfig, WeatherInfo};
+use chrono::{DateTime, FixedOffset, Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tokio::time::sleep;
 
 /// Default first-retry wait when a telescope is unreachable at startup. A rig
@@ -30,6 +48,19 @@ pub(crate) const DEFAULT_RECONNECT_MAX: Duration = Duration::from_secs(600);
 /// are spaced out (≈60s, then 120s, …), so a small count still means minutes.
 const OFFLINE_FAILURE_THRESHOLD: u32 = 3;
 
+/// How often a rig that pushes updates is still polled in full. Pushes carry
+/// the steady state; this pass catches anything a lagged subscriber missed
+/// and keeps reachability tracking alive.
+const STREAM_RESYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A checkpoint older than this is from an earlier night. The rig's history
+/// has moved on since, and replaying all of it as missed would flood chat,
+/// so the updater starts from a fresh baseline instead.
+const CHECKPOINT_MAX_AGE: chrono::Duration = chrono::Duration::hours(12);
+
+/// How often the live status's sky rows are refreshed.
+const SKY_REFRESH_SECONDS: i64 = 600;
+
 /// Double `current`, capped at `max` — but never below `initial`, so a
 /// misconfigured `max < initial` can't shrink the wait. Shared by the startup
 /// baseline retry and the mid-run reconnect loop so both back off identically.
@@ -59,7 +90,7 @@ fn format_duration(duration: chrono::Duration) -> String {
 }
 
 /// Information about the current observation target
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct TargetInfo {
     name: String,
     source: TargetSource,
@@ -68,13 +99,13 @@ struct TargetInfo {
     rotation: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 enum TargetSource {
     Sequence,
     TsTargetStart,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct TrackedSequenceOperation {
     operation: SequenceOperation,
     started_at: DateTime<Utc>,
@@ -220,6 +251,9 @@ struct BoundedSeenSet {
     order: BTreeMap<u64, String>,
     next_seq: u64,
     capacity: usize,
+    /// Count of keys added (not refreshed), so a checkpoint is only written
+    /// when something new was seen.
+    added: u64,
 }
 
 impl BoundedSeenSet {
@@ -229,9 +263,28 @@ impl BoundedSeenSet {
             order: BTreeMap::new(),
             next_seq: 0,
             capacity: capacity.max(1),
+            added: 0,
         }
     }
 
+    /// A set holding `keys`, oldest first.
+    fn from_keys(capacity: usize, keys: Vec<String>) -> Self {
+        let mut set = Self::new(capacity);
+        for key in keys {
+            set.insert(key);
+        }
+        set
+    }
+
+    /// The keys, least recently seen first.
+    fn keys(&self) -> Vec<String> {
+        self.order.values().cloned().collect()
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.seen.contains_key(key)
+    }
+
     /// Record `key`, returning true when it had already been recorded. A
     /// repeat sighting refreshes the key's position so it outlives keys that
     /// have genuinely fallen out of the source history.
@@ -246,6 +299,7 @@ impl BoundedSeenSet {
         }
 
         self.order.insert(seq, key);
+        self.added += 1;
         while self.seen.len() > self.capacity {
             let Some((_, evicted)) = self.order.pop_first() else {
                 break;
@@ -288,6 +342,24 @@ fn claim_plate_solve_output(
 /// it is still visible in the source history.
 const SEEN_SET_CAPACITY: usize = 20_000;
 
+/// Autofocus runs kept for the night's temperature trend.
+const AUTOFOCUS_HISTORY: usize = 100;
+
+/// What an updater has already announced, saved between runs so a restart
+/// delivers exactly what happened while it was down.
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    saved_at: DateTime<Utc>,
+    events_seen: Vec<String>,
+    images_seen: Vec<String>,
+    plate_solve_outputs_seen: Vec<String>,
+    current_target: Option<TargetInfo>,
+    last_filter: Option<FilterInfo>,
+    sequence_operations: Vec<TrackedSequenceOperation>,
+    #[serde(default)]
+    autofocus_runs: Vec<AutofocusData>,
+}
+
 /// State management for the chat updater
 struct UpdaterState {
     events_seen: BoundedSeenSet,
@@ -302,6 +374,30 @@ struct UpdaterState {
     last_mount_event: Option<String>,
     /// Latest guider-state event we've observed (START, STOP, DITHER).
     last_guider_event: Option<String>,
+    /// Latest dome or flat panel event; the live status re-reads both
+    /// devices when it changes.
+    last_enclosure_event: Option<String>,
+    /// Weather thresholds currently exceeded, so each is announced once.
+    condition_alerts: BTreeSet<ConditionAlert>,
+    /// The safety monitor's last verdict; `None` until one is connected.
+    safe: Option<bool>,
+    /// Weather alerts raised since the last session report.
+    condition_alerts_raised: usize,
+    /// Rolling image-quality baselines per target and filter.
+    quality: QualityMonitor,
+    /// Critical alerts awaiting acknowledgement or recovery.
+    alerts: AlertDesk,
+    /// Time of the newest frame the last session report covered, so a
+    /// finish followed by a morning park reports once.
+    last_report_end: Option<DateTime<FixedOffset>>,
+    /// Tonight's autofocus results, oldest first, for the temperature
+    /// chart; cleared by the session report.
+    autofocus_runs: Vec<AutofocusData>,
+    /// Whether a session thread was opened this run, and the target it is
+    /// for; a sequence that starts before any target is known opens one
+    /// the first Target Scheduler target then adopts.
+    session_thread_open: bool,
+    session_thread_target: Option<String>,
     /// True if the last sequence event was STARTING (not FINISHED).
     sequence_running: bool,
     /// Active TS-WAITSTART wait-end time, if NINA is currently waiting.
@@ -319,6 +415,8 @@ struct UpdaterState {
     /// `upsert_status` call when nothing meaningful has changed since the
     /// previous poll cycle.
     last_status_fingerprint: Option<String>,
+    /// The site the mount last reported, for the sky rows of the live status.
+    site: Option<Site>,
     /// Whether the telescope is currently *reported* as connected. Drives the
     /// offline/reconnect logging and chat alerts. Set true once the startup
     /// baseline succeeds; flips to false only after the failure debounce.
@@ -341,12 +439,23 @@ impl UpdaterState {
             last_filter: None,
             last_mount_event: None,
             last_guider_event: None,
+            last_enclosure_event: None,
+            condition_alerts: BTreeSet::new(),
+            safe: None,
+            condition_alerts_raised: 0,
+            quality: QualityMonitor::default(),
+            alerts: AlertDesk::default(),
+            last_report_end: None,
+            autofocus_runs: Vec::new(),
+            session_thread_open: false,
+            session_thread_target: None,
             sequence_running: false,
             wait_until: None,
             center_event_seen_at: None,
             sequence_operations: HashMap::new(),
             plate_solve_outputs_seen: BoundedSeenSet::new(SEEN_SET_CAPACITY),
             last_status_fingerprint: None,
+            site: None,
             connected: false,
             consecutive_failures: 0,
         }
@@ -370,6 +479,9 @@ impl UpdaterState {
             .unwrap_or("");
         let mount = self.last_mount_event.as_deref().unwrap_or("");
         let guider = self.last_guider_event.as_deref().unwrap_or("");
+        let enclosure = self.last_enclosure_event.as_deref().unwrap_or("");
+        let conditions = format!("{:?}{:?}", self.safe, self.condition_alerts);
+        let alerts = self.alerts.summary().unwrap_or_default();
         let wait_minutes = self
             .wait_until
             .map(|end| {
@@ -422,13 +534,72 @@ impl UpdaterState {
             .meridian_flip_time
             .map(|h| (h * 60.0).round() as i64)
             .unwrap_or(-1);
+        // Twilight countdowns and the target's altitude move steadily; a
+        // ten-minute bucket keeps them fresh without an edit every cycle.
+        let sky = self
+            .site
+            .map_or(-1, |_| Utc::now().timestamp() / SKY_REFRESH_SECONDS);
         format!(
-            "t={target}|f={filter}|m={mount}|g={guider}|w={wait_minutes}|sr={}|flip={flip_minutes}|ops={}",
+            "t={target}|f={filter}|m={mount}|g={guider}|e={enclosure}|c={conditions}|a={alerts}|w={wait_minutes}|sr={}|flip={flip_minutes}|sky={sky}|ops={}",
             self.sequence_running,
             operations.join(",")
         )
     }
 
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            saved_at: Utc::now(),
+            events_seen: self.events_seen.keys(),
+            images_seen: self.images_seen.keys(),
+            plate_solve_outputs_seen: self.plate_solve_outputs_seen.keys(),
+            current_target: self.current_target.clone(),
+            last_filter: self.last_filter.clone(),
+            sequence_operations: self.sequence_operations.values().cloned().collect(),
+            autofocus_runs: self.autofocus_runs.clone(),
+        }
+    }
+
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        self.events_seen = BoundedSeenSet::from_keys(SEEN_SET_CAPACITY, checkpoint.events_seen);
+        self.images_seen = BoundedSeenSet::from_keys(SEEN_SET_CAPACITY, checkpoint.images_seen);
+        self.plate_solve_outputs_seen =
+            BoundedSeenSet::from_keys(SEEN_SET_CAPACITY, checkpoint.plate_solve_outputs_seen);
+        self.current_target = checkpoint.current_target;
+        self.last_filter = checkpoint.last_filter;
+        self.sequence_operations = checkpoint
+            .sequence_operations
+            .into_iter()
+            .map(|tracked| (tracked.operation.key.clone(), tracked))
+            .collect();
+        self.autofocus_runs = checkpoint.autofocus_runs;
+    }
+
+    /// Changes whenever the checkpoint would: a new key, a target or
+    /// filter change, or a tracked operation's progress.
+    fn checkpoint_fingerprint(&self) -> String {
+        let mut operations = self
+            .sequence_operations
+            .iter()
+            .map(|(key, tracked)| {
+                format!(
+                    "{key}:{}:{}:{:?}",
+                    tracked.operation.status, tracked.last_milestone, tracked.last_output_key
+                )
+            })
+            .collect::<Vec<_>>();
+        operations.sort();
+        format!(
+            "{}|{}|{}|{:?}|{:?}|{}|{:?}",
+            self.events_seen.added,
+            self.images_seen.added,
+            self.plate_solve_outputs_seen.added,
+            self.current_target.as_ref().map(|t| &t.name),
+            self.last_filter.as_ref().map(|f| &f.name),
+            operations.join(","),
+            self.autofocus_runs.last().map(|run| &run.timestamp)
+        )
+    }
+
     fn event_key(event: &Event) -> String {
         format!("{}|{}|{:?}", event.time, event.event, event.details)
     }
@@ -468,6 +639,63 @@ pub struct ChatUpdater {
     /// restart on every deploy, rig reconnect, and config change, and it
     /// announces scope presence from the connection layer instead.
     announce_lifecycle: bool,
+    weather_alerts: WeatherAlertConfig,
+    image_quality: ImageQualityConfig,
+    critical_alerts: CriticalAlertConfig,
+    coalescing: CoalescingConfig,
+    /// Post event and image notifications to the chat target into a
+    /// Discord thread per session.
+    session_threads: bool,
+    /// Local horizon drawn on the mount's sky chart.
+    horizon: Option<HorizonProfile>,
+    /// Acknowledge clicks and reactions from every chat service.
+    acknowledgements: broadcast::Receiver<Acknowledgement>,
+    /// Where event and image notifications go, each with its own rules.
+    routes: Vec<NotificationRoute>,
+    /// What the event or image currently being handled looks like to the
+    /// rules; `None` for notifications rules do not apply to.
+    notice: Option<Notice>,
+    /// Bursts, digests and `batch` rule holds being held per route.
+    coalescer: Mutex<Coalescer>,
+    /// Where the dedupe state survives restarts, if anywhere.
+    checkpoint: Option<Arc<dyn CheckpointStore>>,
+    /// The state last written to `checkpoint`.
+    checkpoint_fingerprint: Option<String>,
+}
+
+/// Embeds cannot carry more fields than this.
+const MAX_BATCH_FIELDS: usize = 25;
+
+/// A held notification as one summary line: its title, then the time and
+/// its first non-empty field.
+fn batch_entry(message: &ChatMessage) -> ChatField {
+    let summary = message
+        .fields
+        .iter()
+        .find(|field| !field.value.is_empty())
+        .map(|field| format!(" · {}: {}", field.name, field.value))
+        .unwrap_or_default();
+    ChatField {
+        name: message.title.clone(),
+        value: format!("{}{summary}", Local::now().format("%H:%M")),
+        inline: false,
+    }
+}
+
+/// A gray summary post listing `entries`, trimmed to what an embed holds.
+fn summary_message(title: String, entries: Vec<ChatField>) -> ChatMessage {
+    let count = entries.len();
+    let mut message = ChatMessage::new(&title).color(colors::GRAY);
+    let shown = if count > MAX_BATCH_FIELDS {
+        MAX_BATCH_FIELDS - 1
+    } else {
+        count
+    };
+    message.fields.extend(entries.into_iter().take(shown));
+    if count > shown {
+        message = message.field("…", &format!("and {} more", count - shown), false);
+    }
+    message
 }
 
 impl ChatUpdater {
@@ -477,16 +705,32 @@ impl ChatUpdater {
         chat_target: ChatTarget,
         chat_manager: Arc<ChatServiceManager>,
     ) -> Self {
+        let acknowledgements = chat_manager.subscribe_acknowledgements();
         Self {
             source,
             state: UpdaterState::new(),
             chat_manager,
-            chat_target,
+            chat_target: chat_target.clone(),
             image_cooldown: Duration::from_secs(60),
             reconnect_initial: DEFAULT_RECONNECT_INITIAL,
             reconnect_max: DEFAULT_RECONNECT_MAX,
             telescope_name,
             announce_lifecycle: true,
+            weather_alerts: WeatherAlertConfig::default(),
+            image_quality: ImageQualityConfig::default(),
+            critical_alerts: CriticalAlertConfig::default(),
+            coalescing: CoalescingConfig::default(),
+            session_threads: false,
+            horizon: None,
+            acknowledgements,
+            routes: vec![NotificationRoute {
+                target: chat_target.clone(),
+                rules: NotificationRules::default(),
+            }],
+            notice: None,
+            coalescer: Mutex::new(Coalescer::default()),
+            checkpoint: None,
+            checkpoint_fingerprint: None,
         }
     }
 
@@ -522,6 +766,75 @@ impl ChatUpdater {
         self
     }
 
+    /// Thresholds for the early weather warnings.
+    pub fn with_weather_alerts(mut self, config: WeatherAlertConfig) -> Self {
+        self.weather_alerts = config;
+        self
+    }
+
+    /// Thresholds for the image-quality anomaly alerts.
+    pub fn with_image_quality_alerts(mut self, config: ImageQualityConfig) -> Self {
+        self.image_quality = config;
+        self
+    }
+
+    /// Mentions and escalation schedule for critical alerts.
+    pub fn with_critical_alerts(mut self, config: CriticalAlertConfig) -> Self {
+        self.critical_alerts = config;
+        self
+    }
+
+    /// Burst window and optional digest interval for notifications.
+    pub fn with_coalescing(mut self, config: CoalescingConfig) -> Self {
+        self.coalescing = config;
+        self
+    }
+
+    /// Open a Discord thread when a sequence or Target Scheduler target
+    /// starts, and post event and image notifications to the chat target
+    /// into it. Alerts and the live status stay in the channel.
+    pub fn with_session_threads(mut self, enabled: bool) -> Self {
+        self.session_threads = enabled;
+        for route in &mut self.routes {
+            route.target.session_thread = enabled.then(|| self.telescope_name.clone());
+        }
+        self
+    }
+
+    /// Draw `horizon` on the sky chart attached to slew and center
+    /// notifications.
+    pub fn with_horizon(mut self, horizon: Option<HorizonProfile>) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    /// Keep what has already been announced in `store`, so a restart posts
+    /// the events and images that arrived while the updater was down rather
+    /// than treating the whole history as already seen.
+    pub fn with_checkpoint(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.checkpoint = Some(store);
+        self
+    }
+
+    /// Rules for event and image notifications to the chat target.
+    pub fn with_notification_rules(mut self, rules: NotificationRules) -> Self {
+        self.routes = vec![NotificationRoute {
+            target: ChatTarget {
+                session_thread: self.session_threads.then(|| self.telescope_name.clone()),
+                ..self.chat_target.clone()
+            },
+            rules,
+        }];
+        self
+    }
+
+    /// Split event and image notifications across destinations with their
+    /// own rules, as the hub does per attached server.
+    pub fn with_notification_routes(mut self, routes: Vec<NotificationRoute>) -> Self {
+        self.routes = routes;
+        self
+    }
+
     /// First-retry wait for an unreachable telescope's baseline.
     pub fn reconnect_initial(&self) -> Duration {
         self.reconnect_initial
@@ -543,6 +856,16 @@ impl ChatUpdater {
         );
         println!("[{n}] Polling interval: {poll_interval:?}");
 
+        // Subscribe before the baseline so nothing pushed while it loads is
+        // lost; anything it already covered deduplicates against seen state.
+        let mut updates = self.source.subscribe_updates();
+        if updates.is_some() {
+            println!(
+                "[{n}] Rig pushes updates; full polls resynchronize every {:?}",
+                poll_interval.max(STREAM_RESYNC_INTERVAL)
+            );
+        }
+
         // If the telescope is unreachable at startup, don't give up forever.
         // Retry the baseline until it succeeds, backing off exponentially — in
         // a multi-telescope setup one offline rig must not kill its own task.
@@ -567,6 +890,7 @@ impl ChatUpdater {
         // Catching up on missed bounded history is automatic because readers
         // deduplicate against seen state, so no re-baseline is needed.
         let mut reconnect_delay = self.reconnect_initial;
+        let mut stream_closed = false;
         loop {
             // Run every reader so live state stays current; the cycle counts as
             // reachable if any Direct query answered.
@@ -575,12 +899,33 @@ impl ChatUpdater {
             let images_ok = self.poll_images().await;
             let reachable = seq_ok || events_ok || images_ok;
 
-            self.record_reachability(reachable).await;
+            if std::mem::take(&mut stream_closed) && !reachable {
+                self.record_disconnect().await;
+            } else {
+                self.record_reachability(reachable).await;
+            }
+            // Offline alerts keep escalating while the rig is unreachable.
+            self.poll_alerts().await;
 
             if reachable {
+                self.poll_conditions().await;
+                self.flush_batches().await;
                 self.refresh_status_message().await;
+                self.save_checkpoint();
                 reconnect_delay = self.reconnect_initial;
-                sleep(poll_interval).await;
+                match updates.as_mut() {
+                    Some(receiver) => {
+                        let window = poll_interval.max(STREAM_RESYNC_INTERVAL);
+                        if !self.stream_updates(receiver, window, poll_interval).await {
+                            eprintln!("[{n}] Update stream closed; falling back to polling.");
+                            updates = None;
+                            // Poll at once: if the rig is gone too, it has
+                            // disconnected rather than stopped streaming.
+                            stream_closed = true;
+                        }
+                    }
+                    None => sleep(poll_interval).await,
+                }
             } else {
                 sleep(reconnect_delay).await;
                 reconnect_delay = self.next_reconnect_delay(reconnect_delay);
@@ -588,6 +933,59 @@ impl ChatUpdater {
         }
     }
 
+    /// Apply pushed updates for up to `window`. Every `tick` the safety and
+    /// weather devices are read, open alerts escalate and held posts flush,
+    /// so conditions, reminders and batches keep the polling schedule rather
+    /// than waiting for the next full poll. Returns early when the
+    /// subscriber lagged, so the caller's full poll resynchronizes at once,
+    /// and returns false when the stream has closed for good.
+    pub(crate) async fn stream_updates(
+        &mut self,
+        updates: &mut broadcast::Receiver<RigUpdate>,
+        window: Duration,
+        tick: Duration,
+    ) -> bool {
+        let now = tokio::time::Instant::now();
+        let deadline = now + window;
+        let mut ticks = tokio::time::interval_at(now + tick, tick);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => return true,
+                _ = ticks.tick() => {
+                    self.poll_conditions().await;
+                    self.poll_alerts().await;
+                    self.flush_batches().await;
+                    self.save_checkpoint();
+                }
+                received = updates.recv() => match received {
+                    Ok(update) => {
+                        self.apply_update(update).await;
+                        self.refresh_status_message().await;
+                        self.save_checkpoint();
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        eprintln!(
+                            "[{}] Missed {skipped} pushed updates; resynchronizing.",
+                            self.telescope_name
+                        );
+                        return true;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return false,
+                },
+            }
+        }
+    }
+
+    /// Apply one pushed update through the same path as the matching poll.
+    pub async fn apply_update(&mut self, update: RigUpdate) {
+        match update {
+            RigUpdate::Event { event } => self.process_events(vec![event]).await,
+            RigUpdate::Image { index, image } => self.process_image(&image, index as usize).await,
+            RigUpdate::Sequence { sequence } => self.process_sequence(sequence).await,
+        }
+    }
+
     /// Record the outcome of a poll cycle and manage the reported-connection
     /// state. Logs and posts a chat alert on each transition, debouncing the
     /// offline direction until `OFFLINE_FAILURE_THRESHOLD` consecutive cycles
@@ -622,8 +1020,19 @@ impl ChatUpdater {
         }
     }
 
-    /// Post an offline/back-online connectivity alert to chat.
-    async fn send_connectivity_notification(&self, online: bool) {
+    /// The rig's update stream closed and it no longer answers: it has
+    /// disconnected, so go offline without waiting out the failure threshold.
+    async fn record_disconnect(&mut self) {
+        self.state.consecutive_failures = self
+            .state
+            .consecutive_failures
+            .max(OFFLINE_FAILURE_THRESHOLD - 1);
+        self.record_reachability(false).await;
+    }
+
+    /// Post an offline/back-online connectivity alert to chat. Going offline
+    /// opens a critical alert; coming back resolves it.
+    async fn send_connectivity_notification(&mut self, online: bool) {
         if !self.announce_lifecycle {
             return;
         }
@@ -647,9 +1056,14 @@ impl ChatUpdater {
             "{}",
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
         ));
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        if online {
+            let message = self.resolve_alert(AlertKind::RigOffline, message);
+            self.chat_manager
+                .send_message(&message, &self.chat_target)
+                .await;
+        } else {
+            self.raise_alert(AlertKind::RigOffline, message).await;
+        }
     }
 
     /// Build a live-status embed from current state and push it to any
@@ -665,6 +1079,9 @@ impl ChatUpdater {
             return;
         }
         let message = self.build_status_message().await;
+        // The first status that learns the site is re-fingerprinted so the
+        // sky rows start refreshing.
+        let fingerprint = self.state.status_fingerprint();
         self.chat_manager
             .upsert_status(&self.telescope_name, &self.chat_target, &message)
             .await;
@@ -674,15 +1091,29 @@ impl ChatUpdater {
     /// Compose the live-status `ChatMessage`. Pulls cheap state from
     /// `self.state` and adds a fresh mount snapshot per cycle (the most
     /// useful single fetch for at-a-glance status).
-    async fn build_status_message(&self) -> ChatMessage {
+    async fn build_status_message(&mut self) -> ChatMessage {
         let mut message = ChatMessage::new(&self.titled("📡 Live status"));
         message = message.color(colors::CYAN);
+        let mount = self.source.get_mount_info().await.ok();
+        if let Some(site) = mount.as_ref().and_then(Site::from_mount) {
+            self.state.site = Some(site);
+        }
+        let now = Utc::now();
 
         let summary = self.format_startup_status();
         if !summary.is_empty() {
             message = message.field("State", &summary, false);
         }
+        if let Some(alerts) = self.state.alerts.summary() {
+            message = message.field("Alerts", &alerts, false);
+        }
 
+        let target_position = self
+            .state
+            .current_target
+            .as_ref()
+            .and_then(|target| target.coordinates.as_ref())
+            .and_then(Equatorial::from_target);
         if let Some(target) = &self.state.current_target {
             message = message.field("Target", &target.name, false);
             if let Some(coords) = &target.coordinates
@@ -690,6 +1121,10 @@ impl ChatUpdater {
             {
                 message = message.field("Coordinates", &s, false);
             }
+            if let (Some(site), Some(position)) = (&self.state.site, &target_position) {
+                let visibility = Visibility::at(site, position, DEFAULT_ALTITUDE_LIMIT, now);
+                message = message.field("Visibility", &visibility.summary(now), false);
+            }
         }
 
         if let Some(filter) = &self.state.last_filter
@@ -706,8 +1141,17 @@ impl ChatUpdater {
             );
         }
 
+        if let Some(site) = &self.state.site {
+            let moon = MoonInfo::at(site, now, target_position.as_ref());
+            let mut sky = moon.summary();
+            if let Some(twilight) = twilight_summary(site, now) {
+                sky = format!("{twilight}\n{sky}");
+            }
+            message = message.field("Sky", &sky, false);
+        }
+
         // Fresh mount snapshot — small payload, very useful at a glance.
-        if let Ok(mount_info) = self.source.get_mount_info().await
+        if let Some(mount_info) = &mount
             && mount_info.is_connected()
         {
             let (ra, dec) = mount_info.get_coordinates();
@@ -716,6 +1160,41 @@ impl ChatUpdater {
                 .field("Pier", mount_info.get_side_of_pier(), true);
         }
 
+        let capabilities = self.source.capabilities();
+        if capabilities.equipment_snapshots {
+            if capabilities.devices.get(RigDevice::Dome) != DeviceState::Absent
+                && let Ok(dome) = self.source.get_dome_info().await
+                && dome.success
+                && dome.response.connected
+            {
+                message = message.field("Dome", &dome.response.summary(), true);
+            }
+            if capabilities.devices.get(RigDevice::FlatDevice) != DeviceState::Absent
+                && let Ok(panel) = self.source.get_flat_device_info().await
+                && panel.success
+                && panel.response.connected
+            {
+                message = message.field("Flat panel", &panel.response.summary(), true);
+            }
+            if capabilities.devices.get(RigDevice::Switch) != DeviceState::Absent
+                && let Ok(switches) = self.source.get_switch_info().await
+                && switches.success
+                && switches.response.connected
+                && let Some(heaters) = switches.response.dew_heater_summary()
+            {
+                message = message.field("Dew heaters", &heaters, true);
+            }
+        }
+
+        if capabilities.observing_conditions {
+            if let Some(weather) = self.weather_snapshot().await {
+                message = message.field("Weather", &weather.summary(), false);
+            }
+            if let Some(safe) = self.state.safe {
+                message = message.field("Safety", safety_label(safe), true);
+            }
+        }
+
         message.footer(&format!(
             "Updated {}",
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
@@ -1214,7 +1693,7 @@ impl ChatUpdater {
                     attach_output: true
                 }
             );
-        let attachments = if attach_output {
+        let mut attachments = if attach_output {
             match &tracked.operation.kind {
                 SequenceOperationKind::MountCenter {
                     output: Some(output),
@@ -1239,20 +1718,63 @@ impl ChatUpdater {
         } else {
             Vec::new()
         };
+        if let OperationUpdate::Finished { .. } = update
+            && let SequenceOperationKind::MountSlew { coordinates, .. }
+            | SequenceOperationKind::MountCenter { coordinates, .. } = &tracked.operation.kind
+        {
+            let destination = coordinates.as_ref().and_then(Equatorial::from_operation);
+            let name = self
+                .state
+                .current_target
+                .as_ref()
+                .map_or(tracked.operation.name.as_str(), |target| {
+                    target.name.as_str()
+                });
+            if let Some(chart) = self
+                .sky_position_attachment(destination.as_ref().map(|position| (name, position)))
+                .await
+            {
+                attachments.push(chart);
+            }
+        }
         self.chat_manager
             .send_message_with_attachments(&message, &self.chat_target, &attachments)
             .await;
     }
 
+    /// The mount's position on the all-sky chart, with `target`'s track.
+    async fn sky_position_attachment(
+        &self,
+        target: Option<(&str, &Equatorial)>,
+    ) -> Option<ChatAttachment> {
+        let mount = self.source.get_mount_info().await.ok()?;
+        match crate::charts::render_sky_position_png(
+            &mount,
+            target,
+            self.horizon.as_ref(),
+            Utc::now(),
+        ) {
+            Ok(png) => Some(ChatAttachment {
+                data: png,
+                filename: "sky_position.png".to_string(),
+            }),
+            Err(e) => {
+                eprintln!("Failed to render sky position chart: {e}");
+                None
+            }
+        }
+    }
+
     pub async fn initialize_baseline(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let n = self.telescope_name.clone();
         let capabilities = self.source.capabilities();
         println!("[{n}] Fetching initial baseline...");
+        let restored = self.restore_checkpoint();
 
         // Load events and find latest TS-TARGETSTART
         if capabilities.event_history {
             let events = self.source.get_event_history().await?;
-            self.process_baseline_events(&events.response);
+            self.process_baseline_events(&events.response, restored);
         }
 
         // Load sequence to get meridian flip time and potential sequence target
@@ -1262,7 +1784,9 @@ impl ChatUpdater {
                     self.state.meridian_flip_time = extract_meridian_flip_time(&sequence);
                     let operations = extract_sequence_operations(&sequence);
                     let camera = self.camera_snapshot_for(&operations).await;
-                    self.reconcile_sequence_operations(operations, camera, false)
+                    // Operations that started or ended while a checkpointed
+                    // updater was down are announced now.
+                    self.reconcile_sequence_operations(operations, camera, restored)
                         .await;
 
                     // Only use sequence target if no TS-TARGETSTART target was found
@@ -1286,8 +1810,9 @@ impl ChatUpdater {
             }
         }
 
-        // Load images
-        if capabilities.image_history {
+        // Load images; after a restore, unseen ones are delivered by the
+        // first poll.
+        if capabilities.image_history && !restored {
             let images = self.source.get_all_image_history().await?;
             for image in &images.response {
                 self.state
@@ -1325,10 +1850,79 @@ impl ChatUpdater {
         Ok(())
     }
 
-    fn process_baseline_events(&mut self, events: &[Event]) {
+    /// Load the last checkpoint into the state, returning whether there was
+    /// a recent one. Events and images it has not seen are then left for the
+    /// first poll to deliver.
+    fn restore_checkpoint(&mut self) -> bool {
+        let Some(store) = &self.checkpoint else {
+            return false;
+        };
+        let Some(saved) = store.load() else {
+            return false;
+        };
+        let n = &self.telescope_name;
+        let checkpoint: Checkpoint = match serde_json::from_str(&saved) {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => {
+                eprintln!("[{n}] Ignoring unreadable checkpoint: {e}");
+                return false;
+            }
+        };
+        let age = Utc::now().signed_duration_since(checkpoint.saved_at);
+        if age > CHECKPOINT_MAX_AGE {
+            println!(
+                "[{n}] Checkpoint is {}h old; starting from a fresh baseline.",
+                age.num_hours()
+            );
+            return false;
+        }
+        println!(
+            "[{n}] Restored checkpoint from {}: {} events, {} images",
+            checkpoint.saved_at.with_timezone(&Local).format("%H:%M:%S"),
+            checkpoint.events_seen.len(),
+            checkpoint.images_seen.len()
+        );
+        self.state.restore(checkpoint);
+        true
+    }
+
+    /// Write the checkpoint if anything in it changed since the last write.
+    pub fn save_checkpoint(&mut self) {
+        let Some(store) = &self.checkpoint else {
+            return;
+        };
+        let fingerprint = self.state.checkpoint_fingerprint();
+        if self.checkpoint_fingerprint.as_ref() == Some(&fingerprint) {
+            return;
+        }
+        match serde_json::to_string(&self.state.checkpoint()) {
+            Ok(checkpoint) => {
+                store.save(&checkpoint);
+                self.checkpoint_fingerprint = Some(fingerprint);
+            }
+            Err(e) => eprintln!(
+                "[{}] Could not serialize checkpoint: {e}",
+                self.telescope_name
+            ),
+        }
+    }
+
+    /// Infer the rig's state from its event history. After a checkpoint
+    /// restore only the events it had seen count; the rest are still to be
+    /// announced, and the restored target and filter stand.
+    fn process_baseline_events(&mut self, events: &[Event], restored: bool) {
         let mut latest_ts_target: Option<(String, TargetInfo)> = None;
 
         for event in events {
+            if restored
+                && !self
+                    .state
+                    .events_seen
+                    .contains(&UpdaterState::event_key(event))
+            {
+                continue;
+            }
+
             // Skip redundant filterwheel events
             if event.event == event_types::FILTERWHEEL_CHANGED
                 && let Some(EventDetails::FilterWheelChange { new, previous }) = &event.details
@@ -1340,7 +1934,8 @@ impl ChatUpdater {
 
             // Remember the last known good filter seen, so when NINA sends
             // empty-array fields later we still have a 'previous' to show.
-            if event.event == event_types::FILTERWHEEL_CHANGED
+            if !restored
+                && event.event == event_types::FILTERWHEEL_CHANGED
                 && let Some(EventDetails::FilterWheelChange { new, .. }) = &event.details
                 && !new.is_unknown()
             {
@@ -1391,6 +1986,9 @@ impl ChatUpdater {
                 | event_types::GUIDER_DITHER => {
                     self.state.last_guider_event = Some(event.event.clone());
                 }
+                name if is_enclosure_event(name) => {
+                    self.state.last_enclosure_event = Some(event.event.clone());
+                }
                 event_types::SEQUENCE_STARTING => self.state.sequence_running = true,
                 event_types::SEQUENCE_FINISHED => self.state.sequence_running = false,
                 event_types::TS_WAITSTART => {
@@ -1416,11 +2014,202 @@ impl ChatUpdater {
         }
 
         // Set the latest TS target if found
-        if let Some((_, target)) = latest_ts_target {
+        if let Some((_, target)) = latest_ts_target
+            && !restored
+        {
             self.state.current_target = Some(target);
         }
     }
 
+    /// Read the weather station and safety monitor and announce thresholds
+    /// crossed since the last read, so conditions turning are reported
+    /// before N.I.N.A.'s own safety trigger acts on them.
+    pub async fn poll_conditions(&mut self) {
+        if !self.source.capabilities().observing_conditions {
+            return;
+        }
+        let weather = self.weather_snapshot().await;
+        if let Some(weather) = &weather {
+            let alerts = weather.alerts(&self.weather_alerts, &self.state.condition_alerts);
+            let raised: Vec<ConditionAlert> = alerts
+                .difference(&self.state.condition_alerts)
+                .copied()
+                .collect();
+            let cleared: Vec<ConditionAlert> = self
+                .state
+                .condition_alerts
+                .difference(&alerts)
+                .copied()
+                .collect();
+            self.state.condition_alerts = alerts;
+            if !raised.is_empty() {
+                self.state.condition_alerts_raised += raised.len();
+                self.send_condition_alert(weather, &raised).await;
+            }
+            if !cleared.is_empty() {
+                let labels: Vec<&str> = cleared.iter().map(|alert| alert.label()).collect();
+                let message = ChatMessage::new(&self.titled("✅ Conditions recovered"))
+                    .color(colors::GREEN)
+                    .field("Cleared", &labels.join("\n"), false)
+                    .field("Weather", &weather.summary(), false);
+                self.chat_manager
+                    .send_message(&message, &self.chat_target)
+                    .await;
+            }
+        }
+
+        let safe = match self.source.get_safety_info().await {
+            Ok(safety) => safety.is_safe(),
+            Err(_) => None,
+        };
+        let previous = std::mem::replace(&mut self.state.safe, safe);
+        // A monitor that connects already safe is not news.
+        if let Some(safe) = safe
+            && previous != Some(safe)
+            && (previous.is_some() || !safe)
+        {
+            let (title, color) = if safe {
+                ("✅ Safety monitor reports safe", colors::GREEN)
+            } else {
+                ("⛔ Safety monitor reports unsafe", colors::RED)
+            };
+            let mut message = ChatMessage::new(&self.titled(title)).color(color);
+            if let Some(weather) = &weather {
+                message = message.field("Weather", &weather.summary(), false);
+            }
+            if safe {
+                let message = self.resolve_alert(AlertKind::Unsafe, message);
+                self.chat_manager
+                    .send_message(&message, &self.chat_target)
+                    .await;
+            } else {
+                self.raise_alert(AlertKind::Unsafe, message).await;
+            }
+        }
+    }
+
+    /// Post a critical condition as an alert that re-notifies on the
+    /// escalation schedule until acknowledged or cleared. Alerts go to the
+    /// telescope's chat target whatever the notification rules say. With
+    /// alerts off, or the same condition already open, the message posts
+    /// plainly.
+    async fn raise_alert(&mut self, kind: AlertKind, message: ChatMessage) {
+        if !self.critical_alerts.enabled || self.state.alerts.is_open(kind) {
+            self.chat_manager
+                .send_message(&message, &self.chat_target)
+                .await;
+            return;
+        }
+        let alert = self.state.alerts.open(kind, message, Utc::now());
+        println!(
+            "[{}] Critical alert opened: {}",
+            self.telescope_name, alert.id
+        );
+        let post = alert.post(
+            &self.telescope_name,
+            self.critical_alerts.mention.as_deref(),
+        );
+        self.chat_manager
+            .send_message(&post, &self.chat_target)
+            .await;
+    }
+
+    /// Close the alert for a condition that cleared, noting on the recovery
+    /// message who handled it.
+    fn resolve_alert(&mut self, kind: AlertKind, message: ChatMessage) -> ChatMessage {
+        match self.state.alerts.resolve(kind) {
+            Some(alert) => {
+                println!(
+                    "[{}] Critical alert resolved: {}",
+                    self.telescope_name, alert.id
+                );
+                message.field("Alert", &alert.resolution(), false)
+            }
+            None => message,
+        }
+    }
+
+    /// Take in acknowledgements from chat and send any reminders that are
+    /// due for unacknowledged alerts.
+    pub async fn poll_alerts(&mut self) {
+        loop {
+            match self.acknowledgements.try_recv() {
+                Ok(acknowledgement) => self.record_acknowledgement(acknowledgement).await,
+                Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+        let due = self
+            .state
+            .alerts
+            .due_reminders(&self.critical_alerts, tokio::time::Instant::now());
+        for (alert, step) in due {
+            let mention = step
+                .mention
+                .as_deref()
+                .or(self.critical_alerts.mention.as_deref());
+            let reminder =
+                alert.reminder(&self.telescope_name, mention, tokio::time::Instant::now());
+            self.chat_manager
+                .send_message(&reminder, &self.chat_target)
+                .await;
+        }
+    }
+
+    async fn record_acknowledgement(&mut self, acknowledgement: Acknowledgement) {
+        if acknowledgement.telescope != self.telescope_name {
+            return;
+        }
+        let Some(alert) = self
+            .state
+            .alerts
+            .acknowledge(&acknowledgement.alert, &acknowledgement.user)
+            .cloned()
+        else {
+            return;
+        };
+        println!(
+            "[{}] Critical alert {} acknowledged by {}",
+            self.telescope_name, alert.id, acknowledgement.user
+        );
+        let message =
+            ChatMessage::new(&self.titled(format!("✋ {} acknowledged", alert.kind.label())))
+                .color(colors::ORANGE)
+                .field("Handled by", &acknowledgement.user, true)
+                .field("Reminders", "Stopped until the condition clears", true);
+        self.chat_manager
+            .send_message(&message, &self.chat_target)
+            .await;
+    }
+
+    async fn weather_snapshot(&self) -> Option<WeatherInfo> {
+        self.source
+            .get_weather_info()
+            .await
+            .ok()
+            .filter(|response| response.success && response.response.connected)
+            .map(|response| response.response)
+    }
+
+    async fn send_condition_alert(&self, weather: &WeatherInfo, raised: &[ConditionAlert]) {
+        let mut message =
+            ChatMessage::new(&self.titled("⚠️ Conditions turning")).color(colors::ORANGE);
+        for alert in raised {
+            message = message.field(
+                alert.label(),
+                &alert.detail(weather, &self.weather_alerts),
+                false,
+            );
+        }
+        message = message.field("Weather", &weather.summary(), false);
+        if let Some(target) = &self.state.current_target {
+            message = message.field("Current Target", &target.name, true);
+        }
+        self.chat_manager
+            .send_message(&message, &self.chat_target)
+            .await;
+    }
+
     /// Returns whether the Direct source responded, so the update loop can
     /// detect a mid-run disconnect without a separate health probe.
     pub async fn poll_events(&mut self) -> bool {
@@ -1429,26 +2218,32 @@ impl ChatUpdater {
         }
         match self.source.get_event_history().await {
             Ok(events) => {
-                for event in events.response {
-                    if !self.should_process_event(&event) {
-                        continue;
-                    }
-
-                    if !self.state.has_seen_event(&event) {
-                        self.print_new_event(&event);
-                        self.handle_event(&event).await;
-                    }
-                }
-                if self.state.wait_until.is_some_and(|end| Utc::now() >= end) {
-                    self.state.wait_until = None;
-                }
+                self.process_events(events.response).await;
                 true
             }
-            Err(e) => {
-                eprintln!("Error fetching events: {e}");
-                false
+            Err(e) => {
+                eprintln!("Error fetching events: {e}");
+                false
+            }
+        }
+    }
+
+    async fn process_events(&mut self, events: Vec<Event>) {
+        for event in events {
+            if !self.should_process_event(&event) {
+                continue;
+            }
+
+            if !self.state.has_seen_event(&event) {
+                self.print_new_event(&event);
+                self.notice = Some(Notice::from_event(&event));
+                self.handle_event(&event).await;
+                self.notice = None;
             }
         }
+        if self.state.wait_until.is_some_and(|end| Utc::now() >= end) {
+            self.state.wait_until = None;
+        }
     }
 
     fn should_process_event(&self, event: &Event) -> bool {
@@ -1480,6 +2275,11 @@ impl ChatUpdater {
             event_types::GUIDER_START | event_types::GUIDER_STOP | event_types::GUIDER_DITHER => {
                 self.state.last_guider_event = Some(event.event.clone());
             }
+            name if is_enclosure_event(name) => {
+                self.state.last_enclosure_event = Some(event.event.clone());
+            }
+            // Read the monitor right away rather than at the next poll.
+            event_types::SAFETY_CHANGED => self.poll_conditions().await,
             event_types::SEQUENCE_STARTING => self.state.sequence_running = true,
             event_types::SEQUENCE_FINISHED => self.state.sequence_running = false,
             event_types::TS_WAITSTART => {
@@ -1508,6 +2308,11 @@ impl ChatUpdater {
             return;
         }
 
+        if event.event == event_types::SEQUENCE_STARTING {
+            let target = self.state.current_target.as_ref().map(|t| t.name.clone());
+            self.open_session_thread(target).await;
+        }
+
         match event.event.as_str() {
             event_types::AUTOFOCUS_FINISHED => self.handle_autofocus_finished(event).await,
             event_types::MOUNT_BEFORE_FLIP
@@ -1527,6 +2332,107 @@ impl ChatUpdater {
             event_types::IMAGE_SAVE => {} // Handled in image polling
             _ => self.handle_generic_event(event).await,
         }
+
+        if ends_night(event) {
+            self.send_session_report().await;
+        }
+    }
+
+    /// Post the end-of-night report with its chart to every destination.
+    /// Skipped when the night has no lights or nothing new since the last
+    /// report.
+    async fn send_session_report(&mut self) {
+        if self.chat_manager.service_count() == 0 {
+            return;
+        }
+        let (images, events) = match (
+            self.source.get_all_image_history().await,
+            self.source.get_event_history().await,
+        ) {
+            (Ok(images), Ok(events)) => (images, events),
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("[{}] Session report unavailable: {e}", self.telescope_name);
+                return;
+            }
+        };
+        let fallback_target = self.state.current_target.as_ref().map(|t| t.name.as_str());
+        let mut report = SessionReport::build(&images, &events.response, fallback_target);
+        if report.light_frames == 0 || report.end == self.state.last_report_end {
+            return;
+        }
+        report.weather_interruptions += self.state.condition_alerts_raised;
+        self.state.condition_alerts_raised = 0;
+        self.state.last_report_end = report.end;
+
+        let mut message = report.chat_message(&self.titled("🌅 Session Report"));
+        let mut attachments = match crate::charts::render_session_report_png(&report) {
+            Ok(png) => vec![ChatAttachment {
+                data: png,
+                filename: "session_report.png".to_string(),
+            }],
+            Err(e) => {
+                eprintln!("Failed to render session report chart: {e}");
+                Vec::new()
+            }
+        };
+        match crate::charts::render_session_trends_png(&images, &events.response) {
+            Ok(png) => attachments.push(ChatAttachment {
+                data: png,
+                filename: "session_trends.png".to_string(),
+            }),
+            Err(e) => eprintln!("Failed to render session trends chart: {e}"),
+        }
+        if let Some((fit, chart)) = self.autofocus_temperature_attachment() {
+            message = message.field("Temperature Compensation", &fit.summary(), false);
+            attachments.push(chart);
+        }
+        self.state.autofocus_runs.clear();
+        // The report is not the finishing event's notification, so that
+        // event's rules do not apply to it.
+        let notice = self.notice.take();
+        self.notify(&message, &attachments).await;
+        self.notice = notice;
+    }
+
+    /// Open a new session thread in every route that uses them, announced
+    /// by a one-line pointer in the channel. Later notifications on those
+    /// routes go into the thread until the next session opens another.
+    async fn open_session_thread(&mut self, target: Option<String>) {
+        let threaded: Vec<&ChatTarget> = self
+            .routes
+            .iter()
+            .map(|route| &route.target)
+            .filter(|target| target.session_thread.is_some())
+            .collect();
+        if threaded.is_empty() {
+            return;
+        }
+        let label = target.as_deref().unwrap_or("Session");
+        let name = format!(
+            "{} · {label} · {}",
+            self.telescope_name,
+            Local::now().format("%Y-%m-%d")
+        );
+        let pointer = ChatMessage::new(&self.titled(format!("🧵 {label} — updates in the thread")))
+            .color(colors::CYAN);
+        for target in threaded {
+            self.chat_manager
+                .start_session_thread(target, &name, &pointer)
+                .await;
+        }
+        self.state.session_thread_open = true;
+        self.state.session_thread_target = target;
+    }
+
+    /// A new target names the thread its sequence just opened, or opens one
+    /// of its own.
+    async fn target_session_thread(&mut self, target_name: &str) {
+        if self.state.session_thread_open && self.state.session_thread_target.is_none() {
+            self.state.session_thread_target = Some(target_name.to_string());
+        } else {
+            self.open_session_thread(Some(target_name.to_string()))
+                .await;
+        }
     }
 
     /// Filter wheel change events from NINA sometimes arrive with empty Name/Id
@@ -1608,9 +2514,7 @@ impl ChatUpdater {
             .field("Previous", &fmt(previous), true)
             .field("New", &fmt(new), true);
 
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
     }
 
     async fn handle_ts_targetstart(&mut self, event: &Event) {
@@ -1645,6 +2549,7 @@ impl ChatUpdater {
                 println!("[TS-TARGETSTART] Target: {}", target_name);
 
                 if event.chat_enabled && self.chat_manager.service_count() > 0 {
+                    self.target_session_thread(target_name).await;
                     if let Some(old) = old_target {
                         self.send_target_change_notification(&old, &new_target)
                             .await;
@@ -1656,13 +2561,15 @@ impl ChatUpdater {
         }
     }
 
-    async fn handle_autofocus_finished(&self, event: &Event) {
+    async fn handle_autofocus_finished(&mut self, event: &Event) {
         println!("[AUTOFOCUS FINISHED] {}", event.time);
+        self.state.quality.focus_changed();
         println!("Fetching autofocus results...");
 
         match self.source.get_last_autofocus().await {
             Ok(autofocus_data) => {
                 self.display_autofocus_results(&autofocus_data);
+                self.record_autofocus_run(&autofocus_data);
 
                 if self.chat_manager.service_count() > 0 {
                     self.send_autofocus_notification(&autofocus_data).await;
@@ -1672,6 +2579,44 @@ impl ChatUpdater {
         }
     }
 
+    /// Keep a successful run for the night's temperature trend. The same
+    /// result can be fetched twice when events arrive close together.
+    fn record_autofocus_run(&mut self, af: &AutofocusResponse) {
+        let runs = &mut self.state.autofocus_runs;
+        if !af.success
+            || runs
+                .last()
+                .is_some_and(|last| last.timestamp == af.response.timestamp)
+        {
+            return;
+        }
+        runs.push(af.response.clone());
+        if runs.len() > AUTOFOCUS_HISTORY {
+            runs.remove(0);
+        }
+    }
+
+    /// The focus-vs-temperature chart, once the night's runs support a
+    /// temperature-compensation estimate.
+    fn autofocus_temperature_attachment(
+        &self,
+    ) -> Option<(TemperatureCompensation, ChatAttachment)> {
+        let fit = TemperatureCompensation::fit(&self.state.autofocus_runs)?;
+        match crate::charts::render_autofocus_temperature_png(&self.state.autofocus_runs) {
+            Ok(png) => Some((
+                fit,
+                ChatAttachment {
+                    data: png,
+                    filename: "autofocus_temperature.png".to_string(),
+                },
+            )),
+            Err(e) => {
+                eprintln!("Failed to render autofocus temperature chart: {e}");
+                None
+            }
+        }
+    }
+
     async fn handle_mount_event(&self, event: &Event) {
         if self.chat_manager.service_count() > 0 {
             self.send_mount_event_notification(event).await;
@@ -1719,7 +2664,7 @@ impl ChatUpdater {
             .await;
     }
 
-    async fn handle_generic_event(&self, event: &Event) {
+    async fn handle_generic_event(&mut self, event: &Event) {
         if self.chat_manager.service_count() > 0 {
             self.send_generic_event_notification(event).await;
         }
@@ -1732,53 +2677,7 @@ impl ChatUpdater {
         }
         match self.source.get_sequence().await {
             Ok(sequence) => {
-                let new_sequence_target = extract_current_target_with_delivery(&sequence);
-                let new_meridian_flip_time = extract_meridian_flip_time(&sequence);
-                let operations = extract_sequence_operations(&sequence);
-                let camera = self.camera_snapshot_for(&operations).await;
-                self.reconcile_sequence_operations(operations, camera, true)
-                    .await;
-
-                self.state.meridian_flip_time = new_meridian_flip_time;
-                self.state.sequence = Some(sequence);
-
-                // Only update target if we don't have a TS-TARGETSTART override
-                if self
-                    .state
-                    .current_target
-                    .as_ref()
-                    .map(|t| t.source != TargetSource::TsTargetStart)
-                    .unwrap_or(true)
-                    && let Some((target_name, chat_enabled)) = new_sequence_target
-                {
-                    let new_target = TargetInfo {
-                        name: target_name.clone(),
-                        source: TargetSource::Sequence,
-                        coordinates: None,
-                        project: None,
-                        rotation: None,
-                    };
-
-                    let old_target = self.state.current_target.clone();
-                    let target_changed = old_target
-                        .as_ref()
-                        .map(|t| t.name != new_target.name)
-                        .unwrap_or(true);
-
-                    if target_changed {
-                        self.state.current_target = Some(new_target.clone());
-                        println!("[SEQUENCE TARGET] {}", target_name);
-
-                        if chat_enabled && self.chat_manager.service_count() > 0 {
-                            if let Some(old) = old_target {
-                                self.send_target_change_notification(&old, &new_target)
-                                    .await;
-                            } else {
-                                self.send_target_start_notification(&new_target).await;
-                            }
-                        }
-                    }
-                }
+                self.process_sequence(sequence).await;
                 true
             }
             Err(e) => {
@@ -1790,6 +2689,57 @@ impl ChatUpdater {
         }
     }
 
+    async fn process_sequence(&mut self, sequence: SequenceResponse) {
+        let new_sequence_target = extract_current_target_with_delivery(&sequence);
+        let new_meridian_flip_time = extract_meridian_flip_time(&sequence);
+        let operations = extract_sequence_operations(&sequence);
+        let camera = self.camera_snapshot_for(&operations).await;
+        self.reconcile_sequence_operations(operations, camera, true)
+            .await;
+
+        self.state.meridian_flip_time = new_meridian_flip_time;
+        self.state.sequence = Some(sequence);
+
+        // Only update target if we don't have a TS-TARGETSTART override
+        if self
+            .state
+            .current_target
+            .as_ref()
+            .map(|t| t.source != TargetSource::TsTargetStart)
+            .unwrap_or(true)
+            && let Some((target_name, chat_enabled)) = new_sequence_target
+        {
+            let new_target = TargetInfo {
+                name: target_name.clone(),
+                source: TargetSource::Sequence,
+                coordinates: None,
+                project: None,
+                rotation: None,
+            };
+
+            let old_target = self.state.current_target.clone();
+            let target_changed = old_target
+                .as_ref()
+                .map(|t| t.name != new_target.name)
+                .unwrap_or(true);
+
+            if target_changed {
+                self.state.current_target = Some(new_target.clone());
+                println!("[SEQUENCE TARGET] {}", target_name);
+
+                if chat_enabled && self.chat_manager.service_count() > 0 {
+                    self.target_session_thread(&target_name).await;
+                    if let Some(old) = old_target {
+                        self.send_target_change_notification(&old, &new_target)
+                            .await;
+                    } else {
+                        self.send_target_start_notification(&new_target).await;
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns whether the Direct source responded (see [`Self::poll_events`]).
     pub async fn poll_images(&mut self) -> bool {
         if !self.source.capabilities().image_history {
@@ -1798,13 +2748,7 @@ impl ChatUpdater {
         match self.source.get_all_image_history().await {
             Ok(images) => {
                 for (index, image) in images.response.iter().enumerate() {
-                    if !self.state.has_seen_image(image) {
-                        self.print_new_image(image);
-
-                        if image.chat_enabled && self.chat_manager.service_count() > 0 {
-                            self.handle_new_image(image, index).await;
-                        }
-                    }
+                    self.process_image(image, index).await;
                 }
                 true
             }
@@ -1815,6 +2759,102 @@ impl ChatUpdater {
         }
     }
 
+    async fn process_image(&mut self, image: &ImageMetadata, index: usize) {
+        if !self.state.has_seen_image(image) {
+            self.print_new_image(image);
+            self.check_image_quality(image).await;
+            if !image.is_snapshot() {
+                self.resolve_imaging_alerts().await;
+            }
+
+            // Snapshots are answered by the command that requested them.
+            if image.chat_enabled && !image.is_snapshot() && self.chat_manager.service_count() > 0 {
+                // Frames every route drops neither post nor start a cooldown.
+                self.notice = Some(Notice::from_image(image));
+                if self.notification_wanted() {
+                    self.handle_new_image(image, index).await;
+                }
+                self.notice = None;
+            }
+        }
+    }
+
+    /// A new sequence frame means a failed instruction or download timeout
+    /// is behind us.
+    async fn resolve_imaging_alerts(&mut self) {
+        let cleared: Vec<AlertKind> = AlertKind::CLEARED_BY_IMAGING
+            .into_iter()
+            .filter(|kind| self.state.alerts.is_open(*kind))
+            .collect();
+        if cleared.is_empty() {
+            return;
+        }
+        let mut message = ChatMessage::new(&self.titled("✅ Imaging resumed")).color(colors::GREEN);
+        for kind in cleared {
+            message = self.resolve_alert(kind, message);
+        }
+        self.chat_manager
+            .send_message(&message, &self.chat_target)
+            .await;
+    }
+
+    /// Judge a new light frame against its target and filter's recent
+    /// frames. Runs whatever the notification rules say about images.
+    async fn check_image_quality(&mut self, image: &ImageMetadata) {
+        if !self.image_quality.enabled || !image.is_light_frame() {
+            return;
+        }
+        let target = self.state.current_target.as_ref().map(|t| t.name.clone());
+        let findings =
+            self.state
+                .quality
+                .observe(&self.image_quality, target.as_deref().unwrap_or(""), image);
+        if !findings.is_empty() && self.chat_manager.service_count() > 0 {
+            self.send_quality_alert(image, target.as_deref(), &findings)
+                .await;
+        }
+    }
+
+    async fn send_quality_alert(
+        &self,
+        image: &ImageMetadata,
+        target: Option<&str>,
+        findings: &[QualityFinding],
+    ) {
+        let mut message = ChatMessage::new(&self.titled("⚠️ Image quality")).color(colors::ORANGE);
+        for finding in findings {
+            message = message.field(
+                finding.anomaly.label(),
+                &format!("{}\nLikely {}", finding.detail(), finding.anomaly.cause()),
+                false,
+            );
+        }
+        if let Some(target) = target {
+            message = message.field("Target", target, true);
+        }
+        message = message.field("Filter", &image.filter, true);
+        let suggestions: Vec<&str> = findings
+            .iter()
+            .map(|finding| finding.anomaly.suggestion())
+            .collect();
+        message = message.field("Suggested action", &suggestions.join("\n"), false);
+        if findings
+            .iter()
+            .any(|finding| finding.anomaly.suggests_autofocus())
+            && self
+                .source
+                .capabilities()
+                .supports_command(RigCommandKind::StartAutofocus)
+        {
+            message = message.action(ChatAction::StartAutofocus {
+                telescope: self.telescope_name.clone(),
+            });
+        }
+        self.chat_manager
+            .send_message(&message, &self.chat_target)
+            .await;
+    }
+
     async fn handle_new_image(&mut self, image: &ImageMetadata, index: usize) {
         let should_send = match self.state.last_image_time {
             None => true,
@@ -2125,9 +3165,32 @@ impl ChatUpdater {
 
         self.add_meridian_flip_info(&mut message);
         self.add_mount_info(&mut message).await;
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        let mut attachments = Vec::new();
+        if let Some(progress) = self.integration_progress(&new_target.name).await {
+            message = message.field("Progress", &progress.summary(), false);
+            match crate::charts::render_integration_progress_png(&progress) {
+                Ok(png) => attachments.push(ChatAttachment {
+                    data: png,
+                    filename: "progress.png".to_string(),
+                }),
+                Err(e) => eprintln!("Failed to render integration progress chart: {e}"),
+            }
+        }
+        self.notify(&message, &attachments).await;
+    }
+
+    /// Integration so far on `target` against the sequence's plan; `None`
+    /// when the histories are unavailable or show nothing for it.
+    async fn integration_progress(&self, target: &str) -> Option<IntegrationProgress> {
+        let images = self.source.get_all_image_history().await.ok()?;
+        let events = self.source.get_event_history().await.ok()?;
+        let progress = IntegrationProgress::build(
+            target,
+            &images,
+            &events.response,
+            self.state.sequence.as_ref(),
+        );
+        (!progress.is_empty()).then_some(progress)
     }
 
     async fn send_target_start_notification(&self, target: &TargetInfo) {
@@ -2151,9 +3214,7 @@ impl ChatUpdater {
 
         self.add_meridian_flip_info(&mut message);
         self.add_mount_info(&mut message).await;
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
     }
 
     async fn send_autofocus_notification(&self, af: &AutofocusResponse) {
@@ -2177,7 +3238,7 @@ impl ChatUpdater {
             position_change.to_string()
         };
 
-        let message =
+        let mut message =
             ChatMessage::new(&self.titled(format!("{success_indicator} Autofocus Completed")))
                 .color(color)
                 .field("Filter", &af_data.filter, true)
@@ -2224,7 +3285,7 @@ impl ChatUpdater {
 
         // Attach the rendered autofocus graph; failures are non-fatal and
         // the notification just goes out without it.
-        let attachments = match crate::charts::render_autofocus_graph_png(af_data) {
+        let mut attachments = match crate::charts::render_autofocus_graph_png(af_data) {
             Ok(png) => vec![ChatAttachment {
                 data: png,
                 filename: "autofocus.png".to_string(),
@@ -2234,9 +3295,11 @@ impl ChatUpdater {
                 Vec::new()
             }
         };
-        self.chat_manager
-            .send_message_with_attachments(&message, &self.chat_target, &attachments)
-            .await;
+        if let Some((fit, chart)) = self.autofocus_temperature_attachment() {
+            message = message.field("Temperature Compensation", &fit.summary(), false);
+            attachments.push(chart);
+        }
+        self.notify(&message, &attachments).await;
     }
 
     async fn send_mount_event_notification(&self, event: &Event) {
@@ -2262,9 +3325,7 @@ impl ChatUpdater {
         }
 
         self.add_mount_info(&mut message).await;
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
     }
 
     async fn send_guider_event_notification(
@@ -2311,9 +3372,7 @@ impl ChatUpdater {
             }
         }
 
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
     }
 
     async fn send_sequence_event_notification(&self, event: &Event) {
@@ -2352,9 +3411,7 @@ impl ChatUpdater {
             }
         }
 
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
     }
 
     async fn send_rotator_synced_notification(
@@ -2381,9 +3438,7 @@ impl ChatUpdater {
                 message = message.field("Sync", "✅", true);
             }
         }
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
     }
 
     async fn send_focuser_user_focused_notification(
@@ -2407,12 +3462,21 @@ impl ChatUpdater {
                 message = message.field("Temp comp", if f.temp_comp { "on" } else { "off" }, true);
             }
         }
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        self.notify(&message, &[]).await;
+    }
+
+    /// Critical events open an alert instead of a plain notification.
+    async fn send_generic_event_notification(&mut self, event: &Event) {
+        let Some(message) = self.generic_event_message(event) else {
+            return;
+        };
+        match AlertKind::for_event(&event.event) {
+            Some(kind) if self.critical_alerts.enabled => self.raise_alert(kind, message).await,
+            _ => self.notify(&message, &[]).await,
+        }
     }
 
-    async fn send_generic_event_notification(&self, event: &Event) {
+    fn generic_event_message(&self, event: &Event) -> Option<ChatMessage> {
         let (color, title) = match &event.details {
             Some(EventDetails::NinaNotification { level, header, .. }) => (
                 nina_level_color(level),
@@ -2453,7 +3517,7 @@ impl ChatUpdater {
                 }
                 EventDetails::TargetStart { .. } => {
                     // Already handled in handle_ts_targetstart
-                    return;
+                    return None;
                 }
                 EventDetails::WaitStart { wait_end_time } => {
                     message = message.field("Wait Until", wait_end_time, false);
@@ -2500,10 +3564,7 @@ impl ChatUpdater {
                 }
             }
         }
-
-        self.chat_manager
-            .send_message(&message, &self.chat_target)
-            .await;
+        Some(message)
     }
 
     async fn send_image_notification(
@@ -2574,19 +3635,134 @@ impl ChatUpdater {
         } else {
             Vec::new()
         };
+        let mut attachments = Vec::new();
         if capabilities.thumbnails {
+            match self.source.get_thumbnail(index as u32).await {
+                Ok(thumbnail) => attachments.push(ChatAttachment {
+                    data: thumbnail.data,
+                    filename: format!("thumbnail_{index}.jpg"),
+                }),
+                Err(e) => eprintln!("Failed to download thumbnail for image {index}: {e}"),
+            }
+        }
+        attachments.extend(extra_attachments);
+        self.notify(&message, &attachments).await;
+    }
+
+    /// Whether any route would post the current notification now or later.
+    fn notification_wanted(&self) -> bool {
+        let Some(notice) = &self.notice else {
+            return true;
+        };
+        let now = Local::now().time();
+        self.routes
+            .iter()
+            .any(|route| *route.rules.action(notice, now) != RuleAction::Drop)
+    }
+
+    /// Post an event or image notification through each route's rules.
+    /// Notifications with no current notice go everywhere unfiltered.
+    /// Delivered notifications pass through the coalescer, which may hold
+    /// them for a burst summary or digest; batched ones always join the
+    /// route's digest, and mentions always post at once.
+    async fn notify(&self, message: &ChatMessage, attachments: &[ChatAttachment]) {
+        let now = Local::now().time();
+        let held = || Held {
+            entry: batch_entry(message),
+            message: message.clone(),
+            attachments: attachments.to_vec(),
+        };
+        for (index, route) in self.routes.iter().enumerate() {
+            let action = match &self.notice {
+                Some(notice) => route.rules.action(notice, now),
+                None => &RuleAction::Deliver,
+            };
+            match action {
+                RuleAction::Drop => {}
+                RuleAction::Deliver => {
+                    if let Some(notice) = &self.notice {
+                        let send_now = self
+                            .coalescer
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .admit(
+                                &self.coalescing,
+                                index,
+                                notice,
+                                held,
+                                tokio::time::Instant::now(),
+                            );
+                        if !send_now {
+                            continue;
+                        }
+                    }
+                    self.chat_manager
+                        .send_message_with_attachments(message, &route.target, attachments)
+                        .await;
+                }
+                RuleAction::Mention { mention } => {
+                    let message = message.clone().mention(mention);
+                    self.chat_manager
+                        .send_message_with_attachments(&message, &route.target, attachments)
+                        .await;
+                }
+                RuleAction::Batch { minutes } => {
+                    self.coalescer
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .hold(
+                            index,
+                            held(),
+                            Duration::from_secs(minutes * 60),
+                            tokio::time::Instant::now(),
+                        );
+                }
+            }
+        }
+    }
+
+    /// Post every held burst and digest that is due.
+    pub async fn flush_batches(&self) {
+        let now = tokio::time::Instant::now();
+        let flushes = self
+            .coalescer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .due(now);
+        for flush in flushes {
+            let Some(route) = self.routes.get(flush.route) else {
+                continue;
+            };
+            let mut held = flush.held;
+            // A lone held notification is just late, not part of a burst.
+            if held.len() == 1
+                && let Some(single) = held.pop()
+            {
+                self.chat_manager
+                    .send_message_with_attachments(
+                        &single.message,
+                        &route.target,
+                        &single.attachments,
+                    )
+                    .await;
+                continue;
+            }
+            let count = held.len();
+            let title = match &flush.family {
+                Some(family) => format!("🔁 {count} more {family} notifications"),
+                None => format!("📰 Digest · {count} notifications"),
+            };
+            // Only the latest image of a burst is worth re-posting.
+            let attachments = held
+                .iter()
+                .rev()
+                .find(|held| !held.attachments.is_empty())
+                .map(|held| held.attachments.clone())
+                .unwrap_or_default();
+            let entries = held.into_iter().map(|held| held.entry).collect();
+            let message = summary_message(self.titled(title), entries);
             self.chat_manager
-                .send_message_with_image(
-                    &message,
-                    &self.chat_target,
-                    &self.source,
-                    index as u32,
-                    extra_attachments,
-                )
-                .await;
-        } else {
-            self.chat_manager
-                .send_message_with_attachments(&message, &self.chat_target, &extra_attachments)
+                .send_message_with_attachments(&message, &route.target, &attachments)
                 .await;
         }
     }
@@ -2669,6 +3845,16 @@ impl ChatUpdater {
     }
 }
 
+fn safety_label(safe: bool) -> &'static str {
+    if safe { "✅ Safe" } else { "⛔ Unsafe" }
+}
+
+/// Dome and flat panel events, which change what the live status shows
+/// for the enclosure.
+fn is_enclosure_event(event: &str) -> bool {
+    event.starts_with("DOME-") || event.starts_with("FLAT-")
+}
+
 fn get_event_color(event: &str) -> u32 {
     match event {
         // Camera events
@@ -2723,9 +3909,16 @@ fn get_event_color(event: &str) -> u32 {
         | event_types::DOME_DISCONNECTED
         | event_types::SAFETY_DISCONNECTED => colors::RED,
         event_types::FLAT_CONNECTED
+        | event_types::DOME_CONNECTED
         | event_types::WEATHER_CONNECTED
         | event_types::SWITCH_CONNECTED
         | event_types::SAFETY_CONNECTED => colors::GREEN,
+
+        // Dome and flat panel events
+        event_types::DOME_SHUTTER_OPENED | event_types::FLAT_COVER_OPENED => colors::BLUE,
+        event_types::DOME_SHUTTER_CLOSED | event_types::DOME_PARKED => colors::YELLOW,
+        event_types::DOME_HOMED | event_types::FLAT_COVER_CLOSED => colors::CYAN,
+        event_types::FLAT_LIGHT_TOGGLED | event_types::FLAT_BRIGHTNESS_CHANGED => colors::GRAY,
         event_types::SAFETY_CHANGED => colors::ORANGE,
         event_types::CAMERA_DOWNLOAD_TIMEOUT => colors::RED,
         event_types::ERROR_PLATESOLVE => colors::RED,
@@ -2758,17 +3951,19 @@ fn nina_level_color(level: &str) -> u32 {
 /// `DateTimeKind.Unspecified` serializes without one, and those used to be
 /// dropped silently — leaving the sequence "waiting until" state unset. Treat
 /// an offset-less stamp as observatory-local, which is what it is.
-fn parse_nina_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
-    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
-        return Some(parsed);
-    }
-    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
-        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
-        .ok()?;
-    Local
-        .from_local_datetime(&naive)
-        .earliest()
-        .map(|local| local.fixed_offset())
+/// Morning hours in which a mount park ends the night rather than
+/// sheltering from clouds mid-session.
+const DAWN_PARK_HOURS: std::ops::Range<u32> = 3..12;
+
+/// Whether this event closes the observing night: the sequence finished, or
+/// the mount parked in the morning.
+fn ends_night(event: &Event) -> bool {
+    match event.event.as_str() {
+        event_types::SEQUENCE_FINISHED => true,
+        event_types::MOUNT_PARKED => parse_nina_timestamp(&event.time)
+            .is_some_and(|time| DAWN_PARK_HOURS.contains(&time.hour())),
+        _ => false,
+    }
 }
 
 fn truncate_to(value: &str, limit: usize) -> String {
@@ -2812,6 +4007,7 @@ fn get_event_title(event: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{CapturingService, SimulatedNight, short_plan};
 
     fn operation(kind: SequenceOperationKind) -> SequenceOperation {
         SequenceOperation {
@@ -2823,16 +4019,6 @@ mod tests {
         }
     }
 
-    #[test]
-    fn nina_timestamps_parse_with_and_without_an_offset() {
-        assert!(parse_nina_timestamp("2026-08-17T04:00:00-07:00").is_some());
-        // DateTimeKind.Unspecified serializes without an offset; these used to
-        // be dropped, leaving the sequence wait state unset.
-        assert!(parse_nina_timestamp("2026-08-17T04:00:00").is_some());
-        assert!(parse_nina_timestamp("2026-08-17T04:00:00.1234567").is_some());
-        assert!(parse_nina_timestamp("not a timestamp").is_none());
-    }
-
     #[test]
     fn chat_titles_stay_within_the_discord_limit() {
         let header = "E".repeat(4_000);
@@ -2876,19 +4062,6 @@ mod tests {
         ));
     }
 
-    #[test]
-    fn nina_timestamp_accepts_offset_and_observatory_local_values() {
-        let offset = parse_nina_timestamp("2026-08-16T20:00:00-07:00").expect("offset time");
-        assert_eq!(offset.offset().local_minus_utc(), -7 * 60 * 60);
-
-        let local = parse_nina_timestamp("2026-08-16T20:00:00.1234567").expect("local time");
-        assert_eq!(
-            local.naive_local(),
-            NaiveDateTime::parse_from_str("2026-08-16T20:00:00.1234567", "%Y-%m-%dT%H:%M:%S%.f")
-                .unwrap()
-        );
-    }
-
     #[test]
     fn chat_titles_are_bounded_below_discords_limit() {
         let title = truncate_chat_title(&"x".repeat(400));
@@ -3027,4 +4200,28 @@ mod tests {
             initial
         );
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn simulated_sessions_open_a_thread_only_when_enabled() {
+        async fn night_with_threads(enabled: bool) -> CapturingService {
+            let night = SimulatedNight::new(short_plan());
+            let mut updater = night.updater().with_session_threads(enabled);
+            updater.initialize_baseline().await.unwrap();
+            for _ in 0..150 {
+                tokio::time::advance(Duration::from_secs(1)).await;
+                updater.poll_events().await;
+                updater.poll_sequence().await;
+            }
+            night.chat
+        }
+
+        let chat = night_with_threads(false).await;
+        assert!(chat.threads.lock().unwrap().is_empty());
+
+        let chat = night_with_threads(true).await;
+        let threads = chat.threads.lock().unwrap().clone();
+        assert_eq!(threads.len(), 1, "{threads:#?}");
+        assert!(threads[0].starts_with("sim · "), "{threads:#?}");
+        assert!(chat.posted("🧵"), "no pointer: {:#?}", chat.titles());
+    }
 }
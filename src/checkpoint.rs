@@ -0,0 +1,157 @@
+//! Where a chat updater keeps its dedupe state between runs.
+//!
+//! The updater serializes what it has already announced — seen event and
+//! image keys, the current target and filter, and the sequence operations it
+//! tracks — and hands the JSON to a store after every cycle that changed it.
+//! On the next start it restores that state before reading the rig, so the
+//! events that happened while it was down are delivered and nothing else.
+//! The local runtime keeps the checkpoint in a file in its data directory;
+//! the hub keeps it in SQLite.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Persistent storage for one updater's checkpoint.
+pub trait CheckpointStore: Send + Sync {
+    /// The last checkpoint saved, if any.
+    fn load(&self) -> Option<String>;
+
+    /// Replace the checkpoint. Failures are logged, not returned: the worst
+    /// outcome of a lost save is the baseline behavior of the next start.
+    fn save(&self, checkpoint: &str);
+}
+
+/// A checkpoint in a JSON file, replaced atomically on every save.
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn write(&self, checkpoint: &str) -> io::Result<()> {
+        let mut temp = self.path.clone();
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "checkpoint".to_string());
+        temp.set_file_name(format!(".{name}.tmp"));
+        fs::write(&temp, checkpoint)?;
+        fs::rename(&temp, &self.path)
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self) -> Option<String> {
+        match fs::read_to_string(&self.path) {
+            Ok(checkpoint) => Some(checkpoint),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => {
+                eprintln!("Could not read checkpoint {}: {error}", self.path.display());
+                None
+            }
+        }
+    }
+
+    fn save(&self, checkpoint: &str) {
+        if let Err(error) = self.write(checkpoint) {
+            eprintln!("Could not save checkpoint {}: {error}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalescing::CoalescingConfig;
+    use crate::test_support::{SimulatedNight, short_plan};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn file_store_round_trips_and_replaces() {
+        let dir = std::env::temp_dir().join(format!(
+            "chatstronomy-checkpoint-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let store = FileCheckpointStore::new(dir.join("updater.json"));
+        assert_eq!(store.load(), None);
+        store.save("{\"a\":1}");
+        store.save("{\"a\":2}");
+        assert_eq!(store.load().as_deref(), Some("{\"a\":2}"));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Keeps the checkpoint in memory, as the file or hub database would.
+    #[derive(Default)]
+    struct MemoryCheckpoint(Mutex<Option<String>>);
+
+    impl CheckpointStore for MemoryCheckpoint {
+        fn load(&self) -> Option<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn save(&self, checkpoint: &str) {
+            *self.0.lock().unwrap() = Some(checkpoint.to_string());
+        }
+    }
+
+    /// Run the short simulated night, replacing the updater at each of
+    /// `restarts` (tokio seconds) after `gap` seconds with nothing polling.
+    /// Returns every title posted except the startup welcomes.
+    async fn restarted_night(restarts: &[u64], gap: u64, checkpoint: bool) -> Vec<String> {
+        let night = SimulatedNight::new(short_plan());
+        let store = Arc::new(MemoryCheckpoint::default());
+        let build = || {
+            let updater = night
+                .updater()
+                .with_image_cooldown(0)
+                .with_coalescing(CoalescingConfig::disabled());
+            if checkpoint {
+                updater.with_checkpoint(store.clone())
+            } else {
+                updater
+            }
+        };
+
+        let mut updater = build();
+        updater.initialize_baseline().await.unwrap();
+        for second in 0..150 {
+            if restarts.contains(&second) {
+                tokio::time::advance(Duration::from_secs(gap)).await;
+                updater = build();
+                updater.initialize_baseline().await.unwrap();
+            }
+            tokio::time::advance(Duration::from_secs(1)).await;
+            updater.poll_events().await;
+            updater.poll_sequence().await;
+            updater.poll_images().await;
+            updater.flush_batches().await;
+            updater.save_checkpoint();
+        }
+        assert!(night.source.finished());
+        night
+            .chat
+            .titles()
+            .into_iter()
+            .filter(|title| !title.contains("monitor started"))
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn restarts_deliver_exactly_what_was_missed() {
+        let mut steady = restarted_night(&[], 0, true).await;
+        let mut resumed = restarted_night(&[30, 70], 10, true).await;
+        let fresh = restarted_night(&[30, 70], 10, false).await;
+        steady.sort();
+        resumed.sort();
+        assert_eq!(resumed, steady);
+        assert!(fresh.len() < steady.len(), "{fresh:#?}");
+    }
+}
@@ -0,0 +1,452 @@
+//! Burst coalescing and digests for event and image notifications.
+//!
+//! Sits between the chat updater's rules and the chat services. The first
+//! notification of a family (the event name up to its first `-`, so every
+//! `GUIDER-*` event is one family) goes out at once and opens a window;
+//! anything else of that family inside the window is held and posted
+//! together when it closes. In digest mode, non-critical families are only
+//! ever posted as one summary per route every few minutes. Critical
+//! families always go straight through, unless a `batch` notification rule
+//! explicitly holds them for the route's digest.
+
+use crate::alerts::AlertKind;
+use crate::chat::{ChatAttachment, ChatField, ChatMessage};
+use crate::notification_rules::Notice;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Per-telescope coalescing settings. Off unless configured, so every
+/// notification posts on its own until a telescope opts in.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CoalescingConfig {
+    /// How long a family stays grouped after its first post; 0, the
+    /// default, disables grouping. About 20 seconds covers a dither's worth
+    /// of guider chatter.
+    #[serde(default)]
+    pub window_seconds: u64,
+    /// Post non-critical families only as a summary this often.
+    #[serde(default)]
+    pub digest_minutes: Option<u64>,
+}
+
+impl CoalescingConfig {
+    /// Every notification posted on its own; the default.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_seconds > 3600 {
+            return Err("coalescing window must be at most 3600 seconds".to_string());
+        }
+        if self
+            .digest_minutes
+            .is_some_and(|minutes| !(1..=24 * 60).contains(&minutes))
+        {
+            return Err("digest interval must be 1 to 1440 minutes".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The family a notification groups under.
+pub fn family(notice: &Notice) -> &str {
+    notice
+        .event
+        .split_once('-')
+        .map_or(notice.event.as_str(), |(family, _)| family)
+}
+
+/// Errors, safety changes and anything that opens a critical alert are
+/// never held back.
+pub fn is_critical(notice: &Notice) -> bool {
+    AlertKind::for_event(&notice.event).is_some()
+        || matches!(family(notice), "ERROR" | "SAFETY")
+        || notice
+            .level
+            .as_deref()
+            .is_some_and(|level| level.eq_ignore_ascii_case("error"))
+}
+
+/// A notification held for a later summary.
+#[derive(Debug, Clone)]
+pub struct Held {
+    /// One-line form for the summary, stamped when it was held.
+    pub entry: ChatField,
+    pub message: ChatMessage,
+    pub attachments: Vec<ChatAttachment>,
+}
+
+/// Held notifications ready to post to one route.
+#[derive(Debug)]
+pub struct Flush {
+    pub route: usize,
+    /// The burst's family, or `None` for a digest.
+    pub family: Option<String>,
+    pub held: Vec<Held>,
+}
+
+#[derive(Debug)]
+struct Window {
+    closes: Instant,
+    held: Vec<Held>,
+}
+
+/// Open windows and digests for one updater's routes.
+#[derive(Debug, Default)]
+pub struct Coalescer {
+    windows: HashMap<(usize, String), Window>,
+    digests: HashMap<usize, Window>,
+}
+
+impl Coalescer {
+    /// Decide whether a notification for `route` posts now. When it does
+    /// not, it is held and comes back from [`Coalescer::due`].
+    pub fn admit(
+        &mut self,
+        config: &CoalescingConfig,
+        route: usize,
+        notice: &Notice,
+        held: impl FnOnce() -> Held,
+        now: Instant,
+    ) -> bool {
+        if is_critical(notice) {
+            return true;
+        }
+        if let Some(minutes) = config.digest_minutes {
+            self.hold(route, held(), Duration::from_secs(minutes * 60), now);
+            return false;
+        }
+        if config.window_seconds == 0 {
+            return true;
+        }
+        let key = (route, family(notice).to_string());
+        match self.windows.get_mut(&key) {
+            Some(window) if window.closes > now => {
+                window.held.push(held());
+                false
+            }
+            _ => {
+                self.windows.insert(
+                    key,
+                    Window {
+                        closes: now + Duration::from_secs(config.window_seconds),
+                        held: Vec::new(),
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Hold a notification for `route`'s next digest, due `wait` after the
+    /// first one held or sooner if a later hold asks. Digest mode holds
+    /// this way, as does a `batch` notification rule whatever the mode.
+    pub fn hold(&mut self, route: usize, held: Held, wait: Duration, now: Instant) {
+        let digest = self.digests.entry(route).or_insert_with(|| Window {
+            closes: now + wait,
+            held: Vec::new(),
+        });
+        digest.closes = digest.closes.min(now + wait);
+        digest.held.push(held);
+    }
+
+    /// Close every window and digest that is due, returning what they held.
+    pub fn due(&mut self, now: Instant) -> Vec<Flush> {
+        let mut flushes = Vec::new();
+        let closed: Vec<(usize, String)> = self
+            .windows
+            .iter()
+            .filter(|(_, window)| window.closes <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in closed {
+            if let Some(window) = self.windows.remove(&key)
+                && !window.held.is_empty()
+            {
+                flushes.push(Flush {
+                    route: key.0,
+                    family: Some(key.1),
+                    held: window.held,
+                });
+            }
+        }
+        let closed: Vec<usize> = self
+            .digests
+            .iter()
+            .filter(|(_, digest)| digest.closes <= now)
+            .map(|(route, _)| *route)
+            .collect();
+        for route in closed {
+            if let Some(digest) = self.digests.remove(&route) {
+                flushes.push(Flush {
+                    route,
+                    family: None,
+                    held: digest.held,
+                });
+            }
+        }
+        flushes.sort_by_key(|flush| flush.route);
+        flushes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_types;
+    use crate::simulator::{FailureKind, NightPlan, ScriptedFailure, SimulatedRigSource};
+    use crate::source::RigSource;
+    use crate::test_support::{SimulatedNight, short_plan};
+
+    fn notice(event: &str) -> Notice {
+        Notice {
+            event: event.to_string(),
+            ..Notice::default()
+        }
+    }
+
+    fn held(title: &str) -> impl FnOnce() -> Held {
+        let title = title.to_string();
+        move || Held {
+            entry: ChatField {
+                name: title.clone(),
+                value: String::new(),
+                inline: false,
+            },
+            message: ChatMessage::new(&title),
+            attachments: Vec::new(),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bursts_post_once_then_summarize() {
+        let config = CoalescingConfig {
+            window_seconds: 20,
+            ..CoalescingConfig::default()
+        };
+        let mut coalescer = Coalescer::default();
+        let dither = notice(event_types::GUIDER_DITHER);
+        let now = Instant::now();
+
+        assert!(coalescer.admit(&config, 0, &dither, held("1"), now));
+        assert!(!coalescer.admit(
+            &config,
+            0,
+            &notice(event_types::GUIDER_START),
+            held("2"),
+            now
+        ));
+        assert!(!coalescer.admit(&config, 0, &dither, held("3"), now));
+        // Other families and routes have windows of their own.
+        assert!(coalescer.admit(
+            &config,
+            0,
+            &notice(event_types::MOUNT_PARKED),
+            held("4"),
+            now
+        ));
+        assert!(coalescer.admit(&config, 1, &dither, held("5"), now));
+        // Critical families bypass even an open window.
+        assert!(coalescer.admit(&config, 0, &notice(event_types::ERROR_AF), held("6"), now));
+        assert!(coalescer.admit(&config, 0, &notice(event_types::ERROR_AF), held("7"), now));
+
+        assert!(coalescer.due(now).is_empty());
+        let later = now + Duration::from_secs(config.window_seconds);
+        let flushes = coalescer.due(later);
+        assert_eq!(flushes.len(), 1);
+        assert_eq!(flushes[0].family.as_deref(), Some("GUIDER"));
+        let titles: Vec<&str> = flushes[0]
+            .held
+            .iter()
+            .map(|held| held.entry.name.as_str())
+            .collect();
+        assert_eq!(titles, ["2", "3"]);
+        assert!(coalescer.admit(&config, 0, &dither, held("8"), later));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn digests_hold_everything_but_critical() {
+        let config = CoalescingConfig {
+            digest_minutes: Some(30),
+            ..CoalescingConfig::default()
+        };
+        let mut coalescer = Coalescer::default();
+        let now = Instant::now();
+        let mut failure = notice(event_types::NINA_NOTIFICATION);
+        failure.level = Some("Error".to_string());
+
+        assert!(!coalescer.admit(&config, 0, &notice(event_types::IMAGE_SAVE), held("1"), now));
+        assert!(!coalescer.admit(
+            &config,
+            0,
+            &notice(event_types::MOUNT_PARKED),
+            held("2"),
+            now
+        ));
+        assert!(coalescer.admit(&config, 0, &failure, held("3"), now));
+        assert!(coalescer.admit(
+            &config,
+            0,
+            &notice(event_types::SEQUENCE_ENTITY_FAILED),
+            held("4"),
+            now
+        ));
+
+        assert!(coalescer.due(now + Duration::from_secs(29 * 60)).is_empty());
+        let flushes = coalescer.due(now + Duration::from_secs(30 * 60));
+        assert_eq!(flushes.len(), 1);
+        assert_eq!(flushes[0].family, None);
+        assert_eq!(flushes[0].held.len(), 2);
+    }
+
+    #[test]
+    fn coalescing_is_opt_in() {
+        let config: CoalescingConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, CoalescingConfig::disabled());
+        assert_eq!(CoalescingConfig::default().window_seconds, 0);
+        let mut coalescer = Coalescer::default();
+        let dither = notice(event_types::GUIDER_DITHER);
+        let now = Instant::now();
+        assert!(coalescer.admit(&config, 0, &dither, held("1"), now));
+        assert!(coalescer.admit(&config, 0, &dither, held("2"), now));
+    }
+
+    #[test]
+    fn validates_limits() {
+        assert!(CoalescingConfig::default().validate().is_ok());
+        let config: CoalescingConfig = serde_json::from_str(r#"{"digest_minutes": 0}"#).unwrap();
+        assert!(config.validate().is_err());
+        assert_eq!(family(&notice("IMAGE-SAVE")), "IMAGE");
+        assert_eq!(family(&notice("CUSTOM")), "CUSTOM");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn batch_holds_join_the_digest_and_the_soonest_due_wins() {
+        let config = CoalescingConfig {
+            digest_minutes: Some(30),
+            ..CoalescingConfig::default()
+        };
+        let mut coalescer = Coalescer::default();
+        let now = Instant::now();
+
+        assert!(!coalescer.admit(&config, 0, &notice(event_types::IMAGE_SAVE), held("1"), now));
+        coalescer.hold(0, held("2")(), Duration::from_secs(5 * 60), now);
+        coalescer.hold(1, held("3")(), Duration::from_secs(5 * 60), now);
+
+        let flushes = coalescer.due(now + Duration::from_secs(5 * 60));
+        assert_eq!(flushes.len(), 2);
+        assert!(flushes.iter().all(|flush| flush.family.is_none()));
+        let titles: Vec<&str> = flushes[0]
+            .held
+            .iter()
+            .map(|held| held.entry.name.as_str())
+            .collect();
+        assert_eq!(titles, ["1", "2"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn held_batches_flush_between_resyncs_on_a_streaming_rig() {
+        let night = SimulatedNight::with_source(
+            SimulatedRigSource::new(short_plan())
+                .with_time_scale(60.0)
+                .with_event_stream(true),
+        );
+        let rules = serde_json::from_str(
+            r#"[{"match": {"events": ["GUIDER-*", "MOUNT-*"]}, "action": "batch", "minutes": 1}]"#,
+        )
+        .unwrap();
+        let mut updater = night.updater().with_notification_rules(rules);
+        let mut updates = night.source.subscribe_updates().unwrap();
+        updater.initialize_baseline().await.unwrap();
+
+        for _ in 0..30 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            updater.poll_events().await;
+        }
+        assert!(!night.chat.posted("📰 Digest"));
+
+        // The batch falls due a minute in, well before the resync window
+        // ends; the tick posts it without waiting for the next full poll.
+        assert!(
+            updater
+                .stream_updates(
+                    &mut updates,
+                    Duration::from_secs(600),
+                    Duration::from_secs(20)
+                )
+                .await
+        );
+        assert!(
+            night.chat.posted("📰 Digest"),
+            "batch still held: {:#?}",
+            night.chat.titles()
+        );
+    }
+
+    /// Every title a short night with a failed plate solve posts under
+    /// `coalescing`, flushing held posts each cycle as the updater does.
+    async fn coalesced_night(coalescing: CoalescingConfig) -> SimulatedNight {
+        let night = SimulatedNight::new(NightPlan {
+            failures: vec![ScriptedFailure {
+                at_minutes: 5.0,
+                kind: FailureKind::PlateSolveFailed,
+            }],
+            ..short_plan()
+        });
+        let mut updater = night
+            .updater()
+            .with_image_cooldown(0)
+            .with_coalescing(coalescing);
+        updater.initialize_baseline().await.unwrap();
+
+        for _ in 0..150 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            updater.poll_events().await;
+            updater.poll_sequence().await;
+            updater.poll_images().await;
+            updater.flush_batches().await;
+        }
+        assert!(night.source.finished());
+        night
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn simulated_bursts_coalesce_and_digests_summarize_but_errors_post_at_once() {
+        let single = coalesced_night(CoalescingConfig::disabled()).await.chat;
+        let grouped = coalesced_night(CoalescingConfig {
+            window_seconds: 20,
+            ..CoalescingConfig::default()
+        })
+        .await
+        .chat;
+        assert!(
+            grouped.count("Guider Dither") < single.count("Guider Dither"),
+            "dithers not grouped: {:#?}",
+            grouped.titles()
+        );
+        assert!(
+            grouped.posted("more GUIDER notifications"),
+            "no burst summary: {:#?}",
+            grouped.titles()
+        );
+        assert_eq!(grouped.count("ERROR-PLATESOLVE"), 1);
+
+        // One real minute is an hour of the simulated night.
+        let digest = coalesced_night(CoalescingConfig {
+            digest_minutes: Some(1),
+            ..CoalescingConfig::default()
+        })
+        .await
+        .chat;
+        assert!(
+            digest.posted("📰 Digest"),
+            "no digest: {:#?}",
+            digest.titles()
+        );
+        assert_eq!(digest.count("Guider Dither"), 0);
+        assert_eq!(digest.count("ERROR-PLATESOLVE"), 1);
+    }
+}
@@ -1,4 +1,9 @@
+use crate::alerts::CriticalAlertConfig;
 use crate::chat::{ChatConfig, TelescopeChatOverrides};
+use crate::coalescing::CoalescingConfig;
+use crate::image_quality::ImageQualityConfig;
+use crate::notification_rules::NotificationRules;
+use crate::weather::WeatherAlertConfig;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -25,6 +30,39 @@ pub struct TelescopeConfig {
     pub image_cooldown_seconds: u64,
     #[serde(default)]
     pub reconnect: ReconnectConfig,
+    /// Write every query this telescope answers to a JSONL session file that
+    /// `ReplayRigSource` can play back. Off when absent.
+    #[serde(default)]
+    pub record_session: Option<String>,
+    /// Keep what has been announced in this JSON file, so a restart posts
+    /// what happened while the runtime was down. Off when absent.
+    #[serde(default)]
+    pub checkpoint_file: Option<String>,
+    /// A N.I.N.A. `.hrz` horizon file, drawn on the mount's sky chart so
+    /// trees and roofs show up next to the target's track. Off when absent.
+    #[serde(default)]
+    pub horizon_file: Option<String>,
+    /// Early warnings from the weather station; the dew point alert is on
+    /// by default, wind and cloud limits only when set.
+    #[serde(default)]
+    pub weather_alerts: WeatherAlertConfig,
+    /// Alerts on star-count collapse, background jumps and HFR drift
+    /// against each target and filter's recent frames. On by default.
+    #[serde(default)]
+    pub image_quality: ImageQualityConfig,
+    /// Offline, unsafe, failed-instruction and download-timeout alerts that
+    /// re-notify until someone acknowledges them in chat.
+    #[serde(default)]
+    pub critical_alerts: CriticalAlertConfig,
+    /// Ordered rules deciding which event and image notifications are
+    /// dropped, delivered, delivered with a mention, or batched.
+    #[serde(default)]
+    pub notification_rules: NotificationRules,
+    /// Groups bursts of same-family notifications into one post, and
+    /// optionally posts non-critical ones only as a periodic digest. Off
+    /// when absent.
+    #[serde(default)]
+    pub coalescing: CoalescingConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +118,14 @@ impl Default for TelescopeConfig {
             chat: TelescopeChatOverrides::default(),
             image_cooldown_seconds: default_image_cooldown_seconds(),
             reconnect: ReconnectConfig::default(),
+            record_session: None,
+            checkpoint_file: None,
+            horizon_file: None,
+            weather_alerts: WeatherAlertConfig::default(),
+            image_quality: ImageQualityConfig::default(),
+            critical_alerts: CriticalAlertConfig::default(),
+            notification_rules: NotificationRules::default(),
+            coalescing: CoalescingConfig::default(),
         }
     }
 }
@@ -222,6 +268,11 @@ impl TelescopeConfig {
         {
             return Err(context("Discord bot is not enabled".to_string()));
         }
+        self.weather_alerts.validate().map_err(context)?;
+        self.image_quality.validate().map_err(context)?;
+        self.critical_alerts.validate().map_err(context)?;
+        self.notification_rules.validate().map_err(context)?;
+        self.coalescing.validate().map_err(context)?;
         Ok(())
     }
 }
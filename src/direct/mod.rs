@@ -2,12 +2,23 @@
 //!
 //! The transport is intentionally separate from these cross-platform types so
 //! identity, registration, and JSON compatibility can be tested on every CI
-//! platform before the Windows named-pipe listener is introduced.
+//! platform. Local runtimes reach the plugin over a current-user named pipe on
+//! Windows and a current-user Unix domain socket elsewhere; both carry the same
+//! line-framed stream.
 
 #[cfg(windows)]
 pub mod pipe_source;
 pub mod protocol;
 pub mod registry;
+#[cfg(unix)]
+pub mod socket_source;
+pub mod stream_source;
+
+/// Local Direct transport of the host platform.
+#[cfg(windows)]
+pub use pipe_source::DirectPipeRigSource as LocalDirectRigSource;
+#[cfg(unix)]
+pub use socket_source::DirectSocketRigSource as LocalDirectRigSource;
 
 pub use protocol::{
     AgentHello, ClientHello, DIRECT_WEBSOCKET_PATH, DirectMessage, LOCAL_PIPE_PREFIX,
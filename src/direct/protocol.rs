@@ -1,6 +1,6 @@
 //! Versioned messages exchanged between N.I.N.A. plugins and a Chatstronomy hub.
 
-use crate::source::{RigCapabilities, RigCommand};
+use crate::source::{RigCapabilities, RigCommand, RigUpdate};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -23,17 +23,30 @@ pub const LEGACY_PAYLOAD_VERSION: u16 = 1;
 /// | 1       | Direct v1 responses without a payload marker. |
 /// | 2       | Sequence operation reporting and Hub image attachments. |
 /// | 3       | `ChatEnabled` delivery flags, `TargetName`, and the `NINA-LOG` and `NINA-NOTIFICATION` event types. |
+/// | 4       | `subscribe`/`push` frames for rigs advertising `event_stream`. |
 ///
 /// Peers negotiate the highest contract they both understand. Payload fields
 /// remain additive, so readers must continue to default fields absent from an
 /// older selected contract.
-pub const CURRENT_PAYLOAD_VERSION: u16 = 3;
+pub const CURRENT_PAYLOAD_VERSION: u16 = 4;
 
 /// Payload contract that first carried the per-event delivery flags.
 pub const DELIVERY_FLAG_PAYLOAD_VERSION: u16 = 3;
 const _: () = assert!(DELIVERY_FLAG_PAYLOAD_VERSION > LEGACY_PAYLOAD_VERSION);
 const _: () = assert!(DELIVERY_FLAG_PAYLOAD_VERSION <= CURRENT_PAYLOAD_VERSION);
 
+/// Payload contract that first carried the pushed update stream. Older peers
+/// never see a `subscribe` frame and keep being polled.
+pub const EVENT_STREAM_PAYLOAD_VERSION: u16 = 4;
+const _: () = assert!(EVENT_STREAM_PAYLOAD_VERSION > DELIVERY_FLAG_PAYLOAD_VERSION);
+const _: () = assert!(EVENT_STREAM_PAYLOAD_VERSION <= CURRENT_PAYLOAD_VERSION);
+
+/// Whether a connection may use the pushed update stream: the rig must
+/// advertise it and both peers must have selected a contract that defines it.
+pub fn event_stream_negotiated(payload_version: u16, capabilities: &RigCapabilities) -> bool {
+    payload_version >= EVENT_STREAM_PAYLOAD_VERSION && capabilities.event_stream
+}
+
 /// Highest payload contract both peers understand.
 ///
 /// A client advertising a newer contract than this build is clamped rather than
@@ -190,6 +203,11 @@ pub enum QueryKind {
     GuiderGraph,
     RotatorInfo,
     FocuserInfo,
+    DomeInfo,
+    FlatDeviceInfo,
+    WeatherInfo,
+    SafetyInfo,
+    SwitchInfo,
     Command { command: RigCommand },
 }
 
@@ -205,6 +223,25 @@ pub struct QueryResult {
     pub error: Option<String>,
 }
 
+/// Topics the hub asks a streaming rig to push. Every topic the rig pushes is
+/// also still answerable as a query, so the hub can resynchronize at will.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSubscription {
+    pub events: bool,
+    pub images: bool,
+    pub sequence: bool,
+}
+
+impl StreamSubscription {
+    pub const fn all() -> Self {
+        Self {
+            events: true,
+            images: true,
+            sequence: true,
+        }
+    }
+}
+
 /// Direct protocol messages. The identity handshake (pair/auth → hello) is
 /// stable; query and heartbeat frames flow after it.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -217,6 +254,10 @@ pub enum DirectMessage {
     Auth(AuthRequest),
     Query(QueryRequest),
     QueryResult(QueryResult),
+    /// Hub to rig, payload v4+: start pushing the subscribed topics.
+    Subscribe(StreamSubscription),
+    /// Rig to hub, payload v4+: one update on a subscribed topic.
+    Push(RigUpdate),
     Heartbeat {
         seq: u64,
     },
@@ -310,11 +351,65 @@ mod tests {
                 "equipment_snapshots": false,
                 "autofocus_details": false,
                 "guider_graph": false,
-                "commands": false
+                "commands": false,
+                "event_stream": false,
+                "observing_conditions": false
             })
         );
     }
 
+    #[test]
+    fn event_stream_requires_capability_and_payload_version() {
+        let streaming = RigCapabilities::all();
+        assert!(event_stream_negotiated(
+            EVENT_STREAM_PAYLOAD_VERSION,
+            &streaming
+        ));
+        // A v3 contract predates push frames even if the flag is set.
+        assert!(!event_stream_negotiated(
+            EVENT_STREAM_PAYLOAD_VERSION - 1,
+            &streaming
+        ));
+        assert!(!event_stream_negotiated(
+            CURRENT_PAYLOAD_VERSION,
+            &RigCapabilities::none()
+        ));
+    }
+
+    #[test]
+    fn capabilities_without_event_stream_flag_default_to_polling() {
+        let message: DirectMessage = serde_json::from_str(include_str!(
+            "../../contracts/direct/v1/fixtures/client-hello-legacy.json"
+        ))
+        .unwrap();
+        let DirectMessage::ClientHello(hello) = message else {
+            panic!("expected client hello");
+        };
+        assert!(!hello.capabilities.event_stream);
+    }
+
+    #[test]
+    fn push_frames_have_stable_json_contract() {
+        let subscribe = DirectMessage::Subscribe(StreamSubscription::all());
+        let value = serde_json::to_value(&subscribe).unwrap();
+        assert_eq!(value["type"], "subscribe");
+        assert_eq!(value["payload"]["events"], true);
+
+        let message: DirectMessage = serde_json::from_str(include_str!(
+            "../../contracts/direct/v1/fixtures/push-event.json"
+        ))
+        .unwrap();
+        let DirectMessage::Push(RigUpdate::Event { event }) = &message else {
+            panic!("expected pushed event");
+        };
+        assert_eq!(event.event, "IMAGE-SAVE");
+        let value = serde_json::to_value(&message).unwrap();
+        assert_eq!(value["type"], "push");
+        assert_eq!(value["payload"]["kind"], "event");
+        let back: DirectMessage = serde_json::from_value(value).unwrap();
+        assert_eq!(back, message);
+    }
+
     #[test]
     fn query_request_has_stable_json_contract() {
         let message = DirectMessage::Query(QueryRequest {
@@ -480,7 +575,18 @@ mod tests {
             include_str!("../../contracts/direct/v1/fixtures/pair.json"),
             include_str!("../../contracts/direct/v1/fixtures/query-guider-graph.json"),
             include_str!("../../contracts/direct/v1/fixtures/query-command.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-slew.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-tracking.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-focuser.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-rotator.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-snapshot.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-dome.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-flat-panel.json"),
+            include_str!("../../contracts/direct/v1/fixtures/query-command-switch.json"),
             include_str!("../../contracts/direct/v1/fixtures/query-result.json"),
+            include_str!("../../contracts/direct/v1/fixtures/subscribe.json"),
+            include_str!("../../contracts/direct/v1/fixtures/push-event.json"),
+            include_str!("../../contracts/direct/v1/fixtures/push-image.json"),
             include_str!("../../contracts/direct/v1/fixtures/heartbeat.json"),
             include_str!("../../contracts/direct/v1/fixtures/error.json"),
         ];
@@ -0,0 +1,219 @@
+//! Native N.I.N.A. source over a current-user Unix domain socket.
+//!
+//! Linux and macOS hosts speak the same line-framed Direct stream as the
+//! Windows named pipe. A Direct pipe name maps to a socket inside a private
+//! per-user directory, and both ends refuse a peer running as another user.
+
+use crate::direct::stream_source::{CONNECT_TIMEOUT, DirectStreamRigSource};
+use crate::source::RigCapabilities;
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::net::{UnixListener, UnixStream};
+
+pub type DirectSocketRigSource = DirectStreamRigSource<UnixStream>;
+
+impl DirectStreamRigSource<UnixStream> {
+    pub async fn connect(pipe_name: &str, capabilities: RigCapabilities) -> Result<Self, String> {
+        let stream = connect_private_socket(&local_socket_path(pipe_name))
+            .await
+            .map_err(|error| format!("could not connect to Direct data socket: {error}"))?;
+        Ok(Self::new(stream, capabilities))
+    }
+}
+
+/// Directory holding the current user's Direct sockets: `$XDG_RUNTIME_DIR`
+/// when the session provides one, otherwise a private directory under `/tmp`.
+/// `/tmp` is used rather than `std::env::temp_dir()` because the macOS
+/// per-user temp path would push socket paths past the `sun_path` limit.
+pub fn local_socket_directory() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from) {
+        Some(runtime_dir) if runtime_dir.is_absolute() => runtime_dir.join("chatstronomy"),
+        _ => PathBuf::from(format!("/tmp/chatstronomy-{}", current_uid())),
+    }
+}
+
+/// Socket path standing in for the Windows pipe of the same name.
+pub fn local_socket_path(pipe_name: &str) -> PathBuf {
+    local_socket_directory().join(format!("{pipe_name}.sock"))
+}
+
+/// Bind a listener reachable only by the current user. The socket directory
+/// is created `0700` and refused when another user owns it or it is shared;
+/// the socket itself is `0600`. A stale socket left by a crashed host is
+/// replaced, but any other file at the path is not.
+pub fn bind_private_socket(path: &Path) -> io::Result<UnixListener> {
+    let directory = path.parent().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "socket path has no directory")
+    })?;
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(directory)?;
+    ensure_private_directory(directory)?;
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("refusing to replace non-socket file {}", path.display()),
+            ));
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error),
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Connect to a current-user socket, waiting for its host to bind it, and
+/// verify that the listening process runs as this user.
+pub async fn connect_private_socket(path: &Path) -> Result<UnixStream, String> {
+    let started = Instant::now();
+    let stream = loop {
+        match UnixStream::connect(path).await {
+            Ok(stream) => break stream,
+            Err(error) if started.elapsed() < CONNECT_TIMEOUT => {
+                if !matches!(
+                    error.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                ) {
+                    return Err(format!("{}: {error}", path.display()));
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            Err(error) => {
+                return Err(format!(
+                    "{} was not available within {} seconds: {error}",
+                    path.display(),
+                    CONNECT_TIMEOUT.as_secs()
+                ));
+            }
+        }
+    };
+    verify_peer(&stream).map_err(|error| error.to_string())?;
+    Ok(stream)
+}
+
+/// Refuse a connection whose peer process runs as another user.
+pub fn verify_peer(stream: &UnixStream) -> io::Result<()> {
+    let peer = stream.peer_cred()?;
+    if peer.uid() != current_uid() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("Direct socket peer runs as uid {}", peer.uid()),
+        ));
+    }
+    Ok(())
+}
+
+fn ensure_private_directory(directory: &Path) -> io::Result<()> {
+    let metadata = std::fs::metadata(directory)?;
+    if metadata.uid() != current_uid() || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} must be owned by the current user and closed to others",
+                directory.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn current_uid() -> u32 {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direct::protocol::{DirectMessage, QueryKind, QueryResult};
+    use crate::source::{RigCommand, RigSource};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    // Kept under /tmp so the path fits macOS's short sun_path.
+    fn scratch_directory() -> PathBuf {
+        PathBuf::from(format!(
+            "/tmp/chatstronomy-test-{}",
+            uuid::Uuid::new_v4().simple()
+        ))
+    }
+
+    #[test]
+    fn private_socket_is_closed_to_other_users() {
+        let directory = scratch_directory();
+        let path = directory.join("direct.sock");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            drop(bind_private_socket(&path).unwrap());
+            // The listener is gone but its socket file remains, as after a
+            // crash; binding again replaces it.
+            let _listener = bind_private_socket(&path).unwrap();
+        });
+
+        let directory_mode = std::fs::metadata(&directory).unwrap().mode();
+        assert_eq!(directory_mode & 0o777, 0o700);
+        let socket_mode = std::fs::symlink_metadata(&path).unwrap().mode();
+        assert_eq!(socket_mode & 0o777, 0o600);
+
+        std::fs::set_permissions(&directory, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let error = runtime.block_on(async { bind_private_socket(&path).unwrap_err() });
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn socket_source_answers_queries_from_the_host() {
+        let directory = scratch_directory();
+        let path = directory.join("direct.sock");
+        let listener = bind_private_socket(&path).unwrap();
+        let host = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            verify_peer(&stream).unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut line = String::new();
+            stream.read_line(&mut line).await.unwrap();
+            let DirectMessage::Query(query) = serde_json::from_str(&line).unwrap() else {
+                panic!("expected a query frame");
+            };
+            assert!(matches!(
+                query.kind,
+                QueryKind::Command {
+                    command: RigCommand::StopGuiding
+                }
+            ));
+            let mut frame = serde_json::to_vec(&DirectMessage::QueryResult(QueryResult {
+                id: query.id,
+                ok: true,
+                payload: serde_json::json!({
+                    "Response": "Guiding stopped",
+                    "Error": "",
+                    "StatusCode": 200,
+                    "Success": true,
+                    "Type": "API"
+                }),
+                error: None,
+            }))
+            .unwrap();
+            frame.push(b'\n');
+            stream.get_mut().write_all(&frame).await.unwrap();
+        });
+
+        let stream = connect_private_socket(&path).await.unwrap();
+        let source = DirectSocketRigSource::new(stream, RigCapabilities::all());
+        let response = source
+            .execute_command(RigCommand::StopGuiding)
+            .await
+            .unwrap();
+        assert!(response.success);
+        host.await.unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
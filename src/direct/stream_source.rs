@@ -0,0 +1,341 @@
+//! Native N.I.N.A. source over a line-framed local Direct stream.
+//!
+//! The framing, handshake and limits are shared by every local transport: the
+//! Windows named pipe and the Unix domain socket only differ in how the
+//! current-user stream is opened.
+
+use crate::api_types::CommandResponse;
+use crate::autofocus::AutofocusResponse;
+use crate::camera::CameraInfoResponse;
+use crate::direct::protocol::{DirectMessage, QueryKind, QueryRequest};
+use crate::dome::DomeInfoResponse;
+use crate::events::EventHistoryResponse;
+use crate::filterwheel::FilterWheelInfoResponse;
+use crate::flat_device::FlatDeviceInfoResponse;
+use crate::focuser::FocuserInfoResponse;
+use crate::guider::{GuiderGraphResponse, GuiderInfoResponse};
+use crate::images::{ImageHistoryResponse, ThumbnailResponse};
+use crate::mount::MountInfoResponse;
+use crate::rotator::RotatorInfoResponse;
+use crate::safety::SafetyInfoResponse;
+use crate::sequence::SequenceResponse;
+use crate::source::{
+    RigCapabilities, RigCommand, RigSource, RigSourceError, RigSourceKind, RigSourceResult,
+};
+use crate::switch::SwitchInfoResponse;
+use crate::weather::WeatherInfoResponse;
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+pub(crate) const QUERY_TIMEOUT: Duration = Duration::from_secs(15);
+// Loaded advanced sequences and long histories can legitimately exceed a
+// megabyte. Keep a bounded response, but leave enough room for a real session.
+pub(crate) const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+pub struct DirectStreamRigSource<S> {
+    capabilities: RigCapabilities,
+    connection: Mutex<BufReader<S>>,
+}
+
+impl<S> DirectStreamRigSource<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Wrap an already opened current-user stream. The platform transports
+    /// verify the peer before handing the stream over.
+    pub fn new(stream: S, capabilities: RigCapabilities) -> Self {
+        Self {
+            capabilities,
+            connection: Mutex::new(BufReader::new(stream)),
+        }
+    }
+    fn unavailable(reason: impl Into<String>) -> RigSourceError {
+        RigSourceError::Unavailable {
+            kind: RigSourceKind::NinaDirect,
+            reason: reason.into(),
+        }
+    }
+
+    fn unsupported(capability: &'static str) -> RigSourceError {
+        RigSourceError::Unsupported {
+            kind: RigSourceKind::NinaDirect,
+            capability,
+        }
+    }
+
+    async fn query_as<T: serde::de::DeserializeOwned>(
+        &self,
+        kind: QueryKind,
+    ) -> RigSourceResult<T> {
+        let id = Uuid::new_v4();
+        // The local current-user stream is synchronous and cannot queue work
+        // across reconnects, so it does not need the remote hub's expiry
+        // deadline. Remote Direct queries set this field at the hub boundary.
+        let request = DirectMessage::Query(QueryRequest {
+            id,
+            expires_at: None,
+            kind,
+        });
+        let mut frame = serde_json::to_vec(&request)
+            .map_err(|error| Self::unavailable(format!("could not encode query: {error}")))?;
+        frame.push(b'\n');
+
+        let exchange = async {
+            let mut connection = self.connection.lock().await;
+            connection.write_all(&frame).await?;
+            connection.flush().await?;
+
+            let mut response = String::new();
+            let bytes = connection.read_line(&mut response).await?;
+            if bytes == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "Direct data stream closed",
+                ));
+            }
+            if response.len() > MAX_FRAME_BYTES {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Direct response exceeds the size limit",
+                ));
+            }
+            Ok::<String, std::io::Error>(response)
+        };
+
+        let response = tokio::time::timeout(QUERY_TIMEOUT, exchange)
+            .await
+            .map_err(|_| Self::unavailable("Direct query timed out"))?
+            .map_err(|error| Self::unavailable(error.to_string()))?;
+        let message: DirectMessage = serde_json::from_str(&response)
+            .map_err(|error| Self::unavailable(format!("invalid Direct response: {error}")))?;
+        let DirectMessage::QueryResult(result) = message else {
+            return Err(Self::unavailable("plugin returned a non-result frame"));
+        };
+        if result.id != id {
+            return Err(Self::unavailable("plugin returned a mismatched query ID"));
+        }
+        if !result.ok {
+            return Err(Self::unavailable(
+                result.error.unwrap_or_else(|| "query failed".to_string()),
+            ));
+        }
+        serde_json::from_value(result.payload)
+            .map_err(|error| Self::unavailable(format!("invalid payload from plugin: {error}")))
+    }
+}
+
+#[async_trait]
+impl<S> RigSource for DirectStreamRigSource<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    fn kind(&self) -> RigSourceKind {
+        RigSourceKind::NinaDirect
+    }
+
+    fn capabilities(&self) -> RigCapabilities {
+        self.capabilities
+    }
+
+    async fn get_event_history(&self) -> RigSourceResult<EventHistoryResponse> {
+        if !self.capabilities.event_history {
+            return Err(Self::unsupported("event history"));
+        }
+        self.query_as(QueryKind::EventHistory).await
+    }
+
+    async fn get_all_image_history(&self) -> RigSourceResult<ImageHistoryResponse> {
+        if !self.capabilities.image_history {
+            return Err(Self::unsupported("image history"));
+        }
+        self.query_as(QueryKind::ImageHistory).await
+    }
+
+    async fn get_sequence(&self) -> RigSourceResult<SequenceResponse> {
+        if !self.capabilities.sequence {
+            return Err(Self::unsupported("sequence"));
+        }
+        self.query_as(QueryKind::Sequence).await
+    }
+
+    async fn get_thumbnail(&self, index: u32) -> RigSourceResult<ThumbnailResponse> {
+        if !self.capabilities.thumbnails {
+            return Err(Self::unsupported("thumbnails"));
+        }
+        self.query_as(QueryKind::Thumbnail { index }).await
+    }
+
+    async fn get_last_autofocus(&self) -> RigSourceResult<AutofocusResponse> {
+        if !self.capabilities.autofocus_details {
+            return Err(Self::unsupported("autofocus details"));
+        }
+        self.query_as(QueryKind::LastAutofocus).await
+    }
+
+    async fn get_mount_info(&self) -> RigSourceResult<MountInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::MountInfo).await
+    }
+
+    async fn get_camera_info(&self) -> RigSourceResult<CameraInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::CameraInfo).await
+    }
+
+    async fn get_filterwheel_info(&self) -> RigSourceResult<FilterWheelInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::FilterwheelInfo).await
+    }
+
+    async fn get_guider_info(&self) -> RigSourceResult<GuiderInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::GuiderInfo).await
+    }
+
+    async fn get_guider_graph(&self) -> RigSourceResult<GuiderGraphResponse> {
+        if !self.capabilities.guider_graph {
+            return Err(Self::unsupported("guider graph"));
+        }
+        self.query_as(QueryKind::GuiderGraph).await
+    }
+
+    async fn get_rotator_info(&self) -> RigSourceResult<RotatorInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::RotatorInfo).await
+    }
+
+    async fn get_focuser_info(&self) -> RigSourceResult<FocuserInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::FocuserInfo).await
+    }
+
+    async fn get_dome_info(&self) -> RigSourceResult<DomeInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::DomeInfo).await
+    }
+
+    async fn get_flat_device_info(&self) -> RigSourceResult<FlatDeviceInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::FlatDeviceInfo).await
+    }
+
+    async fn get_weather_info(&self) -> RigSourceResult<WeatherInfoResponse> {
+        if !self.capabilities.observing_conditions {
+            return Err(Self::unsupported("observing conditions"));
+        }
+        self.query_as(QueryKind::WeatherInfo).await
+    }
+
+    async fn get_safety_info(&self) -> RigSourceResult<SafetyInfoResponse> {
+        if !self.capabilities.observing_conditions {
+            return Err(Self::unsupported("observing conditions"));
+        }
+        self.query_as(QueryKind::SafetyInfo).await
+    }
+
+    async fn get_switch_info(&self) -> RigSourceResult<SwitchInfoResponse> {
+        if !self.capabilities.equipment_snapshots {
+            return Err(Self::unsupported("equipment snapshots"));
+        }
+        self.query_as(QueryKind::SwitchInfo).await
+    }
+
+    async fn execute_command(&self, command: RigCommand) -> RigSourceResult<CommandResponse> {
+        if !self.capabilities.supports_command(command.kind()) {
+            return Err(Self::unsupported("commands"));
+        }
+        self.query_as(QueryKind::Command { command }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direct::protocol::QueryResult;
+
+    async fn answer_next_query(
+        host: &mut BufReader<tokio::io::DuplexStream>,
+        answer: impl FnOnce(Uuid) -> DirectMessage,
+    ) {
+        let mut line = String::new();
+        host.read_line(&mut line).await.unwrap();
+        let DirectMessage::Query(query) = serde_json::from_str(&line).unwrap() else {
+            panic!("expected a query frame");
+        };
+        let mut frame = serde_json::to_vec(&answer(query.id)).unwrap();
+        frame.push(b'\n');
+        host.get_mut().write_all(&frame).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn mismatched_and_failed_results_are_unavailable() {
+        let (client, host) = tokio::io::duplex(64 * 1024);
+        let source = DirectStreamRigSource::new(client, RigCapabilities::all());
+        let mut host = BufReader::new(host);
+
+        let (result, ()) = tokio::join!(
+            source.get_guider_info(),
+            answer_next_query(&mut host, |_| {
+                DirectMessage::QueryResult(QueryResult {
+                    id: Uuid::new_v4(),
+                    ok: true,
+                    payload: serde_json::Value::Null,
+                    error: None,
+                })
+            })
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("mismatched query ID")
+        );
+
+        let (result, ()) = tokio::join!(
+            source.get_guider_info(),
+            answer_next_query(&mut host, |id| {
+                DirectMessage::QueryResult(QueryResult {
+                    id,
+                    ok: false,
+                    payload: serde_json::Value::Null,
+                    error: Some("guider is not connected".to_string()),
+                })
+            })
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("guider is not connected")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn silent_host_times_out() {
+        let (client, _host) = tokio::io::duplex(64 * 1024);
+        let source = DirectStreamRigSource::new(client, RigCapabilities::all());
+
+        let error = source.get_mount_info().await.unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+    }
+}
@@ -42,8 +42,9 @@ fn retry_delay_for(status: reqwest::StatusCode, retry_after: Option<&str>) -> Op
 /// Run a webhook request, retrying rate-limited and transient failures.
 ///
 /// The request is rebuilt per attempt because a multipart body cannot be
-/// cloned. The final failure is returned so the caller still sees the status.
-async fn send_with_retry<F, Fut>(mut build: F) -> Result<(), DiscordError>
+/// cloned. The final failure is returned so the caller still sees the status;
+/// on success the response is handed back for callers that read it.
+async fn send_with_retry<F, Fut>(mut build: F) -> Result<reqwest::Response, DiscordError>
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = reqwest::Result<reqwest::Response>>,
@@ -52,7 +53,7 @@ where
     loop {
         let response = build().await?;
         if response.status().is_success() {
-            return Ok(());
+            return Ok(response);
         }
 
         let delay = retry_delay(&response).filter(|_| attempt < MAX_SEND_ATTEMPTS);
@@ -79,6 +80,8 @@ where
 pub struct DiscordWebhook {
     client: Client,
     webhook_url: String,
+    /// Post into this thread of the webhook's channel instead.
+    thread_id: Option<u64>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -105,6 +108,9 @@ pub struct WebhookMessage {
     pub attachments: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub flags: Option<u32>,
+    /// Open a new post with this name; forum channels only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -268,9 +274,35 @@ impl DiscordWebhook {
         Ok(Self {
             client,
             webhook_url,
+            thread_id: None,
         })
     }
 
+    /// Send everything into `thread_id` rather than the channel itself.
+    pub fn in_thread(mut self, thread_id: u64) -> Self {
+        self.thread_id = Some(thread_id);
+        self
+    }
+
+    /// The webhook's snowflake, from its URL.
+    pub fn webhook_id(&self) -> Option<u64> {
+        self.webhook_url
+            .split("/webhooks/")
+            .nth(1)?
+            .split('/')
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// The URL to post to, with the thread selected when there is one.
+    fn post_url(&self) -> String {
+        match self.thread_id {
+            Some(thread_id) => format!("{}?thread_id={thread_id}", self.webhook_url),
+            None => self.webhook_url.clone(),
+        }
+    }
+
     pub async fn execute(&self, message: &WebhookMessage) -> Result<(), DiscordError> {
         self.execute_with_params(message, None).await
     }
@@ -280,7 +312,7 @@ impl DiscordWebhook {
         message: &WebhookMessage,
         params: Option<HashMap<&str, &str>>,
     ) -> Result<(), DiscordError> {
-        let mut url = self.webhook_url.clone();
+        let mut url = self.post_url();
 
         // Add query parameters if provided
         if let Some(params) = params {
@@ -289,7 +321,8 @@ impl DiscordWebhook {
                 .map(|(k, v)| format!("{k}={v}"))
                 .collect::<Vec<_>>()
                 .join("&");
-            url = format!("{url}?{query_string}");
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url = format!("{url}{separator}{query_string}");
         }
 
         send_with_retry(|| {
@@ -300,6 +333,32 @@ impl DiscordWebhook {
                 .send()
         })
         .await
+        .map(|_| ())
+    }
+
+    /// Open a new post in the webhook's forum channel, starting with
+    /// `embed`, and return the new thread's ID. Discord rejects this for
+    /// webhooks of ordinary text channels.
+    pub async fn start_forum_thread(&self, name: &str, embed: Embed) -> Result<u64, DiscordError> {
+        let message = WebhookMessage {
+            embeds: Some(vec![embed]),
+            thread_name: Some(name.to_string()),
+            ..WebhookMessage::default()
+        };
+        let url = format!("{}?wait=true", self.webhook_url);
+        let response = send_with_retry(|| {
+            self.client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(&message)
+                .send()
+        })
+        .await?;
+        let posted: serde_json::Value = response.json().await?;
+        forum_thread_id(&posted).ok_or_else(|| DiscordError::Http {
+            status: 200,
+            message: "forum post response has no channel_id".to_string(),
+        })
     }
 
     pub async fn execute_with_embed(
@@ -319,6 +378,7 @@ impl DiscordWebhook {
             payload_json: None,
             attachments: None,
             flags: None,
+            thread_name: None,
         };
 
         self.execute(&message).await
@@ -354,6 +414,7 @@ impl DiscordWebhook {
             payload_json: None,
             attachments: None,
             flags: None,
+            thread_name: None,
         };
 
         let payload_json = serde_json::to_string(&message)?;
@@ -371,9 +432,10 @@ impl DiscordWebhook {
             }
             form = form.text("payload_json", payload_json.clone());
 
-            self.client.post(&self.webhook_url).multipart(form).send()
+            self.client.post(self.post_url()).multipart(form).send()
         })
         .await
+        .map(|_| ())
     }
 }
 
@@ -477,6 +539,11 @@ impl Embed {
     }
 }
 
+/// The thread a `wait=true` forum post opened: the message's channel.
+fn forum_thread_id(posted: &serde_json::Value) -> Option<u64> {
+    posted.get("channel_id")?.as_str()?.parse().ok()
+}
+
 // Color constants for common embed colors
 pub mod colors {
     pub const RED: u32 = 0xFF0000;
@@ -520,4 +587,20 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn threads_are_selected_by_query_and_read_from_the_post() {
+        let webhook =
+            DiscordWebhook::new("https://discord.com/api/webhooks/123456/token-abc".to_string())
+                .unwrap();
+        assert_eq!(webhook.webhook_id(), Some(123456));
+        let threaded = webhook.in_thread(42);
+        assert_eq!(
+            threaded.post_url(),
+            "https://discord.com/api/webhooks/123456/token-abc?thread_id=42"
+        );
+        let posted = serde_json::json!({"id": "1", "channel_id": "987654321"});
+        assert_eq!(forum_thread_id(&posted), Some(987654321));
+        assert_eq!(forum_thread_id(&serde_json::json!({"id": "1"})), None);
+    }
 }
@@ -0,0 +1,188 @@
+use crate::serde_helpers::de_f64_tolerant;
+use crate::source::RigCommand;
+use serde::{Deserialize, Serialize};
+
+/// Dome snapshot returned by a Direct N.I.N.A. rig.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DomeInfoResponse {
+    pub response: DomeInfo,
+    pub error: String,
+    pub status_code: i32,
+    pub success: bool,
+    #[serde(rename = "Type")]
+    pub response_type: String,
+}
+
+/// The dome and shutter fields Chatstronomy reports and checks commands
+/// against. Unknown additive fields are intentionally ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DomeInfo {
+    pub connected: bool,
+    /// ASCOM shutter state as N.I.N.A. names it: `ShutterOpen`,
+    /// `ShutterClosed`, `ShutterOpening`, `ShutterClosing`, `ShutterError`,
+    /// or `ShutterNone` when the dome has no shutter.
+    #[serde(default)]
+    pub shutter_status: String,
+    #[serde(default)]
+    pub can_set_shutter: bool,
+    #[serde(default)]
+    pub can_park: bool,
+    #[serde(default)]
+    pub can_find_home: bool,
+    #[serde(default)]
+    pub can_set_azimuth: bool,
+    #[serde(default)]
+    pub at_park: bool,
+    #[serde(default)]
+    pub at_home: bool,
+    #[serde(default)]
+    pub slewing: bool,
+    #[serde(default, deserialize_with = "de_f64_tolerant")]
+    pub azimuth: f64,
+    /// Whether N.I.N.A. is slaving the dome to the mount.
+    #[serde(default)]
+    pub is_following: bool,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+impl DomeInfo {
+    /// The shutter state without N.I.N.A.'s `Shutter` prefix, lowercased
+    /// (`open`, `closing`, ...).
+    pub fn shutter_label(&self) -> String {
+        let status = self.shutter_status.trim();
+        let status = status.strip_prefix("Shutter").unwrap_or(status);
+        if status.is_empty() {
+            "unknown".to_string()
+        } else {
+            status.to_ascii_lowercase()
+        }
+    }
+
+    pub fn shutter_open(&self) -> bool {
+        self.shutter_status.eq_ignore_ascii_case("ShutterOpen")
+    }
+
+    /// One-line state for status embeds.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("Shutter {}", self.shutter_label())];
+        if self.at_park {
+            parts.push("parked".to_string());
+        } else if self.azimuth.is_finite() {
+            parts.push(format!("Az {:.0}°", self.azimuth));
+        }
+        if self.slewing {
+            parts.push("slewing".to_string());
+        }
+        if self.is_following {
+            parts.push("slaved".to_string());
+        }
+        parts.join(" · ")
+    }
+}
+
+impl DomeInfoResponse {
+    /// Check a dome command against the live dome. Closing the shutter is
+    /// only refused when the dome cannot close at all, never because it is
+    /// moving: it is the command used when weather turns. A snapshot that
+    /// failed or shows the dome disconnected says nothing certain about the
+    /// shutter, so a close goes out and the rig has the final say.
+    pub fn check_command(&self, command: &RigCommand) -> Result<(), String> {
+        if !matches!(
+            command,
+            RigCommand::OpenDomeShutter
+                | RigCommand::CloseDomeShutter
+                | RigCommand::ParkDome
+                | RigCommand::SetDomeSlaving { .. }
+        ) {
+            return Ok(());
+        }
+        let dome = &self.response;
+        if !(self.success && dome.connected) {
+            return match command {
+                RigCommand::CloseDomeShutter => Ok(()),
+                _ => Err("the dome is not connected".to_string()),
+            };
+        }
+        match command {
+            RigCommand::OpenDomeShutter | RigCommand::CloseDomeShutter if !dome.can_set_shutter => {
+                Err("the dome has no controllable shutter".to_string())
+            }
+            RigCommand::OpenDomeShutter if dome.shutter_status.ends_with("Error") => {
+                Err("the shutter reports an error; check the dome before opening".to_string())
+            }
+            RigCommand::ParkDome if !dome.can_park => Err("the dome cannot park".to_string()),
+            RigCommand::ParkDome | RigCommand::SetDomeSlaving { enabled: true } if dome.slewing => {
+                Err(format!(
+                    "the dome is still slewing (at {:.0}°)",
+                    dome.azimuth
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dome_snapshot() {
+        let json = r#"{"Response":{"Connected":true,"ShutterStatus":"ShutterOpen","DriverCanFollow":false,"CanSetShutter":true,"CanSetPark":false,"CanSetAzimuth":true,"CanSyncAzimuth":true,"CanPark":true,"CanFindHome":true,"AtPark":false,"AtHome":false,"DriverFollowing":false,"Slewing":false,"Azimuth":183.4,"IsFollowing":true,"IsSynchronized":true,"Name":"ScopeDome","DisplayName":"ScopeDome LS"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let parsed: DomeInfoResponse = serde_json::from_str(json).unwrap();
+
+        assert!(parsed.response.connected);
+        assert!(parsed.response.shutter_open());
+        assert_eq!(parsed.response.shutter_label(), "open");
+        assert_eq!(parsed.response.summary(), "Shutter open · Az 183° · slaved");
+    }
+
+    #[test]
+    fn tolerates_unknown_dome_fields() {
+        let json = r#"{"Response":{"Connected":false,"Azimuth":"NaN"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let parsed: DomeInfoResponse = serde_json::from_str(json).unwrap();
+
+        assert!(parsed.response.azimuth.is_nan());
+        assert_eq!(parsed.response.shutter_label(), "unknown");
+        assert_eq!(
+            parsed.check_command(&RigCommand::OpenDomeShutter),
+            Err("the dome is not connected".to_string())
+        );
+    }
+
+    #[test]
+    fn closing_is_left_to_the_rig_when_the_dome_cannot_be_read() {
+        let json = r#"{"Response":{"Connected":false},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let mut dome: DomeInfoResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(dome.check_command(&RigCommand::CloseDomeShutter), Ok(()));
+        assert!(dome.check_command(&RigCommand::ParkDome).is_err());
+
+        dome.response.connected = true;
+        dome.success = false;
+        assert_eq!(dome.check_command(&RigCommand::CloseDomeShutter), Ok(()));
+        assert!(dome.check_command(&RigCommand::OpenDomeShutter).is_err());
+    }
+
+    #[test]
+    fn closing_is_accepted_while_the_dome_moves() {
+        let json = r#"{"Response":{"Connected":true,"ShutterStatus":"ShutterOpening","CanSetShutter":true,"CanPark":true,"Slewing":true,"Azimuth":90},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let mut dome: DomeInfoResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(dome.check_command(&RigCommand::CloseDomeShutter), Ok(()));
+        assert!(
+            dome.check_command(&RigCommand::ParkDome)
+                .unwrap_err()
+                .contains("slewing")
+        );
+        dome.response.shutter_status = "ShutterError".to_string();
+        assert!(dome.check_command(&RigCommand::OpenDomeShutter).is_err());
+        assert_eq!(dome.check_command(&RigCommand::CloseDomeShutter), Ok(()));
+        dome.response.can_set_shutter = false;
+        assert!(dome.check_command(&RigCommand::CloseDomeShutter).is_err());
+    }
+}
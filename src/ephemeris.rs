@@ -0,0 +1,801 @@
+//! Sun, moon and target positions for the observing site.
+//!
+//! Low-precision series good to a fraction of a degree and a minute or two
+//! in time, which is plenty for "astronomical dawn in 42 min" or "sets below
+//! 30° at 03:10". Everything is computed locally from the mount's site and
+//! the target's coordinates; nothing is looked up over the network. Target
+//! coordinates are used as given (J2000 in practice); precession over a few
+//! decades moves them well under a degree.
+
+use crate::events::TargetCoordinates;
+use crate::mount::MountInfoResponse;
+use crate::sequence::OperationCoordinates;
+use chrono::{DateTime, Duration, Local, Utc};
+
+/// Altitude limit used when none is given.
+pub const DEFAULT_ALTITUDE_LIMIT: f64 = 30.0;
+
+/// Solar days per sidereal day.
+const SIDEREAL_RATE: f64 = 1.002_737_909;
+
+/// Where the telescope is: degrees (east positive) and meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Site {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: f64,
+}
+
+impl Site {
+    /// The site the mount reports, unless it reports none (N.I.N.A. leaves
+    /// latitude and longitude at zero until they are set).
+    pub fn from_mount(mount: &MountInfoResponse) -> Option<Self> {
+        let (latitude, longitude, elevation) = mount.get_site_info();
+        let site = Self {
+            latitude,
+            longitude,
+            elevation: f64::from(elevation),
+        };
+        site.is_valid().then_some(site)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.latitude.is_finite()
+            && self.longitude.is_finite()
+            && (-90.0..=90.0).contains(&self.latitude)
+            && (-180.0..=360.0).contains(&self.longitude)
+            && (self.latitude != 0.0 || self.longitude != 0.0)
+    }
+}
+
+/// Right ascension in hours and declination in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equatorial {
+    pub ra_hours: f64,
+    pub dec_degrees: f64,
+}
+
+impl Equatorial {
+    pub fn from_target(coordinates: &TargetCoordinates) -> Option<Self> {
+        Self::checked(coordinates.ra, coordinates.dec)
+    }
+
+    pub fn from_operation(coordinates: &OperationCoordinates) -> Option<Self> {
+        Self::checked(coordinates.ra_hours?, coordinates.dec_degrees?)
+    }
+
+    fn checked(ra_hours: f64, dec_degrees: f64) -> Option<Self> {
+        (ra_hours.is_finite() && dec_degrees.is_finite() && (-90.0..=90.0).contains(&dec_degrees))
+            .then(|| Self {
+                ra_hours: ra_hours.rem_euclid(24.0),
+                dec_degrees,
+            })
+    }
+
+    /// Angular distance to `other` in degrees.
+    pub fn separation(&self, other: &Equatorial) -> f64 {
+        let (ra1, dec1) = (
+            (self.ra_hours * 15.0).to_radians(),
+            self.dec_degrees.to_radians(),
+        );
+        let (ra2, dec2) = (
+            (other.ra_hours * 15.0).to_radians(),
+            other.dec_degrees.to_radians(),
+        );
+        let cos = dec1.sin() * dec2.sin() + dec1.cos() * dec2.cos() * (ra1 - ra2).cos();
+        cos.clamp(-1.0, 1.0).acos().to_degrees()
+    }
+}
+
+/// Days since J2000.0.
+fn days_since_j2000(at: DateTime<Utc>) -> f64 {
+    at.timestamp_millis() as f64 / 86_400_000.0 + 2_440_587.5 - 2_451_545.0
+}
+
+/// Greenwich mean sidereal time shifted to `longitude` (east positive), in
+/// hours. Accurate to well under a second over the years a rig runs.
+pub fn local_sidereal_hours(at: DateTime<Utc>, longitude: f64) -> f64 {
+    let days = days_since_j2000(at);
+    (18.697_374_558 + 24.065_709_824_419_08 * days + longitude / 15.0).rem_euclid(24.0)
+}
+
+/// Altitude and azimuth (north through east) in degrees.
+pub fn alt_az(ra_hours: f64, dec: f64, lst_hours: f64, latitude: f64) -> (f64, f64) {
+    let hour_angle = ((lst_hours - ra_hours) * 15.0).to_radians();
+    let (dec, latitude) = (dec.to_radians(), latitude.to_radians());
+    let altitude =
+        (dec.sin() * latitude.sin() + dec.cos() * latitude.cos() * hour_angle.cos()).asin();
+    let azimuth = (-hour_angle.sin() * dec.cos())
+        .atan2(latitude.cos() * dec.sin() - latitude.sin() * dec.cos() * hour_angle.cos());
+    (
+        altitude.to_degrees(),
+        azimuth.to_degrees().rem_euclid(360.0),
+    )
+}
+
+/// Altitude and azimuth of `position` from `site` at `at`.
+pub fn horizontal(site: &Site, position: &Equatorial, at: DateTime<Utc>) -> (f64, f64) {
+    alt_az(
+        position.ra_hours,
+        position.dec_degrees,
+        local_sidereal_hours(at, site.longitude),
+        site.latitude,
+    )
+}
+
+fn sin_deg(degrees: f64) -> f64 {
+    degrees.to_radians().sin()
+}
+
+fn cos_deg(degrees: f64) -> f64 {
+    degrees.to_radians().cos()
+}
+
+fn obliquity(days: f64) -> f64 {
+    23.439 - 0.000_000_4 * days
+}
+
+fn ecliptic_to_equatorial(longitude: f64, latitude: f64, obliquity: f64) -> Equatorial {
+    let x = cos_deg(latitude) * cos_deg(longitude);
+    let y = cos_deg(obliquity) * cos_deg(latitude) * sin_deg(longitude)
+        - sin_deg(obliquity) * sin_deg(latitude);
+    let z = sin_deg(obliquity) * cos_deg(latitude) * sin_deg(longitude)
+        + cos_deg(obliquity) * sin_deg(latitude);
+    Equatorial {
+        ra_hours: (y.atan2(x).to_degrees() / 15.0).rem_euclid(24.0),
+        dec_degrees: z.clamp(-1.0, 1.0).asin().to_degrees(),
+    }
+}
+
+/// The sun's apparent ecliptic longitude in degrees.
+fn sun_longitude(days: f64) -> f64 {
+    let mean_longitude = 280.460 + 0.985_647_4 * days;
+    let anomaly = 357.528 + 0.985_600_3 * days;
+    (mean_longitude + 1.915 * sin_deg(anomaly) + 0.020 * sin_deg(2.0 * anomaly)).rem_euclid(360.0)
+}
+
+pub fn sun_position(at: DateTime<Utc>) -> Equatorial {
+    let days = days_since_j2000(at);
+    ecliptic_to_equatorial(sun_longitude(days), 0.0, obliquity(days))
+}
+
+/// The moon's ecliptic longitude and latitude and horizontal parallax, in
+/// degrees.
+fn moon_ecliptic(days: f64) -> (f64, f64, f64) {
+    let t = days / 36_525.0;
+    let longitude = 218.32 + 481_267.881 * t + 6.29 * sin_deg(135.0 + 477_198.87 * t)
+        - 1.27 * sin_deg(259.3 - 413_335.36 * t)
+        + 0.66 * sin_deg(235.7 + 890_534.22 * t)
+        + 0.21 * sin_deg(269.9 + 954_397.74 * t)
+        - 0.19 * sin_deg(357.5 + 35_999.05 * t)
+        - 0.11 * sin_deg(186.5 + 966_404.03 * t);
+    let latitude = 5.13 * sin_deg(93.3 + 483_202.02 * t) + 0.28 * sin_deg(228.2 + 960_400.89 * t)
+        - 0.28 * sin_deg(318.3 + 6_003.15 * t)
+        - 0.17 * sin_deg(217.6 - 407_332.21 * t);
+    let parallax = 0.9508
+        + 0.0518 * cos_deg(135.0 + 477_198.87 * t)
+        + 0.0095 * cos_deg(259.3 - 413_335.36 * t)
+        + 0.0078 * cos_deg(235.7 + 890_534.22 * t)
+        + 0.0028 * cos_deg(269.9 + 954_397.74 * t);
+    (longitude.rem_euclid(360.0), latitude, parallax)
+}
+
+pub fn moon_position(at: DateTime<Utc>) -> Equatorial {
+    let days = days_since_j2000(at);
+    let (longitude, latitude, _) = moon_ecliptic(days);
+    ecliptic_to_equatorial(longitude, latitude, obliquity(days))
+}
+
+/// Altitude of the sun's center in degrees, without refraction.
+pub fn sun_altitude(site: &Site, at: DateTime<Utc>) -> f64 {
+    horizontal(site, &sun_position(at), at).0
+}
+
+/// The moon as seen from the site.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonInfo {
+    /// Illuminated fraction, 0 to 1.
+    pub illumination: f64,
+    /// Elongation east of the sun, 0 to 360 degrees; under 180 is waxing.
+    pub elongation: f64,
+    /// Topocentric altitude in degrees.
+    pub altitude: f64,
+    /// Degrees from the target, when there is one.
+    pub separation: Option<f64>,
+}
+
+impl MoonInfo {
+    pub fn at(site: &Site, at: DateTime<Utc>, target: Option<&Equatorial>) -> Self {
+        let days = days_since_j2000(at);
+        let (longitude, latitude, parallax) = moon_ecliptic(days);
+        let position = ecliptic_to_equatorial(longitude, latitude, obliquity(days));
+        let elongation = (longitude - sun_longitude(days)).rem_euclid(360.0);
+        // The phase angle is close to the supplement of the elongation.
+        let phase_angle = 180.0
+            - (cos_deg(latitude) * cos_deg(elongation))
+                .acos()
+                .to_degrees();
+        let geocentric = horizontal(site, &position, at).0;
+        Self {
+            illumination: (1.0 + cos_deg(phase_angle)) / 2.0,
+            elongation,
+            altitude: geocentric - parallax * cos_deg(geocentric),
+            separation: target.map(|target| position.separation(target)),
+        }
+    }
+
+    pub fn waxing(&self) -> bool {
+        self.elongation < 180.0
+    }
+
+    /// The phase's emoji and name.
+    pub fn phase(&self) -> (&'static str, &'static str) {
+        const PHASES: [(&str, &str); 8] = [
+            ("🌑", "New moon"),
+            ("🌒", "Waxing crescent"),
+            ("🌓", "First quarter"),
+            ("🌔", "Waxing gibbous"),
+            ("🌕", "Full moon"),
+            ("🌖", "Waning gibbous"),
+            ("🌗", "Last quarter"),
+            ("🌘", "Waning crescent"),
+        ];
+        PHASES[(((self.elongation + 22.5) / 45.0) as usize) % 8]
+    }
+
+    /// e.g. "🌔 Waxing gibbous 78% · up 34° · 52° from target".
+    pub fn summary(&self) -> String {
+        let (emoji, name) = self.phase();
+        let mut summary = format!("{emoji} {name} {:.0}%", self.illumination * 100.0);
+        if self.altitude > 0.0 {
+            summary.push_str(&format!(" · up {:.0}°", self.altitude));
+        } else {
+            summary.push_str(" · down");
+        }
+        if let Some(separation) = self.separation {
+            summary.push_str(&format!(" · {separation:.0}° from target"));
+        }
+        summary
+    }
+}
+
+/// The sun altitudes that bound a night.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Twilight {
+    /// Sunrise and sunset.
+    Horizon,
+    Civil,
+    Nautical,
+    Astronomical,
+}
+
+impl Twilight {
+    pub const ALL: [Twilight; 4] = [
+        Twilight::Horizon,
+        Twilight::Civil,
+        Twilight::Nautical,
+        Twilight::Astronomical,
+    ];
+
+    /// The sun's center altitude for this event at `site`. Sunrise and
+    /// sunset allow for refraction, the solar radius and the horizon dip
+    /// from the site's elevation.
+    fn threshold(self, site: &Site) -> f64 {
+        match self {
+            Twilight::Horizon => -0.833 - 0.0347 * site.elevation.max(0.0).sqrt(),
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+
+    fn label(self, dawn: bool) -> &'static str {
+        match (self, dawn) {
+            (Twilight::Horizon, false) => "Sunset",
+            (Twilight::Horizon, true) => "Sunrise",
+            (Twilight::Civil, false) => "Civil dusk",
+            (Twilight::Civil, true) => "Civil dawn",
+            (Twilight::Nautical, false) => "Nautical dusk",
+            (Twilight::Nautical, true) => "Nautical dawn",
+            (Twilight::Astronomical, false) => "Astronomical dusk",
+            (Twilight::Astronomical, true) => "Astronomical dawn",
+        }
+    }
+}
+
+/// The sun crossing one twilight altitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwilightEvent {
+    pub kind: Twilight,
+    /// Rising (dawn) rather than setting (dusk).
+    pub dawn: bool,
+    pub at: DateTime<Utc>,
+}
+
+impl TwilightEvent {
+    pub fn label(&self) -> &'static str {
+        self.kind.label(self.dawn)
+    }
+}
+
+/// Step for scanning the sun's altitude; the sun moves under 2.5° in it, so
+/// no crossing of a threshold is skipped.
+const SCAN_STEP_MINUTES: i64 = 10;
+
+/// Every time in `[start, end)` the sun crosses `kind`'s altitude, in order.
+fn crossings(
+    site: &Site,
+    kind: Twilight,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<TwilightEvent> {
+    let threshold = kind.threshold(site);
+    let above = |at| sun_altitude(site, at) - threshold;
+    let step = Duration::minutes(SCAN_STEP_MINUTES);
+    let mut events = Vec::new();
+    let mut from = start;
+    let mut from_value = above(from);
+    while from < end {
+        let to = from + step;
+        let to_value = above(to);
+        if (from_value < 0.0) != (to_value < 0.0) {
+            // Bisect to within a few seconds.
+            let (mut low, mut high) = (from, to);
+            while high - low > Duration::seconds(5) {
+                let middle = low + (high - low) / 2;
+                if (above(middle) < 0.0) == (from_value < 0.0) {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            events.push(TwilightEvent {
+                kind,
+                dawn: from_value < 0.0,
+                at: low + (high - low) / 2,
+            });
+        }
+        from = to;
+        from_value = to_value;
+    }
+    events
+}
+
+/// The next sunrise, sunset or twilight boundary after `at`, within a day.
+pub fn next_twilight_event(site: &Site, at: DateTime<Utc>) -> Option<TwilightEvent> {
+    Twilight::ALL
+        .into_iter()
+        .flat_map(|kind| crossings(site, kind, at, at + Duration::days(1)))
+        .min_by_key(|event| event.at)
+}
+
+/// Dusk and dawn for each twilight of one night. Either may be missing near
+/// the poles or around midsummer, when the sun never gets that low.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Night {
+    /// From sunset outward to astronomical twilight.
+    pub twilights: Vec<TwilightWindow>,
+}
+
+/// When the sun crosses one twilight threshold going down and coming back up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwilightWindow {
+    pub kind: Twilight,
+    pub dusk: Option<DateTime<Utc>>,
+    pub dawn: Option<DateTime<Utc>>,
+}
+
+impl Night {
+    /// The night in progress at `at`, or the coming one while the sun is up.
+    /// Nights run from one local solar noon to the next.
+    pub fn around(site: &Site, at: DateTime<Utc>) -> Self {
+        let noon_hours = (12.0 - site.longitude / 15.0).rem_euclid(24.0);
+        let midnight = at
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight exists")
+            .and_utc();
+        let mut start = midnight + Duration::seconds((noon_hours * 3600.0) as i64);
+        if start > at {
+            start -= Duration::days(1);
+        }
+        if at - start > Duration::hours(12)
+            && sun_altitude(site, at) > Twilight::Horizon.threshold(site)
+        {
+            start += Duration::days(1);
+        }
+        let end = start + Duration::days(1);
+        let twilights = Twilight::ALL
+            .into_iter()
+            .map(|kind| {
+                let events = crossings(site, kind, start, end);
+                let dusk = events
+                    .iter()
+                    .find(|event| !event.dawn)
+                    .map(|event| event.at);
+                let dawn = events
+                    .iter()
+                    .rev()
+                    .find(|event| event.dawn && dusk.is_none_or(|dusk| event.at > dusk))
+                    .map(|event| event.at);
+                TwilightWindow { kind, dusk, dawn }
+            })
+            .collect();
+        Self { twilights }
+    }
+
+    pub fn get(&self, kind: Twilight) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        self.twilights
+            .iter()
+            .find(|window| window.kind == kind)
+            .map_or((None, None), |window| (window.dusk, window.dawn))
+    }
+
+    /// One line per twilight, e.g. "Astronomical 18:40 → 05:59".
+    pub fn summary(&self) -> String {
+        let time = |at: Option<DateTime<Utc>>| at.map_or_else(|| "—".to_string(), clock);
+        let mut lines: Vec<String> = self
+            .twilights
+            .iter()
+            .map(|window| {
+                let name = match window.kind {
+                    Twilight::Horizon => "Sun",
+                    Twilight::Civil => "Civil",
+                    Twilight::Nautical => "Nautical",
+                    Twilight::Astronomical => "Astronomical",
+                };
+                format!("{name} {} → {}", time(window.dusk), time(window.dawn))
+            })
+            .collect();
+        match self.dark_hours() {
+            Some(dark) => lines.push(format!(
+                "Dark for {}h {:02}m",
+                dark.num_hours(),
+                dark.num_minutes() % 60
+            )),
+            None => lines.push("No astronomical darkness".to_string()),
+        }
+        lines.join("\n")
+    }
+
+    /// The darkest window with both a dusk and a dawn, from astronomical
+    /// twilight out to sunset and sunrise.
+    pub fn span(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        self.twilights
+            .iter()
+            .rev()
+            .find_map(|window| window.dusk.zip(window.dawn))
+    }
+
+    /// Astronomical darkness, when there is any.
+    pub fn dark_hours(&self) -> Option<Duration> {
+        match self.get(Twilight::Astronomical) {
+            (Some(dusk), Some(dawn)) => Some(dawn - dusk),
+            _ => None,
+        }
+    }
+}
+
+/// Whether and when a target crosses an altitude limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Crossing {
+    /// Next times it climbs above and drops below the limit.
+    Crosses {
+        rises: DateTime<Utc>,
+        sets: DateTime<Utc>,
+    },
+    AlwaysAbove,
+    NeverAbove,
+}
+
+/// Where a target is now and when it rises, transits and sets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Visibility {
+    pub altitude: f64,
+    pub azimuth: f64,
+    /// The meridian crossing nearest `at`, up to twelve hours either side.
+    pub transit: DateTime<Utc>,
+    pub transit_altitude: f64,
+    pub limit: f64,
+    pub crossing: Crossing,
+}
+
+impl Visibility {
+    pub fn at(site: &Site, target: &Equatorial, limit: f64, at: DateTime<Utc>) -> Self {
+        let lst = local_sidereal_hours(at, site.longitude);
+        let (altitude, azimuth) = alt_az(target.ra_hours, target.dec_degrees, lst, site.latitude);
+        let hour_angle = (lst - target.ra_hours + 12.0).rem_euclid(24.0) - 12.0;
+        let solar = |sidereal_hours: f64| {
+            Duration::milliseconds((sidereal_hours / SIDEREAL_RATE * 3_600_000.0) as i64)
+        };
+        let transit = at - solar(hour_angle);
+        let transit_altitude = 90.0 - (site.latitude - target.dec_degrees).abs();
+
+        let cos_limit_angle = (sin_deg(limit)
+            - sin_deg(site.latitude) * sin_deg(target.dec_degrees))
+            / (cos_deg(site.latitude) * cos_deg(target.dec_degrees));
+        let crossing = if cos_limit_angle.is_nan() || cos_limit_angle > 1.0 {
+            Crossing::NeverAbove
+        } else if cos_limit_angle < -1.0 {
+            Crossing::AlwaysAbove
+        } else {
+            let limit_hours = cos_limit_angle.acos().to_degrees() / 15.0;
+            Crossing::Crosses {
+                rises: at + solar((-limit_hours - hour_angle).rem_euclid(24.0)),
+                sets: at + solar((limit_hours - hour_angle).rem_euclid(24.0)),
+            }
+        };
+        Self {
+            altitude,
+            azimuth,
+            transit,
+            transit_altitude,
+            limit,
+            crossing,
+        }
+    }
+
+    /// e.g. "Alt 52° · transits 23:40 at 71° · sets below 30° at 03:10".
+    pub fn summary(&self, now: DateTime<Utc>) -> String {
+        let transit = if self.transit <= now {
+            format!("transited {}", clock(self.transit))
+        } else {
+            format!("transits {}", clock(self.transit))
+        };
+        let limit = format!("{:.0}°", self.limit);
+        let crossing = match self.crossing {
+            Crossing::Crosses { sets, .. } if self.altitude >= self.limit => {
+                format!("sets below {limit} at {}", clock(sets))
+            }
+            Crossing::Crosses { rises, .. } => format!("rises above {limit} at {}", clock(rises)),
+            Crossing::AlwaysAbove => format!("stays above {limit}"),
+            Crossing::NeverAbove => format!("never reaches {limit}"),
+        };
+        format!(
+            "Alt {:.0}° · {transit} at {:.0}° · {crossing}",
+            self.altitude, self.transit_altitude
+        )
+    }
+}
+
+/// One sample of a target's path across the sky.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub at: DateTime<Utc>,
+    pub altitude: f64,
+    pub azimuth: f64,
+}
+
+/// Where `position` is every `step` from `start` through `end`.
+pub fn track(
+    site: &Site,
+    position: &Equatorial,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: Duration,
+) -> Vec<TrackPoint> {
+    let mut points = Vec::new();
+    let mut at = start;
+    while at <= end && step > Duration::zero() {
+        let (altitude, azimuth) = horizontal(site, position, at);
+        points.push(TrackPoint {
+            at,
+            altitude,
+            azimuth,
+        });
+        at += step;
+    }
+    points
+}
+
+/// Local wall-clock time, as the rest of the chat output shows it.
+pub fn clock(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local).format("%H:%M").to_string()
+}
+
+/// "in 42 min", "in 3h 05m", or "now".
+pub fn countdown(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    let minutes = (to - from).num_minutes();
+    match minutes {
+        ..=0 => "now".to_string(),
+        1..=59 => format!("in {minutes} min"),
+        _ => format!("in {}h {:02}m", minutes / 60, minutes % 60),
+    }
+}
+
+/// One line for the next twilight boundary, e.g. "Astronomical dawn in 42
+/// min (05:12)".
+pub fn twilight_summary(site: &Site, at: DateTime<Utc>) -> Option<String> {
+    let event = next_twilight_event(site, at)?;
+    Some(format!(
+        "{} {} ({})",
+        event.label(),
+        countdown(at, event.at),
+        clock(event.at)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn greenwich() -> Site {
+        Site {
+            latitude: 51.4769,
+            longitude: 0.0,
+            elevation: 0.0,
+        }
+    }
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn minutes_apart(a: DateTime<Utc>, b: DateTime<Utc>) -> i64 {
+        (a - b).num_minutes().abs()
+    }
+
+    #[test]
+    fn sun_matches_the_almanac() {
+        // March equinox 2024-03-20 03:06 UTC: the sun crosses the equator.
+        let sun = sun_position(utc("2024-03-20T03:06:00Z"));
+        assert!(sun.dec_degrees.abs() < 0.05, "{sun:?}");
+
+        // Greenwich on the June solstice: sunset 20:21 UTC, sunrise 03:43,
+        // and the sun never gets below -18°, so no astronomical night.
+        let night = Night::around(&greenwich(), utc("2024-06-21T15:00:00Z"));
+        let (sunset, sunrise) = night.get(Twilight::Horizon);
+        assert!(minutes_apart(sunset.unwrap(), utc("2024-06-21T20:21:00Z")) <= 3);
+        assert!(minutes_apart(sunrise.unwrap(), utc("2024-06-22T03:43:00Z")) <= 3);
+        assert_eq!(night.get(Twilight::Astronomical), (None, None));
+        assert_eq!(night.dark_hours(), None);
+        assert!(night.summary().ends_with("No astronomical darkness"));
+        let (dusk, dawn) = night.get(Twilight::Nautical);
+        assert!(dusk.is_some());
+        assert_eq!(night.span(), dusk.zip(dawn));
+
+        // In December the night is long and fully dark; the morning after
+        // still belongs to the same night until the sun is up.
+        let winter = Night::around(&greenwich(), utc("2024-12-21T23:00:00Z"));
+        let (dusk, dawn) = winter.get(Twilight::Astronomical);
+        assert!(minutes_apart(dusk.unwrap(), utc("2024-12-21T17:58:00Z")) <= 5);
+        assert!(minutes_apart(dawn.unwrap(), utc("2024-12-22T06:00:00Z")) <= 5);
+        assert_eq!(winter.span(), dusk.zip(dawn));
+        assert_eq!(
+            Night::around(&greenwich(), utc("2024-12-22T05:00:00Z")),
+            winter
+        );
+    }
+
+    #[test]
+    fn next_twilight_is_the_nearest_boundary() {
+        let event = next_twilight_event(&greenwich(), utc("2024-12-22T05:30:00Z")).unwrap();
+        assert_eq!(event.label(), "Astronomical dawn");
+        assert_eq!(
+            countdown(utc("2024-12-22T05:30:00Z"), event.at),
+            format!(
+                "in {} min",
+                (event.at - utc("2024-12-22T05:30:00Z")).num_minutes()
+            )
+        );
+        assert_eq!(countdown(event.at, event.at), "now");
+        assert_eq!(
+            countdown(utc("2024-12-22T05:30:00Z"), utc("2024-12-22T08:35:00Z")),
+            "in 3h 05m"
+        );
+    }
+
+    #[test]
+    fn moon_phase_and_separation() {
+        // Full moon 2024-04-23 23:49 UTC, new moon 2024-04-08 18:21 UTC
+        // (the total eclipse, so the moon sits on the sun).
+        let site = greenwich();
+        let full = MoonInfo::at(&site, utc("2024-04-23T23:49:00Z"), None);
+        assert!(full.illumination > 0.98, "{full:?}");
+        assert_eq!(full.phase().1, "Full moon");
+        let new = MoonInfo::at(&site, utc("2024-04-08T18:21:00Z"), None);
+        assert!(new.illumination < 0.02, "{new:?}");
+        assert_eq!(new.phase().1, "New moon");
+        let eclipse = utc("2024-04-08T18:21:00Z");
+        assert!(moon_position(eclipse).separation(&sun_position(eclipse)) < 1.0);
+
+        // First quarter 2024-04-15 19:13 UTC.
+        let quarter = MoonInfo::at(
+            &site,
+            utc("2024-04-15T19:13:00Z"),
+            Some(&sun_position(utc("2024-04-15T19:13:00Z"))),
+        );
+        assert!((quarter.illumination - 0.5).abs() < 0.05, "{quarter:?}");
+        assert!(quarter.waxing());
+        assert!((quarter.separation.unwrap() - 90.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn targets_rise_transit_and_set_around_the_limit() {
+        let site = Site {
+            latitude: 40.0,
+            longitude: -105.0,
+            elevation: 1600.0,
+        };
+        let m42 = Equatorial {
+            ra_hours: 5.588,
+            dec_degrees: -5.39,
+        };
+        let now = utc("2025-01-15T00:00:00Z");
+        let visibility = Visibility::at(&site, &m42, 30.0, now);
+        assert!((visibility.transit_altitude - 44.61).abs() < 0.01);
+        // At transit the hour angle is zero and the altitude is the peak.
+        let (peak, _) = horizontal(&site, &m42, visibility.transit);
+        assert!((peak - visibility.transit_altitude).abs() < 0.05);
+        let Crossing::Crosses { rises, sets } = visibility.crossing else {
+            panic!("M42 crosses 30° at 40°N: {visibility:?}");
+        };
+        for crossing in [rises, sets] {
+            assert!(crossing > now);
+            assert!((horizontal(&site, &m42, crossing).0 - 30.0).abs() < 0.05);
+        }
+        // Five hours before transit M42 is still low in the east.
+        assert!(visibility.altitude < 30.0);
+        assert!(
+            visibility
+                .summary(now)
+                .contains(&format!("rises above 30° at {}", clock(rises)))
+        );
+
+        let polaris = Equatorial {
+            ra_hours: 2.53,
+            dec_degrees: 89.26,
+        };
+        assert_eq!(
+            Visibility::at(&site, &polaris, 30.0, now).crossing,
+            Crossing::AlwaysAbove
+        );
+        let south = Equatorial {
+            ra_hours: 12.0,
+            dec_degrees: -60.0,
+        };
+        let hidden = Visibility::at(&site, &south, 30.0, now);
+        assert_eq!(hidden.crossing, Crossing::NeverAbove);
+        assert!(hidden.summary(now).ends_with("never reaches 30°"));
+
+        // The track peaks due south at the transit altitude.
+        let path = track(
+            &site,
+            &m42,
+            now,
+            now + Duration::days(1),
+            Duration::minutes(5),
+        );
+        assert_eq!(path.len(), 289);
+        let highest = path
+            .iter()
+            .max_by(|a, b| a.altitude.total_cmp(&b.altitude))
+            .unwrap();
+        assert!((highest.altitude - visibility.transit_altitude).abs() < 0.1);
+        assert!((highest.azimuth - 180.0).abs() < 2.0);
+        assert!(minutes_apart(highest.at, visibility.transit) <= 3);
+    }
+
+    #[test]
+    fn unset_sites_and_coordinates_are_rejected() {
+        assert!(
+            !Site {
+                latitude: 0.0,
+                longitude: 0.0,
+                elevation: 0.0
+            }
+            .is_valid()
+        );
+        assert!(greenwich().is_valid());
+        assert_eq!(Equatorial::checked(f64::NAN, 10.0), None);
+        assert_eq!(
+            Equatorial::checked(25.0, 10.0),
+            Some(Equatorial {
+                ra_hours: 1.0,
+                dec_degrees: 10.0
+            })
+        );
+    }
+}
@@ -12,7 +12,7 @@ pub struct EventHistoryResponse {
     pub response_type: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Event {
     pub time: String,
@@ -27,7 +27,7 @@ fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum EventDetails {
     FilterWheelChange {
@@ -96,7 +96,7 @@ pub enum EventDetails {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct FilterInfo {
     // NINA occasionally returns Name:[] / Id:[] (empty arrays) when the slot is unknown.
@@ -113,7 +113,7 @@ impl FilterInfo {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct TargetCoordinates {
     // TS-TARGETSTART events on c925 (and likely elsewhere) sometimes ship
@@ -184,11 +184,20 @@ pub mod event_types {
     pub const GUIDER_DITHER: &str = "GUIDER-DITHER";
     pub const FLAT_CONNECTED: &str = "FLAT-CONNECTED";
     pub const FLAT_DISCONNECTED: &str = "FLAT-DISCONNECTED";
+    pub const FLAT_LIGHT_TOGGLED: &str = "FLAT-LIGHT-TOGGLED";
+    pub const FLAT_BRIGHTNESS_CHANGED: &str = "FLAT-BRIGHTNESS-CHANGED";
+    pub const FLAT_COVER_OPENED: &str = "FLAT-COVER-OPENED";
+    pub const FLAT_COVER_CLOSED: &str = "FLAT-COVER-CLOSED";
     pub const WEATHER_CONNECTED: &str = "WEATHER-CONNECTED";
     pub const WEATHER_DISCONNECTED: &str = "WEATHER-DISCONNECTED";
     pub const SWITCH_CONNECTED: &str = "SWITCH-CONNECTED";
     pub const SWITCH_DISCONNECTED: &str = "SWITCH-DISCONNECTED";
+    pub const DOME_CONNECTED: &str = "DOME-CONNECTED";
     pub const DOME_DISCONNECTED: &str = "DOME-DISCONNECTED";
+    pub const DOME_SHUTTER_OPENED: &str = "DOME-SHUTTER-OPENED";
+    pub const DOME_SHUTTER_CLOSED: &str = "DOME-SHUTTER-CLOSED";
+    pub const DOME_PARKED: &str = "DOME-PARKED";
+    pub const DOME_HOMED: &str = "DOME-HOMED";
     pub const SAFETY_CONNECTED: &str = "SAFETY-CONNECTED";
     pub const SAFETY_DISCONNECTED: &str = "SAFETY-DISCONNECTED";
     pub const SAFETY_CHANGED: &str = "SAFETY-CHANGED";
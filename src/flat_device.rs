@@ -0,0 +1,144 @@
+use crate::source::RigCommand;
+use serde::{Deserialize, Serialize};
+
+/// Flat panel (cover calibrator) snapshot returned by a Direct N.I.N.A. rig.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct FlatDeviceInfoResponse {
+    pub response: FlatDeviceInfo,
+    pub error: String,
+    pub status_code: i32,
+    pub success: bool,
+    #[serde(rename = "Type")]
+    pub response_type: String,
+}
+
+/// The panel fields Chatstronomy reports and checks commands against.
+/// Unknown additive fields are intentionally ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct FlatDeviceInfo {
+    pub connected: bool,
+    /// N.I.N.A.'s cover state: `Open`, `Closed`, `NeitherOpenNorClosed`
+    /// (moving), `Unknown`, `Error`, or `NotPresent` for panels without a
+    /// motorized cover.
+    #[serde(default)]
+    pub cover_state: String,
+    #[serde(default)]
+    pub light_on: bool,
+    #[serde(default)]
+    pub brightness: i32,
+    #[serde(default)]
+    pub min_brightness: i32,
+    #[serde(default)]
+    pub max_brightness: i32,
+    #[serde(default)]
+    pub supports_open_close: bool,
+    #[serde(default)]
+    pub supports_on_off: bool,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+impl FlatDeviceInfo {
+    /// One-line state for status embeds.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.supports_open_close {
+            let cover = match self.cover_state.as_str() {
+                "NeitherOpenNorClosed" => "moving",
+                "" => "unknown",
+                state => state,
+            };
+            parts.push(format!("Cover {}", cover.to_ascii_lowercase()));
+        }
+        parts.push(if self.light_on {
+            format!("Light on ({})", self.brightness)
+        } else {
+            "Light off".to_string()
+        });
+        parts.join(" · ")
+    }
+}
+
+impl FlatDeviceInfoResponse {
+    /// Check a flat panel command against the live panel.
+    pub fn check_command(&self, command: &RigCommand) -> Result<(), String> {
+        if !matches!(
+            command,
+            RigCommand::SetFlatLight { .. }
+                | RigCommand::SetFlatBrightness { .. }
+                | RigCommand::OpenFlatCover
+                | RigCommand::CloseFlatCover
+        ) {
+            return Ok(());
+        }
+        let panel = &self.response;
+        if !(self.success && panel.connected) {
+            return Err("the flat panel is not connected".to_string());
+        }
+        match command {
+            RigCommand::SetFlatLight { .. } | RigCommand::SetFlatBrightness { .. }
+                if !panel.supports_on_off =>
+            {
+                Err("the flat panel has no controllable light".to_string())
+            }
+            RigCommand::SetFlatBrightness { brightness }
+                if !(panel.min_brightness..=panel.max_brightness).contains(brightness) =>
+            {
+                Err(format!(
+                    "brightness {brightness} is outside {}–{}",
+                    panel.min_brightness, panel.max_brightness
+                ))
+            }
+            RigCommand::OpenFlatCover | RigCommand::CloseFlatCover
+                if !panel.supports_open_close =>
+            {
+                Err("the flat panel has no motorized cover".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flat_panel_snapshot() {
+        let json = r#"{"Response":{"CoverState":"Closed","LocalizedCoverState":"Closed","LocalizedLightOnState":"On","LightOn":true,"Brightness":120,"SupportsOpenClose":true,"MinBrightness":0,"MaxBrightness":255,"SupportsOnOff":true,"SupportedActions":[],"Connected":true,"Name":"Flip-Flat","DisplayName":"Alnitak Flip-Flat"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let parsed: FlatDeviceInfoResponse = serde_json::from_str(json).unwrap();
+
+        assert!(parsed.response.connected);
+        assert_eq!(parsed.response.max_brightness, 255);
+        assert_eq!(parsed.response.summary(), "Cover closed · Light on (120)");
+    }
+
+    #[test]
+    fn panel_commands_are_checked_against_live_state() {
+        let json = r#"{"Response":{"CoverState":"NotPresent","LightOn":false,"Brightness":0,"SupportsOpenClose":false,"MinBrightness":1,"MaxBrightness":100,"SupportsOnOff":true,"Connected":true},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let mut panel: FlatDeviceInfoResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(panel.response.summary(), "Light off");
+        assert_eq!(
+            panel.check_command(&RigCommand::SetFlatLight { on: true }),
+            Ok(())
+        );
+        assert!(
+            panel
+                .check_command(&RigCommand::SetFlatBrightness { brightness: 200 })
+                .unwrap_err()
+                .contains("1–100")
+        );
+        assert!(panel.check_command(&RigCommand::OpenFlatCover).is_err());
+        panel.response.connected = false;
+        assert!(
+            panel
+                .check_command(&RigCommand::SetFlatBrightness { brightness: 50 })
+                .is_err()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::serde_helpers::de_f64_tolerant;
+use crate::source::{FocuserTarget, RigCommand};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +36,51 @@ pub struct FocuserInfo {
     pub temp_comp_available: bool,
 }
 
+impl FocuserInfoResponse {
+    /// Check a focuser command against the live focuser, so a move is never
+    /// queued behind another one or sent to a disconnected device.
+    pub fn check_command(&self, command: &RigCommand) -> Result<(), String> {
+        let focuser = &self.response;
+        if !matches!(
+            command,
+            RigCommand::MoveFocuser { .. } | RigCommand::SetFocuserTempComp { .. }
+        ) {
+            return Ok(());
+        }
+        if !(self.success && focuser.connected) {
+            return Err("the focuser is not connected".to_string());
+        }
+        if focuser.is_moving || focuser.is_settling {
+            return Err(format!(
+                "the focuser is still moving (at {})",
+                focuser.position
+            ));
+        }
+        match command {
+            RigCommand::MoveFocuser { target } => {
+                let position = match *target {
+                    FocuserTarget::Position(position) => i64::from(position),
+                    FocuserTarget::RelativeSteps(steps) => {
+                        i64::from(focuser.position) + i64::from(steps)
+                    }
+                };
+                if !(0..=i64::from(i32::MAX)).contains(&position) {
+                    return Err(format!("focuser position {position} is out of range"));
+                }
+                if focuser.temp_comp {
+                    // ASCOM focusers refuse moves while compensating.
+                    return Err("temperature compensation is on; turn it off first".to_string());
+                }
+                Ok(())
+            }
+            RigCommand::SetFocuserTempComp { .. } if !focuser.temp_comp_available => {
+                Err("the focuser has no temperature compensation".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,4 +103,41 @@ mod tests {
         assert!((parsed.response.temperature - 14.7).abs() < 1e-6);
         assert!(parsed.response.temp_comp_available);
     }
+
+    #[test]
+    fn focuser_commands_are_checked_against_live_state() {
+        let json = r#"{"Response":{"Position":3325,"StepSize":1,"Temperature":14.7,"IsMoving":false,"IsSettling":false,"TempComp":false,"TempCompAvailable":false,"Connected":true},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let mut focuser: FocuserInfoResponse = serde_json::from_str(json).unwrap();
+        let nudge = RigCommand::MoveFocuser {
+            target: FocuserTarget::RelativeSteps(-40),
+        };
+        assert_eq!(focuser.check_command(&nudge), Ok(()));
+        assert!(
+            focuser
+                .check_command(&RigCommand::MoveFocuser {
+                    target: FocuserTarget::RelativeSteps(-4000),
+                })
+                .is_err()
+        );
+        assert!(
+            focuser
+                .check_command(&RigCommand::SetFocuserTempComp { enabled: true })
+                .is_err()
+        );
+
+        focuser.response.is_moving = true;
+        assert!(
+            focuser
+                .check_command(&nudge)
+                .unwrap_err()
+                .contains("moving")
+        );
+        focuser.response.connected = false;
+        assert!(
+            focuser
+                .check_command(&nudge)
+                .unwrap_err()
+                .contains("connected")
+        );
+    }
 }
@@ -0,0 +1,544 @@
+//! Guiding performance from the guide step history.
+//!
+//! N.I.N.A.'s guide graph carries each step's RA/Dec error and correction
+//! pulses but only summarizes them as RMS. This looks further: the dominant
+//! periodic error in RA from a windowed Fourier transform, Dec backlash as
+//! runs of same-direction pulses the error does not answer, how long
+//! dithers take to settle, and which axis dominates. The history carries
+//! no timestamps, so periods and settle times assume the guide cadence the
+//! caller gives. `/chatstronomy guider analyze` reports it with a spectrum
+//! chart.
+
+use crate::guider::GuideStepsHistory;
+use crate::session_report::median;
+use std::f64::consts::PI;
+
+/// Guide exposure plus overhead assumed when none is given, in seconds.
+pub const DEFAULT_GUIDE_CADENCE_SECONDS: f64 = 2.0;
+
+/// Fewest undisturbed steps worth a spectrum.
+const MIN_SPECTRUM_STEPS: usize = 32;
+/// Shortest period considered periodic error, in steps. Anything faster is
+/// seeing.
+const MIN_PERIOD_STEPS: f64 = 4.0;
+/// A spectral peak must stand this many times above the median bin.
+const PEAK_PROMINENCE: f64 = 3.0;
+/// A dither has settled once the error is back within this multiple of the
+/// median undisturbed error...
+const SETTLE_FACTOR: f64 = 2.5;
+/// ...and stays there for this many steps.
+const SETTLE_HOLD: usize = 2;
+/// Same-direction Dec pulses in a row that point at backlash when the error
+/// does not shrink over them.
+const BACKLASH_RUN: usize = 4;
+/// Over a backlash run the Dec error keeps at least this share of its size,
+const BACKLASH_RESPONSE: f64 = 0.8;
+/// having started at this many times the median Dec error.
+const BACKLASH_ERROR: f64 = 2.0;
+/// One axis dominates when its RMS is this many times the other's.
+const AXIS_IMBALANCE: f64 = 1.5;
+
+/// Amplitude of the RA error at one period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrumBin {
+    pub period_seconds: f64,
+    pub amplitude: f64,
+}
+
+/// The strongest periodic component of the RA error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodicError {
+    pub period_seconds: f64,
+    /// Half the peak-to-peak swing, in the history's unit.
+    pub amplitude: f64,
+}
+
+/// Runs of same-direction Dec pulses the error did not answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecBacklash {
+    pub runs: usize,
+    /// Steps in the longest run.
+    pub longest: usize,
+    /// Pulse time summed over the longest run, in milliseconds.
+    pub longest_pulse_ms: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DitherSettle {
+    pub dithers: usize,
+    /// Dithers the error recovered from before the next one or the end of
+    /// the history.
+    pub settled: usize,
+    pub median_seconds: Option<f64>,
+    pub longest_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuidingAnalysis {
+    pub steps: usize,
+    pub cadence_seconds: f64,
+    /// "arcsec" or "px", as the history is scaled.
+    pub unit: &'static str,
+    /// Standard deviation of each axis over undisturbed steps.
+    pub ra_rms: f64,
+    pub dec_rms: f64,
+    /// RA error amplitude by period, longest period first. Empty when the
+    /// history is too short.
+    pub spectrum: Vec<SpectrumBin>,
+    pub periodic_error: Option<PeriodicError>,
+    pub dec_backlash: Option<DecBacklash>,
+    pub dither_settle: Option<DitherSettle>,
+}
+
+impl GuidingAnalysis {
+    /// Analyze `history`, taking one step every `cadence_seconds`. None when
+    /// it holds too few usable steps to say anything.
+    pub fn of(history: &GuideStepsHistory, cadence_seconds: f64) -> Option<Self> {
+        let steps = &history.guide_steps;
+        let n = steps.len();
+        let ra: Vec<f64> = steps.iter().map(|s| s.ra_distance_raw_display).collect();
+        let dec: Vec<f64> = steps.iter().map(|s| s.dec_distance_raw_display).collect();
+        let distance: Vec<f64> = ra.iter().zip(&dec).map(|(r, d)| r.hypot(*d)).collect();
+        let dithers: Vec<bool> = steps
+            .iter()
+            .map(GuideStepsHistory::is_dither_step)
+            .collect();
+        let cadence_seconds = if cadence_seconds.is_finite() && cadence_seconds > 0.0 {
+            cadence_seconds
+        } else {
+            DEFAULT_GUIDE_CADENCE_SECONDS
+        };
+
+        // Dither steps and the recovery after them say nothing about how
+        // the mount tracks; leave them out of everything but settling.
+        let threshold =
+            SETTLE_FACTOR * median((0..n).filter(|i| !dithers[*i]).map(|i| distance[i]))?;
+        let mut disturbed = dithers.clone();
+        let mut settle_steps = Vec::new();
+        let dither_count = dithers.iter().filter(|d| **d).count();
+        for start in (0..n).filter(|i| dithers[*i]) {
+            let end = (start + 1..n).find(|i| dithers[*i]).unwrap_or(n);
+            let within = |i: usize| distance[i].is_finite() && distance[i] <= threshold;
+            let settled_at = (start + 1..end)
+                .find(|i| *i + SETTLE_HOLD <= end && (*i..*i + SETTLE_HOLD).all(within));
+            let recovered = settled_at.unwrap_or(end);
+            disturbed[start..recovered].fill(true);
+            if let Some(settled_at) = settled_at {
+                settle_steps.push((settled_at - start) as f64 * cadence_seconds);
+            }
+        }
+        let usable: Vec<usize> = (0..n)
+            .filter(|i| !disturbed[*i] && ra[*i].is_finite() && dec[*i].is_finite())
+            .collect();
+        if usable.len() < 2 {
+            return None;
+        }
+
+        let spectrum = if usable.len() >= MIN_SPECTRUM_STEPS {
+            spectrum(&fill_gaps(&ra, &usable), cadence_seconds)
+        } else {
+            Vec::new()
+        };
+        let periodic_error = dominant_period(&spectrum, cadence_seconds);
+
+        Some(Self {
+            steps: n,
+            cadence_seconds,
+            unit: history.scale_unit(),
+            ra_rms: standard_deviation(usable.iter().map(|i| ra[*i])),
+            dec_rms: standard_deviation(usable.iter().map(|i| dec[*i])),
+            spectrum,
+            periodic_error,
+            dec_backlash: dec_backlash(history, &disturbed),
+            dither_settle: (dither_count > 0).then(|| DitherSettle {
+                dithers: dither_count,
+                settled: settle_steps.len(),
+                median_seconds: median(settle_steps.iter().copied()),
+                longest_seconds: settle_steps.iter().copied().reduce(f64::max),
+            }),
+        })
+    }
+
+    /// How long the history covers, in seconds.
+    pub fn span_seconds(&self) -> f64 {
+        self.steps as f64 * self.cadence_seconds
+    }
+
+    /// An amount in the history's unit, e.g. `0.62″` or `0.31 px`.
+    pub fn amount(&self, value: f64) -> String {
+        if self.unit == "arcsec" {
+            format!("{value:.2}″")
+        } else {
+            format!("{value:.2} px")
+        }
+    }
+
+    /// "RA 0.62″ · Dec 0.31″ — RA dominates (2.0×): ...".
+    pub fn rms_summary(&self) -> String {
+        let rms = format!(
+            "RA {} · Dec {}",
+            self.amount(self.ra_rms),
+            self.amount(self.dec_rms)
+        );
+        if self.ra_rms > AXIS_IMBALANCE * self.dec_rms {
+            format!(
+                "{rms} — RA dominates ({:.1}×): periodic error, balance or wind",
+                self.ra_rms / self.dec_rms
+            )
+        } else if self.dec_rms > AXIS_IMBALANCE * self.ra_rms {
+            format!(
+                "{rms} — Dec dominates ({:.1}×): polar alignment, backlash or flexure",
+                self.dec_rms / self.ra_rms
+            )
+        } else {
+            format!("{rms} — balanced")
+        }
+    }
+
+    pub fn periodic_error_summary(&self) -> String {
+        match &self.periodic_error {
+            Some(pe) => format!(
+                "±{} every {:.0} s",
+                self.amount(pe.amplitude),
+                pe.period_seconds
+            ),
+            None if self.spectrum.is_empty() => {
+                format!("Need {MIN_SPECTRUM_STEPS} undisturbed steps for a spectrum")
+            }
+            None => format!(
+                "No dominant period up to {:.0} s",
+                self.span_seconds() / 2.0
+            ),
+        }
+    }
+
+    pub fn backlash_summary(&self) -> String {
+        match &self.dec_backlash {
+            Some(backlash) => format!(
+                "{} run{} of {BACKLASH_RUN}+ same-direction pulses without response \
+                 (longest {} steps, {:.1} s of pulses)",
+                backlash.runs,
+                if backlash.runs == 1 { "" } else { "s" },
+                backlash.longest,
+                backlash.longest_pulse_ms / 1000.0
+            ),
+            None => "No sign of backlash".to_string(),
+        }
+    }
+
+    pub fn settle_summary(&self) -> String {
+        let Some(settle) = &self.dither_settle else {
+            return "No dithers in the history".to_string();
+        };
+        let mut summary = match (settle.median_seconds, settle.longest_seconds) {
+            (Some(median), Some(longest)) => format!(
+                "{} dither{}: median {median:.0} s to settle, longest {longest:.0} s",
+                settle.dithers,
+                if settle.dithers == 1 { "" } else { "s" }
+            ),
+            _ => format!(
+                "{} dither{}",
+                settle.dithers,
+                if settle.dithers == 1 { "" } else { "s" }
+            ),
+        };
+        let unsettled = settle.dithers - settle.settled;
+        if unsettled > 0 {
+            summary.push_str(&format!(", {unsettled} not settled within the history"));
+        }
+        summary
+    }
+}
+
+/// `values` at the `usable` indices, linearly interpolated across the rest
+/// and held flat past either end.
+fn fill_gaps(values: &[f64], usable: &[usize]) -> Vec<f64> {
+    let mut filled = Vec::with_capacity(values.len());
+    let mut next = 0;
+    for i in 0..values.len() {
+        while next < usable.len() && usable[next] < i {
+            next += 1;
+        }
+        let value = match (next.checked_sub(1).map(|p| usable[p]), usable.get(next)) {
+            (_, Some(&after)) if after == i => values[i],
+            (Some(before), Some(&after)) => {
+                let t = (i - before) as f64 / (after - before) as f64;
+                values[before] + (values[after] - values[before]) * t
+            }
+            (Some(before), None) => values[before],
+            (None, Some(&after)) => values[after],
+            (None, None) => 0.0,
+        };
+        filled.push(value);
+    }
+    filled
+}
+
+/// Amplitude spectrum of `series` about its mean, Hann-windowed, longest
+/// period first. A plain DFT: guide histories are a few hundred steps.
+///
+/// There is no detrending: guiding already takes out drift, and the
+/// regression line through a few cycles of periodic error is itself a ramp
+/// that would leak into the longest periods.
+fn spectrum(series: &[f64], cadence_seconds: f64) -> Vec<SpectrumBin> {
+    let n = series.len();
+    let mean = series.iter().sum::<f64>() / n as f64;
+    let window: Vec<f64> = (0..n)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / n as f64).cos())
+        .collect();
+    let gain: f64 = window.iter().sum();
+    let samples: Vec<f64> = series
+        .iter()
+        .zip(&window)
+        .map(|(y, w)| (y - mean) * w)
+        .collect();
+
+    (1..=n / 2)
+        .map(|k| {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (i, sample) in samples.iter().enumerate() {
+                let phase = 2.0 * PI * (k * i) as f64 / n as f64;
+                re += sample * phase.cos();
+                im -= sample * phase.sin();
+            }
+            SpectrumBin {
+                period_seconds: n as f64 * cadence_seconds / k as f64,
+                amplitude: 2.0 * re.hypot(im) / gain,
+            }
+        })
+        .collect()
+}
+
+/// The strongest bin that repeats at least twice in the history and is
+/// slower than seeing, when it stands clear of the rest.
+fn dominant_period(spectrum: &[SpectrumBin], cadence_seconds: f64) -> Option<PeriodicError> {
+    let floor = median(spectrum.iter().map(|bin| bin.amplitude))?;
+    let span = spectrum.first()?.period_seconds;
+    let (index, bin) = spectrum
+        .iter()
+        .enumerate()
+        .filter(|(_, bin)| bin.period_seconds <= span / 2.0)
+        .filter(|(_, bin)| bin.period_seconds >= MIN_PERIOD_STEPS * cadence_seconds)
+        .max_by(|a, b| a.1.amplitude.total_cmp(&b.1.amplitude))
+        .filter(|(_, bin)| bin.amplitude >= PEAK_PROMINENCE * floor && bin.amplitude > 0.0)?;
+    // A period between two bins splits its power over both. Under a Hann
+    // window the ratio of the larger neighbor to the peak gives the true
+    // frequency exactly for a pure tone, and with it the amplitude the
+    // window's scalloping hid. Bin `index` is `index + 1` cycles over the
+    // history.
+    let before = index.checked_sub(1).map_or(0.0, |i| spectrum[i].amplitude);
+    let after = spectrum.get(index + 1).map_or(0.0, |bin| bin.amplitude);
+    let (ratio, direction) = if after >= before {
+        (after / bin.amplitude, 1.0)
+    } else {
+        (before / bin.amplitude, -1.0)
+    };
+    let offset = (direction * (2.0 * ratio - 1.0) / (ratio + 1.0)).clamp(-0.5, 0.5);
+    let cycles = index as f64 + 1.0 + offset;
+    let amplitude = if offset.abs() > 1e-9 {
+        bin.amplitude * PI * offset * (1.0 - offset * offset) / (PI * offset).sin()
+    } else {
+        bin.amplitude
+    };
+    Some(PeriodicError {
+        period_seconds: span / cycles,
+        amplitude,
+    })
+}
+
+/// Runs of at least `BACKLASH_RUN` undisturbed steps pulsing Dec the same
+/// way while the Dec error keeps its size.
+///
+/// A mount drifting in Dec from polar misalignment is pulsed one way all
+/// night too, but the pulses keep its error small; only runs starting from
+/// an error well above the usual one count.
+fn dec_backlash(history: &GuideStepsHistory, disturbed: &[bool]) -> Option<DecBacklash> {
+    let typical = median(
+        history
+            .guide_steps
+            .iter()
+            .zip(disturbed)
+            .filter(|(_, disturbed)| !**disturbed)
+            .map(|(step, _)| step.dec_distance_raw_display.abs()),
+    )?;
+    let mut runs: Vec<(usize, f64)> = Vec::new();
+    let mut run: Vec<usize> = Vec::new();
+    let close = |run: &mut Vec<usize>, runs: &mut Vec<(usize, f64)>| {
+        if run.len() >= BACKLASH_RUN {
+            let steps = &history.guide_steps;
+            let first = steps[run[0]].dec_distance_raw_display.abs();
+            let last = steps[run[run.len() - 1]].dec_distance_raw_display.abs();
+            if first > BACKLASH_ERROR * typical && last >= BACKLASH_RESPONSE * first {
+                let pulses = run.iter().map(|i| steps[*i].dec_duration.abs()).sum();
+                runs.push((run.len(), pulses));
+            }
+        }
+        run.clear();
+    };
+    for (i, step) in history.guide_steps.iter().enumerate() {
+        let pulse = step.dec_duration;
+        let usable = !disturbed[i]
+            && pulse.is_finite()
+            && pulse != 0.0
+            && step.dec_distance_raw_display.is_finite();
+        let same_way = run
+            .last()
+            .is_some_and(|last| history.guide_steps[*last].dec_duration.signum() == pulse.signum());
+        if !usable || !same_way {
+            close(&mut run, &mut runs);
+        }
+        if usable {
+            run.push(i);
+        }
+    }
+    close(&mut run, &mut runs);
+
+    let (longest, longest_pulse_ms) = runs
+        .iter()
+        .copied()
+        .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))?;
+    Some(DecBacklash {
+        runs: runs.len(),
+        longest,
+        longest_pulse_ms,
+    })
+}
+
+fn standard_deviation(values: impl Iterator<Item = f64>) -> f64 {
+    let values: Vec<f64> = values.collect();
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guider::{GUIDER_SCALE_ARCSECONDS, GuideGraphStep};
+
+    fn step(ra: f64, dec: f64, dec_duration: f64, dither: bool) -> GuideGraphStep {
+        GuideGraphStep {
+            id: 0,
+            id_offset_left: 0.0,
+            id_offset_right: 0.0,
+            ra_distance_raw: ra,
+            ra_distance_raw_display: ra,
+            ra_duration: 0.0,
+            dec_distance_raw: dec,
+            dec_distance_raw_display: dec,
+            dec_duration,
+            dither: serde_json::json!(if dither { "1" } else { "NO" }),
+        }
+    }
+
+    fn history(guide_steps: Vec<GuideGraphStep>) -> GuideStepsHistory {
+        GuideStepsHistory {
+            rms: None,
+            interval: 0.0,
+            max_y: 4.0,
+            min_y: -4.0,
+            max_duration_y: 0.0,
+            min_duration_y: 0.0,
+            history_size: guide_steps.len() as i32,
+            guide_steps,
+            pixel_scale: 1.0,
+            scale: GUIDER_SCALE_ARCSECONDS,
+        }
+    }
+
+    /// Small deterministic jitter standing in for seeing.
+    fn jitter(i: usize) -> f64 {
+        ((i * 7919) % 13) as f64 / 13.0 * 0.2 - 0.1
+    }
+
+    #[test]
+    fn finds_periodic_error_backlash_and_dither_settling() {
+        // 160 steps at 2 s: a 0.8″ RA sine every 40 steps (80 s), a Dec
+        // stall from step 20 to 27, and a dither at step 100 the error takes
+        // a few steps to come back from.
+        let mut steps: Vec<GuideGraphStep> = (0..160)
+            .map(|i| {
+                let ra = 0.8 * (2.0 * PI * i as f64 / 40.0).sin() + jitter(i);
+                let dec = 0.2 * jitter(i + 3) * 5.0;
+                let pulse = if i % 2 == 0 { 50.0 } else { -50.0 };
+                step(ra, dec, pulse, false)
+            })
+            .collect();
+        for (offset, i) in (20..28).enumerate() {
+            steps[i].dec_distance_raw_display = 0.6 + 0.01 * offset as f64;
+            steps[i].dec_duration = 300.0;
+        }
+        steps[28].dec_duration = -50.0;
+        steps[100] = step(3.0, 2.5, 0.0, true);
+        for (recovery, i) in [2.8, 2.2, 1.6, 1.0].into_iter().zip(101..) {
+            steps[i].ra_distance_raw_display += recovery;
+        }
+
+        let analysis = GuidingAnalysis::of(&history(steps), 2.0).unwrap();
+        assert_eq!(analysis.steps, 160);
+        assert_eq!(analysis.span_seconds(), 320.0);
+        assert_eq!(analysis.spectrum.len(), 80);
+        assert_eq!(analysis.spectrum[0].period_seconds, 320.0);
+
+        let pe = analysis.periodic_error.unwrap();
+        assert!((pe.period_seconds - 80.0).abs() < 4.0, "{pe:?}");
+        assert!((pe.amplitude - 0.8).abs() < 0.1, "{pe:?}");
+        assert_eq!(
+            analysis.periodic_error_summary(),
+            format!("±{:.2}″ every {:.0} s", pe.amplitude, pe.period_seconds)
+        );
+
+        let backlash = analysis.dec_backlash.unwrap();
+        assert_eq!(backlash.runs, 1);
+        assert_eq!(backlash.longest, 8);
+        assert_eq!(backlash.longest_pulse_ms, 2400.0);
+
+        let settle = analysis.dither_settle.unwrap();
+        assert_eq!((settle.dithers, settle.settled), (1, 1));
+        let seconds = settle.median_seconds.unwrap();
+        assert!((4.0..=10.0).contains(&seconds), "{settle:?}");
+
+        // The sine puts RA well above Dec.
+        assert!(analysis.ra_rms > 0.5);
+        assert!(analysis.rms_summary().contains("RA dominates"));
+    }
+
+    #[test]
+    fn short_or_quiet_histories_say_so() {
+        let quiet: Vec<GuideGraphStep> = (0..64)
+            .map(|i| step(jitter(i), jitter(i + 5), 0.0, false))
+            .collect();
+        let analysis = GuidingAnalysis::of(&history(quiet), 2.0).unwrap();
+        assert_eq!(analysis.dec_backlash, None);
+        assert_eq!(analysis.dither_settle, None);
+        assert_eq!(analysis.settle_summary(), "No dithers in the history");
+        assert_eq!(analysis.backlash_summary(), "No sign of backlash");
+        assert!(analysis.rms_summary().ends_with("balanced"));
+
+        let short: Vec<GuideGraphStep> = (0..10)
+            .map(|i| step(jitter(i), jitter(i + 5), 0.0, false))
+            .collect();
+        let analysis = GuidingAnalysis::of(&history(short), 2.0).unwrap();
+        assert!(analysis.spectrum.is_empty());
+        assert_eq!(
+            analysis.periodic_error_summary(),
+            "Need 32 undisturbed steps for a spectrum"
+        );
+
+        assert_eq!(GuidingAnalysis::of(&history(Vec::new()), 2.0), None);
+
+        // A period between two bins (200 steps hold 4.17 cycles of 48) is
+        // still found.
+        let between: Vec<GuideGraphStep> = (0..200)
+            .map(|i| {
+                let ra = 0.6 * (2.0 * PI * i as f64 / 48.0).sin();
+                step(ra, jitter(i), 0.0, false)
+            })
+            .collect();
+        let pe = GuidingAnalysis::of(&history(between), 2.0)
+            .unwrap()
+            .periodic_error
+            .unwrap();
+        assert!((pe.period_seconds - 96.0).abs() < 3.0, "{pe:?}");
+        assert!((pe.amplitude - 0.6).abs() < 0.06, "{pe:?}");
+    }
+}
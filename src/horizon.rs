@@ -0,0 +1,117 @@
+//! A site's local horizon: trees, roofs and walls as an altitude per
+//! azimuth.
+//!
+//! Profiles use N.I.N.A.'s `.hrz` format, so the file a rig already feeds
+//! its sequence's horizon checks works here too: one `azimuth altitude`
+//! pair per line in degrees, whitespace separated, `#` starting a comment.
+//! Altitudes between points are interpolated linearly, wrapping through
+//! north.
+
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonProfile {
+    /// (azimuth, altitude) in degrees, sorted by azimuth in `[0, 360)`.
+    points: Vec<(f64, f64)>,
+}
+
+impl HorizonProfile {
+    /// Parse `.hrz` text. The error names the first bad line.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut points = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.split_whitespace().map(str::parse::<f64>);
+            let (Some(Ok(azimuth)), Some(Ok(altitude))) = (fields.next(), fields.next()) else {
+                return Err(format!(
+                    "line {}: expected \"azimuth altitude\", got \"{line}\"",
+                    index + 1
+                ));
+            };
+            if !azimuth.is_finite() || !(-90.0..=90.0).contains(&altitude) {
+                return Err(format!("line {}: point out of range", index + 1));
+            }
+            points.push((azimuth.rem_euclid(360.0), altitude));
+        }
+        if points.is_empty() {
+            return Err("no horizon points".to_string());
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { points })
+    }
+
+    /// Read and parse a `.hrz` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// The horizon altitude at `azimuth` degrees.
+    pub fn altitude_at(&self, azimuth: f64) -> f64 {
+        let azimuth = azimuth.rem_euclid(360.0);
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        // Bracketing points, with the last point standing in below the first
+        // and the first above the last, 360° on.
+        let (before, after) = match self.points.iter().position(|(az, _)| *az >= azimuth) {
+            Some(0) => ((last.0 - 360.0, last.1), first),
+            Some(index) => (self.points[index - 1], self.points[index]),
+            None => (last, (first.0 + 360.0, first.1)),
+        };
+        let span = after.0 - before.0;
+        if span <= 0.0 {
+            return after.1;
+        }
+        before.1 + (after.1 - before.1) * (azimuth - before.0) / span
+    }
+
+    /// Is a position at `altitude`/`azimuth` above the horizon?
+    pub fn clears(&self, altitude: f64, azimuth: f64) -> bool {
+        altitude > self.altitude_at(azimuth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hrz_and_interpolates_through_north() {
+        let profile = HorizonProfile::parse(
+            "# Backyard, measured 2025-06\n\
+             0 20\n\
+             90\t10   # fence\n\
+             \n\
+             180 30\n\
+             270 10\n",
+        )
+        .unwrap();
+        assert_eq!(profile.points().len(), 4);
+        assert_eq!(profile.altitude_at(45.0), 15.0);
+        assert_eq!(profile.altitude_at(135.0), 20.0);
+        assert_eq!(profile.altitude_at(315.0), 15.0);
+        assert_eq!(profile.altitude_at(-45.0), 15.0);
+        assert_eq!(profile.altitude_at(360.0), 20.0);
+        assert!(profile.clears(25.0, 225.0));
+        assert!(!profile.clears(25.0, 180.0));
+
+        let single = HorizonProfile::parse("120 25").unwrap();
+        assert_eq!(single.altitude_at(300.0), 25.0);
+
+        assert_eq!(
+            HorizonProfile::parse("0 20\n90 high\n"),
+            Err("line 2: expected \"azimuth altitude\", got \"90 high\"".to_string())
+        );
+        assert!(HorizonProfile::parse("# nothing\n").is_err());
+        assert!(HorizonProfile::parse("10 95").is_err());
+    }
+}
@@ -256,6 +256,21 @@ const MIGRATIONS: &[&str] = &[
     DROP TABLE telescopes;
     ALTER TABLE telescopes_v9 RENAME TO telescopes;
     CREATE INDEX idx_telescopes_owner ON telescopes(owner_id);",
+    // V10: each attached server filters the telescope's notifications with
+    // its own rules, stored as the JSON rule list.
+    "ALTER TABLE telescope_attachments
+        ADD COLUMN notification_rules TEXT NOT NULL DEFAULT '[]';",
+    // V11: a destination channel may gather each session's notifications
+    // into a Discord thread of its own.
+    "ALTER TABLE telescope_channels
+        ADD COLUMN session_threads INTEGER NOT NULL DEFAULT 0;",
+    // V12: each telescope's chat updater saves what it has announced, so an
+    // updater restarted by a deploy or reconnect posts only what it missed.
+    "CREATE TABLE updater_checkpoints (
+        telescope_id INTEGER PRIMARY KEY REFERENCES telescopes(id) ON DELETE CASCADE,
+        checkpoint TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    ) STRICT;",
 ];
 
 #[derive(Debug, thiserror::Error)]
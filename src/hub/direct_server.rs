@@ -4,23 +4,25 @@
 //! first frame authenticates: a one-time pairing token (first connect) or the
 //! durable rig credential minted by that pairing. After the handshake the hub
 //! sends [`QueryRequest`] frames and the rig answers with [`QueryResult`];
-//! heartbeats keep NATs open. One telescope has one active connection — a
-//! newer authenticated connection replaces the older one.
+//! heartbeats keep NATs open. A rig that negotiated the event stream is sent
+//! one `subscribe` frame and then pushes updates unprompted. One telescope
+//! has one active connection — a newer authenticated connection replaces
+//! the older one.
 
 use super::server::HubState;
 use crate::direct::protocol::{
     AgentHello, AuthRequest, CURRENT_PAYLOAD_VERSION, ClientHello, DirectMessage,
     LEGACY_PAYLOAD_VERSION, PROTOCOL_VERSION, PairRequest, QueryKind, QueryRequest, QueryResult,
-    RigId, negotiate_payload_version,
+    RigId, StreamSubscription, event_stream_negotiated, negotiate_payload_version,
 };
-use crate::source::RigCapabilities;
+use crate::source::{RigCapabilities, RigUpdate};
 use axum::extract::State;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::response::Response;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::{mpsc, oneshot, watch};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 use uuid::Uuid;
 
 /// How long the hub waits for the authentication frame.
@@ -42,6 +44,10 @@ const MAX_PENDING_QUERIES: usize = 32;
 /// Default wait for a rig to answer a query.
 pub const QUERY_TIMEOUT: Duration = Duration::from_secs(20);
 
+/// Pushed updates buffered per subscriber. A subscriber that falls further
+/// behind is told it lagged and resynchronizes with a full poll.
+const UPDATE_BUFFER: usize = 256;
+
 /// One live, authenticated rig connection.
 pub struct RigConnection {
     pub telescope_id: i64,
@@ -52,9 +58,14 @@ pub struct RigConnection {
     /// Additive response-payload contract selected during the hello exchange.
     /// Version 1 means an older, unmarked Direct client.
     pub payload_version: u16,
+    /// Capabilities as negotiated: `event_stream` is cleared when the selected
+    /// payload contract predates push frames.
     pub capabilities: RigCapabilities,
     pub profile_name: String,
     outgoing: mpsc::Sender<DirectMessage>,
+    /// Taken when the socket goes away, so subscribers see the stream close
+    /// instead of waiting on a rig that will never push again.
+    updates: Mutex<Option<broadcast::Sender<RigUpdate>>>,
     pending: Mutex<HashMap<Uuid, oneshot::Sender<QueryResult>>>,
     /// Priority control path for replacement/revocation. It is deliberately
     /// separate from ordinary query traffic so no stale command can overtake
@@ -116,6 +127,37 @@ impl RigConnection {
         }
     }
 
+    /// Receive updates the rig pushes from now on. Always succeeds; a rig
+    /// that does not stream simply never sends any, and once the rig has
+    /// disconnected the receiver reports the stream closed.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<RigUpdate> {
+        match self.updates.lock().ok().as_deref().and_then(Option::as_ref) {
+            Some(updates) => updates.subscribe(),
+            None => broadcast::channel(1).1,
+        }
+    }
+
+    fn publish(&self, update: RigUpdate) {
+        // No live subscriber is fine: the next poll catches up.
+        if let Ok(updates) = self.updates.lock()
+            && let Some(updates) = updates.as_ref()
+        {
+            let _ = updates.send(update);
+        }
+    }
+
+    /// The socket is gone: fail every waiting query and close the update
+    /// stream, so subscribers learn of it now rather than at their next
+    /// poll.
+    fn disconnect(&self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.clear();
+        }
+        if let Ok(mut updates) = self.updates.lock() {
+            updates.take();
+        }
+    }
+
     fn remove_pending(&self, id: &Uuid) {
         if let Ok(mut pending) = self.pending.lock() {
             pending.remove(id);
@@ -177,6 +219,7 @@ impl RigConnection {
                 capabilities: crate::source::RigCapabilities::all(),
                 profile_name: format!("stub-{telescope_id}"),
                 outgoing,
+                updates: Mutex::new(Some(broadcast::channel(UPDATE_BUFFER).0)),
                 pending: Mutex::new(HashMap::new()),
                 close_request,
             }),
@@ -530,13 +573,19 @@ async fn handle_socket(state: HubState, mut socket: WebSocket, client_ip: String
     let payload_version = negotiate_payload_version(hello.payload_version);
     let (outgoing_tx, mut outgoing_rx) = mpsc::channel(MAX_PENDING_QUERIES);
     let (close_request, mut close_requests) = watch::channel(None);
+    let streaming = event_stream_negotiated(payload_version, &hello.capabilities);
+    let capabilities = RigCapabilities {
+        event_stream: streaming,
+        ..hello.capabilities
+    };
     let connection = Arc::new(RigConnection {
         telescope_id,
         connection_id,
         payload_version,
-        capabilities: hello.capabilities,
+        capabilities,
         profile_name: hello.profile_name.clone(),
         outgoing: outgoing_tx,
+        updates: Mutex::new(Some(broadcast::channel(UPDATE_BUFFER).0)),
         pending: Mutex::new(HashMap::new()),
         close_request,
     });
@@ -566,6 +615,19 @@ async fn handle_socket(state: HubState, mut socket: WebSocket, client_ip: String
         );
     }
 
+    if streaming
+        && !send_message(
+            &mut socket,
+            &DirectMessage::Subscribe(StreamSubscription::all()),
+        )
+        .await
+    {
+        state
+            .rig_connections
+            .remove_if_current(telescope_id, connection_id);
+        return;
+    }
+
     let idle = tokio::time::sleep(CLIENT_IDLE_TIMEOUT);
     tokio::pin!(idle);
     loop {
@@ -603,6 +665,11 @@ async fn handle_socket(state: HubState, mut socket: WebSocket, client_ip: String
                     Message::Text(text) => {
                         match serde_json::from_str::<DirectMessage>(&text) {
                             Ok(DirectMessage::QueryResult(result)) => connection.resolve(result),
+                            // Pushes from a rig that was never subscribed are
+                            // outside the negotiated contract and ignored.
+                            Ok(DirectMessage::Push(update)) if streaming => {
+                                connection.publish(update)
+                            }
                             Ok(DirectMessage::Heartbeat { seq }) => {
                                 if state
                                     .rig_connections
@@ -654,9 +721,7 @@ async fn handle_socket(state: HubState, mut socket: WebSocket, client_ip: String
     state
         .rig_connections
         .remove_if_current(telescope_id, connection_id);
-    if let Ok(mut pending) = connection.pending.lock() {
-        pending.clear();
-    }
+    connection.disconnect();
     println!("Rig connection {connection_id} disconnected for telescope {telescope_id}");
 }
 
@@ -936,6 +1001,7 @@ mod tests {
         let DirectMessage::PairResult(first_result) = first_response else {
             panic!("expected pair result");
         };
+        expect_subscribe(&mut first).await;
 
         let (mut replacement, _) = connect_async(&url).await.unwrap();
         replacement
@@ -957,6 +1023,7 @@ mod tests {
         let DirectMessage::AgentHello(replacement_hello) = replacement_response else {
             panic!("expected agent hello");
         };
+        expect_subscribe(&mut replacement).await;
 
         // Let the retired handler send its replacement error and close frame,
         // then finish its cleanup. That cleanup must not unregister the new
@@ -1005,6 +1072,186 @@ mod tests {
         replacement.close(None).await.unwrap();
     }
 
+    type ClientSocket = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn expect_subscribe(socket: &mut ClientSocket) {
+        let frame = tokio::time::timeout(Duration::from_secs(1), socket.next())
+            .await
+            .expect("streaming rig is subscribed")
+            .unwrap()
+            .unwrap();
+        let message: DirectMessage = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+        assert_eq!(message, DirectMessage::Subscribe(StreamSubscription::all()));
+    }
+
+    #[tokio::test]
+    async fn streaming_rig_is_subscribed_and_pushes_reach_subscribers() {
+        let (hub_base, _db, state, telescope_id, pairing_token) = spawn_hub().await;
+        let url = format!("{}/v1/direct", hub_base.replacen("http://", "ws://", 1));
+        let (mut socket, _) = connect_async(&url).await.unwrap();
+        socket
+            .send(WsMessage::Text(
+                serde_json::to_string(&DirectMessage::Pair(PairRequest {
+                    pairing_token,
+                    hello: hello_fixture(),
+                }))
+                .unwrap()
+                .into(),
+            ))
+            .await
+            .unwrap();
+        let _ = socket.next().await.expect("pair response").unwrap();
+        expect_subscribe(&mut socket).await;
+
+        let connection = state
+            .rig_connections
+            .get(telescope_id)
+            .expect("streaming rig connected");
+        assert!(connection.capabilities.event_stream);
+        let mut updates = connection.subscribe_updates();
+
+        socket
+            .send(WsMessage::Text(
+                include_str!("../../contracts/direct/v1/fixtures/push-event.json").into(),
+            ))
+            .await
+            .unwrap();
+        let update = tokio::time::timeout(Duration::from_secs(1), updates.recv())
+            .await
+            .expect("push delivered")
+            .unwrap();
+        let RigUpdate::Event { event } = update else {
+            panic!("expected pushed event");
+        };
+        assert_eq!(event.event, "IMAGE-SAVE");
+        socket.close(None).await.unwrap();
+
+        // Once the rig goes, subscribers see the stream close rather than
+        // waiting on pushes that will never come.
+        let closed = tokio::time::timeout(Duration::from_secs(1), updates.recv())
+            .await
+            .expect("stream closed on disconnect");
+        assert!(matches!(closed, Err(broadcast::error::RecvError::Closed)));
+        assert!(matches!(
+            connection.subscribe_updates().try_recv(),
+            Err(broadcast::error::TryRecvError::Closed)
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn updater_reports_a_dropped_streaming_rig_offline_within_a_poll() {
+        use crate::chat::{ChatServiceManager, ChatTarget};
+        use crate::chat_updater::ChatUpdater;
+        use crate::direct::protocol::QueryKind;
+        use crate::hub::direct_source::DirectRigSource;
+        use crate::simulator::SimulatedRigSource;
+        use crate::source::RigSource;
+        use crate::test_support::{CapturingService, short_plan};
+
+        const POLL: Duration = Duration::from_secs(5);
+        let (connection, mut queries) = RigConnection::stub(1, Uuid::new_v4());
+        assert!(connection.capabilities.event_stream);
+        // The rig answers the updater's reads from a simulated night.
+        let rig = tokio::spawn({
+            let connection = connection.clone();
+            async move {
+                let night = SimulatedRigSource::new(short_plan()).with_time_scale(60.0);
+                while let Some(message) = queries.recv().await {
+                    let DirectMessage::Query(query) = message else {
+                        continue;
+                    };
+                    let payload = match query.kind {
+                        QueryKind::EventHistory => {
+                            serde_json::to_value(night.get_event_history().await.unwrap())
+                        }
+                        QueryKind::ImageHistory => {
+                            serde_json::to_value(night.get_all_image_history().await.unwrap())
+                        }
+                        QueryKind::Sequence => {
+                            serde_json::to_value(night.get_sequence().await.unwrap())
+                        }
+                        _ => Ok(serde_json::Value::Null),
+                    }
+                    .unwrap();
+                    connection.resolve(QueryResult {
+                        id: query.id,
+                        ok: !payload.is_null(),
+                        error: payload.is_null().then(|| "not simulated".to_string()),
+                        payload,
+                    });
+                }
+            }
+        });
+
+        let chat = CapturingService::default();
+        let mut manager = ChatServiceManager::new();
+        manager.add_service(Box::new(chat.clone()));
+        let mut updater = ChatUpdater::new(
+            Arc::new(DirectRigSource::new(connection.clone())),
+            "c925".to_string(),
+            ChatTarget::default(),
+            Arc::new(manager),
+        );
+        tokio::spawn(async move { updater.start_polling(POLL).await });
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert!(chat.posted("monitor started"), "{:#?}", chat.titles());
+        assert!(!chat.posted("Telescope offline"));
+
+        rig.abort();
+        let _ = rig.await;
+        connection.disconnect();
+        tokio::time::sleep(POLL).await;
+        assert!(
+            chat.posted("Telescope offline"),
+            "still online a poll after the rig dropped: {:#?}",
+            chat.titles()
+        );
+    }
+
+    #[tokio::test]
+    async fn rig_below_stream_payload_version_is_polled() {
+        let (hub_base, _db, state, telescope_id, pairing_token) = spawn_hub().await;
+        let url = format!("{}/v1/direct", hub_base.replacen("http://", "ws://", 1));
+        let (mut socket, _) = connect_async(&url).await.unwrap();
+        let mut hello = hello_fixture();
+        hello.payload_version = crate::direct::protocol::EVENT_STREAM_PAYLOAD_VERSION - 1;
+        socket
+            .send(WsMessage::Text(
+                serde_json::to_string(&DirectMessage::Pair(PairRequest {
+                    pairing_token,
+                    hello,
+                }))
+                .unwrap()
+                .into(),
+            ))
+            .await
+            .unwrap();
+        let _ = socket.next().await.expect("pair response").unwrap();
+
+        // No subscribe frame: the next frame answers the heartbeat.
+        socket
+            .send(WsMessage::Text(
+                serde_json::to_string(&DirectMessage::Heartbeat { seq: 1 })
+                    .unwrap()
+                    .into(),
+            ))
+            .await
+            .unwrap();
+        let frame = socket.next().await.unwrap().unwrap();
+        let message: DirectMessage = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+        assert!(matches!(message, DirectMessage::HeartbeatAck { seq: 1 }));
+
+        // The flag alone is not enough: v3 predates push frames.
+        let connection = state
+            .rig_connections
+            .get(telescope_id)
+            .expect("rig connected");
+        assert!(!connection.capabilities.event_stream);
+        socket.close(None).await.unwrap();
+    }
+
     #[tokio::test]
     async fn bad_pairing_token_rejected() {
         let (hub_base, _db, _state, _telescope_id, _token) = spawn_hub().await;
@@ -3,26 +3,33 @@
 //! Every read becomes a query round trip to the connected rig; the payload
 //! is JSON for Chatstronomy's shared response types,
 //! so everything downstream (chat updater, bot commands, charts) works
-//! unchanged.
+//! unchanged. Updates a streaming rig pushes are forwarded to subscribers.
 
 use super::direct_server::{QUERY_TIMEOUT, RigConnection};
 use crate::api_types::CommandResponse;
 use crate::autofocus::AutofocusResponse;
 use crate::camera::CameraInfoResponse;
 use crate::direct::protocol::QueryKind;
+use crate::dome::DomeInfoResponse;
 use crate::events::EventHistoryResponse;
 use crate::filterwheel::FilterWheelInfoResponse;
+use crate::flat_device::FlatDeviceInfoResponse;
 use crate::focuser::FocuserInfoResponse;
 use crate::guider::{GuiderGraphResponse, GuiderInfoResponse};
 use crate::images::{ImageHistoryResponse, ThumbnailResponse};
 use crate::mount::MountInfoResponse;
 use crate::rotator::RotatorInfoResponse;
+use crate::safety::SafetyInfoResponse;
 use crate::sequence::SequenceResponse;
 use crate::source::{
     RigCapabilities, RigCommand, RigSource, RigSourceError, RigSourceKind, RigSourceResult,
+    RigUpdate,
 };
+use crate::switch::SwitchInfoResponse;
+use crate::weather::WeatherInfoResponse;
 use async_trait::async_trait;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 pub struct DirectRigSource {
     connection: Arc<RigConnection>,
@@ -117,7 +124,34 @@ impl RigSource for DirectRigSource {
         self.query_as(QueryKind::FocuserInfo).await
     }
 
+    async fn get_dome_info(&self) -> RigSourceResult<DomeInfoResponse> {
+        self.query_as(QueryKind::DomeInfo).await
+    }
+
+    async fn get_flat_device_info(&self) -> RigSourceResult<FlatDeviceInfoResponse> {
+        self.query_as(QueryKind::FlatDeviceInfo).await
+    }
+
+    async fn get_weather_info(&self) -> RigSourceResult<WeatherInfoResponse> {
+        self.query_as(QueryKind::WeatherInfo).await
+    }
+
+    async fn get_safety_info(&self) -> RigSourceResult<SafetyInfoResponse> {
+        self.query_as(QueryKind::SafetyInfo).await
+    }
+
+    async fn get_switch_info(&self) -> RigSourceResult<SwitchInfoResponse> {
+        self.query_as(QueryKind::SwitchInfo).await
+    }
+
     async fn execute_command(&self, command: RigCommand) -> RigSourceResult<CommandResponse> {
         self.query_as(QueryKind::Command { command }).await
     }
+
+    fn subscribe_updates(&self) -> Option<broadcast::Receiver<RigUpdate>> {
+        self.connection
+            .capabilities
+            .event_stream
+            .then(|| self.connection.subscribe_updates())
+    }
 }
@@ -114,6 +114,13 @@ impl RigResolver for HubRigResolver {
         Ok((row.name, source))
     }
 
+    fn telescope_names(&self, invocation: &CommandContext) -> Vec<String> {
+        invocation
+            .guild_id
+            .map(|guild_id| self.guild_names(guild_id as i64))
+            .unwrap_or_default()
+    }
+
     fn write_allowed(&self, invocation: &CommandContext, telescope: &str) -> Result<(), String> {
         let row = self.find_telescope(invocation, Some(telescope))?;
         let attachment = self.invoking_attachment(&row, invocation)?;
@@ -316,6 +323,7 @@ mod tests {
             &AttachmentUpdate {
                 write_policy: Some("roles".to_string()),
                 allowed_role_ids: Some(vec![1111]),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -10,6 +10,7 @@ use super::discord_api::{DiscordOauthClient, parse_snowflake, snowflake_string};
 use super::guild_check::{CachedGuildChecker, GuildChecker, SerenityGuildChecker};
 use super::store::{GuildSnapshot, SessionRow, UserRow};
 use super::tenants::TelescopeRow;
+use crate::notification_rules::NotificationRules;
 use axum::extract::{Path, Query, State};
 use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE, COOKIE, SET_COOKIE};
 use axum::http::{HeaderMap, StatusCode};
@@ -195,7 +196,7 @@ pub fn router(state: HubState) -> Router {
         )
         .route(
             "/api/attachments/{attachment_id}/channels/{route_id}",
-            delete(api_delete_channel_route),
+            delete(api_delete_channel_route).patch(api_update_channel_route),
         )
         .route(
             "/api/guilds/{guild_id}/subscribe",
@@ -514,6 +515,7 @@ fn attachment_json(a: &super::tenants::AttachmentRow) -> serde_json::Value {
         "write_policy": a.write_policy,
         "allowed_role_ids": a.allowed_role_ids.iter().copied()
             .map(snowflake_string).collect::<Vec<_>>(),
+        "notification_rules": a.notification_rules,
     })
 }
 
@@ -524,6 +526,7 @@ fn route_json(route: &super::tenants::ChannelRoute) -> serde_json::Value {
         "channel_id": snowflake_string(route.channel_id),
         "channel_name": route.channel_name,
         "guild_name": route.guild_name,
+        "session_threads": route.session_threads,
     })
 }
 
@@ -1069,6 +1072,7 @@ async fn api_guild_attachments(
 struct UpdateAttachmentBody {
     write_policy: Option<String>,
     allowed_role_ids: Option<Vec<String>>,
+    notification_rules: Option<serde_json::Value>,
 }
 
 /// The attachment's guild managers set THEIR server's command policy.
@@ -1101,9 +1105,22 @@ async fn api_update_attachment(
             Some(parsed)
         }
     };
+    let rules = match body
+        .notification_rules
+        .clone()
+        .map(serde_json::from_value::<NotificationRules>)
+    {
+        None => None,
+        Some(Ok(rules)) => match rules.validate() {
+            Ok(()) => Some(rules),
+            Err(e) => return bad_request(&e),
+        },
+        Some(Err(e)) => return bad_request(&format!("invalid notification rules: {e}")),
+    };
     let update = super::tenants::AttachmentUpdate {
         write_policy: body.write_policy.clone(),
         allowed_role_ids: roles,
+        notification_rules: rules,
     };
     if let Err(e) = state.db.update_attachment(attachment.id, &update) {
         return internal_error(e);
@@ -1228,6 +1245,60 @@ async fn api_add_channel_route(
     }
 }
 
+#[derive(Deserialize)]
+struct UpdateChannelBody {
+    session_threads: bool,
+}
+
+/// Change how a destination is posted to. Only the managers of the guild
+/// the channel lives in.
+async fn api_update_channel_route(
+    State(state): State<HubState>,
+    Path((attachment_id, route_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<UpdateChannelBody>,
+) -> Response {
+    let (attachment, session) =
+        match attachment_for_manage(&state, &headers, &attachment_id, true).await {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+    let route_id: i64 = match route_id.parse() {
+        Ok(id) => id,
+        Err(_) => return bad_request("invalid route id"),
+    };
+    match state.db.get_route(route_id) {
+        Ok(Some(route))
+            if route.telescope_id == attachment.telescope_id
+                && route.guild_id == attachment.guild_id => {}
+        Ok(_) => return (StatusCode::NOT_FOUND, "no such destination").into_response(),
+        Err(e) => return internal_error(e),
+    }
+    if let Err(e) = state
+        .db
+        .set_route_session_threads(route_id, body.session_threads)
+    {
+        return internal_error(e);
+    }
+    match state.db.get_route(route_id) {
+        Ok(Some(route)) => {
+            state.db.audit(
+                session.discord_user_id,
+                attachment.guild_id,
+                "destination_updated",
+                &format!(
+                    "#{} session threads {}",
+                    route.channel_name,
+                    if route.session_threads { "on" } else { "off" }
+                ),
+            );
+            Json(route_json(&route)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, "no such destination").into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
 /// Remove a destination. Managers of the attachment's guild, or the
 /// telescope's owner.
 async fn api_delete_channel_route(
@@ -1555,8 +1626,16 @@ pub async fn serve(
             state.db.clone(),
             state.rig_connections.clone(),
         ));
-        let (service, _gateway) = crate::chat::run_bot(&bot_config, resolver).await?;
         let mut manager = crate::chat::ChatServiceManager::new();
+        let status_state =
+            crate::chat::StatusState::load_shared(std::path::Path::new(&bot_config.state_file));
+        let (service, _gateway) = crate::chat::run_bot(
+            &bot_config,
+            resolver,
+            manager.acknowledgement_sender(),
+            status_state,
+        )
+        .await?;
         manager.add_service(Box::new(service));
         let updaters = Arc::new(super::updaters::UpdaterManager::new(
             state.db.clone(),
@@ -2101,6 +2180,51 @@ mod tests {
             .unwrap();
         assert_eq!(updated["write_policy"], "roles");
 
+        // Notification rules round-trip; invalid ones are refused.
+        let rules = serde_json::json!([
+            {"match": {"levels": ["error"]}, "action": "mention", "mention": "<@&1111>"}
+        ]);
+        let updated: serde_json::Value = client
+            .patch(format!("{base}/api/attachments/{attachment_id}"))
+            .header("x-csrf-token", &csrf)
+            .json(&serde_json::json!({ "notification_rules": rules }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(updated["notification_rules"], rules);
+        assert_eq!(updated["write_policy"], "roles");
+        let refused = client
+            .patch(format!("{base}/api/attachments/{attachment_id}"))
+            .header("x-csrf-token", &csrf)
+            .json(&serde_json::json!({
+                "notification_rules": [{"match": {"message": "("}, "action": "drop"}],
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(refused.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        // Session threads are switched per destination channel.
+        assert_eq!(route["session_threads"], false);
+        let route_id = route["route_id"].as_i64().unwrap();
+        let threaded: serde_json::Value = client
+            .patch(format!(
+                "{base}/api/attachments/{attachment_id}/channels/{route_id}"
+            ))
+            .header("x-csrf-token", &csrf)
+            .json(&serde_json::json!({ "session_threads": true }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(threaded["session_threads"], true);
+        assert!(db.get_route(route_id).unwrap().unwrap().session_threads);
+
         // The owner's view rolls everything up.
         let mine: serde_json::Value = client
             .get(format!("{base}/api/telescopes"))
@@ -8,6 +8,7 @@
 //! deliver the feed into channels of attached guilds.
 
 use super::db::{Db, DbError, unix_now};
+use crate::notification_rules::NotificationRules;
 use rusqlite::OptionalExtension;
 
 /// Pairing tokens expire after an hour; they exist only to move a secret
@@ -53,6 +54,8 @@ pub struct AttachmentRow {
     pub can_command: bool,
     pub write_policy: String,
     pub allowed_role_ids: Vec<i64>,
+    /// This guild's filtering of the telescope's notifications.
+    pub notification_rules: NotificationRules,
 }
 
 /// Changes applied to an attachment. `None` keeps the current value.
@@ -60,6 +63,7 @@ pub struct AttachmentRow {
 pub struct AttachmentUpdate {
     pub write_policy: Option<String>,
     pub allowed_role_ids: Option<Vec<i64>>,
+    pub notification_rules: Option<NotificationRules>,
 }
 
 /// An attachment as listed on a guild's management page.
@@ -80,6 +84,8 @@ pub struct ChannelRoute {
     /// Display snapshots taken when the route was created.
     pub channel_name: String,
     pub guild_name: String,
+    /// Post each session into a Discord thread rather than the channel.
+    pub session_threads: bool,
 }
 
 /// A rig credential row as needed by the connection handshake.
@@ -133,6 +139,7 @@ fn attachment_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<AttachmentRow>
         can_command: r.get(3)?,
         write_policy: r.get(4)?,
         allowed_role_ids: roles_from_json(&r.get::<_, String>(5)?),
+        notification_rules: serde_json::from_str(&r.get::<_, String>(6)?).unwrap_or_default(),
     })
 }
 
@@ -144,6 +151,7 @@ fn route_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<ChannelRoute> {
         channel_id: r.get(3)?,
         channel_name: r.get(4)?,
         guild_name: r.get(5)?,
+        session_threads: r.get(6)?,
     })
 }
 
@@ -153,10 +161,11 @@ const TELESCOPE_COLUMNS: &str = "telescopes.id, telescopes.owner_id, telescopes.
 const ATTACHMENT_COLUMNS: &str = "telescope_attachments.id, \
      telescope_attachments.telescope_id, telescope_attachments.guild_id, \
      telescope_attachments.can_command, telescope_attachments.write_policy, \
-     telescope_attachments.allowed_role_ids";
+     telescope_attachments.allowed_role_ids, telescope_attachments.notification_rules";
 const ROUTE_COLUMNS: &str = "telescope_channels.id, telescope_channels.telescope_id, \
      telescope_channels.guild_id, telescope_channels.channel_id, \
-     telescope_channels.channel_name, telescope_channels.guild_name";
+     telescope_channels.channel_name, telescope_channels.guild_name, \
+     telescope_channels.session_threads";
 
 impl Db {
     /// Register a guild (or refresh its name) as a tenant.
@@ -268,6 +277,35 @@ impl Db {
         })
     }
 
+    /// The telescope's chat updater checkpoint, if one was saved.
+    pub fn updater_checkpoint(&self, telescope_id: i64) -> Result<Option<String>, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT checkpoint FROM updater_checkpoints WHERE telescope_id = ?1",
+                rusqlite::params![telescope_id],
+                |row| row.get(0),
+            )
+            .optional()
+        })
+    }
+
+    pub fn save_updater_checkpoint(
+        &self,
+        telescope_id: i64,
+        checkpoint: &str,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO updater_checkpoints (telescope_id, checkpoint, updated_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT (telescope_id) DO UPDATE
+                 SET checkpoint = excluded.checkpoint, updated_at = excluded.updated_at",
+                rusqlite::params![telescope_id, checkpoint, unix_now()],
+            )
+            .map(|_| ())
+        })
+    }
+
     // ---------- Attachments (telescope x guild) ----------
 
     /// Attach a telescope to a guild. `can_command: false` is a feed-only
@@ -337,12 +375,12 @@ impl Db {
                 Ok(GuildAttachment {
                     attachment: attachment_from_row(r)?,
                     telescope: TelescopeRow {
-                        id: r.get(6)?,
-                        owner_id: r.get(7)?,
-                        name: r.get(8)?,
-                        image_cooldown_seconds: r.get(9)?,
+                        id: r.get(7)?,
+                        owner_id: r.get(8)?,
+                        name: r.get(9)?,
+                        image_cooldown_seconds: r.get(10)?,
                     },
-                    owner_name: r.get(10)?,
+                    owner_name: r.get(11)?,
                 })
             })?;
             rows.collect()
@@ -375,6 +413,15 @@ impl Db {
                     rusqlite::params![roles_to_json(roles), id],
                 )?;
             }
+            if let Some(rules) = &update.notification_rules {
+                conn.execute(
+                    "UPDATE telescope_attachments SET notification_rules = ?1 WHERE id = ?2",
+                    rusqlite::params![
+                        serde_json::to_string(rules).unwrap_or_else(|_| "[]".to_string()),
+                        id
+                    ],
+                )?;
+            }
             Ok(())
         })
     }
@@ -527,6 +574,16 @@ impl Db {
         })
     }
 
+    pub fn set_route_session_threads(&self, route_id: i64, enabled: bool) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE telescope_channels SET session_threads = ?1 WHERE id = ?2",
+                rusqlite::params![enabled, route_id],
+            )
+            .map(|_| ())
+        })
+    }
+
     pub fn delete_route(&self, route_id: i64) -> Result<(), DbError> {
         self.with_conn(|conn| {
             conn.execute(
@@ -809,6 +866,21 @@ mod tests {
         assert!(db.get_telescope(t.id).unwrap().is_none());
     }
 
+    #[test]
+    fn updater_checkpoints_replace_and_go_with_the_telescope() {
+        let db = db_with_users();
+        let t = db.create_telescope(1, "c925").unwrap();
+        assert_eq!(db.updater_checkpoint(t.id).unwrap(), None);
+        db.save_updater_checkpoint(t.id, "{\"v\":1}").unwrap();
+        db.save_updater_checkpoint(t.id, "{\"v\":2}").unwrap();
+        assert_eq!(
+            db.updater_checkpoint(t.id).unwrap().as_deref(),
+            Some("{\"v\":2}")
+        );
+        db.delete_telescope(t.id).unwrap();
+        assert_eq!(db.updater_checkpoint(t.id).unwrap(), None);
+    }
+
     #[test]
     fn attachment_lifecycle() {
         let db = db_with_users();
@@ -839,12 +911,44 @@ mod tests {
             &AttachmentUpdate {
                 write_policy: Some("roles".to_string()),
                 allowed_role_ids: Some(vec![7]),
+                ..Default::default()
             },
         )
         .unwrap();
         let updated = db.get_attachment(home.id).unwrap().unwrap();
         assert_eq!(updated.write_policy, "roles");
         assert_eq!(updated.allowed_role_ids, vec![7]);
+        assert!(updated.notification_rules.is_empty());
+
+        // Notification rules are per guild too.
+        db.update_attachment(
+            club.id,
+            &AttachmentUpdate {
+                notification_rules: Some(
+                    serde_json::from_str(
+                        r#"[{"match": {"events": ["NINA-LOG"]}, "action": "drop"}]"#,
+                    )
+                    .unwrap(),
+                ),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            db.guild_attachments(200).unwrap()[0]
+                .attachment
+                .notification_rules
+                .0
+                .len(),
+            1
+        );
+        assert!(
+            db.get_attachment(home.id)
+                .unwrap()
+                .unwrap()
+                .notification_rules
+                .is_empty()
+        );
 
         // Detaching removes the attachment and its guild's routes only.
         db.add_channel_route(t.id, 100, 42, "obs", "home", 1)
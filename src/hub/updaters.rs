@@ -11,7 +11,9 @@ use super::direct_server::RigConnections;
 use super::direct_source::DirectRigSource;
 use crate::chat::{ChatMessage, ChatServiceManager, ChatTarget};
 use crate::chat_updater::ChatUpdater;
-use std::collections::HashMap;
+use crate::checkpoint::CheckpointStore;
+use crate::notification_rules::NotificationRoute;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
@@ -47,10 +49,13 @@ pub struct PresenceEvent {
 struct RunningUpdater {
     connection_id: Uuid,
     /// The config the updater was built with. A change in the database
-    /// (destinations added or removed, cooldown adjusted) restarts the
-    /// updater, which otherwise freezes its config at construction.
+    /// (destinations added or removed, cooldown or notification rules
+    /// adjusted) restarts the updater, which otherwise freezes its config at
+    /// construction.
     channels: Vec<i64>,
+    thread_channels: Vec<i64>,
     image_cooldown_seconds: i64,
+    notification_rules: String,
     handle: tokio::task::JoinHandle<()>,
 }
 
@@ -170,12 +175,7 @@ impl UpdaterManager {
             if channels.is_empty() {
                 continue;
             }
-            let target = ChatTarget {
-                discord_webhook_url: None,
-                matrix_room_id: None,
-                discord_channel_id: None,
-                discord_channel_ids: channels.iter().map(|c| *c as u64).collect(),
-            };
+            let target = channel_target(&channels);
             let message = if event.online {
                 ChatMessage::new(&format!(
                     "🔭 [{}] Telescope connected",
@@ -204,6 +204,67 @@ impl UpdaterManager {
         channels
     }
 
+    /// The destination channels that post into session threads, sorted for
+    /// comparison.
+    fn thread_channels(&self, telescope_id: i64) -> Vec<i64> {
+        let mut channels: Vec<i64> = self
+            .db
+            .telescope_routes(telescope_id)
+            .map(|routes| {
+                routes
+                    .iter()
+                    .filter(|r| r.session_threads)
+                    .map(|r| r.channel_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        channels.sort_unstable();
+        channels
+    }
+
+    /// Each attached guild's notification rules, serialized for comparison.
+    fn notification_rules(&self, telescope_id: i64) -> String {
+        let rules: BTreeMap<i64, _> = self
+            .db
+            .telescope_attachments(telescope_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|attachment| (attachment.guild_id, attachment.notification_rules))
+            .collect();
+        serde_json::to_string(&rules).unwrap_or_default()
+    }
+
+    /// The telescope's channels grouped by guild, each group filtered by
+    /// that guild's attachment rules. Channels with session threads form
+    /// their own group, whose posts go into the telescope's threads.
+    fn notification_routes(&self, telescope_id: i64, telescope: &str) -> Vec<NotificationRoute> {
+        let mut by_guild: BTreeMap<(i64, bool), Vec<i64>> = BTreeMap::new();
+        for route in self.db.telescope_routes(telescope_id).unwrap_or_default() {
+            by_guild
+                .entry((route.guild_id, route.session_threads))
+                .or_default()
+                .push(route.channel_id);
+        }
+        let attachments = self
+            .db
+            .telescope_attachments(telescope_id)
+            .unwrap_or_default();
+        by_guild
+            .into_iter()
+            .map(|((guild_id, threads), channels)| NotificationRoute {
+                target: ChatTarget {
+                    session_thread: threads.then(|| telescope.to_string()),
+                    ..channel_target(&channels)
+                },
+                rules: attachments
+                    .iter()
+                    .find(|attachment| attachment.guild_id == guild_id)
+                    .map(|attachment| attachment.notification_rules.clone())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
     /// One reconcile pass. Returns (started, stopped) counts.
     pub fn reconcile_once(&self) -> (usize, usize) {
         let mut started = 0;
@@ -222,7 +283,9 @@ impl UpdaterManager {
             let config_current = matches!(
                 self.db.get_telescope(*telescope_id),
                 Ok(Some(row)) if row.image_cooldown_seconds == updater.image_cooldown_seconds
-            ) && self.route_channels(*telescope_id) == updater.channels;
+            ) && self.route_channels(*telescope_id) == updater.channels
+                && self.thread_channels(*telescope_id) == updater.thread_channels
+                && self.notification_rules(*telescope_id) == updater.notification_rules;
             let keep = connection_current && config_current;
             if !keep {
                 updater.handle.abort();
@@ -252,19 +315,19 @@ impl UpdaterManager {
 
             let connection_id = connection.connection_id;
             let source = Arc::new(DirectRigSource::new(connection));
-            let target = ChatTarget {
-                discord_webhook_url: None,
-                matrix_room_id: None,
-                discord_channel_id: None,
-                discord_channel_ids: channels.iter().map(|c| *c as u64).collect(),
-            };
+            let notification_rules = self.notification_rules(telescope_id);
             let mut updater = ChatUpdater::new(
                 source,
                 telescope.name.clone(),
-                target,
+                channel_target(&channels),
                 self.chat_manager.clone(),
             )
             .with_image_cooldown(telescope.image_cooldown_seconds.max(0) as u64)
+            .with_notification_routes(self.notification_routes(telescope_id, &telescope.name))
+            .with_checkpoint(Arc::new(DbCheckpointStore {
+                db: self.db.clone(),
+                telescope_id,
+            }))
             // Hub updaters restart on every deploy, reconnect, and config
             // change; presence is announced from connection state instead.
             .with_lifecycle_announcements(false);
@@ -276,7 +339,9 @@ impl UpdaterManager {
                 RunningUpdater {
                     connection_id,
                     channels,
+                    thread_channels: self.thread_channels(telescope_id),
                     image_cooldown_seconds: telescope.image_cooldown_seconds,
+                    notification_rules,
                     handle,
                 },
             );
@@ -304,6 +369,49 @@ impl UpdaterManager {
     }
 }
 
+/// A telescope's updater checkpoint, kept in the hub database.
+struct DbCheckpointStore {
+    db: Db,
+    telescope_id: i64,
+}
+
+impl CheckpointStore for DbCheckpointStore {
+    fn load(&self) -> Option<String> {
+        self.db
+            .updater_checkpoint(self.telescope_id)
+            .unwrap_or_else(|e| {
+                eprintln!(
+                    "Could not load checkpoint for telescope {}: {e}",
+                    self.telescope_id
+                );
+                None
+            })
+    }
+
+    fn save(&self, checkpoint: &str) {
+        if let Err(e) = self
+            .db
+            .save_updater_checkpoint(self.telescope_id, checkpoint)
+        {
+            eprintln!(
+                "Could not save checkpoint for telescope {}: {e}",
+                self.telescope_id
+            );
+        }
+    }
+}
+
+/// A target posting to the given Discord channels through the hub's bot.
+fn channel_target(channels: &[i64]) -> ChatTarget {
+    ChatTarget {
+        discord_webhook_url: None,
+        matrix_room_id: None,
+        discord_channel_id: None,
+        discord_channel_ids: channels.iter().map(|c| *c as u64).collect(),
+        session_thread: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,6 +495,43 @@ mod tests {
         assert_eq!(manager.running_count(), 0);
     }
 
+    #[tokio::test]
+    async fn notification_rule_changes_restart_updater() {
+        let (db, connections, manager, id) = setup();
+        db.add_channel_route(id, 100, 42, "obs", "g", 1).unwrap();
+        db.register_guild(200, "club", 1).unwrap();
+        db.attach_telescope(id, 200, false, 1).unwrap();
+        db.add_channel_route(id, 200, 900, "feed", "club", 1)
+            .unwrap();
+        connect(&connections, id);
+        assert_eq!(manager.reconcile_once(), (1, 0));
+
+        // Each guild's channels form one route with that guild's rules.
+        let routes = manager.notification_routes(id, "Scope");
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[1].target.discord_channel_ids, vec![900]);
+        assert!(routes.iter().all(|route| route.rules.is_empty()));
+
+        let club = db.attachment_for(id, 200).unwrap().unwrap();
+        db.update_attachment(
+            club.id,
+            &crate::hub::tenants::AttachmentUpdate {
+                notification_rules: Some(
+                    serde_json::from_str(
+                        r#"[{"match": {"image_types": ["FLAT"]}, "action": "drop"}]"#,
+                    )
+                    .unwrap(),
+                ),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(manager.reconcile_once(), (1, 1));
+        assert!(manager.notification_routes(id, "Scope")[0].rules.is_empty());
+        assert!(!manager.notification_routes(id, "Scope")[1].rules.is_empty());
+        assert_eq!(manager.reconcile_once(), (0, 0));
+    }
+
     #[tokio::test]
     async fn presence_first_observation_is_silent_then_transitions_announce() {
         let (db, connections, manager, id) = setup();
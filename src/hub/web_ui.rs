@@ -156,6 +156,11 @@ pub const INDEX_HTML: &str = r#"<!doctype html>
   select.pick { width: 230px; max-width: 100%; }
   input.name { width: 230px; max-width: 100%; }
   input.num { width: 6.2rem; }
+  textarea.rules {
+    width: 100%; min-height: 6rem; background: var(--bg); color: var(--text);
+    border: 1px solid var(--control-border); border-radius: 7px; padding: .45rem .55rem;
+    font-family: ui-monospace, monospace; font-size: .8rem;
+  }
 
   .chips { display: flex; flex-wrap: wrap; gap: .4rem; align-items: center; }
   .chip {
@@ -594,6 +599,7 @@ function ico(name) {
     globe: '<circle cx="12" cy="12" r="10"/><line x1="2" y1="12" x2="22" y2="12"/><path d="M12 2a15.3 15.3 0 0 1 4 10 15.3 15.3 0 0 1-4 10 15.3 15.3 0 0 1-4-10 15.3 15.3 0 0 1 4-10z"/>',
     clock: '<circle cx="12" cy="12" r="10"/><polyline points="12 6 12 12 16 14"/>',
     zap: '<polygon points="13 2 3 14 12 14 11 22 21 10 12 10 13 2"/>',
+    bell: '<path d="M18 8A6 6 0 0 0 6 8c0 7-3 9-3 9h18s-3-2-3-9"/><path d="M13.73 21a2 2 0 0 1-3.46 0"/>',
   };
   return '<svg class="ico" viewBox="0 0 24 24" fill="none" stroke="currentColor" ' +
     'stroke-width="2" stroke-linecap="round" stroke-linejoin="round" aria-hidden="true">' +
@@ -683,7 +689,12 @@ function channelChips(a) {
   }
   return '<div class="chips">' + a.channels.map((route) => {
     const name = route.channel_name ? '#' + route.channel_name : "channel " + route.channel_id;
+    const threads = route.session_threads;
     return '<span class="chip on">' + esc(name) +
+      ' <button type="button" class="rm th-route" data-route="' + route.route_id +
+      '" data-on="' + threads + '" aria-pressed="' + threads +
+      '" title="' + (threads ? "Posting into session threads" : "Open a thread per session") +
+      '">' + (threads ? '🧵' : '#') + "</button>" +
       ' <button type="button" class="rm rm-route" data-route="' + route.route_id +
       '" aria-label="Remove ' + esc(name) + ' from delivery" title="Remove channel">' +
       "✕</button></span>";
@@ -743,6 +754,12 @@ async function renderAttachments(g, el) {
       '<button class="b-addchan"' + (canAddChannel ? "" : " disabled") +
       ">Add channel</button></div></div>" +
       commands +
+      '<div class="section"><label>' + ico("bell") + "Notifications — rules for this server</label>" +
+      '<textarea class="rules f-rules" spellcheck="false" placeholder=\'[{"match": {"events": ["NINA-LOG"]}, "action": "drop"}]\'>' +
+      esc(a.notification_rules.length ? JSON.stringify(a.notification_rules, null, 2) : "") +
+      '</textarea><div class="controls"><button class="b-rules">Save rules</button>' +
+      '<span class="hint">First match wins: drop, deliver, mention, or batch. ' +
+      "Unmatched notifications are delivered.</span></div></div>" +
       "</div>";
   }
   if (!data.attachments.length) {
@@ -799,6 +816,20 @@ async function renderAttachments(g, el) {
       };
     });
 
+    row.querySelector(".b-rules").onclick = async () => {
+      const text = row.querySelector(".f-rules").value.trim();
+      let rules;
+      try { rules = text ? JSON.parse(text) : []; }
+      catch (e) { toast("Rules are not valid JSON"); return; }
+      try {
+        await api("/api/attachments/" + id, {
+          method: "PATCH",
+          body: JSON.stringify({ notification_rules: rules }),
+        });
+        toast("Notification rules saved");
+      } catch (e) { toast(e.message); }
+    };
+
     row.querySelector(".b-addchan").onclick = async () => {
       const box = row.querySelector(".f-addchan");
       if (box.disabled || !box.value) return;
@@ -809,6 +840,17 @@ async function renderAttachments(g, el) {
         renderAll("delivery", id);
       } catch (e) { toast(e.message); }
     };
+    row.querySelectorAll(".th-route").forEach((btn) => {
+      btn.onclick = async () => {
+        const on = btn.dataset.on !== "true";
+        try {
+          await api("/api/attachments/" + id + "/channels/" + btn.dataset.route,
+            { method: "PATCH", body: JSON.stringify({ session_threads: on }) });
+          toast(on ? "Sessions open a thread here" : "Posting straight to the channel");
+          renderAll("delivery", id);
+        } catch (e) { toast(e.message); }
+      };
+    });
     row.querySelectorAll(".rm-route").forEach((btn) => {
       btn.onclick = async () => {
         try {
@@ -1011,6 +1053,13 @@ mod tests {
         assert_eq!(INDEX_HTML.matches("class=\"token-out\"").count(), 1);
     }
 
+    #[test]
+    fn routes_toggle_session_threads() {
+        assert!(INDEX_HTML.contains("th-route"));
+        assert!(INDEX_HTML.contains("session_threads: on"));
+        assert!(INDEX_HTML.contains("method: \"PATCH\""));
+    }
+
     #[test]
     fn page_surfaces_missing_bot_token() {
         // A hub without a bot token must say so, not just look broken.
@@ -0,0 +1,402 @@
+//! Image-quality anomaly detection over the light frames a rig saves.
+//!
+//! Each target and filter keeps a rolling baseline of recent normal frames.
+//! A new frame is judged against it for three failure modes: the star count
+//! collapsing (clouds), the background median jumping (light leak, moonrise
+//! or dawn), and HFR drifting past the value measured right after the last
+//! autofocus (focus drift or dew).
+
+use crate::images::ImageMetadata;
+use crate::session_report::median;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// Per-telescope thresholds for image-quality alerts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageQualityConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Normal frames a baseline holds; nothing is judged until it is full.
+    #[serde(default = "default_baseline_frames")]
+    pub baseline_frames: usize,
+    /// Warn when a frame has fewer stars than this fraction of the baseline.
+    #[serde(default = "default_star_collapse_ratio")]
+    pub star_collapse_ratio: f64,
+    /// Warn when the background median exceeds the baseline by this factor.
+    #[serde(default = "default_median_jump_ratio")]
+    pub median_jump_ratio: f64,
+    /// Warn when HFR exceeds the post-autofocus baseline by this factor.
+    #[serde(default = "default_hfr_drift_ratio")]
+    pub hfr_drift_ratio: f64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_baseline_frames() -> usize {
+    5
+}
+
+fn default_star_collapse_ratio() -> f64 {
+    0.5
+}
+
+fn default_median_jump_ratio() -> f64 {
+    1.5
+}
+
+fn default_hfr_drift_ratio() -> f64 {
+    1.2
+}
+
+impl Default for ImageQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            baseline_frames: default_baseline_frames(),
+            star_collapse_ratio: default_star_collapse_ratio(),
+            median_jump_ratio: default_median_jump_ratio(),
+            hfr_drift_ratio: default_hfr_drift_ratio(),
+        }
+    }
+}
+
+impl ImageQualityConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.baseline_frames) {
+            return Err("image quality baseline must hold 1 to 100 frames".to_string());
+        }
+        if !(self.star_collapse_ratio > 0.0 && self.star_collapse_ratio < 1.0) {
+            return Err("image quality star collapse ratio must be between 0 and 1".to_string());
+        }
+        for (name, ratio) in [
+            ("median jump", self.median_jump_ratio),
+            ("HFR drift", self.hfr_drift_ratio),
+        ] {
+            if !(ratio.is_finite() && ratio > 1.0) {
+                return Err(format!("image quality {name} ratio must be above 1"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A way a frame can fall short of its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QualityAnomaly {
+    StarCollapse,
+    MedianJump,
+    HfrDrift,
+}
+
+impl QualityAnomaly {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::StarCollapse => "Star count collapsed",
+            Self::MedianJump => "Background jumped",
+            Self::HfrDrift => "HFR drifting",
+        }
+    }
+
+    /// The usual culprit.
+    pub fn cause(self) -> &'static str {
+        match self {
+            Self::StarCollapse => "clouds or an obstruction",
+            Self::MedianJump => "a light leak, moonlight or dawn",
+            Self::HfrDrift => "focus drift or dew",
+        }
+    }
+
+    pub fn suggestion(self) -> &'static str {
+        match self {
+            Self::StarCollapse => "Check the sky; pause the sequence if clouds are moving in",
+            Self::MedianJump => "Check for stray light and the time to astronomical dawn",
+            Self::HfrDrift => "Run autofocus; if HFR stays high, check the dew heaters",
+        }
+    }
+
+    /// Whether an autofocus run is the suggested fix.
+    pub fn suggests_autofocus(self) -> bool {
+        self == Self::HfrDrift
+    }
+}
+
+/// One anomaly a frame raised, with the numbers behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityFinding {
+    pub anomaly: QualityAnomaly,
+    pub measured: f64,
+    pub baseline: f64,
+}
+
+impl QualityFinding {
+    /// e.g. "312 vs 1240 baseline (25%)".
+    pub fn detail(&self) -> String {
+        let percent = self.measured / self.baseline * 100.0;
+        match self.anomaly {
+            QualityAnomaly::HfrDrift => format!(
+                "{:.2} vs {:.2} after autofocus ({percent:.0}%)",
+                self.measured, self.baseline
+            ),
+            _ => format!(
+                "{:.0} vs {:.0} baseline ({percent:.0}%)",
+                self.measured, self.baseline
+            ),
+        }
+    }
+}
+
+/// Rolling baselines for one telescope, keyed by target and filter.
+#[derive(Debug, Default)]
+pub struct QualityMonitor {
+    baselines: HashMap<(String, String), Baseline>,
+}
+
+#[derive(Debug, Default)]
+struct Baseline {
+    /// (stars, median) of the latest normal frames.
+    recent: VecDeque<(f64, f64)>,
+    /// HFRs of the first frames after the last autofocus, until the
+    /// reference is set.
+    focus_frames: Vec<f64>,
+    focused_hfr: Option<f64>,
+    /// Anomalies already announced; each re-arms once a frame clears it.
+    active: BTreeSet<QualityAnomaly>,
+}
+
+impl QualityMonitor {
+    /// Judge one light frame against its target and filter's baseline,
+    /// returning the anomalies it newly raises.
+    pub fn observe(
+        &mut self,
+        config: &ImageQualityConfig,
+        target: &str,
+        image: &ImageMetadata,
+    ) -> Vec<QualityFinding> {
+        let baseline = self
+            .baselines
+            .entry((target.to_string(), image.filter.clone()))
+            .or_default();
+        let stars = f64::from(image.stars);
+        let hfr = (image.hfr.is_finite() && image.hfr > 0.0).then_some(image.hfr);
+
+        let mut findings = Vec::new();
+        if baseline.recent.len() >= config.baseline_frames {
+            let base_stars = median(baseline.recent.iter().map(|(stars, _)| *stars));
+            let base_median = median(baseline.recent.iter().map(|(_, median)| *median));
+            if let Some(base) = base_stars.filter(|base| *base > 0.0)
+                && stars < base * config.star_collapse_ratio
+            {
+                findings.push(QualityFinding {
+                    anomaly: QualityAnomaly::StarCollapse,
+                    measured: stars,
+                    baseline: base,
+                });
+            }
+            if let Some(base) = base_median.filter(|base| *base > 0.0)
+                && image.median > base * config.median_jump_ratio
+            {
+                findings.push(QualityFinding {
+                    anomaly: QualityAnomaly::MedianJump,
+                    measured: image.median,
+                    baseline: base,
+                });
+            }
+        }
+        // Clouded or washed-out frames say nothing reliable about focus, and
+        // must not become the baseline either.
+        let normal = findings.is_empty();
+        if normal {
+            baseline.recent.push_back((stars, image.median));
+            while baseline.recent.len() > config.baseline_frames {
+                baseline.recent.pop_front();
+            }
+            match (baseline.focused_hfr, hfr) {
+                (Some(reference), Some(hfr)) if hfr > reference * config.hfr_drift_ratio => {
+                    findings.push(QualityFinding {
+                        anomaly: QualityAnomaly::HfrDrift,
+                        measured: hfr,
+                        baseline: reference,
+                    });
+                }
+                (None, Some(hfr)) => {
+                    baseline.focus_frames.push(hfr);
+                    if baseline.focus_frames.len() >= config.baseline_frames {
+                        baseline.focused_hfr = median(baseline.focus_frames.drain(..));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let current: BTreeSet<QualityAnomaly> =
+            findings.iter().map(|finding| finding.anomaly).collect();
+        let previous = std::mem::replace(&mut baseline.active, current);
+        findings.retain(|finding| !previous.contains(&finding.anomaly));
+        findings
+    }
+
+    /// A new autofocus run: every HFR reference is re-measured from the
+    /// frames that follow it.
+    pub fn focus_changed(&mut self) {
+        for baseline in self.baselines.values_mut() {
+            baseline.focus_frames.clear();
+            baseline.focused_hfr = None;
+            baseline.active.remove(&QualityAnomaly::HfrDrift);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{FailureKind, NightPlan, ScriptedFailure};
+    use crate::test_support::SimulatedNight;
+
+    fn frame(stars: i32, median: f64, hfr: f64) -> ImageMetadata {
+        ImageMetadata {
+            chat_enabled: true,
+            exposure_time: 300.0,
+            image_type: "LIGHT".to_string(),
+            filter: "Ha".to_string(),
+            rms_text: String::new(),
+            temperature: -10.0,
+            camera_name: "ZWO ASI2600MM Pro".to_string(),
+            gain: 100,
+            offset: 50,
+            date: "2025-08-07T22:00:00.000-07:00".to_string(),
+            telescope_name: "RedCat".to_string(),
+            focal_length: 250,
+            st_dev: 40.0,
+            mean: 800.0,
+            median,
+            stars,
+            hfr,
+            is_bayered: false,
+        }
+    }
+
+    fn anomalies(findings: &[QualityFinding]) -> Vec<QualityAnomaly> {
+        findings.iter().map(|finding| finding.anomaly).collect()
+    }
+
+    #[test]
+    fn star_collapse_alerts_once_and_rearms() {
+        let config = ImageQualityConfig::default();
+        let mut monitor = QualityMonitor::default();
+        for _ in 0..config.baseline_frames {
+            assert!(
+                monitor
+                    .observe(&config, "M31", &frame(1200, 800.0, 2.0))
+                    .is_empty()
+            );
+        }
+
+        let clouded = monitor.observe(&config, "M31", &frame(140, 800.0, 3.5));
+        assert_eq!(anomalies(&clouded), [QualityAnomaly::StarCollapse]);
+        assert_eq!(clouded[0].detail(), "140 vs 1200 baseline (12%)");
+        // Still clouded: no repeat, and the clouded frames did not drag the
+        // baseline down.
+        assert!(
+            monitor
+                .observe(&config, "M31", &frame(160, 800.0, 3.5))
+                .is_empty()
+        );
+        assert!(
+            monitor
+                .observe(&config, "M31", &frame(1180, 800.0, 2.0))
+                .is_empty()
+        );
+        let again = monitor.observe(&config, "M31", &frame(100, 800.0, 2.0));
+        assert_eq!(anomalies(&again), [QualityAnomaly::StarCollapse]);
+
+        // Another target has its own baseline, still filling.
+        assert!(
+            monitor
+                .observe(&config, "M33", &frame(10, 5000.0, 2.0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn median_jump_and_hfr_drift_against_their_references() {
+        let config = ImageQualityConfig::default();
+        let mut monitor = QualityMonitor::default();
+        for _ in 0..config.baseline_frames {
+            monitor.observe(&config, "M31", &frame(1200, 800.0, 2.0));
+        }
+
+        let dawn = monitor.observe(&config, "M31", &frame(1100, 2400.0, 2.1));
+        assert_eq!(anomalies(&dawn), [QualityAnomaly::MedianJump]);
+
+        let soft = monitor.observe(&config, "M31", &frame(1100, 820.0, 2.6));
+        assert_eq!(anomalies(&soft), [QualityAnomaly::HfrDrift]);
+        assert!(soft[0].anomaly.suggests_autofocus());
+        assert_eq!(soft[0].detail(), "2.60 vs 2.00 after autofocus (130%)");
+
+        // After a refocus the reference is re-measured, so the same HFR is
+        // not judged until the new baseline fills.
+        monitor.focus_changed();
+        for _ in 0..config.baseline_frames {
+            assert!(
+                monitor
+                    .observe(&config, "M31", &frame(1100, 820.0, 2.6))
+                    .is_empty()
+            );
+        }
+        assert!(
+            monitor
+                .observe(&config, "M31", &frame(1100, 820.0, 2.7))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn validates_thresholds() {
+        assert!(ImageQualityConfig::default().validate().is_ok());
+        let config: ImageQualityConfig =
+            serde_json::from_str(r#"{"hfr_drift_ratio": 0.9}"#).unwrap();
+        assert!(config.validate().is_err());
+        let config = ImageQualityConfig {
+            baseline_frames: 0,
+            ..ImageQualityConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn passing_clouds_on_a_simulated_night_raise_one_alert() {
+        let night = SimulatedNight::new(NightPlan {
+            filters: vec!["L".to_string()],
+            frames_per_filter: 10,
+            exposure_seconds: 120.0,
+            meridian_flip_after_minutes: 600.0,
+            failures: vec![ScriptedFailure {
+                at_minutes: 30.0,
+                kind: FailureKind::Clouds { minutes: 4.0 },
+            }],
+            ..NightPlan::default()
+        });
+        let mut updater = night
+            .updater()
+            .with_image_quality_alerts(ImageQualityConfig {
+                baseline_frames: 3,
+                ..ImageQualityConfig::default()
+            });
+        updater.initialize_baseline().await.unwrap();
+
+        for _ in 0..150 {
+            tokio::time::advance(std::time::Duration::from_secs(1)).await;
+            updater.poll_events().await;
+            updater.poll_images().await;
+        }
+        assert!(night.source.finished());
+        assert_eq!(
+            night.chat.count("Image quality"),
+            1,
+            "expected one quality alert in {:#?}",
+            night.chat.titles()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::timestamps::parse_nina_timestamp;
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,7 +49,7 @@ pub struct ImageHistoryResponse {
     pub response_type: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ImageMetadata {
     #[serde(default = "default_true")]
@@ -82,6 +84,8 @@ pub mod image_types {
     pub const DARK: &str = "DARK";
     pub const FLAT: &str = "FLAT";
     pub const BIAS: &str = "BIAS";
+    /// An ad-hoc exposure taken outside the sequence.
+    pub const SNAPSHOT: &str = "SNAPSHOT";
 }
 
 impl ImageHistoryResponse {
@@ -136,6 +140,23 @@ impl ImageHistoryResponse {
         counts
     }
 
+    /// The first snapshot at or past `from` taken no earlier than `since`,
+    /// with its index. Sequence frames saved alongside it are passed over.
+    pub fn find_snapshot_since(
+        &self,
+        from: usize,
+        since: DateTime<FixedOffset>,
+    ) -> Option<(usize, &ImageMetadata)> {
+        self.response
+            .iter()
+            .enumerate()
+            .skip(from)
+            .find(|(_, image)| {
+                image.is_snapshot()
+                    && parse_nina_timestamp(&image.date).is_some_and(|date| date >= since)
+            })
+    }
+
     /// Get basic statistics about the image session
     pub fn get_session_stats(&self) -> SessionStats {
         let total_images = self.response.len();
@@ -199,6 +220,11 @@ impl ImageMetadata {
             || self.image_type == image_types::FLAT
             || self.image_type == image_types::BIAS
     }
+
+    /// Check if this is an ad-hoc snapshot rather than a sequence frame
+    pub fn is_snapshot(&self) -> bool {
+        self.image_type == image_types::SNAPSHOT
+    }
 }
 
 #[cfg(test)]
@@ -372,6 +398,57 @@ mod tests {
         assert_eq!(filter_counts.get("HA"), Some(&2));
     }
 
+    #[test]
+    fn finds_the_new_snapshot_among_sequence_frames() {
+        let frame = |image_type: &str, date: &str| ImageMetadata {
+            chat_enabled: true,
+            exposure_time: 30.0,
+            image_type: image_type.to_string(),
+            filter: "L".to_string(),
+            rms_text: String::new(),
+            temperature: -10.0,
+            camera_name: "Test Camera".to_string(),
+            gain: 100,
+            offset: 10,
+            date: date.to_string(),
+            telescope_name: "Test Telescope".to_string(),
+            focal_length: 500,
+            st_dev: 100.0,
+            mean: 1000.0,
+            median: 950.0,
+            stars: 500,
+            hfr: 2.0,
+            is_bayered: false,
+        };
+        let snapshot = |date: &str| frame(image_types::SNAPSHOT, date);
+        let light = |date: &str| frame(image_types::LIGHT, date);
+        let images = ImageHistoryResponse {
+            response: vec![
+                snapshot("2025-08-07T22:00:00-07:00"),
+                light("2025-08-07T22:05:00-07:00"),
+                light("2025-08-07T22:10:00-07:00"),
+                snapshot("2025-08-07T22:10:30-07:00"),
+            ],
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        };
+        let sent = DateTime::parse_from_rfc3339("2025-08-07T22:09:00-07:00").unwrap();
+        let (index, found) = images.find_snapshot_since(2, sent).unwrap();
+        assert_eq!(index, 3);
+        assert_eq!(found.date, "2025-08-07T22:10:30-07:00");
+        // An older snapshot is not the one asked for, even from index 0.
+        assert_eq!(images.find_snapshot_since(0, sent).unwrap().0, 3);
+
+        // Only the sequence frame has landed so far.
+        let waiting = ImageHistoryResponse {
+            response: images.response[..3].to_vec(),
+            ..images
+        };
+        assert!(waiting.find_snapshot_since(2, sent).is_none());
+    }
+
     #[test]
     fn test_load_image_history_from_file() {
         // Test loading the example image history file if it exists
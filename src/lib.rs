@@ -1,5 +1,6 @@
 #![recursion_limit = "256"]
 
+pub mod alerts;
 pub mod api_types;
 pub mod artifact_contract;
 pub mod autofocus;
@@ -7,22 +8,42 @@ pub mod camera;
 pub mod charts;
 pub mod chat;
 pub mod chat_updater;
+pub mod checkpoint;
+pub mod coalescing;
 pub mod config;
 pub mod direct;
 pub mod discord;
+pub mod dome;
+pub mod ephemeris;
 pub mod error;
 pub mod events;
 pub mod filterwheel;
+pub mod flat_device;
 pub mod focuser;
+pub mod guide_analysis;
 pub mod guider;
+pub mod horizon;
 #[cfg(feature = "hub")]
 pub mod hub;
+pub mod image_quality;
 pub mod images;
 pub mod mount;
+pub mod notification_rules;
 pub mod plugin_runtime;
+pub mod progress;
+pub mod recording;
+pub mod relay;
 pub mod rotator;
+pub mod safety;
 pub mod sequence;
 pub mod serde_helpers;
 pub mod service_wrapper;
+pub mod session_report;
+pub mod simulator;
 pub mod source;
+pub mod switch;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod timestamps;
 pub mod version;
+pub mod weather;
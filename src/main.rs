@@ -23,8 +23,38 @@ enum Commands {
         #[arg(long)]
         init: bool,
     },
-    /// Run a plugin-owned local Direct process configured over a secure pipe.
-    #[cfg(windows)]
+    /// Forward local N.I.N.A. Direct clients to a Hub from this gateway.
+    #[cfg(any(windows, unix))]
+    Relay {
+        #[arg(long = "relay-config", default_value = "relay.json")]
+        relay_config: String,
+        /// Write a default relay configuration and exit.
+        #[arg(long)]
+        init: bool,
+    },
+    /// Run a simulated N.I.N.A. rig through the configured chat services.
+    Simulate {
+        /// Chatstronomy configuration; without one the night is only logged.
+        #[arg(long)]
+        config: Option<String>,
+        /// Night plan JSON (target, filters, scripted failures).
+        #[arg(long)]
+        plan: Option<String>,
+        /// Simulated seconds per real second.
+        #[arg(long, default_value_t = 60.0)]
+        time_scale: f64,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Poll interval in seconds.
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+        /// Push updates over the event stream instead of being polled.
+        #[arg(long)]
+        push: bool,
+    },
+    /// Run a plugin-owned local Direct process configured over a secure pipe
+    /// (a current-user Unix domain socket outside Windows).
+    #[cfg(any(windows, unix))]
     PluginRuntime {
         #[arg(long)]
         bootstrap_pipe: String,
@@ -32,7 +62,7 @@ enum Commands {
         log_file: String,
     },
     /// Internal Direct transport and chart-rendering diagnostic.
-    #[cfg(windows)]
+    #[cfg(any(windows, unix))]
     #[command(name = "direct-render-probe", hide = true)]
     DirectRenderProbe {
         #[arg(long)]
@@ -61,12 +91,37 @@ async fn main() {
             .map_err(|error| error.into()),
         #[cfg(feature = "hub")]
         Commands::Hub { hub_config, init } => cmd_hub(&hub_config, init).await,
+        #[cfg(any(windows, unix))]
+        Commands::Relay { relay_config, init } => cmd_relay(&relay_config, init).await,
+        Commands::Simulate {
+            config,
+            plan,
+            time_scale,
+            seed,
+            interval,
+            push,
+        } => {
+            cmd_simulate(
+                config.as_deref(),
+                plan.as_deref(),
+                time_scale,
+                seed,
+                interval,
+                push,
+            )
+            .await
+        }
         #[cfg(windows)]
         Commands::PluginRuntime {
             bootstrap_pipe,
             log_file,
         } => chatstronomy::plugin_runtime::run_from_named_pipe(&bootstrap_pipe, &log_file).await,
-        #[cfg(windows)]
+        #[cfg(unix)]
+        Commands::PluginRuntime {
+            bootstrap_pipe,
+            log_file,
+        } => chatstronomy::plugin_runtime::run_from_unix_socket(&bootstrap_pipe, &log_file).await,
+        #[cfg(any(windows, unix))]
         Commands::DirectRenderProbe {
             pipe_name,
             guider_output,
@@ -106,16 +161,90 @@ async fn cmd_hub(config_path: &str, init: bool) -> Result<(), Box<dyn std::error
     Ok(())
 }
 
-#[cfg(windows)]
+#[cfg(any(windows, unix))]
+async fn cmd_relay(config_path: &str, init: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use chatstronomy::relay::{Relay, RelayConfig};
+
+    if init {
+        if std::path::Path::new(config_path).exists() {
+            return Err(format!("Refusing to overwrite existing file {config_path}").into());
+        }
+        RelayConfig::default().save_to_file(config_path)?;
+        println!("Wrote default relay configuration to {config_path}");
+        return Ok(());
+    }
+
+    let config = RelayConfig::load_from_file(config_path).map_err(|error| {
+        format!("{error}. Run `chatstronomy relay --init` to create a configuration.")
+    })?;
+    std::sync::Arc::new(Relay::new(&config)?).run().await
+}
+
+async fn cmd_simulate(
+    config_path: Option<&str>,
+    plan_path: Option<&str>,
+    time_scale: f64,
+    seed: u64,
+    interval: u64,
+    push: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use chatstronomy::config::{Config, load_json_file};
+    use chatstronomy::service_wrapper::ServiceWrapper;
+    use chatstronomy::simulator::{NightPlan, SimulatedRigSource};
+    use chatstronomy::source::SharedRigSource;
+    use std::sync::Arc;
+
+    if !time_scale.is_finite() || time_scale <= 0.0 {
+        return Err("--time-scale must be a positive number".into());
+    }
+    let config: Config = match config_path {
+        Some(path) => load_json_file(path)?,
+        None => Config::default(),
+    };
+    config.validate()?;
+    let plan = match plan_path {
+        Some(path) => NightPlan::load(path)?,
+        None => NightPlan::default(),
+    };
+    plan.validate()?;
+
+    let mut sources = std::collections::HashMap::new();
+    for (offset, telescope) in config.telescopes.iter().enumerate() {
+        let source = Arc::new(
+            SimulatedRigSource::new(plan.clone())
+                .with_seed(seed.wrapping_add(offset as u64))
+                .with_time_scale(time_scale)
+                .with_event_stream(push),
+        );
+        if push {
+            tokio::spawn(
+                source
+                    .clone()
+                    .run_clock(std::time::Duration::from_millis(500)),
+            );
+        }
+        println!(
+            "[{}] Simulating '{}' at {time_scale}x",
+            telescope.name, plan.target_name
+        );
+        sources.insert(telescope.name.clone(), source as SharedRigSource);
+    }
+    ServiceWrapper::new(config)?
+        .run_cli_with_sources(interval, sources)
+        .await?;
+    Ok(())
+}
+
+#[cfg(any(windows, unix))]
 async fn cmd_direct_render_probe(
     pipe_name: &str,
     guider_output: &str,
     autofocus_output: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use chatstronomy::direct::pipe_source::DirectPipeRigSource;
+    use chatstronomy::direct::LocalDirectRigSource;
     use chatstronomy::source::{RigCapabilities, RigSource};
 
-    let source = DirectPipeRigSource::connect(pipe_name, RigCapabilities::all()).await?;
+    let source = LocalDirectRigSource::connect(pipe_name, RigCapabilities::all()).await?;
     let graph = source.get_guider_graph().await?;
     let png = chatstronomy::charts::render_guider_graph_png(&graph.response)?;
     std::fs::write(guider_output, png)?;
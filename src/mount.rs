@@ -1,3 +1,4 @@
+use crate::source::RigCommand;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -159,11 +160,126 @@ impl MountInfoResponse {
             self.response.site_elevation,
         )
     }
+
+    /// Check a mount command against what this mount reports it can do, so an
+    /// impossible request is refused before it reaches N.I.N.A.
+    pub fn check_command(&self, command: &RigCommand) -> Result<(), String> {
+        let mount = &self.response;
+        match command {
+            RigCommand::SlewToCoordinates {
+                ra_hours,
+                dec_degrees,
+                ..
+            } => {
+                if !(0.0..24.0).contains(ra_hours) {
+                    return Err(format!("RA {ra_hours} is outside 0–24 h"));
+                }
+                if !(-90.0..=90.0).contains(dec_degrees) {
+                    return Err(format!("Dec {dec_degrees} is outside ±90°"));
+                }
+                if !self.is_connected() {
+                    return Err("the mount is not connected".to_string());
+                }
+                if !mount.can_slew {
+                    return Err(format!("{} cannot slew", mount.name));
+                }
+                if mount.at_park {
+                    return Err("the mount is parked; unpark it first".to_string());
+                }
+                Ok(())
+            }
+            RigCommand::SetTracking { enabled, rate } => {
+                if !self.is_connected() {
+                    return Err("the mount is not connected".to_string());
+                }
+                if !mount.can_set_tracking_enabled {
+                    return Err(format!("{} cannot switch tracking", mount.name));
+                }
+                if *enabled && mount.at_park {
+                    return Err("the mount is parked; unpark it first".to_string());
+                }
+                if let Some(rate) = rate
+                    && !mount
+                        .tracking_modes
+                        .iter()
+                        .any(|mode| mode.eq_ignore_ascii_case(rate.nina_name()))
+                {
+                    return Err(format!(
+                        "{} does not support {} tracking",
+                        mount.name,
+                        rate.nina_name()
+                    ));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parse right ascension given as decimal hours (`5.588`) or sexagesimal
+/// hours (`05:35:17`, `5h35m17s`, `5 35 17.3`).
+pub fn parse_ra_hours(text: &str) -> Result<f64, String> {
+    let hours = parse_sexagesimal(text).ok_or_else(|| format!("cannot read RA '{text}'"))?;
+    if !(0.0..24.0).contains(&hours) {
+        return Err(format!("RA '{text}' is outside 0–24 h"));
+    }
+    Ok(hours)
+}
+
+/// Parse declination given as decimal degrees (`-5.391`) or sexagesimal
+/// degrees (`-05:23:28`, `-5°23'28"`, `+41 16 09`).
+pub fn parse_dec_degrees(text: &str) -> Result<f64, String> {
+    let degrees = parse_sexagesimal(text).ok_or_else(|| format!("cannot read Dec '{text}'"))?;
+    if !(-90.0..=90.0).contains(&degrees) {
+        return Err(format!("Dec '{text}' is outside ±90°"));
+    }
+    Ok(degrees)
+}
+
+fn parse_sexagesimal(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let cleaned: String = unsigned
+        .chars()
+        .map(|c| match c {
+            'h' | 'H' | 'm' | 'M' | 's' | 'S' | 'd' | 'D' | '°' | '\'' | '"' | ':' | '′' | '″' => {
+                ' '
+            }
+            c => c,
+        })
+        .collect();
+    let parts: Vec<f64> = cleaned
+        .split_whitespace()
+        .map(|part| part.parse::<f64>().ok().filter(|value| value.is_finite()))
+        .collect::<Option<_>>()?;
+    if parts.is_empty() || parts.len() > 3 || parts.iter().any(|part| *part < 0.0) {
+        return None;
+    }
+    // Minutes and seconds must be whole sub-units of the leading field, and
+    // only the last field may carry a fraction: "5.5 30" is ambiguous.
+    if parts.iter().skip(1).any(|part| *part >= 60.0)
+        || parts[..parts.len() - 1]
+            .iter()
+            .any(|part| part.fract() != 0.0)
+    {
+        return None;
+    }
+    let value = parts
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(part, scale)| part / scale)
+        .sum::<f64>();
+    Some(if negative { -value } else { value })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::source::TrackingMode;
 
     #[test]
     fn test_mount_info_deserialization() {
@@ -307,4 +423,170 @@ mod tests {
         assert!((lon - (-121.166)).abs() < 0.001);
         assert_eq!(elev, 100);
     }
+
+    /// A connected, unparked mount able to slew and track, for the command
+    /// checks to override field by field.
+    const SAMPLE_MOUNT_JSON: &str = r#"{
+        "Response": {
+            "SiderealTime": 20.46,
+            "RightAscension": 20.045,
+            "Declination": 35.541,
+            "SiteLatitude": 38.661,
+            "SiteLongitude": -121.166,
+            "SiteElevation": 100,
+            "RightAscensionString": "20:02:45",
+            "DeclinationString": "35° 32' 29\"",
+            "Coordinates": {
+                "RA": 20.045,
+                "RAString": "20:02:45",
+                "RADegrees": 300.689,
+                "Dec": 35.541,
+                "DecString": "35° 32' 29\"",
+                "Epoch": "JNOW",
+                "DateTime": {
+                    "Now": "2025-08-09T00:20:10.1340528-07:00",
+                    "UtcNow": "2025-08-09T07:20:10.1340586Z"
+                }
+            },
+            "TimeToMeridianFlip": 11.618,
+            "SideOfPier": "pierEast",
+            "Altitude": 84.14,
+            "AltitudeString": "84° 08' 25\"",
+            "Azimuth": 239.755,
+            "AzimuthString": "239° 45' 19\"",
+            "SiderealTimeString": "20:27:39",
+            "HoursToMeridianString": "11:35:07",
+            "AtPark": false,
+            "TrackingRate": {},
+            "TrackingEnabled": true,
+            "TrackingModes": ["Sidereal", "King"],
+            "AtHome": false,
+            "CanFindHome": true,
+            "CanPark": true,
+            "CanSetPark": true,
+            "CanSetTrackingEnabled": true,
+            "CanSetDeclinationRate": true,
+            "CanSetRightAscensionRate": true,
+            "EquatorialSystem": "JNOW",
+            "HasUnknownEpoch": false,
+            "TimeToMeridianFlipString": "11:37:07",
+            "Slewing": false,
+            "GuideRateRightAscensionArcsecPerSec": 7.52,
+            "GuideRateDeclinationArcsecPerSec": 7.52,
+            "CanMovePrimaryAxis": true,
+            "CanMoveSecondaryAxis": true,
+            "PrimaryAxisRates": [{}],
+            "SecondaryAxisRates": [{}],
+            "SupportedActions": ["Telescope:SetParkPosition"],
+            "AlignmentMode": "GermanPolar",
+            "CanPulseGuide": true,
+            "IsPulseGuiding": false,
+            "CanSetPierSide": true,
+            "CanSlew": true,
+            "UTCDate": "2025-08-09T07:20:08.459",
+            "Connected": true,
+            "Name": "ASCOM GS Sky Telescope",
+            "DisplayName": "ASCOM GS Sky Telescope",
+            "DeviceId": "ASCOM.GS.Sky.Telescope"
+        },
+        "Error": "",
+        "StatusCode": 200,
+        "Success": true,
+        "Type": "API"
+    }"#;
+
+    fn sample_mount(overrides: serde_json::Value) -> MountInfoResponse {
+        let mut value: serde_json::Value = serde_json::from_str(SAMPLE_MOUNT_JSON).unwrap();
+        for (key, field) in overrides.as_object().unwrap() {
+            value["Response"][key] = field.clone();
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn coordinates_parse_from_decimal_and_sexagesimal_text() {
+        assert_eq!(parse_ra_hours("5.5").unwrap(), 5.5);
+        assert!((parse_ra_hours("05:35:17.3").unwrap() - 5.588139).abs() < 1e-6);
+        assert!((parse_ra_hours("5h35m17s").unwrap() - 5.588056).abs() < 1e-6);
+        assert!((parse_dec_degrees("-05:23:28").unwrap() + 5.391111).abs() < 1e-6);
+        assert!((parse_dec_degrees("+41° 16' 09\"").unwrap() - 41.269167).abs() < 1e-6);
+        assert!((parse_dec_degrees("-0 30").unwrap() + 0.5).abs() < 1e-9);
+
+        assert!(parse_ra_hours("24:00:00").is_err());
+        assert!(parse_ra_hours("5:75:00").is_err());
+        assert!(parse_ra_hours("M42").is_err());
+        assert!(parse_dec_degrees("91").is_err());
+        assert!(parse_dec_degrees("").is_err());
+    }
+
+    #[test]
+    fn only_the_last_sexagesimal_field_takes_a_fraction() {
+        assert!(parse_ra_hours("5.5 30").is_err());
+        assert!(parse_ra_hours("5:30.5:10").is_err());
+        assert!(parse_dec_degrees("-41.2° 16'").is_err());
+        assert!((parse_ra_hours("5 30.5").unwrap() - 5.508333).abs() < 1e-6);
+        assert_eq!(parse_dec_degrees("41.25").unwrap(), 41.25);
+    }
+
+    #[test]
+    fn mount_commands_are_checked_against_reported_abilities() {
+        let slew = RigCommand::SlewToCoordinates {
+            ra_hours: 5.5,
+            dec_degrees: -5.0,
+            center: false,
+        };
+        let sidereal = RigCommand::SetTracking {
+            enabled: true,
+            rate: Some(TrackingMode::Sidereal),
+        };
+        let mount = sample_mount(serde_json::json!({}));
+        assert_eq!(mount.check_command(&slew), Ok(()));
+        assert_eq!(mount.check_command(&sidereal), Ok(()));
+        assert!(
+            mount
+                .check_command(&RigCommand::SetTracking {
+                    enabled: true,
+                    rate: Some(TrackingMode::Lunar),
+                })
+                .unwrap_err()
+                .contains("Lunar")
+        );
+        assert!(
+            mount
+                .check_command(&RigCommand::SlewToCoordinates {
+                    ra_hours: 25.0,
+                    dec_degrees: 0.0,
+                    center: false,
+                })
+                .is_err()
+        );
+
+        let fixed =
+            sample_mount(serde_json::json!({"CanSlew": false, "CanSetTrackingEnabled": false}));
+        assert!(
+            fixed
+                .check_command(&slew)
+                .unwrap_err()
+                .contains("cannot slew")
+        );
+        assert!(fixed.check_command(&sidereal).is_err());
+
+        let parked = sample_mount(serde_json::json!({"AtPark": true}));
+        assert!(parked.check_command(&slew).unwrap_err().contains("parked"));
+        assert!(parked.check_command(&sidereal).is_err());
+        let stop_tracking = RigCommand::SetTracking {
+            enabled: false,
+            rate: None,
+        };
+        assert_eq!(parked.check_command(&stop_tracking), Ok(()));
+        assert_eq!(parked.check_command(&RigCommand::StopSlew), Ok(()));
+
+        let offline = sample_mount(serde_json::json!({"Connected": false}));
+        assert!(
+            offline
+                .check_command(&slew)
+                .unwrap_err()
+                .contains("not connected")
+        );
+    }
 }
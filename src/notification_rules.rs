@@ -0,0 +1,415 @@
+//! Declarative notification rules.
+//!
+//! A telescope (and, on the hub, each server it is attached to) can list
+//! rules that decide what happens to an event or image notification before
+//! it reaches chat. Rules are checked in order and the first one that
+//! matches decides; a notification no rule matches is delivered, so an empty
+//! list behaves exactly as before.
+//!
+//! ```json
+//! [
+//!   {"match": {"events": ["NINA-LOG"], "levels": ["info", "debug"]}, "action": "drop"},
+//!   {"match": {"levels": ["error"]}, "action": "mention", "mention": "<@&123456>"},
+//!   {"match": {"events": ["GUIDER-*"], "between": {"start": "23:00", "end": "06:00"}},
+//!    "action": "batch", "minutes": 30}
+//! ]
+//! ```
+
+use crate::chat::ChatTarget;
+use crate::events::{Event, EventDetails, event_types};
+use crate::images::ImageMetadata;
+use chrono::NaiveTime;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The ordered rules of one destination.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct NotificationRules(pub Vec<NotificationRule>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Every criterion given must hold; an empty match matches everything.
+    #[serde(default, rename = "match")]
+    pub matches: RuleMatch,
+    #[serde(flatten)]
+    pub action: RuleAction,
+}
+
+/// What a matching rule does with the notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RuleAction {
+    Drop,
+    Deliver,
+    /// Deliver with `mention` (a Discord `<@user>`/`<@&role>`, or a Matrix
+    /// user ID or `@room`) outside the embed, so it notifies.
+    Mention {
+        mention: String,
+    },
+    /// Hold the notification for the route's digest, posted as one summary
+    /// once `minutes` have passed since the first notification held.
+    Batch {
+        #[serde(default = "default_batch_minutes")]
+        minutes: u64,
+    },
+}
+
+fn default_batch_minutes() -> u64 {
+    15
+}
+
+/// Delivering is the outcome when no rule matches.
+static DELIVER: RuleAction = RuleAction::Deliver;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleMatch {
+    /// Event types; a trailing `*` matches by prefix (`GUIDER-*`). Image
+    /// notifications have the type `IMAGE-SAVE`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+    /// Levels of N.I.N.A. notifications and log entries, case-insensitive.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub levels: Vec<String>,
+    /// Regex on a N.I.N.A. notification's header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<Pattern>,
+    /// Regex on a N.I.N.A. notification's or log entry's message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Pattern>,
+    /// Image types (`LIGHT`, `FLAT`, ...), case-insensitive.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image_types: Vec<String>,
+    /// Filter names, case-insensitive.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<String>,
+    /// Local time of day the rule applies in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub between: Option<TimeWindow>,
+}
+
+/// A regex kept with its source, so rules serialize back as written.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Regex::new(&source)
+            .map(Self)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A daily `HH:MM` window. A window whose end is before its start spans
+/// midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    #[serde(with = "hh_mm")]
+    pub start: NaiveTime,
+    #[serde(with = "hh_mm")]
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+mod hh_mm {
+    use chrono::NaiveTime;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&time.format("%H:%M"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        NaiveTime::parse_from_str(&text, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(&text, "%H:%M:%S"))
+            .map_err(|_| serde::de::Error::custom(format!("expected HH:MM, got '{text}'")))
+    }
+}
+
+/// The facts about one notification that rules match on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Notice {
+    pub event: String,
+    pub level: Option<String>,
+    pub header: Option<String>,
+    pub message: Option<String>,
+    pub image_type: Option<String>,
+    pub filter: Option<String>,
+}
+
+impl Notice {
+    pub fn from_event(event: &Event) -> Self {
+        let mut notice = Self {
+            event: event.event.clone(),
+            ..Self::default()
+        };
+        match &event.details {
+            Some(EventDetails::NinaNotification {
+                level,
+                header,
+                message,
+            }) => {
+                notice.level = Some(level.clone());
+                notice.header = Some(header.clone());
+                notice.message = Some(message.clone());
+            }
+            Some(EventDetails::NinaLog { level, message, .. }) => {
+                notice.level = Some(level.clone());
+                notice.message = Some(message.clone());
+            }
+            Some(EventDetails::FilterWheelChange { new, .. }) => {
+                notice.filter = Some(new.name.clone());
+            }
+            _ => {}
+        }
+        notice
+    }
+
+    pub fn from_image(image: &ImageMetadata) -> Self {
+        Self {
+            event: event_types::IMAGE_SAVE.to_string(),
+            image_type: Some(image.image_type.clone()),
+            filter: Some(image.filter.clone()),
+            ..Self::default()
+        }
+    }
+}
+
+impl RuleMatch {
+    /// Whether every given criterion holds for `notice` at local `time`.
+    /// A criterion on a fact the notice does not carry never holds.
+    pub fn matches(&self, notice: &Notice, time: NaiveTime) -> bool {
+        let listed = |list: &[String], value: &Option<String>| {
+            list.is_empty()
+                || value
+                    .as_deref()
+                    .is_some_and(|value| list.iter().any(|item| item.eq_ignore_ascii_case(value)))
+        };
+        let found = |pattern: &Option<Pattern>, value: &Option<String>| match pattern {
+            None => true,
+            Some(Pattern(regex)) => value.as_deref().is_some_and(|value| regex.is_match(value)),
+        };
+        (self.events.is_empty()
+            || self
+                .events
+                .iter()
+                .any(|pattern| event_type_matches(pattern, &notice.event)))
+            && listed(&self.levels, &notice.level)
+            && found(&self.header, &notice.header)
+            && found(&self.message, &notice.message)
+            && listed(&self.image_types, &notice.image_type)
+            && listed(&self.filters, &notice.filter)
+            && self.between.is_none_or(|window| window.contains(time))
+    }
+}
+
+fn event_type_matches(pattern: &str, event: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => event
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix)),
+        None => pattern.eq_ignore_ascii_case(event),
+    }
+}
+
+impl NotificationRules {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The action of the first rule matching `notice` at local `time`.
+    pub fn action(&self, notice: &Notice, time: NaiveTime) -> &RuleAction {
+        self.0
+            .iter()
+            .find(|rule| rule.matches.matches(notice, time))
+            .map_or(&DELIVER, |rule| &rule.action)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, rule) in self.0.iter().enumerate() {
+            let position = index + 1;
+            match &rule.action {
+                RuleAction::Mention { mention } if mention.trim().is_empty() => {
+                    return Err(format!("notification rule {position}: mention is empty"));
+                }
+                RuleAction::Batch { minutes: 0 } => {
+                    return Err(format!(
+                        "notification rule {position}: batch minutes must be positive"
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One set of destinations and the rules notifications to it go through.
+/// Self-hosted telescopes have a single route; on the hub every attached
+/// server is a route with that server's rules.
+#[derive(Debug, Clone)]
+pub struct NotificationRoute {
+    pub target: ChatTarget,
+    pub rules: NotificationRules,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{SimulatedNight, short_plan};
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn log(level: &str, message: &str) -> Notice {
+        Notice {
+            event: event_types::NINA_LOG.to_string(),
+            level: Some(level.to_string()),
+            message: Some(message.to_string()),
+            ..Notice::default()
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_decides() {
+        let rules: NotificationRules = serde_json::from_str(
+            r#"[
+                {"match": {"events": ["NINA-LOG"], "message": "(?i)timeout"}, "action": "mention", "mention": "<@&42>"},
+                {"match": {"events": ["nina-*"], "levels": ["INFO", "debug"]}, "action": "drop"},
+                {"match": {"image_types": ["flat"]}, "action": "batch"}
+            ]"#,
+        )
+        .unwrap();
+        assert!(rules.validate().is_ok());
+
+        assert_eq!(
+            rules.action(&log("info", "Camera download TIMEOUT"), at(1, 0)),
+            &RuleAction::Mention {
+                mention: "<@&42>".to_string()
+            }
+        );
+        assert_eq!(
+            rules.action(&log("Info", "Saved"), at(1, 0)),
+            &RuleAction::Drop
+        );
+        assert_eq!(
+            rules.action(&log("warning", "Saved"), at(1, 0)),
+            &RuleAction::Deliver
+        );
+
+        let flat = Notice {
+            event: event_types::IMAGE_SAVE.to_string(),
+            image_type: Some("FLAT".to_string()),
+            filter: Some("Ha".to_string()),
+            ..Notice::default()
+        };
+        assert_eq!(
+            rules.action(&flat, at(1, 0)),
+            &RuleAction::Batch { minutes: 15 }
+        );
+        // Level criteria never match notices without a level.
+        assert_eq!(
+            NotificationRules::default().action(&flat, at(1, 0)),
+            &RuleAction::Deliver
+        );
+    }
+
+    #[test]
+    fn time_windows_span_midnight() {
+        let rules: NotificationRules = serde_json::from_str(
+            r#"[{"match": {"between": {"start": "23:00", "end": "06:30"}}, "action": "drop"}]"#,
+        )
+        .unwrap();
+        let notice = Notice {
+            event: "GUIDER-DITHER".to_string(),
+            ..Notice::default()
+        };
+
+        assert_eq!(rules.action(&notice, at(23, 30)), &RuleAction::Drop);
+        assert_eq!(rules.action(&notice, at(6, 0)), &RuleAction::Drop);
+        assert_eq!(rules.action(&notice, at(6, 30)), &RuleAction::Deliver);
+        assert_eq!(
+            serde_json::to_value(&rules).unwrap()[0]["match"]["between"]["end"],
+            "06:30"
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(
+            serde_json::from_str::<NotificationRules>(
+                r#"[{"match": {"header": "("}, "action": "drop"}]"#
+            )
+            .is_err()
+        );
+        assert!(
+            serde_json::from_str::<NotificationRules>(
+                r#"[{"match": {"level": ["info"]}, "action": "drop"}]"#
+            )
+            .is_err()
+        );
+        let zero: NotificationRules =
+            serde_json::from_str(r#"[{"action": "batch", "minutes": 0}]"#).unwrap();
+        assert!(zero.validate().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rules_drop_and_batch_a_simulated_nights_posts() {
+        let night = SimulatedNight::new(short_plan());
+        let rules = serde_json::from_str(
+            r#"[
+                {"match": {"events": ["IMAGE-SAVE"]}, "action": "drop"},
+                {"match": {"events": ["GUIDER-*", "MOUNT-*"]}, "action": "batch", "minutes": 1}
+            ]"#,
+        )
+        .unwrap();
+        let mut updater = night
+            .updater()
+            .with_image_cooldown(0)
+            .with_notification_rules(rules);
+        updater.initialize_baseline().await.unwrap();
+
+        for _ in 0..150 {
+            tokio::time::advance(std::time::Duration::from_secs(1)).await;
+            updater.poll_events().await;
+            updater.poll_sequence().await;
+            updater.poll_images().await;
+            updater.flush_batches().await;
+        }
+        assert!(night.source.finished());
+
+        let titles = night.chat.titles();
+        assert!(
+            !night.chat.posted("Frame Captured"),
+            "images posted: {titles:#?}"
+        );
+        assert!(
+            !night.chat.posted("Mount Parked"),
+            "mount event posted: {titles:#?}"
+        );
+        assert!(night.chat.posted("📰 Digest"), "no batch in {titles:#?}");
+        assert!(
+            night.chat.posted("Sequence Finished"),
+            "unmatched event held: {titles:#?}"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Secure bootstrap contract used by the N.I.N.A. plugin-owned runtime.
 //!
-//! Delivery credentials arrive over a current-user-only Windows named pipe.
+//! Delivery credentials arrive over a current-user-only Windows named pipe, or
+//! a current-user Unix domain socket on Linux and macOS hosts.
 //! They are converted directly into the existing in-memory [`Config`] and are
 //! never accepted through command-line flags or written to a JSON file.
 
@@ -26,6 +27,14 @@ pub struct PluginRuntimeBootstrap {
     #[serde(default)]
     pub matrix: Option<PluginRuntimeMatrix>,
     pub data_directory: String,
+    /// Record this profile's rig queries to a JSONL session file in the data
+    /// directory, for replay when reproducing a night.
+    #[serde(default)]
+    pub record_session: bool,
+    /// The profile's custom horizon file, so the sky chart draws the same
+    /// horizon the sequence checks against.
+    #[serde(default)]
+    pub horizon_file: Option<String>,
     #[serde(default = "default_exit_on_control_disconnect")]
     pub exit_on_control_disconnect: bool,
 }
@@ -199,11 +208,31 @@ impl PluginRuntimeBootstrap {
             });
         }
 
+        let record_session = self.record_session.then(|| {
+            PathBuf::from(&self.data_directory)
+                .join(format!(
+                    "chatstronomy-session-{}-{}.jsonl",
+                    self.profile.profile_id.simple(),
+                    chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+                ))
+                .to_string_lossy()
+                .into_owned()
+        });
+        let checkpoint_file = PathBuf::from(&self.data_directory)
+            .join(format!(
+                "chatstronomy-updater-{}.json",
+                self.profile.profile_id.simple()
+            ))
+            .to_string_lossy()
+            .into_owned();
         let config = Config {
             chat,
             telescopes: vec![TelescopeConfig {
                 name: self.profile.profile_name,
                 chat: telescope_chat,
+                record_session,
+                checkpoint_file: Some(checkpoint_file),
+                horizon_file: self.horizon_file,
                 ..TelescopeConfig::default()
             }],
             ..Config::default()
@@ -232,25 +261,13 @@ pub async fn run_from_named_pipe(
     pipe_name: &str,
     log_file: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use crate::direct::pipe_source::DirectPipeRigSource;
-    use crate::service_wrapper::ServiceWrapper;
-    use crate::source::SharedRigSource;
-    use std::collections::HashMap;
-    use std::fs::{self, OpenOptions};
     use std::os::windows::io::AsRawHandle;
     use std::time::{Duration, Instant};
-    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
     use tokio::net::windows::named_pipe::ClientOptions;
     use winapi::um::processenv::SetStdHandle;
     use winapi::um::winbase::{STD_ERROR_HANDLE, STD_OUTPUT_HANDLE};
 
-    if let Some(parent) = Path::new(log_file).parent() {
-        fs::create_dir_all(parent)?;
-    }
-    let stdout = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(log_file)?;
+    let stdout = open_log_file(log_file)?;
     let stderr = stdout.try_clone()?;
     // SAFETY: both files remain open for the process lifetime (forgotten below),
     // and SetStdHandle only replaces this process's stdout/stderr handles.
@@ -282,8 +299,61 @@ pub async fn run_from_named_pipe(
             Err(error) => return Err(error.into()),
         }
     };
+    run_bootstrap(pipe).await
+}
+
+/// Unix counterpart of [`run_from_named_pipe`]: the bootstrap pipe name maps to
+/// a current-user socket through
+/// [`local_socket_path`](crate::direct::socket_source::local_socket_path).
+#[cfg(unix)]
+pub async fn run_from_unix_socket(
+    pipe_name: &str,
+    log_file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::direct::socket_source::{connect_private_socket, local_socket_path};
+    use std::os::unix::io::AsRawFd;
+
+    let log = open_log_file(log_file)?;
+    // SAFETY: dup2 only replaces this process's stdout/stderr descriptors with
+    // duplicates of an open file; the originals are closed by the kernel.
+    unsafe {
+        if libc::dup2(log.as_raw_fd(), libc::STDOUT_FILENO) < 0
+            || libc::dup2(log.as_raw_fd(), libc::STDERR_FILENO) < 0
+        {
+            return Err(std::io::Error::last_os_error().into());
+        }
+    }
+
+    let socket = connect_private_socket(&local_socket_path(pipe_name)).await?;
+    run_bootstrap(socket).await
+}
+
+#[cfg(any(windows, unix))]
+fn open_log_file(log_file: &str) -> std::io::Result<std::fs::File> {
+    if let Some(parent) = Path::new(log_file).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_file)
+}
+
+/// Read the bootstrap from an opened control stream, connect the Direct data
+/// stream it names, acknowledge with `ready`, and run until the plugin sends
+/// `shutdown` or (when configured) disconnects.
+#[cfg(any(windows, unix))]
+pub async fn run_bootstrap<S>(control: S) -> Result<(), Box<dyn std::error::Error>>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite,
+{
+    use crate::direct::LocalDirectRigSource;
+    use crate::service_wrapper::ServiceWrapper;
+    use crate::source::SharedRigSource;
+    use std::collections::HashMap;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 
-    let (read_half, mut write_half) = tokio::io::split(pipe);
+    let (read_half, mut write_half) = tokio::io::split(control);
     let mut lines = BufReader::new(read_half).lines();
     let bootstrap_json = lines
         .next_line()
@@ -298,10 +368,9 @@ pub async fn run_from_named_pipe(
         PluginRuntimeSource::NinaDirect {
             pipe_name,
             capabilities,
-        } => std::sync::Arc::new(DirectPipeRigSource::connect(pipe_name, *capabilities).await?),
+        } => std::sync::Arc::new(LocalDirectRigSource::connect(pipe_name, *capabilities).await?),
     };
     let config = bootstrap.into_config()?;
-
     write_half
         .write_all(
             format!(
@@ -341,7 +410,7 @@ pub async fn run_from_named_pipe(
     }
 }
 
-#[cfg(windows)]
+#[cfg(any(windows, unix))]
 #[derive(Deserialize)]
 struct RuntimeControlMessage {
     #[serde(rename = "type")]
@@ -394,6 +463,10 @@ mod tests {
         assert_eq!(bootstrap.poll_interval_seconds(), 5);
         let config = bootstrap.into_config().unwrap();
         assert_eq!(config.telescopes[0].name, "North Rig");
+        assert!(config.telescopes[0].record_session.is_none());
+        let checkpoint = config.telescopes[0].checkpoint_file.as_deref().unwrap();
+        assert!(checkpoint.starts_with(&*std::env::temp_dir().to_string_lossy()));
+        assert!(checkpoint.ends_with("chatstronomy-updater-460a8c6228ce478192e5ab2440982175.json"));
         assert_eq!(
             config.chat.discord.unwrap().default_webhook_url.as_deref(),
             Some("https://discord.com/api/v10/webhooks/123/token")
@@ -448,4 +521,45 @@ mod tests {
         assert_eq!(config.telescopes[0].name, "North Rig");
         assert!(!json.contains("http://"));
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_runtime_completes_the_bootstrap_handshake() {
+        use crate::direct::socket_source::{bind_private_socket, local_socket_path, verify_peer};
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let pipe_name = format!("chatstronomy-direct-test-{}", Uuid::new_v4().simple());
+        let data_path = local_socket_path(&pipe_name);
+        let data_listener = bind_private_socket(&data_path).unwrap();
+        let json = sample_json(
+            r#"{"kind":"discord_webhook","webhook_url":"https://discord.com/api/webhooks/123/token"}"#,
+            "null",
+        )
+        .replace("chatstronomy-direct-test", &pipe_name);
+        let bootstrap: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let (runtime_end, plugin_end) = tokio::io::duplex(64 * 1024);
+
+        let plugin = async move {
+            let mut control = BufReader::new(plugin_end);
+            let mut frame = bootstrap.to_string().into_bytes();
+            frame.push(b'\n');
+            control.get_mut().write_all(&frame).await.unwrap();
+            let (data_stream, _) = data_listener.accept().await.unwrap();
+            verify_peer(&data_stream).unwrap();
+            let mut ready = String::new();
+            control.read_line(&mut ready).await.unwrap();
+            let ready: serde_json::Value = serde_json::from_str(&ready).unwrap();
+            assert_eq!(ready["type"], "ready");
+            assert_eq!(ready["protocol_version"], PLUGIN_RUNTIME_PROTOCOL_VERSION);
+            control
+                .get_mut()
+                .write_all(b"{\"type\":\"shutdown\"}\n")
+                .await
+                .unwrap();
+            data_stream
+        };
+        let (result, _data_stream) = tokio::join!(run_bootstrap(runtime_end), plugin);
+        result.unwrap();
+        std::fs::remove_file(&data_path).unwrap();
+    }
 }
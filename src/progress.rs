@@ -0,0 +1,238 @@
+//! Integration progress for one target.
+//!
+//! Light time acquired per filter comes from the image history, with each
+//! frame credited to the target the latest Target Scheduler start names.
+//! Planned totals come from the sequence tree's exposure instructions when
+//! it carries them. `/chatstronomy progress` and the target-change
+//! notification show it as text and as a stacked bar chart.
+
+use crate::events::{Event, EventDetails};
+use crate::images::ImageHistoryResponse;
+use crate::sequence::{SequenceResponse, extract_planned_exposures};
+use crate::session_report::format_hours;
+use crate::timestamps::parse_nina_timestamp;
+use chrono::{DateTime, FixedOffset};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterProgress {
+    pub filter: String,
+    pub frames: usize,
+    /// Light exposure acquired, in seconds.
+    pub seconds: f64,
+    /// What the sequence plans for this filter, when it says.
+    pub planned_frames: Option<u32>,
+    pub planned_seconds: Option<f64>,
+}
+
+impl FilterProgress {
+    /// Acquired over planned, when there is a plan.
+    pub fn fraction(&self) -> Option<f64> {
+        self.planned_seconds
+            .filter(|planned| *planned > 0.0)
+            .map(|planned| self.seconds / planned)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrationProgress {
+    pub target: String,
+    /// In filter name order, planned-only filters included.
+    pub filters: Vec<FilterProgress>,
+}
+
+impl IntegrationProgress {
+    /// Progress on `target`. Light frames before the first target start in
+    /// the events are credited to `target`, as are all of them when the
+    /// events name no targets.
+    pub fn build(
+        target: &str,
+        images: &ImageHistoryResponse,
+        events: &[Event],
+        sequence: Option<&SequenceResponse>,
+    ) -> Self {
+        let mut starts: Vec<(DateTime<FixedOffset>, &str)> = events
+            .iter()
+            .filter_map(|event| match &event.details {
+                Some(EventDetails::TargetStart { target_name, .. }) => {
+                    parse_nina_timestamp(&event.time).map(|time| (time, target_name.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        starts.sort_by_key(|(time, _)| *time);
+        let is_target = |date: &str| {
+            let credited = parse_nina_timestamp(date).and_then(|date| {
+                starts
+                    .iter()
+                    .rev()
+                    .find(|(started, _)| *started <= date)
+                    .map(|(_, name)| *name)
+            });
+            credited.is_none_or(|name| name.trim().eq_ignore_ascii_case(target.trim()))
+        };
+
+        // Filters match ignoring case: sequences often say "HA" where the
+        // wheel reports "Ha". The image history's spelling wins.
+        let mut filters: Vec<FilterProgress> = Vec::new();
+        for image in images
+            .get_light_frames()
+            .into_iter()
+            .filter(|image| is_target(&image.date))
+        {
+            let progress = filter_entry(&mut filters, &image.filter);
+            progress.frames += 1;
+            progress.seconds += image.exposure_time;
+        }
+        for planned in sequence
+            .map(|sequence| extract_planned_exposures(sequence, target))
+            .unwrap_or_default()
+        {
+            let progress = filter_entry(&mut filters, &planned.filter);
+            *progress.planned_frames.get_or_insert(0) += planned.frames;
+            *progress.planned_seconds.get_or_insert(0.0) +=
+                f64::from(planned.frames) * planned.exposure_seconds;
+        }
+        filters.sort_by_key(|f| f.filter.to_ascii_lowercase());
+
+        Self {
+            target: target.to_string(),
+            filters,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Light seconds acquired across filters.
+    pub fn seconds(&self) -> f64 {
+        self.filters.iter().map(|f| f.seconds).sum()
+    }
+
+    /// Planned seconds across the filters that have a plan.
+    pub fn planned_seconds(&self) -> Option<f64> {
+        self.filters
+            .iter()
+            .filter_map(|f| f.planned_seconds)
+            .reduce(|a, b| a + b)
+    }
+
+    /// "Ha 4h 00m/6h 00m · OIII 1h 00m/6h 00m", or "No light frames yet".
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No light frames yet".to_string();
+        }
+        self.filters
+            .iter()
+            .map(|f| match f.planned_seconds {
+                Some(planned) => format!(
+                    "{} {}/{}",
+                    f.filter,
+                    format_hours(f.seconds),
+                    format_hours(planned)
+                ),
+                None => format!("{} {}", f.filter, format_hours(f.seconds)),
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// The row for `name`, matched ignoring case, added if missing.
+fn filter_entry<'a>(filters: &'a mut Vec<FilterProgress>, name: &str) -> &'a mut FilterProgress {
+    let index = match filters
+        .iter()
+        .position(|f| f.filter.eq_ignore_ascii_case(name))
+    {
+        Some(index) => index,
+        None => {
+            filters.push(FilterProgress {
+                filter: name.to_string(),
+                frames: 0,
+                seconds: 0.0,
+                planned_frames: None,
+                planned_seconds: None,
+            });
+            filters.len() - 1
+        }
+    };
+    &mut filters[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::event_types;
+    use crate::test_support::light;
+
+    fn history(lights: Vec<crate::images::ImageMetadata>) -> ImageHistoryResponse {
+        ImageHistoryResponse {
+            response: lights,
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        }
+    }
+
+    fn start(time: &str, name: &str) -> Event {
+        Event {
+            time: time.to_string(),
+            event: event_types::TS_TARGETSTART.to_string(),
+            chat_enabled: true,
+            details: Some(EventDetails::TargetStart {
+                target_name: name.to_string(),
+                project_name: None,
+                rotation: None,
+                target_end_time: None,
+                coordinates: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn credits_frames_to_the_target_and_compares_with_the_plan() {
+        let images = history(vec![
+            light("2025-08-07T22:10:00-07:00", "Ha", 2.0, 900, ""),
+            light("2025-08-07T22:20:00-07:00", "Ha", 2.0, 900, ""),
+            light("2025-08-07T23:10:00-07:00", "OIII", 2.0, 900, ""),
+            light("2025-08-08T01:10:00-07:00", "Ha", 2.0, 900, ""),
+        ]);
+        let events = vec![
+            start("2025-08-07T22:00:00-07:00", "North American"),
+            start("2025-08-08T01:00:00-07:00", "M27"),
+        ];
+        let sequence: SequenceResponse =
+            serde_json::from_str(&std::fs::read_to_string("example_sequence_2.json").unwrap())
+                .unwrap();
+
+        let progress =
+            IntegrationProgress::build("North American", &images, &events, Some(&sequence));
+        let rows: Vec<_> = progress
+            .filters
+            .iter()
+            .map(|f| (f.filter.as_str(), f.frames, f.seconds, f.planned_frames))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Ha", 2, 600.0, Some(12)),
+                ("OIII", 1, 300.0, Some(12)),
+                ("SII", 0, 0.0, Some(12)),
+            ]
+        );
+        assert_eq!(progress.seconds(), 900.0);
+        assert_eq!(progress.planned_seconds(), Some(10_800.0));
+        assert_eq!(progress.filters[0].fraction(), Some(600.0 / 3_600.0));
+        assert_eq!(
+            progress.summary(),
+            "Ha 10m/1h 00m · OIII 5m/1h 00m · SII 0m/1h 00m"
+        );
+
+        // Without a plan or target starts, every light counts.
+        let progress = IntegrationProgress::build("M27", &images, &[], None);
+        assert_eq!(progress.summary(), "Ha 15m · OIII 5m");
+        assert_eq!(progress.planned_seconds(), None);
+        assert!(IntegrationProgress::build("M27", &history(Vec::new()), &[], None).is_empty());
+    }
+}
@@ -0,0 +1,714 @@
+//! Relay mode: one gateway runtime forwards local Direct sessions to a Hub.
+//!
+//! Observatory networks sometimes allow a single machine to reach the
+//! internet. The relay listens on the local Direct transport (the current-user
+//! named pipe on Windows, a Unix domain socket elsewhere) and opens one Hub
+//! `/v1/direct` WebSocket per local client. A client opens with its
+//! `client_hello`; the relay presents the stored rig credential for that
+//! client's [`RigId`] in an `auth` frame, so N.I.N.A. instances never hold a
+//! Hub credential. A `pair` frame is forwarded once and the minted credential
+//! is kept by the relay instead of the client.
+//!
+//! After the handshake every frame is forwarded verbatim in both directions.
+//! Queries keep the Hub's `expires_at` deadline, which the rig enforces, and
+//! heartbeats keep their sequence numbers, so the Hub sees each profile as if
+//! it had connected directly.
+
+use crate::config::{ConfigError, load_json_file};
+use crate::direct::protocol::{
+    AuthRequest, DIRECT_WEBSOCKET_PATH, DirectMessage, PairRequest, RigId,
+};
+use crate::direct::stream_source::MAX_FRAME_BYTES;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+/// Local transport name clients connect to when the configuration does not
+/// choose one. Maps to a named pipe or socket like any Direct pipe name.
+pub const DEFAULT_RELAY_PIPE_NAME: &str = "chatstronomy-relay-v1";
+
+/// How long a local client has to send its first frame, and the Hub to answer
+/// the forwarded authentication.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayConfig {
+    /// Hub origin, e.g. `https://hub.chatstronomy.com`. Plain `http://` is only
+    /// accepted for a loopback Hub.
+    pub hub_url: String,
+    /// Local pipe or socket name the N.I.N.A. instances connect to.
+    #[serde(default = "default_pipe_name")]
+    pub pipe_name: String,
+    /// File holding the rig credentials presented on the clients' behalf.
+    /// Written by the relay when a client pairs through it.
+    #[serde(default = "default_credentials_file")]
+    pub credentials_file: String,
+}
+
+fn default_pipe_name() -> String {
+    DEFAULT_RELAY_PIPE_NAME.to_string()
+}
+
+fn default_credentials_file() -> String {
+    "chatstronomy-relay-credentials.json".to_string()
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            hub_url: "https://hub.chatstronomy.com".to_string(),
+            pipe_name: default_pipe_name(),
+            credentials_file: default_credentials_file(),
+        }
+    }
+}
+
+impl RelayConfig {
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        load_json_file(path)
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.websocket_url()?;
+        if self.pipe_name.is_empty()
+            || self.pipe_name.len() > 200
+            || !self
+                .pipe_name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+        {
+            return Err(
+                "relay pipe name must contain only ASCII letters, digits, '-' or '_'".to_string(),
+            );
+        }
+        if self.credentials_file.trim().is_empty() {
+            return Err("relay credentials file cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// The Hub's Direct WebSocket endpoint derived from `hub_url`.
+    pub fn websocket_url(&self) -> Result<String, String> {
+        let mut url = Url::parse(&self.hub_url)
+            .map_err(|error| format!("relay Hub URL is invalid: {error}"))?;
+        let loopback = match url.host() {
+            Some(url::Host::Domain(host)) => host == "localhost",
+            Some(url::Host::Ipv4(address)) => address.is_loopback(),
+            Some(url::Host::Ipv6(address)) => address.is_loopback(),
+            None => return Err("relay Hub URL has no host".to_string()),
+        };
+        let scheme = match url.scheme() {
+            "https" => "wss",
+            "http" if loopback => "ws",
+            _ => return Err("relay Hub URL must be an https:// origin".to_string()),
+        };
+        url.set_scheme(scheme)
+            .map_err(|_| "relay Hub URL cannot carry a WebSocket scheme".to_string())?;
+        url.set_path(DIRECT_WEBSOCKET_PATH);
+        url.set_query(None);
+        Ok(url.to_string())
+    }
+}
+
+/// Rig credentials the relay holds, keyed by the [`RigId`] they are bound to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelayCredentials {
+    #[serde(default)]
+    pub rigs: HashMap<String, String>,
+}
+
+impl RelayCredentials {
+    /// Load credentials from `path`. A missing file means nothing has paired
+    /// through this relay yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(io::Error::other),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Atomic save through a sibling temp file. On Unix the file is created
+    /// readable by the current user only.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "credentials".to_string());
+        let temp = path.with_file_name(format!(".{file_name}.tmp"));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        io::Write::write_all(&mut options.open(&temp)?, json.as_bytes())?;
+        std::fs::rename(&temp, path)
+    }
+
+    pub fn get(&self, rig_id: &RigId) -> Option<&str> {
+        self.rigs.get(&rig_id.to_string()).map(String::as_str)
+    }
+
+    pub fn set(&mut self, rig_id: RigId, credential: String) {
+        self.rigs.insert(rig_id.to_string(), credential);
+    }
+}
+
+/// Forwards local Direct clients to the configured Hub.
+pub struct Relay {
+    websocket_url: String,
+    pipe_name: String,
+    credentials_path: PathBuf,
+    credentials: Mutex<RelayCredentials>,
+}
+
+type HubSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+impl Relay {
+    pub fn new(config: &RelayConfig) -> Result<Self, String> {
+        config.validate()?;
+        let credentials_path = PathBuf::from(&config.credentials_file);
+        let credentials = RelayCredentials::load(&credentials_path).map_err(|error| {
+            format!(
+                "could not read relay credentials {}: {error}",
+                credentials_path.display()
+            )
+        })?;
+        Ok(Self {
+            websocket_url: config.websocket_url()?,
+            pipe_name: config.pipe_name.clone(),
+            credentials_path,
+            credentials: Mutex::new(credentials),
+        })
+    }
+
+    /// Accept local clients on the Unix socket named by the configuration
+    /// until the listener fails. Each client is served on its own task.
+    #[cfg(unix)]
+    pub async fn run(self: std::sync::Arc<Self>) -> Result<(), Box<dyn std::error::Error>> {
+        use crate::direct::socket_source::{bind_private_socket, local_socket_path, verify_peer};
+
+        let path = local_socket_path(&self.pipe_name);
+        let listener = bind_private_socket(&path)?;
+        println!(
+            "Relaying Direct clients on {} to {}",
+            path.display(),
+            self.websocket_url
+        );
+        loop {
+            let (stream, _) = listener.accept().await?;
+            if let Err(error) = verify_peer(&stream) {
+                eprintln!("Refused relay client: {error}");
+                continue;
+            }
+            let relay = self.clone();
+            tokio::spawn(async move { relay.serve_logged(stream).await });
+        }
+    }
+
+    /// Accept local clients on the named pipe given by the configuration.
+    /// Another pipe instance is created before each connected one is handed
+    /// off, so clients never find the name missing.
+    #[cfg(windows)]
+    pub async fn run(self: std::sync::Arc<Self>) -> Result<(), Box<dyn std::error::Error>> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let full_name = format!(r"\\.\pipe\{}", self.pipe_name);
+        let mut server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .reject_remote_clients(true)
+            .create(&full_name)?;
+        println!(
+            "Relaying Direct clients on {full_name} to {}",
+            self.websocket_url
+        );
+        loop {
+            server.connect().await?;
+            let connected = server;
+            server = ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(&full_name)?;
+            let relay = self.clone();
+            tokio::spawn(async move { relay.serve_logged(connected).await });
+        }
+    }
+
+    #[cfg(any(windows, unix))]
+    async fn serve_logged<S>(&self, local: S)
+    where
+        S: AsyncRead + AsyncWrite + Send,
+    {
+        if let Err(error) = self.serve(local).await {
+            eprintln!("Relay session ended: {error}");
+        }
+    }
+
+    /// Serve one local client: authenticate it to the Hub, then forward frames
+    /// both ways until either side disconnects.
+    pub async fn serve<S>(&self, local: S) -> Result<(), String>
+    where
+        S: AsyncRead + AsyncWrite + Send,
+    {
+        let (read_half, mut write_half) = tokio::io::split(local);
+        let mut reader = FrameReader::new(read_half);
+
+        let first = match tokio::time::timeout(HANDSHAKE_TIMEOUT, reader.next()).await {
+            Ok(Ok(Some(line))) => serde_json::from_str::<DirectMessage>(&line)
+                .map_err(|error| format!("invalid first frame: {error}"))?,
+            Ok(Ok(None)) => return Ok(()),
+            Ok(Err(error)) => return Err(error.to_string()),
+            Err(_) => return Err("client sent no hello".to_string()),
+        };
+        let (upstream_first, pairing_rig) = match self.upstream_handshake(first).await {
+            Ok(handshake) => handshake,
+            Err(refusal) => {
+                write_message(&mut write_half, &refusal).await?;
+                return Ok(());
+            }
+        };
+
+        let mut hub = match self.connect_hub(&upstream_first).await {
+            Ok(hub) => hub,
+            Err(error) => {
+                write_message(
+                    &mut write_half,
+                    &DirectMessage::Error {
+                        message: format!("relay could not reach the Hub: {error}"),
+                        retryable: true,
+                    },
+                )
+                .await?;
+                return Err(error);
+            }
+        };
+        let reply = match tokio::time::timeout(HANDSHAKE_TIMEOUT, next_text(&mut hub)).await {
+            Ok(Some(text)) => serde_json::from_str::<DirectMessage>(&text)
+                .map_err(|error| format!("invalid Hub handshake reply: {error}"))?,
+            _ => {
+                write_message(
+                    &mut write_half,
+                    &DirectMessage::Error {
+                        message: "the Hub did not answer the relayed handshake".to_string(),
+                        retryable: true,
+                    },
+                )
+                .await?;
+                return Err("Hub handshake timed out".to_string());
+            }
+        };
+        let agent_hello = match reply {
+            DirectMessage::AgentHello(hello) => hello,
+            DirectMessage::PairResult(result) => {
+                self.store_credential(pairing_rig, result.credential)
+                    .await?;
+                result.agent_hello
+            }
+            other => {
+                write_message(&mut write_half, &other).await?;
+                return Ok(());
+            }
+        };
+        write_message(
+            &mut write_half,
+            &DirectMessage::AgentHello(agent_hello.clone()),
+        )
+        .await?;
+        println!(
+            "Relaying rig {} (connection {})",
+            agent_hello.rig_id, agent_hello.connection_id
+        );
+
+        self.forward(&mut reader, &mut write_half, &mut hub).await;
+        let _ = hub.close(None).await;
+        println!("Relay for rig {} closed", agent_hello.rig_id);
+        Ok(())
+    }
+
+    /// Turn the client's first frame into the one sent upstream. The error
+    /// branch is the frame to send back to the client instead.
+    async fn upstream_handshake(
+        &self,
+        first: DirectMessage,
+    ) -> Result<(DirectMessage, Option<RigId>), DirectMessage> {
+        match first {
+            DirectMessage::ClientHello(hello) => {
+                let rig_id = RigId {
+                    node_id: hello.node_id,
+                    profile_id: hello.profile_id,
+                };
+                let credentials = self.credentials.lock().await;
+                let credential = credentials.get(&rig_id).ok_or_else(|| DirectMessage::Error {
+                    message: format!(
+                        "relay holds no Hub credential for rig {rig_id}; pair through the relay first"
+                    ),
+                    retryable: false,
+                })?;
+                Ok((
+                    DirectMessage::Auth(AuthRequest {
+                        credential: credential.to_string(),
+                        hello,
+                    }),
+                    None,
+                ))
+            }
+            DirectMessage::Pair(PairRequest {
+                pairing_token,
+                hello,
+            }) => {
+                let rig_id = RigId {
+                    node_id: hello.node_id,
+                    profile_id: hello.profile_id,
+                };
+                Ok((
+                    DirectMessage::Pair(PairRequest {
+                        pairing_token,
+                        hello,
+                    }),
+                    Some(rig_id),
+                ))
+            }
+            // A client that brought its own credential is passed through.
+            auth @ DirectMessage::Auth(_) => Ok((auth, None)),
+            _ => Err(DirectMessage::Error {
+                message: "first frame must be client_hello, pair or auth".to_string(),
+                retryable: false,
+            }),
+        }
+    }
+
+    async fn connect_hub(&self, first: &DirectMessage) -> Result<HubSocket, String> {
+        let (mut hub, _) = tokio::time::timeout(
+            HANDSHAKE_TIMEOUT,
+            tokio_tungstenite::connect_async(self.websocket_url.as_str()),
+        )
+        .await
+        .map_err(|_| "connection timed out".to_string())?
+        .map_err(|error| error.to_string())?;
+        let json = serde_json::to_string(first).map_err(|error| error.to_string())?;
+        hub.send(Message::Text(json.into()))
+            .await
+            .map_err(|error| error.to_string())?;
+        Ok(hub)
+    }
+
+    async fn store_credential(
+        &self,
+        rig_id: Option<RigId>,
+        credential: String,
+    ) -> Result<(), String> {
+        let rig_id = rig_id.ok_or("Hub sent a pairing result for a non-pairing handshake")?;
+        let mut credentials = self.credentials.lock().await;
+        credentials.set(rig_id, credential);
+        credentials.save(&self.credentials_path).map_err(|error| {
+            format!(
+                "paired rig {rig_id} but could not save {}: {error}",
+                self.credentials_path.display()
+            )
+        })?;
+        println!("Stored the Hub credential for rig {rig_id}");
+        Ok(())
+    }
+
+    /// Forward frames verbatim until either side closes. Lines from the
+    /// client become WebSocket text frames and vice versa.
+    async fn forward<R, W>(&self, reader: &mut FrameReader<R>, writer: &mut W, hub: &mut HubSocket)
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        loop {
+            tokio::select! {
+                line = reader.next() => {
+                    let Ok(Some(line)) = line else { break };
+                    if hub.send(Message::Text(line.into())).await.is_err() {
+                        break;
+                    }
+                }
+                inbound = hub.next() => {
+                    let text = match inbound {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => continue,
+                        _ => break,
+                    };
+                    if write_line(writer, text.as_str()).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Newline-framed reader for the local side. The partial frame lives in the
+/// reader rather than the read future, so a read raced against the Hub socket
+/// in `select!` never loses bytes.
+struct FrameReader<R> {
+    reader: BufReader<R>,
+    buffer: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> FrameReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Next frame without its line ending, refusing frames over the Direct
+    /// size limit. `None` is the end of the stream.
+    async fn next(&mut self) -> io::Result<Option<String>> {
+        let limit = (MAX_FRAME_BYTES + 1).saturating_sub(self.buffer.len()) as u64;
+        (&mut self.reader)
+            .take(limit)
+            .read_until(b'\n', &mut self.buffer)
+            .await?;
+        if !self.buffer.ends_with(b"\n") {
+            if self.buffer.len() > MAX_FRAME_BYTES {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Direct frame exceeds the size limit",
+                ));
+            }
+            // End of stream, possibly after an unterminated fragment.
+            return Ok(None);
+        }
+        let frame = String::from_utf8(std::mem::take(&mut self.buffer))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(Some(frame.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+async fn next_text(hub: &mut HubSocket) -> Option<String> {
+    while let Some(frame) = hub.next().await {
+        match frame {
+            Ok(Message::Text(text)) => return Some(text.to_string()),
+            Ok(Message::Ping(_) | Message::Pong(_)) => continue,
+            _ => return None,
+        }
+    }
+    None
+}
+
+async fn write_message<W>(writer: &mut W, message: &DirectMessage) -> Result<(), String>
+where
+    W: AsyncWrite + Unpin,
+{
+    let json = serde_json::to_string(message).map_err(|error| error.to_string())?;
+    write_line(writer, &json)
+        .await
+        .map_err(|error| error.to_string())
+}
+
+/// Write one frame. A Hub frame containing a raw newline would split on the
+/// local transport, so it is re-encoded compactly first.
+async fn write_line<W>(writer: &mut W, text: &str) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut frame = if text.contains('\n') {
+        serde_json::from_str::<serde_json::Value>(text)
+            .and_then(|value| serde_json::to_vec(&value))
+            .map_err(io::Error::other)?
+    } else {
+        text.as_bytes().to_vec()
+    };
+    frame.push(b'\n');
+    writer.write_all(&frame).await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hub_url_must_be_https_except_on_loopback() {
+        let mut config = RelayConfig::default();
+        assert_eq!(
+            config.websocket_url().unwrap(),
+            "wss://hub.chatstronomy.com/v1/direct"
+        );
+        config.hub_url = "http://127.0.0.1:8080/".to_string();
+        assert_eq!(
+            config.websocket_url().unwrap(),
+            "ws://127.0.0.1:8080/v1/direct"
+        );
+        config.hub_url = "http://hub.example.com".to_string();
+        assert!(config.validate().unwrap_err().contains("https://"));
+        config.hub_url = "https://hub.example.com".to_string();
+        config.pipe_name = "../escape".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[cfg(feature = "hub")]
+    #[tokio::test]
+    async fn relay_pairs_once_then_forwards_queries_and_heartbeats() {
+        use crate::direct::protocol::{ClientHello, QueryKind, QueryResult};
+        use crate::hub::config::HubConfig;
+        use crate::hub::db::Db;
+        use crate::hub::server::{self, HubState};
+        use crate::hub::store::UserRow;
+
+        let db = Db::open_in_memory().unwrap();
+        db.upsert_user(&UserRow {
+            discord_user_id: 1,
+            username: "admin".to_string(),
+            email: None,
+            email_verified: false,
+            avatar_url: None,
+        })
+        .unwrap();
+        db.register_guild(100, "g", 1).unwrap();
+        let telescope = db.create_telescope(1, "c925").unwrap();
+        let pairing_token = db.issue_pairing_token(telescope.id, 1).unwrap();
+        let state = HubState::build(HubConfig::default(), db, None).unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let router = server::router(state.clone());
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+            .unwrap()
+        });
+
+        let credentials_file = std::env::temp_dir().join(format!(
+            "chatstronomy-relay-{}.json",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let relay = Relay::new(&RelayConfig {
+            hub_url: format!("http://{address}"),
+            credentials_file: credentials_file.to_string_lossy().into_owned(),
+            ..RelayConfig::default()
+        })
+        .unwrap();
+        let DirectMessage::ClientHello(hello) = serde_json::from_str(include_str!(
+            "../contracts/direct/v1/fixtures/client-hello.json"
+        ))
+        .unwrap() else {
+            panic!("expected client hello");
+        };
+        let rig_id = RigId {
+            node_id: hello.node_id,
+            profile_id: hello.profile_id,
+        };
+
+        // Drive one local client against the relay until it hangs up.
+        async fn session<F, Fut>(relay: &Relay, first: DirectMessage, client: F)
+        where
+            F: FnOnce(
+                FrameReader<tokio::io::ReadHalf<tokio::io::DuplexStream>>,
+                tokio::io::WriteHalf<tokio::io::DuplexStream>,
+            ) -> Fut,
+            Fut: std::future::Future<Output = ()>,
+        {
+            let (local, relay_end) = tokio::io::duplex(1 << 20);
+            let (read_half, mut write_half) = tokio::io::split(local);
+            write_message(&mut write_half, &first).await.unwrap();
+            let (served, ()) = tokio::join!(
+                relay.serve(relay_end),
+                client(FrameReader::new(read_half), write_half)
+            );
+            served.unwrap();
+        }
+        async fn recv<R: AsyncRead + Unpin>(reader: &mut FrameReader<R>) -> DirectMessage {
+            serde_json::from_str(&reader.next().await.unwrap().unwrap()).unwrap()
+        }
+
+        let first = DirectMessage::Pair(PairRequest {
+            pairing_token,
+            hello: hello.clone(),
+        });
+        let hub = state.clone();
+        session(&relay, first, |mut reader, mut writer| async move {
+            let DirectMessage::AgentHello(agent_hello) = recv(&mut reader).await else {
+                panic!("expected the pairing to surface as an agent hello");
+            };
+            assert_eq!(agent_hello.rig_id, rig_id);
+            assert_eq!(
+                recv(&mut reader).await,
+                DirectMessage::Subscribe(crate::direct::protocol::StreamSubscription::all())
+            );
+
+            let connection = hub.rig_connections.get(telescope.id).unwrap();
+            let query = tokio::spawn(async move {
+                connection
+                    .query(QueryKind::MountInfo, Duration::from_secs(5))
+                    .await
+            });
+            let DirectMessage::Query(request) = recv(&mut reader).await else {
+                panic!("expected a relayed query");
+            };
+            assert_eq!(request.kind, QueryKind::MountInfo);
+            assert!(request.expires_at.is_some());
+            let answer = DirectMessage::QueryResult(QueryResult {
+                id: request.id,
+                ok: true,
+                payload: serde_json::json!({"Response": {"Connected": true}}),
+                error: None,
+            });
+            write_message(&mut writer, &answer).await.unwrap();
+            let result = query.await.unwrap().unwrap();
+            assert_eq!(result.payload["Response"]["Connected"], true);
+
+            write_message(&mut writer, &DirectMessage::Heartbeat { seq: 7 })
+                .await
+                .unwrap();
+            assert_eq!(
+                recv(&mut reader).await,
+                DirectMessage::HeartbeatAck { seq: 7 }
+            );
+        })
+        .await;
+
+        // The credential stayed with the relay, so a plain hello reconnects.
+        let stored = RelayCredentials::load(&credentials_file).unwrap();
+        assert!(stored.get(&rig_id).unwrap().starts_with("csrc_"));
+        session(
+            &relay,
+            DirectMessage::ClientHello(hello.clone()),
+            |mut reader, _writer| async move {
+                let DirectMessage::AgentHello(agent_hello) = recv(&mut reader).await else {
+                    panic!("expected an agent hello");
+                };
+                assert_eq!(agent_hello.rig_id, rig_id);
+            },
+        )
+        .await;
+
+        let stranger = ClientHello {
+            profile_id: uuid::Uuid::new_v4(),
+            ..hello
+        };
+        session(
+            &relay,
+            DirectMessage::ClientHello(stranger),
+            |mut reader, _writer| async move {
+                let DirectMessage::Error { message, retryable } = recv(&mut reader).await else {
+                    panic!("expected a refusal");
+                };
+                assert!(message.contains("pair through the relay"));
+                assert!(!retryable);
+            },
+        )
+        .await;
+        std::fs::remove_file(&credentials_file).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+use crate::source::RigCommand;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +32,28 @@ pub struct RotatorInfo {
     pub synced: bool,
 }
 
+impl RotatorInfoResponse {
+    /// Check a rotator command against the live rotator.
+    pub fn check_command(&self, command: &RigCommand) -> Result<(), String> {
+        let RigCommand::MoveRotator { position_angle, .. } = command else {
+            return Ok(());
+        };
+        if !(0.0..360.0).contains(position_angle) {
+            return Err(format!("angle {position_angle} is outside 0–360°"));
+        }
+        if !(self.success && self.response.connected) {
+            return Err("the rotator is not connected".to_string());
+        }
+        if self.response.is_moving {
+            return Err(format!(
+                "the rotator is still moving (at {:.1}°)",
+                self.response.position
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,4 +74,30 @@ mod tests {
         let parsed: RotatorInfoResponse = serde_json::from_str(json).unwrap();
         assert!(!parsed.response.connected);
     }
+
+    #[test]
+    fn rotator_moves_are_checked_against_live_state() {
+        let json = r#"{"Response":{"CanReverse":false,"Reverse":false,"MechanicalPosition":0,"Position":104.04,"StepSize":0.5,"IsMoving":true,"Synced":true,"Connected":true},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let mut rotator: RotatorInfoResponse = serde_json::from_str(json).unwrap();
+        let rotate = RigCommand::MoveRotator {
+            position_angle: 90.0,
+            mechanical: false,
+        };
+        assert!(
+            rotator
+                .check_command(&rotate)
+                .unwrap_err()
+                .contains("moving")
+        );
+        rotator.response.is_moving = false;
+        assert_eq!(rotator.check_command(&rotate), Ok(()));
+        assert!(
+            rotator
+                .check_command(&RigCommand::MoveRotator {
+                    position_angle: 360.0,
+                    mechanical: true,
+                })
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Safety monitor snapshot returned by a Direct N.I.N.A. rig.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SafetyInfoResponse {
+    pub response: SafetyInfo,
+    pub error: String,
+    pub status_code: i32,
+    pub success: bool,
+    #[serde(rename = "Type")]
+    pub response_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SafetyInfo {
+    pub connected: bool,
+    #[serde(default)]
+    pub is_safe: bool,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+impl SafetyInfoResponse {
+    /// The monitor's verdict, or `None` when it is not connected.
+    pub fn is_safe(&self) -> Option<bool> {
+        (self.success && self.response.connected).then_some(self.response.is_safe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_safety_monitor_snapshot() {
+        let json = r#"{"Response":{"IsSafe":false,"SupportedActions":[],"Connected":true,"Name":"Safety Monitor","DisplayName":"AAG Safety"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let parsed: SafetyInfoResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.is_safe(), Some(false));
+
+        let json = r#"{"Response":{"Connected":false},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let parsed: SafetyInfoResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.is_safe(), None);
+    }
+}
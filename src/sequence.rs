@@ -3,7 +3,7 @@ use chrono::{DateTime as ChronoDateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SequenceResponse {
     pub response: Vec<Value>,
@@ -171,7 +171,9 @@ pub struct AltitudeCondition {
 }
 
 /// A long-running sequence operation that benefits from chat progress updates.
-#[derive(Debug, Clone, PartialEq)]
+/// Serializable so a chat updater can carry the ones it tracks across a
+/// restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SequenceOperation {
     /// Stable position within the sequence tree for correlating adjacent polls.
     pub key: String,
@@ -183,14 +185,16 @@ pub struct SequenceOperation {
     pub kind: SequenceOperationKind,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SequenceOperationKind {
     CameraCooling {
         target_temperature: f64,
+        #[serde(with = "optional_millis")]
         minimum_duration: Option<chrono::Duration>,
     },
     TimeWait {
         target_time: Option<ChronoDateTime<FixedOffset>>,
+        #[serde(with = "optional_millis")]
         configured_duration: Option<chrono::Duration>,
     },
     MountSlew {
@@ -207,7 +211,7 @@ pub enum SequenceOperationKind {
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OperationCoordinates {
     pub ra_hours: Option<f64>,
     pub ra_string: Option<String>,
@@ -249,7 +253,7 @@ impl OperationCoordinates {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlateSolveOutput {
     pub solve_time: Option<String>,
     pub success: Option<bool>,
@@ -263,10 +267,33 @@ pub struct PlateSolveOutput {
     pub ra_pixel_error: Option<f64>,
     pub dec_pixel_error: Option<f64>,
     pub flipped: Option<bool>,
+    /// Not kept across restarts; the solve was already posted with it.
+    #[serde(skip)]
     pub thumbnail: Option<Vec<u8>>,
     pub thumbnail_media_type: Option<String>,
 }
 
+/// Durations as whole milliseconds.
+mod optional_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<chrono::Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&duration.num_milliseconds()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<chrono::Duration>, D::Error> {
+        Ok(Option::<i64>::deserialize(deserializer)?.map(chrono::Duration::milliseconds))
+    }
+}
+
 impl SequenceOperation {
     pub fn is_active(&self) -> bool {
         matches!(
@@ -568,6 +595,19 @@ impl SequenceResponse {
             })
     }
 
+    /// Whether N.I.N.A. is executing the sequence, i.e. any top-level
+    /// container is running.
+    pub fn is_running(&self) -> bool {
+        // The first entry holds the global triggers, which have no status
+        // of their own; the containers follow it.
+        self.response.iter().skip(1).any(|container| {
+            container
+                .get("Status")
+                .and_then(Value::as_str)
+                .is_some_and(|status| status.eq_ignore_ascii_case("RUNNING"))
+        })
+    }
+
     /// Get all containers from the response
     pub fn get_containers(&self) -> Vec<Container> {
         self.response
@@ -705,6 +745,94 @@ pub fn extract_current_target(sequence: &SequenceResponse) -> Option<String> {
     extract_current_target_with_delivery(sequence).map(|(name, _)| name)
 }
 
+/// One exposure instruction (Smart Exposure, Take Many Exposures) in a
+/// target's part of the sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedExposure {
+    pub filter: String,
+    /// Exposures the instruction is set to take.
+    pub frames: u32,
+    pub exposure_seconds: f64,
+}
+
+/// The light exposures the sequence plans for `target`. The target's
+/// container is matched by its `TargetName`, or by its name without the
+/// "_Container" suffix, ignoring case. Empty when the target is not in the
+/// tree (Target Scheduler targets, for one) or plans no counted lights.
+pub fn extract_planned_exposures(
+    sequence: &SequenceResponse,
+    target: &str,
+) -> Vec<PlannedExposure> {
+    fn is_target(obj: &serde_json::Map<String, Value>, target: &str) -> bool {
+        let named = |key: &str| {
+            obj.get(key)
+                .and_then(Value::as_str)
+                .map(|name| name.strip_suffix("_Container").unwrap_or(name).trim())
+                .is_some_and(|name| name.eq_ignore_ascii_case(target))
+        };
+        obj.contains_key("Items") && (named("TargetName") || named("Name"))
+    }
+
+    fn find_target<'a>(
+        values: &'a [Value],
+        target: &str,
+    ) -> Option<&'a serde_json::Map<String, Value>> {
+        values.iter().filter_map(Value::as_object).find_map(|obj| {
+            if is_target(obj, target) {
+                return Some(obj);
+            }
+            obj.get("Items")
+                .and_then(Value::as_array)
+                .and_then(|items| find_target(items, target))
+        })
+    }
+
+    fn collect(values: &[Value], planned: &mut Vec<PlannedExposure>) {
+        for obj in values.iter().filter_map(Value::as_object) {
+            let is_light = obj
+                .get("Type")
+                .and_then(Value::as_str)
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("LIGHT"));
+            let frames = obj.get("Iterations").and_then(Value::as_u64);
+            let exposure_seconds = obj.get("ExposureTime").and_then(Value::as_f64);
+            if is_light
+                && let (Some(frames), Some(exposure_seconds)) = (frames, exposure_seconds)
+                && frames > 0
+            {
+                // Direct payloads send the filter's name; full N.I.N.A.
+                // serializations nest it in a filter object.
+                let filter = match obj.get("Filter") {
+                    Some(Value::String(name)) => name.clone(),
+                    Some(Value::Object(filter)) => filter
+                        .get("Name")
+                        .or_else(|| filter.get("_name"))
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                    _ => String::new(),
+                };
+                planned.push(PlannedExposure {
+                    filter,
+                    frames: u32::try_from(frames).unwrap_or(u32::MAX),
+                    exposure_seconds,
+                });
+            }
+            if let Some(items) = obj.get("Items").and_then(Value::as_array) {
+                collect(items, planned);
+            }
+        }
+    }
+
+    let mut planned = Vec::new();
+    if let Some(items) = find_target(&sequence.response, target.trim())
+        .and_then(|container| container.get("Items"))
+        .and_then(Value::as_array)
+    {
+        collect(items, &mut planned);
+    }
+    planned
+}
+
 /// Extract the meridian flip time from a sequence response
 ///
 /// This function looks for the "Meridian Flip_Trigger" in the GlobalTriggers section
@@ -1455,4 +1583,45 @@ mod tests {
             println!("example_sequence_2.json not found, skipping file test");
         }
     }
+
+    #[test]
+    fn planned_exposures_come_from_the_target_container() {
+        let json_content = std::fs::read_to_string("example_sequence_2.json").unwrap();
+        let sequence: SequenceResponse = serde_json::from_str(&json_content).unwrap();
+
+        let planned = extract_planned_exposures(&sequence, "north american");
+        assert_eq!(
+            planned
+                .iter()
+                .map(|p| (p.filter.as_str(), p.frames, p.exposure_seconds))
+                .collect::<Vec<_>>(),
+            vec![("HA", 12, 300.0), ("OIII", 12, 300.0), ("SII", 12, 300.0)]
+        );
+        assert_eq!(
+            extract_planned_exposures(&sequence, "Gecko Nebula").len(),
+            7
+        );
+        assert!(extract_planned_exposures(&sequence, "M31").is_empty());
+    }
+
+    #[test]
+    fn is_running_looks_at_the_containers_only() {
+        let json_content = std::fs::read_to_string("example_sequence.json").unwrap();
+        let sequence: SequenceResponse = serde_json::from_str(&json_content).unwrap();
+        assert!(sequence.is_running());
+
+        let sequence: SequenceResponse = serde_json::from_value(serde_json::json!({
+            "Response": [
+                { "GlobalTriggers": [], "Status": "RUNNING" },
+                { "Name": "Start_Container", "Status": "FINISHED", "Items": [] },
+                { "Name": "Targets_Container", "Status": "CREATED", "Items": [] }
+            ],
+            "Error": "",
+            "StatusCode": 200,
+            "Success": true,
+            "Type": "API"
+        }))
+        .unwrap();
+        assert!(!sequence.is_running());
+    }
 }
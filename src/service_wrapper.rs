@@ -1,13 +1,18 @@
 //! Chat delivery and updater orchestration for plugin-owned Direct runtimes.
 
 use crate::chat::{
-    ChatServiceManager, DiscordChatService, MatrixChatService, StaticRigResolver, run_bot,
+    ChatServiceManager, DiscordChatService, MatrixChatService, StaticRigResolver, StatusState,
+    default_state_file, run_bot,
 };
 use crate::chat_updater::ChatUpdater;
+use crate::checkpoint::FileCheckpointStore;
 use crate::config::{Config, TelescopeConfig};
 use crate::error::{ChatError, ChatstronomyError, ServiceError, ServiceResult};
+use crate::horizon::HorizonProfile;
+use crate::recording::RecordingRigSource;
 use crate::source::SharedRigSource;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -40,11 +45,14 @@ impl ServiceWrapper {
             }
         }
 
-        let (chat_manager, _bot_join) = build_shared_chat_manager(&self.config, &sources)
-            .await
-            .map_err(|error| ServiceError::Initialization {
-                reason: error.to_string(),
-            })?;
+        let sources = record_sessions(&self.config.telescopes, sources)?;
+        let horizons = load_horizons(&self.config.telescopes)?;
+        let (chat_manager, _bot_join) =
+            build_shared_chat_manager(&self.config, &sources, &horizons)
+                .await
+                .map_err(|error| ServiceError::Initialization {
+                    reason: error.to_string(),
+                })?;
         let chat_manager = Arc::new(chat_manager);
         let poll_interval = Duration::from_secs(interval);
         let mut handles = Vec::new();
@@ -56,8 +64,9 @@ impl ServiceWrapper {
                 .get(&telescope.name)
                 .expect("source coverage validated")
                 .clone();
+            let horizon = horizons.get(&telescope.name).cloned();
             handles.push(tokio::spawn(async move {
-                let mut updater = build_chat_updater(telescope, manager, source);
+                let mut updater = build_chat_updater(telescope, manager, source, horizon);
                 updater.start_polling(poll_interval).await;
             }));
         }
@@ -68,19 +77,77 @@ impl ServiceWrapper {
     }
 }
 
+/// Wrap the source of every telescope with `record_session` set, so both the
+/// updater and bot commands land in the session file.
+fn record_sessions(
+    telescopes: &[TelescopeConfig],
+    mut sources: HashMap<String, SharedRigSource>,
+) -> ServiceResult<HashMap<String, SharedRigSource>> {
+    for telescope in telescopes {
+        let Some(path) = &telescope.record_session else {
+            continue;
+        };
+        let source = sources
+            .remove(&telescope.name)
+            .expect("source coverage validated");
+        let recorder = RecordingRigSource::create(source, path).map_err(|error| {
+            ServiceError::Initialization {
+                reason: format!(
+                    "could not create session recording '{path}' for '{}': {error}",
+                    telescope.name
+                ),
+            }
+        })?;
+        println!("[{}] Recording rig session to {path}", telescope.name);
+        sources.insert(telescope.name.clone(), Arc::new(recorder));
+    }
+    Ok(sources)
+}
+
+/// Load the horizon file of every telescope that sets one.
+fn load_horizons(telescopes: &[TelescopeConfig]) -> ServiceResult<HashMap<String, HorizonProfile>> {
+    let mut horizons = HashMap::new();
+    for telescope in telescopes {
+        let Some(path) = &telescope.horizon_file else {
+            continue;
+        };
+        let horizon = HorizonProfile::load(path).map_err(|error| ServiceError::Initialization {
+            reason: format!(
+                "could not load horizon file for '{}': {error}",
+                telescope.name
+            ),
+        })?;
+        horizons.insert(telescope.name.clone(), horizon);
+    }
+    Ok(horizons)
+}
+
 async fn build_shared_chat_manager(
     config: &Config,
     sources: &HashMap<String, SharedRigSource>,
+    horizons: &HashMap<String, HorizonProfile>,
 ) -> Result<(ChatServiceManager, Option<tokio::task::JoinHandle<()>>), ChatstronomyError> {
     let mut manager = ChatServiceManager::new();
     let mut bot_join = None;
+    // The bot's live-status messages and every Discord service's session
+    // threads share one state file.
+    let state_file = PathBuf::from(
+        config
+            .chat
+            .discord_bot
+            .as_ref()
+            .filter(|bot| bot.enabled)
+            .map_or_else(default_state_file, |bot| bot.state_file.clone()),
+    );
+    let status_state = StatusState::load_shared(&state_file);
 
     if let Some(discord) = &config.chat.discord
         && discord.enabled
     {
-        manager.add_service(Box::new(DiscordChatService::new(
-            discord.default_webhook_url.clone(),
-        )));
+        manager.add_service(Box::new(
+            DiscordChatService::new(discord.default_webhook_url.clone())
+                .with_status_state(status_state.clone(), state_file.clone()),
+        ));
     }
 
     if let Some(matrix) = &config.chat.matrix
@@ -98,7 +165,8 @@ async fn build_shared_chat_manager(
                 service_name: "Matrix".to_string(),
                 reason: error.to_string(),
             })
-        })?;
+        })?
+        .with_acknowledgements(manager.acknowledgement_sender());
         manager.add_service(Box::new(service));
     }
 
@@ -121,10 +189,16 @@ async fn build_shared_chat_manager(
             rig_sources: sources.clone(),
             channel_to_telescope,
             write_acl: bot.write_acl.iter().copied().collect(),
+            horizons: horizons.clone(),
         });
-        let (service, join) = run_bot(bot, resolver)
-            .await
-            .map_err(ChatstronomyError::Chat)?;
+        let (service, join) = run_bot(
+            bot,
+            resolver,
+            manager.acknowledgement_sender(),
+            status_state,
+        )
+        .await
+        .map_err(ChatstronomyError::Chat)?;
         manager.add_service(Box::new(service));
         bot_join = Some(join);
     }
@@ -139,8 +213,9 @@ fn build_chat_updater(
     telescope: TelescopeConfig,
     manager: Arc<ChatServiceManager>,
     source: SharedRigSource,
+    horizon: Option<HorizonProfile>,
 ) -> ChatUpdater {
-    ChatUpdater::new(
+    let updater = ChatUpdater::new(
         source,
         telescope.name,
         telescope.chat.to_chat_target(),
@@ -151,4 +226,15 @@ fn build_chat_updater(
         telescope.reconnect.initial_seconds,
         telescope.reconnect.max_seconds,
     )
+    .with_weather_alerts(telescope.weather_alerts)
+    .with_image_quality_alerts(telescope.image_quality)
+    .with_critical_alerts(telescope.critical_alerts)
+    .with_coalescing(telescope.coalescing)
+    .with_notification_rules(telescope.notification_rules)
+    .with_session_threads(telescope.chat.session_threads)
+    .with_horizon(horizon);
+    match telescope.checkpoint_file {
+        Some(path) => updater.with_checkpoint(Arc::new(FileCheckpointStore::new(path))),
+        None => updater,
+    }
 }
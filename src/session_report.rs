@@ -0,0 +1,646 @@
+//! End-of-night session report.
+//!
+//! Built from the rig's image and event histories: what was integrated per
+//! target and filter, how the frames looked, how guiding and autofocus went,
+//! and what interrupted the night. The updater posts it when the sequence
+//! finishes or the mount parks in the morning; `/chatstronomy report` builds
+//! it on demand.
+
+use crate::chat::ChatMessage;
+use crate::discord::colors;
+use crate::events::{Event, EventDetails, event_types};
+use crate::images::{ImageHistoryResponse, ImageMetadata};
+use crate::timestamps::parse_nina_timestamp;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Shown for light frames taken before any target was announced.
+const NO_TARGET: &str = "(no target)";
+
+/// Fewest guided lights before the start and end of a night's guiding are
+/// compared.
+const GUIDING_TREND_FRAMES: usize = 9;
+/// How much worse, as a ratio and in arcseconds, the last third of the
+/// night's guiding must be than the first third to be flagged. Both have to
+/// hold, so a good night drifting from 0.40″ to 0.52″ stays quiet.
+const GUIDING_WORSE_RATIO: f64 = 1.25;
+const GUIDING_WORSE_ARCSEC: f64 = 0.15;
+
+/// An observing night runs from noon to noon, so a session that crosses
+/// midnight stays one night.
+fn night_of(time: &DateTime<FixedOffset>) -> NaiveDate {
+    (*time - Duration::hours(12)).date_naive()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetIntegration {
+    pub target: String,
+    pub filter: String,
+    pub frames: usize,
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterQuality {
+    pub filter: String,
+    pub frames: usize,
+    pub median_hfr: Option<f64>,
+    pub median_stars: Option<f64>,
+}
+
+/// One light frame on the report chart's timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportFrame {
+    /// Minutes since the first frame of the night.
+    pub minutes: f64,
+    pub filter: String,
+    pub hfr: Option<f64>,
+    pub stars: Option<f64>,
+    /// Total guiding RMS in arcseconds, when the frame was guided.
+    pub rms: Option<f64>,
+    /// Median pixel value in ADU.
+    pub median: Option<f64>,
+    /// Sensor temperature in °C.
+    pub temperature: Option<f64>,
+}
+
+/// Something the rig did during the night that can explain a change in the
+/// frames after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MarkerKind {
+    Dither,
+    FilterChange,
+    Autofocus,
+    MeridianFlip,
+}
+
+impl MarkerKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            MarkerKind::FilterChange => "Filter change",
+            MarkerKind::Autofocus => "Autofocus",
+            MarkerKind::Dither => "Dither",
+            MarkerKind::MeridianFlip => "Meridian flip",
+        }
+    }
+
+    fn of(event: &str) -> Option<Self> {
+        match event {
+            event_types::FILTERWHEEL_CHANGED => Some(MarkerKind::FilterChange),
+            event_types::AUTOFOCUS_FINISHED => Some(MarkerKind::Autofocus),
+            event_types::GUIDER_DITHER => Some(MarkerKind::Dither),
+            event_types::MOUNT_AFTER_FLIP => Some(MarkerKind::MeridianFlip),
+            _ => None,
+        }
+    }
+}
+
+/// A marker on the report timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportMarker {
+    /// Minutes since the first frame of the night.
+    pub minutes: f64,
+    pub kind: MarkerKind,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionReport {
+    pub night: Option<NaiveDate>,
+    pub start: Option<DateTime<FixedOffset>>,
+    pub end: Option<DateTime<FixedOffset>>,
+    pub total_images: usize,
+    pub light_frames: usize,
+    /// Light exposure in seconds.
+    pub light_seconds: f64,
+    pub frames_by_type: BTreeMap<String, usize>,
+    pub integration: Vec<TargetIntegration>,
+    pub filters: Vec<FilterQuality>,
+    /// Smallest and largest total guiding RMS over guided lights, arcsec.
+    pub guiding_rms: Option<(f64, f64)>,
+    /// Median total RMS over the first and last thirds of the guided
+    /// lights, when the last third is clearly worse.
+    pub guiding_worsened: Option<(f64, f64)>,
+    pub autofocus_runs: usize,
+    /// Failure counts by kind (`ERROR-AF`, `ERROR-PLATESOLVE`, errors...).
+    pub failures: BTreeMap<String, usize>,
+    /// Safety monitor changes during the night, plus any weather alerts the
+    /// caller adds.
+    pub weather_interruptions: usize,
+    pub timeline: Vec<ReportFrame>,
+    /// Filter changes, autofocus runs, dithers and flips between the first
+    /// and last frame, in time order.
+    pub markers: Vec<ReportMarker>,
+}
+
+impl SessionReport {
+    /// Report on the most recent night in the histories. Lights taken
+    /// before the night's first target announcement are credited to
+    /// `fallback_target` when given.
+    pub fn build(
+        images: &ImageHistoryResponse,
+        events: &[Event],
+        fallback_target: Option<&str>,
+    ) -> Self {
+        let dated: Vec<(DateTime<FixedOffset>, &ImageMetadata)> = images
+            .response
+            .iter()
+            .filter(|image| !image.is_snapshot())
+            .filter_map(|image| parse_nina_timestamp(&image.date).map(|date| (date, image)))
+            .collect();
+        let Some(night) = dated.iter().map(|(date, _)| night_of(date)).max() else {
+            return Self::default();
+        };
+        let tonight: Vec<(DateTime<FixedOffset>, &ImageMetadata)> = dated
+            .into_iter()
+            .filter(|(date, _)| night_of(date) == night)
+            .collect();
+        let events: Vec<(DateTime<FixedOffset>, &Event)> = events
+            .iter()
+            .filter_map(|event| parse_nina_timestamp(&event.time).map(|time| (time, event)))
+            .filter(|(time, _)| night_of(time) == night)
+            .collect();
+
+        let session = ImageHistoryResponse {
+            response: tonight.iter().map(|(_, image)| (*image).clone()).collect(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: images.response_type.clone(),
+        };
+        let stats = session.get_session_stats();
+        let start = tonight.iter().map(|(date, _)| *date).min();
+        let end = tonight.iter().map(|(date, _)| *date).max();
+
+        let mut targets: Vec<(DateTime<FixedOffset>, &str)> = events
+            .iter()
+            .filter_map(|(time, event)| match &event.details {
+                Some(EventDetails::TargetStart { target_name, .. }) => {
+                    Some((*time, target_name.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        targets.sort_by_key(|(time, _)| *time);
+        let target_at = |date: &DateTime<FixedOffset>| {
+            targets
+                .iter()
+                .rev()
+                .find(|(started, _)| started <= date)
+                .map(|(_, name)| *name)
+                .or(fallback_target)
+                .unwrap_or(NO_TARGET)
+                .to_string()
+        };
+
+        let lights: Vec<&(DateTime<FixedOffset>, &ImageMetadata)> = tonight
+            .iter()
+            .filter(|(_, image)| image.is_light_frame())
+            .collect();
+        let mut integration: BTreeMap<(String, String), (usize, f64)> = BTreeMap::new();
+        for (date, image) in &lights {
+            let entry = integration
+                .entry((target_at(date), image.filter.clone()))
+                .or_default();
+            entry.0 += 1;
+            entry.1 += image.exposure_time;
+        }
+
+        let light_history = ImageHistoryResponse {
+            response: lights.iter().map(|(_, image)| (*image).clone()).collect(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: images.response_type.clone(),
+        };
+        let mut filters: Vec<FilterQuality> = light_history
+            .count_images_by_filter()
+            .into_iter()
+            .map(|(filter, frames)| {
+                let of_filter = light_history.get_images_by_filter(&filter);
+                FilterQuality {
+                    median_hfr: median(
+                        of_filter
+                            .iter()
+                            .filter(|image| image.hfr > 0.0)
+                            .map(|image| image.hfr),
+                    ),
+                    median_stars: median(
+                        of_filter
+                            .iter()
+                            .filter(|image| image.stars >= 0)
+                            .map(|image| image.stars as f64),
+                    ),
+                    filter,
+                    frames,
+                }
+            })
+            .collect();
+        filters.sort_by(|a, b| a.filter.cmp(&b.filter));
+
+        let timeline: Vec<ReportFrame> = lights
+            .iter()
+            .map(|(date, image)| ReportFrame {
+                minutes: start.map_or(0.0, |start| (*date - start).num_seconds() as f64 / 60.0),
+                filter: image.filter.clone(),
+                hfr: (image.hfr > 0.0).then_some(image.hfr),
+                stars: (image.stars >= 0).then_some(image.stars as f64),
+                rms: total_rms_arcsec(&image.rms_text),
+                median: (image.median > 0.0).then_some(image.median),
+                temperature: Some(image.temperature).filter(|t| t.is_finite()),
+            })
+            .collect();
+        let mut markers: Vec<ReportMarker> = match (start, end) {
+            (Some(start), Some(end)) => events
+                .iter()
+                .filter(|(time, _)| *time >= start && *time <= end)
+                .filter_map(|(time, event)| {
+                    MarkerKind::of(&event.event).map(|kind| ReportMarker {
+                        minutes: (*time - start).num_seconds() as f64 / 60.0,
+                        kind,
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        markers.sort_by(|a, b| a.minutes.total_cmp(&b.minutes));
+        let guiding_rms = timeline.iter().filter_map(|frame| frame.rms).fold(
+            None,
+            |range: Option<(f64, f64)>, rms| {
+                Some(range.map_or((rms, rms), |(lo, hi)| (lo.min(rms), hi.max(rms))))
+            },
+        );
+        let guiding_worsened = guiding_worsened(&timeline);
+
+        let mut failures: BTreeMap<String, usize> = BTreeMap::new();
+        let mut autofocus_runs = 0;
+        let mut weather_interruptions = 0;
+        for (_, event) in &events {
+            match event.event.as_str() {
+                event_types::AUTOFOCUS_FINISHED => autofocus_runs += 1,
+                event_types::SAFETY_CHANGED => weather_interruptions += 1,
+                event_types::ERROR_AF
+                | event_types::ERROR_PLATESOLVE
+                | event_types::SEQUENCE_ENTITY_FAILED
+                | event_types::CAMERA_DOWNLOAD_TIMEOUT => {
+                    *failures.entry(event.event.clone()).or_default() += 1;
+                }
+                event_types::NINA_NOTIFICATION => {
+                    if let Some(EventDetails::NinaNotification { level, .. }) = &event.details
+                        && level.eq_ignore_ascii_case("error")
+                    {
+                        *failures.entry("N.I.N.A. errors".to_string()).or_default() += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            night: Some(night),
+            start,
+            end,
+            total_images: stats.total_images,
+            light_frames: stats.light_frames,
+            light_seconds: stats.total_exposure_time,
+            frames_by_type: session.count_images_by_type().into_iter().collect(),
+            integration: integration
+                .into_iter()
+                .map(|((target, filter), (frames, seconds))| TargetIntegration {
+                    target,
+                    filter,
+                    frames,
+                    seconds,
+                })
+                .collect(),
+            filters,
+            guiding_rms,
+            guiding_worsened,
+            autofocus_runs,
+            failures,
+            weather_interruptions,
+            timeline,
+            markers,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_images == 0
+    }
+
+    /// Light integration per filter across all targets, in seconds.
+    pub fn integration_by_filter(&self) -> BTreeMap<&str, f64> {
+        let mut totals = BTreeMap::new();
+        for entry in &self.integration {
+            *totals.entry(entry.filter.as_str()).or_insert(0.0) += entry.seconds;
+        }
+        totals
+    }
+
+    /// The report as a chat message; `title` is used as given.
+    pub fn chat_message(&self, title: &str) -> ChatMessage {
+        let mut message = ChatMessage::new(title).color(colors::PURPLE);
+        if self.is_empty() {
+            return message.field("Images", "No frames in the image history.", false);
+        }
+        if let (Some(night), Some(start), Some(end)) = (self.night, self.start, self.end) {
+            message = message.field(
+                "Night",
+                &format!(
+                    "{} · {}–{}",
+                    night.format("%Y-%m-%d"),
+                    start.format("%H:%M"),
+                    end.format("%H:%M")
+                ),
+                false,
+            );
+        }
+        message = message
+            .field(
+                "Integration",
+                &format!(
+                    "{} ({} lights)",
+                    format_hours(self.light_seconds),
+                    self.light_frames
+                ),
+                true,
+            )
+            .field(
+                "Frames",
+                &self
+                    .frames_by_type
+                    .iter()
+                    .map(|(kind, count)| format!("{kind} {count}"))
+                    .collect::<Vec<_>>()
+                    .join(" · "),
+                true,
+            );
+
+        if !self.integration.is_empty() {
+            let mut by_target: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+            for entry in &self.integration {
+                by_target.entry(&entry.target).or_default().push(format!(
+                    "{} {}×{}",
+                    entry.filter,
+                    entry.frames,
+                    format_hours(entry.seconds)
+                ));
+            }
+            let lines: Vec<String> = by_target
+                .into_iter()
+                .map(|(target, filters)| format!("**{target}**: {}", filters.join(", ")))
+                .collect();
+            message = message.field("Per target", &lines.join("\n"), false);
+        }
+
+        if !self.filters.is_empty() {
+            let lines: Vec<String> = self
+                .filters
+                .iter()
+                .map(|quality| {
+                    let hfr = quality
+                        .median_hfr
+                        .map_or_else(|| "—".to_string(), |hfr| format!("{hfr:.2}"));
+                    let stars = quality
+                        .median_stars
+                        .map_or_else(|| "—".to_string(), |stars| format!("{stars:.0}"));
+                    format!("{}: HFR {hfr} · stars {stars}", quality.filter)
+                })
+                .collect();
+            message = message.field("Median quality", &lines.join("\n"), false);
+        }
+
+        message = message
+            .field(
+                "Guiding RMS",
+                &match (self.guiding_rms, self.guiding_worsened) {
+                    (None, _) => "unguided".to_string(),
+                    (Some((lo, hi)), None) => format!("{lo:.2}″–{hi:.2}″"),
+                    (Some((lo, hi)), Some((early, late))) => {
+                        format!("{lo:.2}″–{hi:.2}″\n⚠️ Worsened: {early:.2}″ → {late:.2}″ median")
+                    }
+                },
+                true,
+            )
+            .field("Autofocus runs", &self.autofocus_runs.to_string(), true)
+            .field(
+                "Weather interruptions",
+                &self.weather_interruptions.to_string(),
+                true,
+            )
+            .field(
+                "Failures",
+                &if self.failures.is_empty() {
+                    "none".to_string()
+                } else {
+                    self.failures
+                        .iter()
+                        .map(|(kind, count)| format!("{kind} ×{count}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                },
+                false,
+            );
+        message
+    }
+}
+
+/// Median total RMS of the first and last thirds of the night's guided
+/// lights, when guiding got clearly worse: wind picking up, the target
+/// sinking into seeing, or balance shifting after a flip.
+fn guiding_worsened(timeline: &[ReportFrame]) -> Option<(f64, f64)> {
+    let mut guided: Vec<(f64, f64)> = timeline
+        .iter()
+        .filter_map(|frame| frame.rms.map(|rms| (frame.minutes, rms)))
+        .collect();
+    if guided.len() < GUIDING_TREND_FRAMES {
+        return None;
+    }
+    guided.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let third = guided.len() / 3;
+    let early = median(guided[..third].iter().map(|(_, rms)| *rms))?;
+    let late = median(guided[guided.len() - third..].iter().map(|(_, rms)| *rms))?;
+    (late >= early * GUIDING_WORSE_RATIO && late - early >= GUIDING_WORSE_ARCSEC)
+        .then_some((early, late))
+}
+
+/// `1h 23m`, or minutes alone under an hour.
+pub(crate) fn format_hours(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as i64;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+pub(crate) fn median(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let mut values: Vec<f64> = values.filter(|value| value.is_finite()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    })
+}
+
+/// The arcsecond total from N.I.N.A.'s `Tot: 0.52 (0.95")` RMS text, or
+/// `None` for unguided frames.
+pub(crate) fn total_rms_arcsec(text: &str) -> Option<f64> {
+    let inner = text.split_once('(')?.1;
+    let number: String = inner
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number.parse::<f64>().ok().filter(|rms| *rms > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{light, sample_events, sample_night};
+
+    #[test]
+    fn reports_the_latest_night() {
+        let report = SessionReport::build(&sample_night(), &sample_events(), None);
+
+        assert_eq!(report.night, NaiveDate::from_ymd_opt(2025, 8, 7));
+        assert_eq!(report.total_images, 4);
+        assert_eq!(report.light_frames, 4);
+        assert_eq!(report.light_seconds, 1_200.0);
+        assert_eq!(
+            report.integration,
+            vec![
+                TargetIntegration {
+                    target: "M27".to_string(),
+                    filter: "OIII".to_string(),
+                    frames: 2,
+                    seconds: 600.0,
+                },
+                TargetIntegration {
+                    target: "NGC 7000".to_string(),
+                    filter: "Ha".to_string(),
+                    frames: 2,
+                    seconds: 600.0,
+                },
+            ]
+        );
+        assert_eq!(report.filters[0].median_hfr, Some(2.2));
+        assert_eq!(report.filters[1].median_stars, Some(670.0));
+        assert_eq!(report.guiding_rms, Some((0.80, 1.02)));
+        assert_eq!(report.guiding_worsened, None);
+        assert_eq!(report.autofocus_runs, 1);
+        assert_eq!(report.weather_interruptions, 1);
+        // The previous night's autofocus failure is not tonight's.
+        assert_eq!(
+            report.failures.keys().collect::<Vec<_>>(),
+            vec![event_types::ERROR_PLATESOLVE]
+        );
+        assert_eq!(report.timeline[2].minutes, 175.0);
+        assert_eq!(report.timeline[0].median, Some(880.0));
+        assert_eq!(report.timeline[0].temperature, Some(-10.0));
+        // Markers cover the frames: the autofocus before the first light
+        // is left off.
+        assert_eq!(
+            report
+                .markers
+                .iter()
+                .map(|marker| (marker.minutes, marker.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (5.0, MarkerKind::Dither),
+                (140.0, MarkerKind::MeridianFlip),
+                (172.0, MarkerKind::FilterChange),
+            ]
+        );
+
+        let message = report.chat_message("Session report");
+        let per_target = message
+            .fields
+            .iter()
+            .find(|field| field.name == "Per target")
+            .unwrap();
+        assert_eq!(
+            per_target.value,
+            "**M27**: OIII 2×10m\n**NGC 7000**: Ha 2×10m"
+        );
+    }
+
+    #[test]
+    fn flags_guiding_that_worsens_through_the_night() {
+        let night = |rms: &[f64]| ImageHistoryResponse {
+            response: rms
+                .iter()
+                .enumerate()
+                .map(|(index, rms)| {
+                    light(
+                        &format!("2025-08-07T22:{:02}:00-07:00", index * 5),
+                        "L",
+                        2.2,
+                        800,
+                        &format!("Tot: 0.40 ({rms:.2}\")"),
+                    )
+                })
+                .collect(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        };
+
+        let windy = [0.70, 0.80, 0.75, 0.90, 1.00, 0.95, 1.10, 1.30, 1.20];
+        let report = SessionReport::build(&night(&windy), &[], None);
+        assert_eq!(report.guiding_worsened, Some((0.75, 1.20)));
+        let guiding = report
+            .chat_message("Session report")
+            .fields
+            .into_iter()
+            .find(|field| field.name == "Guiding RMS")
+            .unwrap();
+        assert_eq!(
+            guiding.value,
+            "0.70″–1.30″\n⚠️ Worsened: 0.75″ → 1.20″ median"
+        );
+
+        // Slightly worse, but not by enough to matter.
+        let steady = [0.40, 0.45, 0.42, 0.44, 0.47, 0.43, 0.48, 0.52, 0.50];
+        let report = SessionReport::build(&night(&steady), &[], None);
+        assert_eq!(report.guiding_worsened, None);
+    }
+
+    #[test]
+    fn empty_histories_report_nothing() {
+        let images = ImageHistoryResponse {
+            response: Vec::new(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        };
+        let report = SessionReport::build(&images, &sample_events(), None);
+        assert!(report.is_empty());
+        assert_eq!(report.chat_message("Session report").fields.len(), 1);
+    }
+
+    #[test]
+    fn nights_run_noon_to_noon() {
+        let evening = DateTime::parse_from_rfc3339("2025-08-06T22:00:00-07:00").unwrap();
+        let morning = DateTime::parse_from_rfc3339("2025-08-07T05:00:00-07:00").unwrap();
+        let afternoon = DateTime::parse_from_rfc3339("2025-08-07T13:00:00-07:00").unwrap();
+        assert_eq!(night_of(&evening), night_of(&morning));
+        assert_ne!(night_of(&morning), night_of(&afternoon));
+    }
+
+    #[test]
+    fn parses_rms_text_and_formats_hours() {
+        assert_eq!(total_rms_arcsec("Tot: 0.52 (0.95\")"), Some(0.95));
+        assert_eq!(total_rms_arcsec("Tot: 0.00 (0.00\")"), None);
+        assert_eq!(total_rms_arcsec(""), None);
+        assert_eq!(format_hours(5_400.0), "1h 30m");
+        assert_eq!(format_hours(600.0), "10m");
+        assert_eq!(median([3.0, 1.0, 2.0, 10.0].into_iter()), Some(2.5));
+    }
+}
@@ -0,0 +1,2642 @@
+//! A simulated N.I.N.A. rig.
+//!
+//! [`SimulatedRigSource`] plays one plausible imaging night behind the
+//! [`RigSource`] interface: the camera cools, the mount slews and centers,
+//! the target starts, autofocus runs on a hyperbolic curve, light frames
+//! accumulate per filter with dithers between them, the mount flips at the
+//! meridian and the sequence finishes with a park. A [`NightPlan`] can script
+//! failures into the night, and [`RigCommand`]s change the simulated state the
+//! way they would on a real rig.
+//!
+//! The night runs on a scaled tokio clock, so a demo can compress hours into
+//! minutes and a paused-time test can drive a `ChatUpdater` through a whole
+//! session deterministically. Noise comes from a seeded generator: the same
+//! plan, seed and query cadence always produce the same night.
+
+use crate::api_types::CommandResponse;
+use crate::autofocus::{
+    AutofocusData, AutofocusResponse, BacklashCompensation, FocusPoint, IntersectionPoint,
+    Intersections, RSquares,
+};
+use crate::camera::{CameraInfo, CameraInfoResponse};
+use crate::dome::{DomeInfo, DomeInfoResponse};
+use crate::ephemeris::{alt_az, local_sidereal_hours};
+use crate::events::{
+    Event, EventDetails, EventHistoryResponse, FilterInfo, TargetCoordinates, event_types,
+};
+use crate::filterwheel::{FilterWheelInfo, FilterWheelInfoResponse};
+use crate::flat_device::{FlatDeviceInfo, FlatDeviceInfoResponse};
+use crate::focuser::{FocuserInfo, FocuserInfoResponse};
+use crate::guider::{
+    GUIDER_SCALE_ARCSECONDS, GuideGraphRms, GuideGraphStep, GuideStepsHistory, GuiderAxisError,
+    GuiderGraphResponse, GuiderInfo, GuiderInfoResponse, GuiderRmsError,
+};
+use crate::images::{ImageHistoryResponse, ImageMetadata, ThumbnailResponse, image_types};
+use crate::mount::{Coordinates, DateTime, MountInfo, MountInfoResponse, TrackingRate};
+use crate::rotator::{RotatorInfo, RotatorInfoResponse};
+use crate::safety::{SafetyInfo, SafetyInfoResponse};
+use crate::sequence::SequenceResponse;
+use crate::source::{
+    CommandKindSet, DeviceHints, DeviceState, FocuserTarget, RigCapabilities, RigCommand,
+    RigSource, RigSourceError, RigSourceKind, RigSourceResult, RigUpdate,
+};
+use crate::switch::{Switch, SwitchInfo, SwitchInfoResponse};
+use crate::weather::{WeatherInfo, WeatherInfoResponse};
+use async_trait::async_trait;
+use chrono::{Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+const UPDATE_BUFFER: usize = 256;
+const CENTER_SECONDS: f64 = 90.0;
+const FLIP_SECONDS: f64 = 120.0;
+const DOWNLOAD_SECONDS: f64 = 5.0;
+const AUTOFOCUS_POINTS: usize = 9;
+const AUTOFOCUS_STEP_SECONDS: f64 = 15.0;
+const AUTOFOCUS_STEP_SIZE: i32 = 40;
+/// Focuser steps over which the HFR curve doubles its minimum (the
+/// hyperbola's `p`).
+const FOCUS_WIDTH: f64 = 80.0;
+const FOCUS_BEST_POSITION: f64 = 4000.0;
+/// Best-focus drift as the tube cools, in steps per °C.
+pub(crate) const FOCUS_STEPS_PER_DEGREE: f64 = -12.0;
+/// How fast the night air cools, in °C per hour.
+const AMBIENT_DROP_PER_HOUR: f64 = 0.8;
+const GUIDE_INTERVAL_SECONDS: f64 = 2.0;
+const GUIDE_HISTORY: i64 = 100;
+const GUIDE_PIXEL_SCALE: f64 = 2.1;
+const GUIDE_RATE_ARCSEC_PER_SECOND: f64 = 7.5;
+/// Worm period and residual amplitude of the simulated periodic error.
+const PERIODIC_ERROR_SECONDS: f64 = 480.0;
+const PERIODIC_ERROR_ARCSEC: f64 = 0.3;
+const DITHER_ARCSEC: f64 = 3.0;
+const DITHER_SETTLE_SECONDS: f64 = 8.0;
+const SIDEREAL_RATE: f64 = 1.002_737_909_35;
+const THUMBNAIL_WIDTH: u32 = 320;
+const THUMBNAIL_HEIGHT: u32 = 214;
+const DOME_PARK_AZIMUTH: f64 = 180.0;
+/// The dew point holds while the air cools, so the spread closes over the
+/// night at `AMBIENT_DROP_PER_HOUR`.
+const DEW_POINT_SPREAD_AT_DUSK: f64 = 6.0;
+const WIND_MEAN: f64 = 2.5;
+const FLAT_MAX_BRIGHTNESS: i32 = 255;
+/// The simulated power box's writable ports: name, maximum and the value
+/// at power-on. Maximum 1 makes an on/off port.
+const SWITCH_PORTS: [(&str, f64, f64); 4] = [
+    ("Camera", 1.0, 1.0),
+    ("Mount", 1.0, 1.0),
+    ("Dew A", 255.0, 128.0),
+    ("Dew B", 255.0, 96.0),
+];
+const SWITCH_INPUT_VOLTAGE: f64 = 12.6;
+
+/// The night to simulate. Every field has a default, so `{}` is a complete
+/// plan and a plan file only lists what it changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NightPlan {
+    pub target_name: String,
+    pub project_name: Option<String>,
+    /// J2000 right ascension. When absent the target is placed so that it
+    /// transits `meridian_flip_after_minutes` into the night.
+    pub ra_hours: Option<f64>,
+    pub dec_degrees: f64,
+    pub rotation: f64,
+    pub site_latitude: f64,
+    pub site_longitude: f64,
+    pub ambient_temperature: f64,
+    pub cooling_temperature: f64,
+    pub cooling_minutes: f64,
+    pub filters: Vec<String>,
+    pub frames_per_filter: u32,
+    pub exposure_seconds: f64,
+    /// Dither after every this many frames; 0 disables dithering.
+    pub dither_every: u32,
+    pub autofocus_on_filter_change: bool,
+    pub meridian_flip_after_minutes: f64,
+    /// Median star HFR in pixels at perfect focus.
+    pub seeing_hfr: f64,
+    pub failures: Vec<ScriptedFailure>,
+}
+
+impl Default for NightPlan {
+    fn default() -> Self {
+        Self {
+            target_name: "Simulated target".to_string(),
+            project_name: Some("Demo".to_string()),
+            ra_hours: None,
+            dec_degrees: 41.269,
+            rotation: 90.0,
+            site_latitude: 38.661,
+            site_longitude: -121.166,
+            ambient_temperature: 15.0,
+            cooling_temperature: -10.0,
+            cooling_minutes: 10.0,
+            filters: ["L", "R", "G", "B"].map(str::to_string).to_vec(),
+            frames_per_filter: 6,
+            exposure_seconds: 300.0,
+            dither_every: 3,
+            autofocus_on_filter_change: true,
+            meridian_flip_after_minutes: 60.0,
+            seeing_hfr: 2.2,
+            failures: Vec::new(),
+        }
+    }
+}
+
+impl NightPlan {
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, crate::config::ConfigError> {
+        let plan: Self = crate::config::load_json_file(path)?;
+        Ok(plan)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.filters.is_empty() {
+            return Err("a night plan needs at least one filter".to_string());
+        }
+        if self.frames_per_filter == 0 {
+            return Err("frames_per_filter must be at least 1".to_string());
+        }
+        let durations = [
+            ("exposure_seconds", self.exposure_seconds),
+            ("cooling_minutes", self.cooling_minutes),
+            (
+                "meridian_flip_after_minutes",
+                self.meridian_flip_after_minutes,
+            ),
+            ("seeing_hfr", self.seeing_hfr),
+        ];
+        for (name, value) in durations {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("{name} must be a non-negative number"));
+            }
+        }
+        if self.exposure_seconds == 0.0 {
+            return Err("exposure_seconds must be positive".to_string());
+        }
+        if !(-90.0..=90.0).contains(&self.dec_degrees) {
+            return Err("dec_degrees must lie between -90 and 90".to_string());
+        }
+        if self
+            .failures
+            .iter()
+            .any(|failure| !failure.at_minutes.is_finite() || failure.at_minutes < 0.0)
+        {
+            return Err("scripted failures need a non-negative at_minutes".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Something going wrong `at_minutes` into the night.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptedFailure {
+    pub at_minutes: f64,
+    #[serde(flatten)]
+    pub kind: FailureKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The next autofocus run cannot fit a curve.
+    AutofocusFailed,
+    /// The next center attempt fails to plate solve and is retried.
+    PlateSolveFailed,
+    /// Guiding stops until the next frame completes.
+    GuideStarLost,
+    /// The next frame never finishes downloading and is retaken.
+    CameraDownloadTimeout,
+    /// Cloud passes over: stars vanish, the background brightens and
+    /// autofocus fails until it clears.
+    Clouds { minutes: f64 },
+    /// Every query fails while the rig is unreachable; the night goes on.
+    RigOffline { minutes: f64 },
+    /// An instruction fails with `message`.
+    InstructionFailed { message: String },
+}
+
+/// [`RigSource`] backed by a simulated night rather than N.I.N.A.
+pub struct SimulatedRigSource {
+    night: Mutex<Night>,
+    started: Instant,
+    time_scale: f64,
+    event_stream: bool,
+    updates: broadcast::Sender<RigUpdate>,
+}
+
+impl SimulatedRigSource {
+    /// Start `plan` now. The sequence begins immediately.
+    pub fn new(plan: NightPlan) -> Self {
+        let (updates, _) = broadcast::channel(UPDATE_BUFFER);
+        Self {
+            night: Mutex::new(Night::new(plan, 1)),
+            started: Instant::now(),
+            time_scale: 1.0,
+            event_stream: false,
+            updates,
+        }
+    }
+
+    /// Seed the noise generator; the default seed is 1.
+    pub fn with_seed(self, seed: u64) -> Self {
+        let plan = self.lock().plan.clone();
+        *self.lock() = Night::new(plan, seed);
+        self
+    }
+
+    /// Run `scale` simulated seconds per real second. Non-positive or
+    /// non-finite values are ignored.
+    pub fn with_time_scale(mut self, scale: f64) -> Self {
+        if scale.is_finite() && scale > 0.0 {
+            self.time_scale = scale;
+        }
+        self
+    }
+
+    /// Advertise `event_stream` and push every change to subscribers, so the
+    /// push path can be exercised as well as polling. Pushes only flow while
+    /// something advances the clock: a query or [`Self::run_clock`].
+    pub fn with_event_stream(mut self, enabled: bool) -> Self {
+        self.event_stream = enabled;
+        self
+    }
+
+    /// Simulated seconds since the night began.
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.started.elapsed().as_secs_f64() * self.time_scale
+    }
+
+    /// True once the sequence has finished and the mount is parked.
+    pub fn finished(&self) -> bool {
+        self.advance().sequence_done
+    }
+
+    /// Advance the night every `tick` so pushed updates flow between
+    /// queries. Runs until the task is dropped.
+    pub async fn run_clock(self: Arc<Self>, tick: Duration) {
+        let mut interval = tokio::time::interval(tick);
+        loop {
+            interval.tick().await;
+            drop(self.advance());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Night> {
+        self.night.lock().expect("simulated night poisoned")
+    }
+
+    /// Bring the night up to the current simulated time and publish what
+    /// happened while the rig is reachable.
+    fn advance(&self) -> MutexGuard<'_, Night> {
+        let mut night = self.lock();
+        night.advance_to(self.elapsed_seconds());
+        if !night.offline() && !night.pending.is_empty() {
+            let pending = std::mem::take(&mut night.pending);
+            if self.event_stream {
+                for update in pending {
+                    let _ = self.updates.send(update);
+                }
+                let _ = self.updates.send(RigUpdate::Sequence {
+                    sequence: night.sequence(),
+                });
+            }
+        }
+        night
+    }
+
+    fn reachable(&self) -> RigSourceResult<MutexGuard<'_, Night>> {
+        let night = self.advance();
+        if night.offline() {
+            return Err(RigSourceError::Unavailable {
+                kind: RigSourceKind::NinaDirect,
+                reason: "simulated rig is offline".to_string(),
+            });
+        }
+        Ok(night)
+    }
+}
+
+#[async_trait]
+impl RigSource for SimulatedRigSource {
+    fn kind(&self) -> RigSourceKind {
+        RigSourceKind::NinaDirect
+    }
+
+    fn capabilities(&self) -> RigCapabilities {
+        let connected = DeviceState::Connected;
+        RigCapabilities {
+            event_stream: self.event_stream,
+            command_kinds: Some(CommandKindSet::all()),
+            devices: DeviceHints {
+                mount: connected,
+                camera: connected,
+                filter_wheel: connected,
+                focuser: connected,
+                rotator: connected,
+                guider: connected,
+                dome: connected,
+                flat_device: connected,
+                switch: connected,
+            },
+            ..RigCapabilities::all()
+        }
+    }
+
+    async fn get_event_history(&self) -> RigSourceResult<EventHistoryResponse> {
+        let night = self.reachable()?;
+        Ok(EventHistoryResponse {
+            response: night.events.clone(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_all_image_history(&self) -> RigSourceResult<ImageHistoryResponse> {
+        let night = self.reachable()?;
+        Ok(ImageHistoryResponse {
+            response: night.images.clone(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_sequence(&self) -> RigSourceResult<SequenceResponse> {
+        Ok(self.reachable()?.sequence())
+    }
+
+    async fn get_thumbnail(&self, index: u32) -> RigSourceResult<ThumbnailResponse> {
+        let night = self.reachable()?;
+        let image =
+            night
+                .images
+                .get(index as usize)
+                .ok_or_else(|| RigSourceError::Unavailable {
+                    kind: RigSourceKind::NinaDirect,
+                    reason: format!("no image at index {index}"),
+                })?;
+        let data = render_thumbnail(image, night.seed ^ u64::from(index)).map_err(|e| {
+            RigSourceError::Unavailable {
+                kind: RigSourceKind::NinaDirect,
+                reason: e.to_string(),
+            }
+        })?;
+        Ok(ThumbnailResponse {
+            data,
+            content_type: "image/png".to_string(),
+            status_code: 200,
+        })
+    }
+
+    async fn get_last_autofocus(&self) -> RigSourceResult<AutofocusResponse> {
+        let night = self.reachable()?;
+        let autofocus =
+            night
+                .last_autofocus
+                .clone()
+                .ok_or_else(|| RigSourceError::Unavailable {
+                    kind: RigSourceKind::NinaDirect,
+                    reason: "no autofocus has run yet".to_string(),
+                })?;
+        Ok(AutofocusResponse {
+            response: autofocus,
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_mount_info(&self) -> RigSourceResult<MountInfoResponse> {
+        Ok(self.reachable()?.mount_info())
+    }
+
+    async fn get_camera_info(&self) -> RigSourceResult<CameraInfoResponse> {
+        let night = self.reachable()?;
+        let temperature = night.camera_temperature();
+        let cooler_on = night.cooler_on();
+        Ok(CameraInfoResponse {
+            response: CameraInfo {
+                connected: true,
+                can_set_temperature: true,
+                cooler_on,
+                cooler_power: if cooler_on {
+                    (30.0 + 3.0 * (night.ambient() - temperature)).clamp(0.0, 100.0)
+                } else {
+                    0.0
+                },
+                temperature,
+                temperature_set_point: night.cooler.to,
+                at_target_temp: cooler_on && (temperature - night.cooler.to).abs() < 0.5,
+                name: CAMERA_NAME.to_string(),
+                display_name: CAMERA_NAME.to_string(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_filterwheel_info(&self) -> RigSourceResult<FilterWheelInfoResponse> {
+        let night = self.reachable()?;
+        Ok(FilterWheelInfoResponse {
+            response: FilterWheelInfo {
+                connected: true,
+                name: "Simulated EFW".to_string(),
+                display_name: "Simulated EFW".to_string(),
+                is_moving: false,
+                selected_filter: Some(night.filter_info(night.selected_filter)),
+                available_filters: (0..night.plan.filters.len())
+                    .map(|index| night.filter_info(index))
+                    .collect(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_guider_info(&self) -> RigSourceResult<GuiderInfoResponse> {
+        let night = self.reachable()?;
+        let history = night.guide_history();
+        let axis = |pixel: f64| GuiderAxisError {
+            pixel,
+            arcseconds: pixel * GUIDE_PIXEL_SCALE,
+        };
+        let rms_error = history.rms.as_ref().map(|rms| GuiderRmsError {
+            ra: axis(rms.ra),
+            dec: axis(rms.dec),
+            total: axis(rms.total),
+            peak_ra: Some(axis(rms.peak_ra)),
+            peak_dec: Some(axis(rms.peak_dec)),
+        });
+        Ok(GuiderInfoResponse {
+            response: GuiderInfo {
+                connected: true,
+                name: "Simulated PHD2".to_string(),
+                display_name: "Simulated PHD2".to_string(),
+                state: if night.guiding_since.is_some() {
+                    "Guiding"
+                } else {
+                    "Stopped"
+                }
+                .to_string(),
+                pixel_scale: GUIDE_PIXEL_SCALE,
+                rms_error,
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_guider_graph(&self) -> RigSourceResult<GuiderGraphResponse> {
+        Ok(GuiderGraphResponse {
+            response: self.reachable()?.guide_history(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_rotator_info(&self) -> RigSourceResult<RotatorInfoResponse> {
+        let night = self.reachable()?;
+        Ok(RotatorInfoResponse {
+            response: RotatorInfo {
+                connected: true,
+                can_reverse: false,
+                reverse: false,
+                position: night.plan.rotation,
+                mechanical_position: (night.plan.rotation
+                    + if night.flipped { 180.0 } else { 0.0 })
+                .rem_euclid(360.0),
+                step_size: 0.5,
+                is_moving: false,
+                synced: true,
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_focuser_info(&self) -> RigSourceResult<FocuserInfoResponse> {
+        let night = self.reachable()?;
+        Ok(FocuserInfoResponse {
+            response: FocuserInfo {
+                connected: true,
+                position: night.focuser_position,
+                step_size: 1.0,
+                temperature: night.ambient(),
+                is_moving: matches!(night.phase, Phase::Autofocus { .. }),
+                is_settling: false,
+                temp_comp: night.temp_comp,
+                temp_comp_available: true,
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_dome_info(&self) -> RigSourceResult<DomeInfoResponse> {
+        let night = self.reachable()?;
+        let shutter = if night.shutter_open {
+            "ShutterOpen"
+        } else {
+            "ShutterClosed"
+        };
+        let azimuth = if night.dome_parked {
+            DOME_PARK_AZIMUTH
+        } else {
+            // A slaved dome follows the mount; otherwise it stays put.
+            let (ra, dec) = night.pointing();
+            alt_az(ra, dec, night.local_sidereal(), night.plan.site_latitude).1
+        };
+        Ok(DomeInfoResponse {
+            response: DomeInfo {
+                connected: true,
+                shutter_status: shutter.to_string(),
+                can_set_shutter: true,
+                can_park: true,
+                can_find_home: true,
+                can_set_azimuth: true,
+                at_park: night.dome_parked,
+                at_home: false,
+                slewing: false,
+                azimuth,
+                is_following: night.dome_slaved,
+                name: "Simulated roll-off".to_string(),
+                display_name: "Simulated roll-off".to_string(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_flat_device_info(&self) -> RigSourceResult<FlatDeviceInfoResponse> {
+        let night = self.reachable()?;
+        let cover = if night.flat_cover_open {
+            "Open"
+        } else {
+            "Closed"
+        };
+        Ok(FlatDeviceInfoResponse {
+            response: FlatDeviceInfo {
+                connected: true,
+                cover_state: cover.to_string(),
+                light_on: night.flat_light_on,
+                brightness: night.flat_brightness,
+                min_brightness: 0,
+                max_brightness: FLAT_MAX_BRIGHTNESS,
+                supports_open_close: true,
+                supports_on_off: true,
+                name: "Simulated Flip-Flat".to_string(),
+                display_name: "Simulated Flip-Flat".to_string(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_switch_info(&self) -> RigSourceResult<SwitchInfoResponse> {
+        let night = self.reachable()?;
+        let writable_switches = SWITCH_PORTS
+            .iter()
+            .zip(night.switch_values)
+            .enumerate()
+            .map(|(id, ((name, maximum, _), value))| Switch {
+                id: id as u32,
+                name: name.to_string(),
+                description: String::new(),
+                value,
+                minimum: 0.0,
+                maximum: *maximum,
+                step_size: 1.0,
+            })
+            .collect();
+        let voltage = Switch {
+            id: SWITCH_PORTS.len() as u32,
+            name: "Input voltage".to_string(),
+            description: "V".to_string(),
+            value: SWITCH_INPUT_VOLTAGE,
+            minimum: 0.0,
+            maximum: 0.0,
+            step_size: 0.0,
+        };
+        Ok(SwitchInfoResponse {
+            response: SwitchInfo {
+                connected: true,
+                writable_switches,
+                readonly_switches: vec![voltage],
+                name: "Simulated power box".to_string(),
+                display_name: "Simulated power box".to_string(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_weather_info(&self) -> RigSourceResult<WeatherInfoResponse> {
+        let night = self.reachable()?;
+        Ok(WeatherInfoResponse {
+            response: night.weather(),
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn get_safety_info(&self) -> RigSourceResult<SafetyInfoResponse> {
+        let night = self.reachable()?;
+        Ok(SafetyInfoResponse {
+            response: SafetyInfo {
+                connected: true,
+                is_safe: !night.cloudy(),
+                name: "Simulated safety monitor".to_string(),
+                display_name: "Simulated safety monitor".to_string(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        })
+    }
+
+    async fn execute_command(&self, command: RigCommand) -> RigSourceResult<CommandResponse> {
+        Ok(self.reachable()?.execute(command))
+    }
+
+    fn subscribe_updates(&self) -> Option<broadcast::Receiver<RigUpdate>> {
+        self.event_stream.then(|| self.updates.subscribe())
+    }
+}
+
+const CAMERA_NAME: &str = "Simulated ASI2600MM";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Idle,
+    Cooling,
+    Centering,
+    /// Measuring point `step`; `resume` continues imaging afterwards.
+    Autofocus {
+        step: usize,
+        resume: bool,
+    },
+    Exposing,
+    Flipping,
+}
+
+/// A camera temperature ramp from `from` to `to` starting at `start`.
+#[derive(Debug, Clone, Copy)]
+struct CoolerRamp {
+    from: f64,
+    to: f64,
+    start: f64,
+    seconds: f64,
+    /// Whether the cooler stays on once the ramp ends (false for warming).
+    hold: bool,
+}
+
+struct Night {
+    plan: NightPlan,
+    seed: u64,
+    rng: SplitMix64,
+    started_wall: chrono::DateTime<Utc>,
+    now: f64,
+    phase: Phase,
+    phase_ends: f64,
+    sequence_running: bool,
+    sequence_done: bool,
+    cooled: bool,
+    target_started: bool,
+    events: Vec<Event>,
+    last_event_ms: i64,
+    images: Vec<ImageMetadata>,
+    pending: Vec<RigUpdate>,
+    cooler: CoolerRamp,
+    selected_filter: usize,
+    filter_stage: usize,
+    frames_on_filter: u32,
+    frames_total: u32,
+    parked: bool,
+    tracking: bool,
+    slewing: bool,
+    flipped: bool,
+    flip_at: f64,
+    target_ra: f64,
+    guiding_since: Option<f64>,
+    dithers: Vec<f64>,
+    focuser_position: i32,
+    temp_comp: bool,
+    shutter_open: bool,
+    dome_parked: bool,
+    dome_slaved: bool,
+    flat_cover_open: bool,
+    flat_light_on: bool,
+    flat_brightness: i32,
+    switch_values: [f64; SWITCH_PORTS.len()],
+    autofocus_start_position: i32,
+    autofocus_points: Vec<FocusPoint>,
+    autofocus_failing: bool,
+    /// A filter change asked for autofocus before the next frame.
+    autofocus_due: bool,
+    last_autofocus: Option<AutofocusData>,
+    failures: VecDeque<ScriptedFailure>,
+    offline_until: f64,
+    clouds_until: f64,
+    autofocus_fails: bool,
+    plate_solve_fails: bool,
+    download_times_out: bool,
+}
+
+impl Night {
+    fn new(plan: NightPlan, seed: u64) -> Self {
+        let started_wall = Utc::now();
+        let mut failures: Vec<ScriptedFailure> = plan.failures.clone();
+        failures.sort_by(|a, b| a.at_minutes.total_cmp(&b.at_minutes));
+        let flip_after = plan.meridian_flip_after_minutes * 60.0;
+        let (target_ra, flip_at) = match plan.ra_hours {
+            Some(ra) => {
+                let lst = local_sidereal_hours(started_wall, plan.site_longitude);
+                let hours = (ra - lst).rem_euclid(24.0) / SIDEREAL_RATE;
+                (ra.rem_euclid(24.0), hours * 3600.0)
+            }
+            None => {
+                let transit = started_wall + chrono::Duration::seconds(flip_after as i64);
+                (
+                    local_sidereal_hours(transit, plan.site_longitude),
+                    flip_after,
+                )
+            }
+        };
+        let ambient = plan.ambient_temperature;
+        let mut night = Self {
+            seed,
+            rng: SplitMix64(seed),
+            started_wall,
+            now: 0.0,
+            phase: Phase::Idle,
+            phase_ends: f64::INFINITY,
+            sequence_running: false,
+            sequence_done: false,
+            cooled: false,
+            target_started: false,
+            events: Vec::new(),
+            last_event_ms: i64::MIN,
+            images: Vec::new(),
+            pending: Vec::new(),
+            cooler: CoolerRamp {
+                from: ambient,
+                to: ambient,
+                start: 0.0,
+                seconds: 0.0,
+                hold: false,
+            },
+            selected_filter: 0,
+            filter_stage: 0,
+            frames_on_filter: 0,
+            frames_total: 0,
+            parked: true,
+            tracking: false,
+            slewing: false,
+            flipped: false,
+            flip_at,
+            target_ra,
+            guiding_since: None,
+            dithers: Vec::new(),
+            focuser_position: FOCUS_BEST_POSITION as i32 + 60,
+            temp_comp: false,
+            shutter_open: true,
+            dome_parked: false,
+            dome_slaved: true,
+            flat_cover_open: true,
+            flat_light_on: false,
+            flat_brightness: 0,
+            switch_values: SWITCH_PORTS.map(|(_, _, on)| on),
+            autofocus_start_position: 0,
+            autofocus_points: Vec::new(),
+            autofocus_failing: false,
+            autofocus_due: false,
+            last_autofocus: None,
+            failures: failures.into(),
+            offline_until: 0.0,
+            clouds_until: 0.0,
+            autofocus_fails: false,
+            plate_solve_fails: false,
+            download_times_out: false,
+            plan,
+        };
+        night.start_sequence();
+        night
+    }
+
+    fn offline(&self) -> bool {
+        self.now < self.offline_until
+    }
+
+    fn cloudy(&self) -> bool {
+        self.now < self.clouds_until
+    }
+
+    fn ambient(&self) -> f64 {
+        self.plan.ambient_temperature - AMBIENT_DROP_PER_HOUR * self.now / 3600.0
+    }
+
+    fn best_focus(&self) -> f64 {
+        FOCUS_BEST_POSITION
+            + FOCUS_STEPS_PER_DEGREE * (self.ambient() - self.plan.ambient_temperature)
+    }
+
+    fn camera_temperature(&self) -> f64 {
+        let ramp = &self.cooler;
+        let elapsed = (self.now - ramp.start).max(0.0);
+        if ramp.seconds <= 0.0 {
+            return ramp.to;
+        }
+        // Exponential approach that lands within 2% of the set point when
+        // the requested duration has passed.
+        let tau = ramp.seconds / 4.0;
+        ramp.to + (ramp.from - ramp.to) * (-elapsed / tau).exp()
+    }
+
+    fn cooler_on(&self) -> bool {
+        self.cooler.hold || self.now < self.cooler.start + self.cooler.seconds
+    }
+
+    fn set_cooler(&mut self, to: f64, seconds: f64, hold: bool) {
+        self.cooler = CoolerRamp {
+            from: self.camera_temperature(),
+            to,
+            start: self.now,
+            seconds,
+            hold,
+        };
+    }
+
+    fn filter_info(&self, index: usize) -> FilterInfo {
+        FilterInfo {
+            name: self.plan.filters[index].clone(),
+            id: index as i32,
+        }
+    }
+
+    fn wall_time(&self, at: f64) -> chrono::DateTime<Utc> {
+        self.started_wall + chrono::Duration::milliseconds((at * 1000.0) as i64)
+    }
+
+    fn nina_time(&self, at: f64) -> String {
+        self.wall_time(at)
+            .with_timezone(&Local)
+            .format("%Y-%m-%dT%H:%M:%S%.6f%:z")
+            .to_string()
+    }
+
+    // --- Clock ----------------------------------------------------------
+
+    /// Run every phase transition and scripted failure up to `to`.
+    fn advance_to(&mut self, to: f64) {
+        loop {
+            let next_failure = self
+                .failures
+                .front()
+                .map_or(f64::INFINITY, |failure| failure.at_minutes * 60.0);
+            let next = self.phase_ends.min(next_failure);
+            if next > to {
+                break;
+            }
+            self.now = self.now.max(next);
+            if next_failure <= self.phase_ends {
+                let failure = self.failures.pop_front().expect("failure is due");
+                self.apply_failure(failure.kind);
+            } else {
+                self.complete_phase();
+            }
+        }
+        self.now = self.now.max(to);
+    }
+
+    fn enter(&mut self, phase: Phase, seconds: f64) {
+        self.phase = phase;
+        self.phase_ends = if phase == Phase::Idle {
+            f64::INFINITY
+        } else {
+            self.now + seconds
+        };
+    }
+
+    fn emit(&mut self, name: &str, details: Option<EventDetails>) {
+        // Events that happen in the same instant still get distinct, ordered
+        // timestamps, which the updater's dedupe keys on.
+        let at_ms = self.wall_time(self.now).timestamp_millis();
+        self.last_event_ms = at_ms.max(self.last_event_ms + 1);
+        let at = (self.last_event_ms - self.started_wall.timestamp_millis()) as f64 / 1000.0;
+        let event = Event {
+            time: self.nina_time(at),
+            event: name.to_string(),
+            chat_enabled: true,
+            details,
+        };
+        self.pending.push(RigUpdate::Event {
+            event: event.clone(),
+        });
+        self.events.push(event);
+    }
+
+    fn complete_phase(&mut self) {
+        match self.phase {
+            Phase::Idle => self.phase_ends = f64::INFINITY,
+            Phase::Cooling => {
+                self.cooled = true;
+                self.start_centering();
+            }
+            Phase::Centering => self.finish_centering(),
+            Phase::Autofocus { step, resume } => self.measure_focus_point(step, resume),
+            Phase::Exposing => self.finish_exposure(),
+            Phase::Flipping => {
+                self.flipped = true;
+                self.emit(event_types::MOUNT_AFTER_FLIP, None);
+                self.start_centering();
+            }
+        }
+    }
+
+    fn start_sequence(&mut self) {
+        self.sequence_running = true;
+        self.sequence_done = false;
+        self.cooled = false;
+        self.target_started = false;
+        self.filter_stage = 0;
+        self.frames_on_filter = 0;
+        self.emit(event_types::SEQUENCE_STARTING, None);
+        if self.parked {
+            self.unpark();
+        }
+        let at_temperature = self.cooler_on()
+            && (self.camera_temperature() - self.plan.cooling_temperature).abs() < 0.5;
+        let seconds = if at_temperature {
+            0.0
+        } else {
+            self.plan.cooling_minutes * 60.0
+        };
+        self.set_cooler(self.plan.cooling_temperature, seconds, true);
+        self.enter(Phase::Cooling, seconds);
+    }
+
+    fn stop_sequence(&mut self) {
+        self.sequence_running = false;
+        self.slewing = false;
+        self.stop_guiding();
+        self.enter(Phase::Idle, 0.0);
+    }
+
+    fn finish_sequence(&mut self) {
+        self.stop_sequence();
+        self.sequence_done = true;
+        self.emit(event_types::SEQUENCE_FINISHED, None);
+        self.park();
+        self.set_cooler(self.ambient(), self.plan.cooling_minutes * 60.0, false);
+    }
+
+    fn unpark(&mut self) {
+        self.parked = false;
+        self.tracking = true;
+        self.emit(event_types::MOUNT_UNPARKED, None);
+    }
+
+    fn park(&mut self) {
+        self.parked = true;
+        self.tracking = false;
+        self.slewing = false;
+        self.emit(event_types::MOUNT_PARKED, None);
+    }
+
+    fn start_centering(&mut self) {
+        self.slewing = true;
+        self.enter(Phase::Centering, CENTER_SECONDS);
+    }
+
+    fn finish_centering(&mut self) {
+        self.slewing = false;
+        if self.plate_solve_fails {
+            self.plate_solve_fails = false;
+            self.emit(event_types::ERROR_PLATESOLVE, None);
+            self.start_centering();
+            return;
+        }
+        self.emit(event_types::MOUNT_CENTER, None);
+        if !self.target_started {
+            self.target_started = true;
+            let details = EventDetails::TargetStart {
+                target_name: self.plan.target_name.clone(),
+                project_name: self.plan.project_name.clone(),
+                rotation: Some(self.plan.rotation),
+                target_end_time: None,
+                coordinates: Some(self.target_coordinates()),
+            };
+            self.emit(event_types::TS_TARGETSTART, Some(details));
+            self.start_guiding();
+            self.start_autofocus(true);
+        } else {
+            self.start_guiding();
+            if self.autofocus_due {
+                self.start_autofocus(true);
+            } else {
+                self.start_exposure();
+            }
+        }
+    }
+
+    fn target_coordinates(&self) -> TargetCoordinates {
+        TargetCoordinates {
+            ra: self.target_ra,
+            dec: self.plan.dec_degrees,
+            ra_string: format_hours(self.target_ra),
+            dec_string: format_degrees(self.plan.dec_degrees),
+            epoch: "J2000".to_string(),
+            ra_degrees: self.target_ra * 15.0,
+        }
+    }
+
+    fn start_guiding(&mut self) {
+        if self.guiding_since.is_none() {
+            self.guiding_since = Some(self.now);
+            self.emit(event_types::GUIDER_START, None);
+        }
+    }
+
+    fn stop_guiding(&mut self) {
+        if self.guiding_since.take().is_some() {
+            self.emit(event_types::GUIDER_STOP, None);
+        }
+    }
+
+    fn select_filter(&mut self, index: usize) {
+        if index == self.selected_filter {
+            return;
+        }
+        let details = EventDetails::FilterWheelChange {
+            new: self.filter_info(index),
+            previous: self.filter_info(self.selected_filter),
+        };
+        self.selected_filter = index;
+        self.emit(event_types::FILTERWHEEL_CHANGED, Some(details));
+    }
+
+    fn start_exposure(&mut self) {
+        self.select_filter(self.filter_stage);
+        self.enter(
+            Phase::Exposing,
+            self.plan.exposure_seconds + DOWNLOAD_SECONDS,
+        );
+    }
+
+    fn finish_exposure(&mut self) {
+        if self.download_times_out {
+            self.download_times_out = false;
+            self.emit(event_types::CAMERA_DOWNLOAD_TIMEOUT, None);
+            self.start_exposure();
+            return;
+        }
+        self.save_image(image_types::LIGHT, self.plan.exposure_seconds, 100);
+        self.frames_on_filter += 1;
+        self.frames_total += 1;
+        if self.guiding_since.is_none() {
+            // Recover from a lost guide star between frames.
+            self.start_guiding();
+        } else if self.plan.dither_every > 0
+            && self.frames_total.is_multiple_of(self.plan.dither_every)
+        {
+            self.dithers.push(self.now);
+            self.emit(event_types::GUIDER_DITHER, None);
+        }
+
+        if self.frames_on_filter >= self.plan.frames_per_filter {
+            self.frames_on_filter = 0;
+            self.filter_stage += 1;
+            if self.filter_stage >= self.plan.filters.len() {
+                self.finish_sequence();
+                return;
+            }
+            self.select_filter(self.filter_stage);
+            self.autofocus_due = self.plan.autofocus_on_filter_change;
+        }
+        if !self.flipped && self.now >= self.flip_at {
+            self.stop_guiding();
+            self.emit(event_types::MOUNT_BEFORE_FLIP, None);
+            self.slewing = true;
+            self.enter(Phase::Flipping, FLIP_SECONDS);
+            return;
+        }
+        if self.autofocus_due {
+            self.start_autofocus(true);
+            return;
+        }
+        self.start_exposure();
+    }
+
+    fn save_image(&mut self, image_type: &str, exposure_seconds: f64, gain: i32) {
+        let filter = self.plan.filters[self.selected_filter].clone();
+        let (brightness, star_factor) = filter_response(&filter);
+        let offset = (f64::from(self.focuser_position) - self.best_focus()) / FOCUS_WIDTH;
+        let seeing = (self.plan.seeing_hfr + 0.1 * self.rng.gaussian()).max(0.8);
+        let mut hfr = seeing * (1.0 + offset * offset).sqrt();
+        let mut stars = 1400.0 * star_factor * (self.plan.seeing_hfr / hfr).powi(2);
+        let mut median = brightness * (1.0 + 0.03 * self.rng.gaussian());
+        if self.cloudy() {
+            hfr *= 1.3;
+            stars *= 0.08;
+            median *= 1.8;
+        }
+        let stars = (stars * (1.0 + 0.05 * self.rng.gaussian())).max(0.0) as i32;
+        let rms = self.guide_history().rms;
+        let rms_text = rms.map_or_else(|| "Tot: 0.00 (0.00\")".to_string(), |rms| rms.total_text);
+        let image = ImageMetadata {
+            chat_enabled: true,
+            exposure_time: exposure_seconds,
+            image_type: image_type.to_string(),
+            filter,
+            rms_text,
+            temperature: (self.camera_temperature() * 10.0).round() / 10.0,
+            camera_name: CAMERA_NAME.to_string(),
+            gain,
+            offset: 50,
+            date: self.nina_time(self.now),
+            telescope_name: "Simulated 107mm APO".to_string(),
+            focal_length: 749,
+            st_dev: median * 0.4,
+            mean: median * 1.05,
+            median: median.round(),
+            stars,
+            hfr,
+            is_bayered: false,
+        };
+        let index = self.images.len() as u32;
+        self.pending.push(RigUpdate::Image {
+            index,
+            image: image.clone(),
+        });
+        self.images.push(image);
+        self.emit(event_types::IMAGE_SAVE, None);
+    }
+
+    // --- Autofocus ------------------------------------------------------
+
+    fn start_autofocus(&mut self, resume: bool) {
+        self.autofocus_due = false;
+        self.autofocus_points.clear();
+        self.autofocus_start_position = self.focuser_position;
+        self.autofocus_failing = std::mem::take(&mut self.autofocus_fails) || self.cloudy();
+        self.emit(event_types::AUTOFOCUS_STARTING, None);
+        self.enter(Phase::Autofocus { step: 0, resume }, AUTOFOCUS_STEP_SECONDS);
+    }
+
+    fn measure_focus_point(&mut self, step: usize, resume: bool) {
+        let half = (AUTOFOCUS_POINTS / 2) as i32;
+        let position = self.autofocus_start_position + (step as i32 - half) * AUTOFOCUS_STEP_SIZE;
+        let hfr = if self.autofocus_failing {
+            (4.0 + 0.8 * self.rng.gaussian()).max(0.5)
+        } else {
+            let offset = (f64::from(position) - self.best_focus()) / FOCUS_WIDTH;
+            self.plan.seeing_hfr * (1.0 + offset * offset).sqrt() + 0.04 * self.rng.gaussian()
+        };
+        self.autofocus_points.push(FocusPoint {
+            position,
+            value: hfr,
+            error: 0.05 + 0.02 * self.rng.next_f64(),
+        });
+        self.emit(
+            event_types::AUTOFOCUS_POINT_ADDED,
+            Some(EventDetails::AutofocusPointAdded { position, hfr }),
+        );
+        if step + 1 < AUTOFOCUS_POINTS {
+            self.enter(
+                Phase::Autofocus {
+                    step: step + 1,
+                    resume,
+                },
+                AUTOFOCUS_STEP_SECONDS,
+            );
+            return;
+        }
+
+        let result = self.fit_autofocus();
+        let succeeded = result.calculated_focus_point.error == 0.0;
+        self.focuser_position = result.calculated_focus_point.position;
+        self.last_autofocus = Some(result);
+        if succeeded {
+            self.emit(event_types::AUTOFOCUS_FINISHED, None);
+        } else {
+            self.emit(event_types::ERROR_AF, None);
+        }
+        if resume {
+            self.start_exposure();
+        } else {
+            self.enter(Phase::Idle, 0.0);
+        }
+    }
+
+    /// Summarize the measured points the way N.I.N.A.'s hyperbolic fit does.
+    fn fit_autofocus(&mut self) -> AutofocusData {
+        let points = self.autofocus_points.clone();
+        let a = self.plan.seeing_hfr;
+        let b = self.best_focus() + 5.0 * self.rng.gaussian();
+        let hyperbola = |x: f64| a * (1.0 + ((x - b) / FOCUS_WIDTH).powi(2)).sqrt();
+        // Second-order expansion of the hyperbola around its minimum.
+        let qa = a / (2.0 * FOCUS_WIDTH * FOCUS_WIDTH);
+        let qb = -2.0 * qa * b;
+        let qc = a + qa * b * b;
+        let quadratic = |x: f64| qa * x * x + qb * x + qc;
+        let left: Vec<&FocusPoint> = points
+            .iter()
+            .filter(|p| f64::from(p.position) < b)
+            .collect();
+        let right: Vec<&FocusPoint> = points
+            .iter()
+            .filter(|p| f64::from(p.position) > b)
+            .collect();
+        let (left_slope, left_intercept, left_r2) = linear_fit(&left);
+        let (right_slope, right_intercept, right_r2) = linear_fit(&right);
+        let hyperbolic_r2 = r_squared(&points, hyperbola);
+        let quadratic_r2 = r_squared(&points, quadratic);
+
+        let failed = self.autofocus_failing || hyperbolic_r2 < 0.8;
+        let initial = FocusPoint {
+            position: self.autofocus_start_position,
+            value: f64::NAN,
+            error: 0.0,
+        };
+        let calculated = if failed {
+            FocusPoint {
+                position: self.autofocus_start_position,
+                value: f64::NAN,
+                error: 1.0,
+            }
+        } else {
+            FocusPoint {
+                position: b.round() as i32,
+                value: a,
+                error: 0.0,
+            }
+        };
+        let intersection = |position: f64, value: f64| IntersectionPoint {
+            position,
+            value,
+            error: 0.0,
+        };
+        let trend_x = (right_intercept - left_intercept) / (left_slope - right_slope);
+        let intersections = if failed {
+            Intersections {
+                trend_line_intersection: None,
+                hyperbolic_minimum: None,
+                quadratic_minimum: None,
+                gaussian_maximum: None,
+            }
+        } else {
+            Intersections {
+                trend_line_intersection: trend_x
+                    .is_finite()
+                    .then(|| intersection(trend_x.round(), left_slope * trend_x + left_intercept)),
+                hyperbolic_minimum: Some(intersection(b.round(), a)),
+                quadratic_minimum: Some(intersection(b.round(), quadratic(b))),
+                gaussian_maximum: None,
+            }
+        };
+        let elapsed = AUTOFOCUS_STEP_SECONDS * AUTOFOCUS_POINTS as f64;
+        AutofocusData {
+            version: 2,
+            filter: self.plan.filters[self.selected_filter].clone(),
+            auto_focuser_name: "NINA".to_string(),
+            star_detector_name: "NINA".to_string(),
+            timestamp: self.nina_time(self.now),
+            temperature: (self.ambient() * 10.0).round() / 10.0,
+            method: "STARHFR".to_string(),
+            fitting: "HYPERBOLIC".to_string(),
+            previous_focus_point: self.last_autofocus.as_ref().map_or_else(
+                || initial.clone(),
+                |last| last.calculated_focus_point.clone(),
+            ),
+            initial_focus_point: initial,
+            calculated_focus_point: calculated,
+            measure_points: points,
+            intersections,
+            fittings: crate::autofocus::Fittings {
+                quadratic: format!("y = {qa} * x^2 + {qb} * x + {qc}"),
+                hyperbolic: format!("y = {a} * cosh(asinh(({b} - x) / {FOCUS_WIDTH}))"),
+                gaussian: String::new(),
+                left_trend: format!("y = {left_slope} * x + {left_intercept}"),
+                right_trend: format!("y = {right_slope} * x + {right_intercept}"),
+            },
+            r_squares: RSquares {
+                quadratic: quadratic_r2,
+                hyperbolic: hyperbolic_r2,
+                left_trend: left_r2,
+                right_trend: right_r2,
+            },
+            backlash_compensation: BacklashCompensation {
+                backlash_compensation_model: "OVERSHOOT".to_string(),
+                backlash_in: 0,
+                backlash_out: 20,
+            },
+            duration: format!("00:{:02}:{:02}", elapsed as u32 / 60, elapsed as u32 % 60),
+        }
+    }
+
+    // --- Failures and commands ------------------------------------------
+
+    fn apply_failure(&mut self, kind: FailureKind) {
+        match kind {
+            FailureKind::AutofocusFailed => self.autofocus_fails = true,
+            FailureKind::PlateSolveFailed => self.plate_solve_fails = true,
+            FailureKind::GuideStarLost => {
+                if self.guiding_since.is_some() {
+                    self.emit(
+                        event_types::NINA_NOTIFICATION,
+                        Some(EventDetails::NinaNotification {
+                            level: "Warning".to_string(),
+                            header: "Guider".to_string(),
+                            message: "Guide star lost".to_string(),
+                        }),
+                    );
+                    self.stop_guiding();
+                }
+            }
+            FailureKind::CameraDownloadTimeout => self.download_times_out = true,
+            FailureKind::Clouds { minutes } => self.clouds_until = self.now + minutes * 60.0,
+            FailureKind::RigOffline { minutes } => {
+                self.offline_until = self.now + minutes * 60.0;
+            }
+            FailureKind::InstructionFailed { message } => {
+                self.emit(
+                    event_types::NINA_NOTIFICATION,
+                    Some(EventDetails::NinaNotification {
+                        level: "Error".to_string(),
+                        header: "Sequence".to_string(),
+                        message,
+                    }),
+                );
+                self.emit(event_types::SEQUENCE_ENTITY_FAILED, None);
+            }
+        }
+    }
+
+    fn execute(&mut self, command: RigCommand) -> CommandResponse {
+        match command {
+            RigCommand::UnparkMount => {
+                if !self.parked {
+                    return command_ok("mount is already unparked");
+                }
+                self.unpark();
+                command_ok("mount unparked")
+            }
+            RigCommand::ParkMount => {
+                if self.parked {
+                    return command_ok("mount is already parked");
+                }
+                if self.sequence_running {
+                    self.stop_sequence();
+                }
+                self.park();
+                command_ok("mount parked")
+            }
+            RigCommand::HomeMount => {
+                if self.parked {
+                    return command_refused("mount is parked");
+                }
+                self.emit(event_types::MOUNT_HOMED, None);
+                command_ok("mount homed")
+            }
+            RigCommand::ChangeFilter { filter_id } => {
+                let Some(index) = usize::try_from(filter_id)
+                    .ok()
+                    .filter(|index| *index < self.plan.filters.len())
+                else {
+                    return command_refused(&format!("no filter with id {filter_id}"));
+                };
+                self.select_filter(index);
+                command_ok(&format!("filter set to {}", self.plan.filters[index]))
+            }
+            RigCommand::StartGuiding { .. } => {
+                if self.parked {
+                    return command_refused("mount is parked");
+                }
+                self.start_guiding();
+                command_ok("guiding")
+            }
+            RigCommand::StopGuiding => {
+                self.stop_guiding();
+                command_ok("guiding stopped")
+            }
+            RigCommand::CoolCamera {
+                temperature,
+                minutes,
+            } => {
+                if !temperature.is_finite() || !minutes.is_finite() || minutes < 0.0 {
+                    return command_refused("invalid cooling request");
+                }
+                self.set_cooler(temperature, minutes * 60.0, true);
+                command_ok(&format!("cooling to {temperature:.1} °C"))
+            }
+            RigCommand::WarmCamera { minutes } => {
+                if !minutes.is_finite() || minutes < 0.0 {
+                    return command_refused("invalid warming request");
+                }
+                self.set_cooler(self.ambient(), minutes * 60.0, false);
+                command_ok("warming")
+            }
+            RigCommand::StartAutofocus => match self.phase {
+                _ if self.parked => command_refused("mount is parked"),
+                Phase::Idle => {
+                    self.start_autofocus(false);
+                    command_ok("autofocus started")
+                }
+                Phase::Exposing => {
+                    // The running frame is discarded, as in N.I.N.A.
+                    self.start_autofocus(true);
+                    command_ok("autofocus started")
+                }
+                _ => command_refused("the rig is busy"),
+            },
+            RigCommand::CancelAutofocus => match self.phase {
+                Phase::Autofocus { resume, .. } => {
+                    self.focuser_position = self.autofocus_start_position;
+                    if resume {
+                        self.start_exposure();
+                    } else {
+                        self.enter(Phase::Idle, 0.0);
+                    }
+                    command_ok("autofocus cancelled")
+                }
+                _ => command_refused("no autofocus is running"),
+            },
+            RigCommand::AbortExposure => {
+                if self.phase != Phase::Exposing {
+                    return command_refused("no exposure is running");
+                }
+                self.start_exposure();
+                command_ok("exposure aborted")
+            }
+            RigCommand::StopSequence => {
+                if !self.sequence_running {
+                    return command_refused("no sequence is running");
+                }
+                self.stop_sequence();
+                command_ok("sequence stopped")
+            }
+            RigCommand::StartSequence { .. } => {
+                if self.sequence_running {
+                    return command_refused("the sequence is already running");
+                }
+                self.start_sequence();
+                command_ok("sequence started")
+            }
+            RigCommand::SlewToCoordinates {
+                ra_hours,
+                dec_degrees,
+                center,
+            } => {
+                if self.parked {
+                    return command_refused("mount is parked");
+                }
+                if self.sequence_running {
+                    return command_refused("the sequence is running");
+                }
+                if !(0.0..24.0).contains(&ra_hours) || !(-90.0..=90.0).contains(&dec_degrees) {
+                    return command_refused("coordinates out of range");
+                }
+                // Slews land instantly; the sequence is stopped, so there
+                // is no exposure to resume after centering.
+                let lst = self.local_sidereal();
+                self.target_ra = ra_hours;
+                self.plan.dec_degrees = dec_degrees;
+                self.flip_at =
+                    self.now + (ra_hours - lst).rem_euclid(24.0) / SIDEREAL_RATE * 3600.0;
+                self.flipped = false;
+                self.tracking = true;
+                if center {
+                    self.emit(event_types::MOUNT_CENTER, None);
+                }
+                command_ok(&format!(
+                    "slewed to {} {}",
+                    format_hours(ra_hours),
+                    format_degrees(dec_degrees)
+                ))
+            }
+            RigCommand::SetTracking { enabled, .. } => {
+                if enabled && self.parked {
+                    return command_refused("mount is parked");
+                }
+                self.tracking = enabled;
+                command_ok(if enabled {
+                    "tracking"
+                } else {
+                    "tracking stopped"
+                })
+            }
+            RigCommand::StopSlew => {
+                self.slewing = false;
+                command_ok("slew stopped")
+            }
+            RigCommand::MoveFocuser { target } => {
+                if matches!(self.phase, Phase::Autofocus { .. }) {
+                    return command_refused("autofocus is running");
+                }
+                if self.temp_comp {
+                    return command_refused("temperature compensation is on");
+                }
+                let position = match target {
+                    FocuserTarget::Position(position) => Some(position),
+                    FocuserTarget::RelativeSteps(steps) => self.focuser_position.checked_add(steps),
+                };
+                let Some(position) = position.filter(|position| *position >= 0) else {
+                    return command_refused("focuser position out of range");
+                };
+                self.focuser_position = position;
+                command_ok(&format!("focuser at {position}"))
+            }
+            RigCommand::SetFocuserTempComp { enabled } => {
+                self.temp_comp = enabled;
+                command_ok(if enabled {
+                    "temperature compensation on"
+                } else {
+                    "temperature compensation off"
+                })
+            }
+            RigCommand::MoveRotator {
+                position_angle,
+                mechanical,
+            } => {
+                if !(0.0..360.0).contains(&position_angle) {
+                    return command_refused("angle out of range");
+                }
+                let flip = if self.flipped { 180.0 } else { 0.0 };
+                self.plan.rotation = if mechanical {
+                    (position_angle - flip).rem_euclid(360.0)
+                } else {
+                    position_angle
+                };
+                command_ok(&format!("rotator at {:.1}°", self.plan.rotation))
+            }
+            RigCommand::CaptureSnapshot {
+                exposure_seconds,
+                filter_id,
+                gain,
+                ..
+            } => {
+                if self.phase != Phase::Idle {
+                    return command_refused("the camera is busy");
+                }
+                if !exposure_seconds.is_finite() || exposure_seconds <= 0.0 {
+                    return command_refused("invalid exposure time");
+                }
+                if let Some(filter_id) = filter_id {
+                    let Some(index) = usize::try_from(filter_id)
+                        .ok()
+                        .filter(|index| *index < self.plan.filters.len())
+                    else {
+                        return command_refused(&format!("no filter with id {filter_id}"));
+                    };
+                    self.select_filter(index);
+                }
+                // The simulated camera downloads instantly.
+                self.save_image(image_types::SNAPSHOT, exposure_seconds, gain.unwrap_or(100));
+                command_ok("snapshot saved")
+            }
+            RigCommand::OpenDomeShutter => {
+                if self.shutter_open {
+                    return command_ok("shutter is already open");
+                }
+                self.shutter_open = true;
+                self.emit(event_types::DOME_SHUTTER_OPENED, None);
+                command_ok("shutter opened")
+            }
+            RigCommand::CloseDomeShutter => {
+                if !self.shutter_open {
+                    return command_ok("shutter is already closed");
+                }
+                self.shutter_open = false;
+                self.emit(event_types::DOME_SHUTTER_CLOSED, None);
+                command_ok("shutter closed")
+            }
+            RigCommand::ParkDome => {
+                self.dome_slaved = false;
+                if !self.dome_parked {
+                    self.dome_parked = true;
+                    self.emit(event_types::DOME_PARKED, None);
+                }
+                command_ok("dome parked")
+            }
+            RigCommand::SetDomeSlaving { enabled } => {
+                self.dome_slaved = enabled;
+                if enabled {
+                    self.dome_parked = false;
+                }
+                command_ok(if enabled {
+                    "dome following the mount"
+                } else {
+                    "dome following stopped"
+                })
+            }
+            RigCommand::SetFlatLight { on } => {
+                self.flat_light_on = on;
+                self.emit(event_types::FLAT_LIGHT_TOGGLED, None);
+                command_ok(if on {
+                    "panel light on"
+                } else {
+                    "panel light off"
+                })
+            }
+            RigCommand::SetFlatBrightness { brightness } => {
+                if !(0..=FLAT_MAX_BRIGHTNESS).contains(&brightness) {
+                    return command_refused("brightness out of range");
+                }
+                self.flat_brightness = brightness;
+                self.emit(event_types::FLAT_BRIGHTNESS_CHANGED, None);
+                command_ok(&format!("panel brightness {brightness}"))
+            }
+            RigCommand::OpenFlatCover => {
+                self.flat_cover_open = true;
+                self.emit(event_types::FLAT_COVER_OPENED, None);
+                command_ok("cover opened")
+            }
+            RigCommand::CloseFlatCover => {
+                if self.phase == Phase::Exposing {
+                    return command_refused("a light frame is exposing");
+                }
+                self.flat_cover_open = false;
+                self.emit(event_types::FLAT_COVER_CLOSED, None);
+                command_ok("cover closed")
+            }
+            RigCommand::SetSwitch { index, value } => {
+                let Some(((name, maximum, _), current)) = SWITCH_PORTS
+                    .iter()
+                    .zip(self.switch_values.iter_mut())
+                    .nth(index as usize)
+                else {
+                    return command_refused(&format!("no writable switch #{index}"));
+                };
+                if !(0.0..=*maximum).contains(&value) {
+                    return command_refused("switch value out of range");
+                }
+                *current = value;
+                command_ok(&format!("{name} set to {value}"))
+            }
+        }
+    }
+
+    // --- Snapshots ------------------------------------------------------
+
+    fn pointing(&self) -> (f64, f64) {
+        if self.parked {
+            // Park at the pole, counterweight down.
+            let lst = self.local_sidereal();
+            ((lst - 6.0).rem_euclid(24.0), 90.0)
+        } else {
+            (self.target_ra, self.plan.dec_degrees)
+        }
+    }
+
+    fn weather(&self) -> WeatherInfo {
+        let temperature = self.ambient();
+        let dew_point = self.plan.ambient_temperature - DEW_POINT_SPREAD_AT_DUSK;
+        // Magnus approximation of relative humidity from the dew point.
+        let magnus = |t: f64| (17.625 * t / (243.04 + t)).exp();
+        let minute = (self.now / 60.0) as u64;
+        let wind = (WIND_MEAN + 0.8 * hashed_gaussian(self.seed, minute, 11)).max(0.0);
+        let cloudy = self.cloudy();
+        WeatherInfo {
+            connected: true,
+            cloud_cover: if cloudy { 90.0 } else { 5.0 },
+            temperature,
+            sky_temperature: temperature - if cloudy { 4.0 } else { 24.0 },
+            humidity: (100.0 * magnus(dew_point) / magnus(temperature)).min(100.0),
+            dew_point,
+            wind_speed: wind,
+            wind_gust: wind * 1.6,
+            wind_direction: 220.0,
+            rain_rate: 0.0,
+            sky_quality: if cloudy { 18.4 } else { 20.8 },
+            name: "Simulated weather station".to_string(),
+            display_name: "Simulated weather station".to_string(),
+        }
+    }
+
+    fn local_sidereal(&self) -> f64 {
+        local_sidereal_hours(self.wall_time(self.now), self.plan.site_longitude)
+    }
+
+    fn mount_info(&self) -> MountInfoResponse {
+        let (ra, dec) = self.pointing();
+        let lst = self.local_sidereal();
+        let (altitude, azimuth) = alt_az(ra, dec, lst, self.plan.site_latitude);
+        let to_flip = if self.flipped {
+            (self.flip_at + 12.0 * 3600.0 / SIDEREAL_RATE - self.now) / 3600.0
+        } else {
+            (self.flip_at - self.now) / 3600.0
+        };
+        let hours_to_meridian = (ra - lst).rem_euclid(24.0);
+        let wall = self.wall_time(self.now);
+        let now = self.nina_time(self.now);
+        let utc_now = wall.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string();
+        MountInfoResponse {
+            response: MountInfo {
+                sidereal_time: lst,
+                right_ascension: ra,
+                declination: dec,
+                site_latitude: self.plan.site_latitude,
+                site_longitude: self.plan.site_longitude,
+                site_elevation: 100,
+                right_ascension_string: format_hours(ra),
+                declination_string: format_degrees(dec),
+                coordinates: Coordinates {
+                    ra,
+                    ra_string: format_hours(ra),
+                    ra_degrees: ra * 15.0,
+                    dec,
+                    dec_string: format_degrees(dec),
+                    epoch: "JNOW".to_string(),
+                    date_time: DateTime {
+                        now,
+                        utc_now: utc_now.clone(),
+                    },
+                },
+                time_to_meridian_flip: to_flip,
+                side_of_pier: if self.flipped { "pierWest" } else { "pierEast" }.to_string(),
+                altitude,
+                altitude_string: format_degrees(altitude),
+                azimuth,
+                azimuth_string: format_degrees(azimuth),
+                sidereal_time_string: format_hours(lst),
+                hours_to_meridian_string: format_hours(hours_to_meridian),
+                at_park: self.parked,
+                tracking_rate: TrackingRate {},
+                tracking_enabled: self.tracking,
+                tracking_modes: ["Sidereal", "Lunar", "Solar", "King", "Stopped"]
+                    .map(str::to_string)
+                    .to_vec(),
+                at_home: false,
+                can_find_home: true,
+                can_park: true,
+                can_set_park: true,
+                can_set_tracking_enabled: true,
+                can_set_declination_rate: false,
+                can_set_right_ascension_rate: false,
+                equatorial_system: "JNOW".to_string(),
+                has_unknown_epoch: false,
+                time_to_meridian_flip_string: format_hours(to_flip.max(0.0)),
+                slewing: self.slewing,
+                guide_rate_right_ascension_arcsec_per_sec: GUIDE_RATE_ARCSEC_PER_SECOND,
+                guide_rate_declination_arcsec_per_sec: GUIDE_RATE_ARCSEC_PER_SECOND,
+                can_move_primary_axis: true,
+                can_move_secondary_axis: true,
+                primary_axis_rates: Vec::new(),
+                secondary_axis_rates: Vec::new(),
+                supported_actions: Vec::new(),
+                alignment_mode: "GermanPolar".to_string(),
+                can_pulse_guide: true,
+                is_pulse_guiding: self.guiding_since.is_some(),
+                can_set_pier_side: false,
+                can_slew: true,
+                utc_date: utc_now,
+                connected: true,
+                name: "Simulated mount".to_string(),
+                display_name: "Simulated mount".to_string(),
+                device_id: "Chatstronomy.Simulator.Mount".to_string(),
+            },
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        }
+    }
+
+    /// The last [`GUIDE_HISTORY`] guide steps. Each step's noise derives from
+    /// the seed and its id alone, so repeated queries agree on history.
+    fn guide_history(&self) -> GuideStepsHistory {
+        let mut steps = Vec::new();
+        if let Some(since) = self.guiding_since {
+            let first = (since / GUIDE_INTERVAL_SECONDS).ceil() as i64;
+            let last = (self.now / GUIDE_INTERVAL_SECONDS).floor() as i64;
+            for id in first.max(last - GUIDE_HISTORY + 1)..=last {
+                steps.push(self.guide_step(id));
+            }
+        }
+        let rms = (!steps.is_empty()).then(|| guide_rms(&steps));
+        GuideStepsHistory {
+            rms,
+            interval: GUIDE_INTERVAL_SECONDS,
+            max_y: 4.0,
+            min_y: -4.0,
+            max_duration_y: 2500.0,
+            min_duration_y: -2500.0,
+            guide_steps: steps,
+            history_size: GUIDE_HISTORY as i32,
+            pixel_scale: GUIDE_PIXEL_SCALE,
+            scale: GUIDER_SCALE_ARCSECONDS,
+        }
+    }
+
+    fn guide_step(&self, id: i64) -> GuideGraphStep {
+        let t = id as f64 * GUIDE_INTERVAL_SECONDS;
+        let noise = |salt: u64| hashed_gaussian(self.seed, id as u64, salt);
+        let seeing = if t < self.clouds_until && self.now < self.clouds_until {
+            2.5
+        } else {
+            1.0
+        };
+        let mut ra = PERIODIC_ERROR_ARCSEC * (2.0 * PI * t / PERIODIC_ERROR_SECONDS).sin()
+            + 0.35 * seeing * noise(1);
+        let mut dec = 0.3 * seeing * noise(2);
+        let mut dither = Value::String("NaN".to_string());
+        for (n, at) in self.dithers.iter().enumerate() {
+            let since = t - at;
+            if (0.0..DITHER_SETTLE_SECONDS * 4.0).contains(&since) {
+                let decay = (-since / DITHER_SETTLE_SECONDS).exp();
+                let sign = |salt: u64| {
+                    if hashed_unit(self.seed, n as u64, salt) < 0.5 {
+                        -1.0
+                    } else {
+                        1.0
+                    }
+                };
+                ra += DITHER_ARCSEC * decay * sign(3);
+                dec += DITHER_ARCSEC * decay * sign(4);
+                if since < GUIDE_INTERVAL_SECONDS {
+                    dither = json!(0.01);
+                }
+            }
+        }
+        let pulse = |arcsec: f64| -0.7 * arcsec / GUIDE_RATE_ARCSEC_PER_SECOND * 1000.0;
+        GuideGraphStep {
+            id,
+            id_offset_left: -0.15,
+            id_offset_right: 0.15,
+            ra_distance_raw: ra / GUIDE_PIXEL_SCALE,
+            ra_distance_raw_display: ra,
+            ra_duration: pulse(ra).round(),
+            dec_distance_raw: dec / GUIDE_PIXEL_SCALE,
+            dec_distance_raw_display: dec,
+            dec_duration: pulse(dec).round(),
+            dither,
+        }
+    }
+
+    /// A sequence tree shaped like N.I.N.A.'s: global triggers first, then
+    /// start, target and end containers.
+    fn sequence(&self) -> SequenceResponse {
+        let running = self.sequence_running;
+        let status = |active: bool, done: bool| {
+            if active {
+                "RUNNING"
+            } else if done {
+                "FINISHED"
+            } else {
+                "CREATED"
+            }
+        };
+        let cooling = running && self.phase == Phase::Cooling;
+        let centering = running && self.phase == Phase::Centering;
+        let imaging = running && self.target_started;
+        let targeting = running && self.cooled;
+        let coordinates = json!({
+            "RA": self.target_ra,
+            "RAString": format_hours(self.target_ra),
+            "RADegrees": self.target_ra * 15.0,
+            "Dec": self.plan.dec_degrees,
+            "DecString": format_degrees(self.plan.dec_degrees),
+            "Epoch": "J2000",
+        });
+        let exposures: Vec<Value> = self
+            .plan
+            .filters
+            .iter()
+            .enumerate()
+            .map(|(index, filter)| {
+                let done = index < self.filter_stage || self.sequence_done;
+                let count = if done {
+                    self.plan.frames_per_filter
+                } else if index == self.filter_stage {
+                    self.frames_on_filter
+                } else {
+                    0
+                };
+                json!({
+                    "Name": "Smart Exposure",
+                    "Status": status(imaging && index == self.filter_stage, done),
+                    "Filter": filter,
+                    "Type": image_types::LIGHT,
+                    "ExposureTime": self.plan.exposure_seconds,
+                    "ExposureCount": count,
+                    "Iterations": self.plan.frames_per_filter,
+                    "DitherTargetExposures": self.plan.dither_every,
+                    "DitherProgressExposures": if self.plan.dither_every > 0 {
+                        self.frames_total % self.plan.dither_every
+                    } else {
+                        0
+                    },
+                    "Gain": 100,
+                    "Offset": 50,
+                    "Binning": {"Name": "1x1", "X": 1, "Y": 1},
+                })
+            })
+            .collect();
+        let target_done = self.sequence_done;
+        let response = vec![
+            json!({
+                "GlobalTriggers": [{
+                    "Name": "Meridian Flip_Trigger",
+                    "Status": status(false, self.flipped),
+                    "TimeToFlip": ((self.flip_at - self.now) / 3600.0).max(0.0),
+                }],
+            }),
+            json!({
+                "Name": "Start_Container",
+                "Status": status(cooling, self.cooled),
+                "Conditions": [],
+                "Triggers": [],
+                "Items": [{
+                    "Name": "Cool Camera",
+                    "OperationKind": "camera_cooling",
+                    "Status": status(cooling, self.cooled),
+                    "Temperature": self.plan.cooling_temperature,
+                    "MinCoolingTime": self.plan.cooling_minutes,
+                }],
+            }),
+            json!({
+                "Name": "Targets_Container",
+                "Status": status(targeting, target_done),
+                "Conditions": [],
+                "Triggers": [],
+                "Items": [{
+                    "Name": format!("{}_Container", self.plan.target_name),
+                    "IsTargetContainer": true,
+                    "TargetName": self.plan.target_name,
+                    "Status": status(targeting, target_done),
+                    "Conditions": [],
+                    "Triggers": [],
+                    "Items": [
+                        {
+                            "Name": "Slew, center and rotate",
+                            "OperationKind": "mount_center",
+                            "Status": status(centering, self.target_started),
+                            "Coordinates": coordinates,
+                            "Rotation": self.plan.rotation,
+                        },
+                        {
+                            "Name": "Target Imaging Instructions_Container",
+                            "Status": status(imaging, target_done),
+                            "Conditions": [],
+                            "Triggers": [],
+                            "Items": exposures,
+                        },
+                    ],
+                }],
+            }),
+            json!({
+                "Name": "End_Container",
+                "Status": status(false, target_done),
+                "Conditions": [],
+                "Triggers": [],
+                "Items": [{
+                    "Name": "Park Scope",
+                    "Status": status(false, target_done),
+                }],
+            }),
+        ];
+        SequenceResponse {
+            response,
+            error: String::new(),
+            status_code: 200,
+            success: true,
+            response_type: "API".to_string(),
+        }
+    }
+}
+
+fn command_ok(message: &str) -> CommandResponse {
+    CommandResponse {
+        response: Value::String(message.to_string()),
+        error: String::new(),
+        status_code: 200,
+        success: true,
+        response_type: "API".to_string(),
+    }
+}
+
+fn command_refused(reason: &str) -> CommandResponse {
+    CommandResponse {
+        response: Value::Null,
+        error: reason.to_string(),
+        status_code: 409,
+        success: false,
+        response_type: "API".to_string(),
+    }
+}
+
+/// Sky background (ADU) and relative star yield for a filter name.
+fn filter_response(filter: &str) -> (f64, f64) {
+    match filter.to_ascii_uppercase().as_str() {
+        "L" | "LUM" | "LUMINANCE" => (900.0, 1.0),
+        "HA" | "OIII" | "SII" => (300.0, 0.35),
+        _ => (520.0, 0.7),
+    }
+}
+
+/// Least-squares line through `points`: slope, intercept and R².
+fn linear_fit(points: &[&FocusPoint]) -> (f64, f64, f64) {
+    if points.len() < 2 {
+        return (f64::NAN, f64::NAN, f64::NAN);
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| f64::from(p.position)).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.value).sum::<f64>() / n;
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for point in points {
+        let dx = f64::from(point.position) - mean_x;
+        sxy += dx * (point.value - mean_y);
+        sxx += dx * dx;
+    }
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let owned: Vec<FocusPoint> = points.iter().map(|p| (*p).clone()).collect();
+    let r2 = r_squared(&owned, |x| slope * x + intercept);
+    (slope, intercept, r2)
+}
+
+fn r_squared(points: &[FocusPoint], model: impl Fn(f64) -> f64) -> f64 {
+    let n = points.len() as f64;
+    let mean = points.iter().map(|p| p.value).sum::<f64>() / n;
+    let (mut residual, mut total) = (0.0, 0.0);
+    for point in points {
+        residual += (point.value - model(f64::from(point.position))).powi(2);
+        total += (point.value - mean).powi(2);
+    }
+    if total == 0.0 {
+        return 0.0;
+    }
+    (1.0 - residual / total).max(0.0)
+}
+
+fn guide_rms(steps: &[GuideGraphStep]) -> GuideGraphRms {
+    let n = steps.len() as f64;
+    let rms =
+        |values: &mut dyn Iterator<Item = f64>| (values.map(|v| v * v).sum::<f64>() / n).sqrt();
+    let ra = rms(&mut steps.iter().map(|s| s.ra_distance_raw));
+    let dec = rms(&mut steps.iter().map(|s| s.dec_distance_raw));
+    let total = (ra * ra + dec * dec).sqrt();
+    let peak = |values: &mut dyn Iterator<Item = f64>| values.fold(0.0_f64, |m, v| m.max(v.abs()));
+    let peak_ra = peak(&mut steps.iter().map(|s| s.ra_distance_raw));
+    let peak_dec = peak(&mut steps.iter().map(|s| s.dec_distance_raw));
+    let text = |label: &str, pixels: f64| {
+        format!("{label}: {pixels:.2} ({:.2}\")", pixels * GUIDE_PIXEL_SCALE)
+    };
+    GuideGraphRms {
+        ra,
+        dec,
+        total,
+        ra_text: text("RA", ra),
+        dec_text: text("Dec", dec),
+        total_text: text("Tot", total),
+        peak_ra_text: text("RA Peak", peak_ra),
+        peak_dec_text: text("Dec Peak", peak_dec),
+        scale: GUIDE_PIXEL_SCALE,
+        peak_ra,
+        peak_dec,
+        data_points: steps.len() as i32,
+    }
+}
+
+fn format_hours(hours: f64) -> String {
+    let seconds = (hours.rem_euclid(24.0) * 3600.0).round() as i64 % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn format_degrees(degrees: f64) -> String {
+    let sign = if degrees < 0.0 { "-" } else { "" };
+    let seconds = (degrees.abs() * 3600.0).round() as i64;
+    format!(
+        "{sign}{}° {:02}' {:02}\"",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Star-field preview for an image: background at its median, one blob per
+/// ten detected stars sized by its HFR.
+fn render_thumbnail(
+    image: &ImageMetadata,
+    seed: u64,
+) -> Result<Vec<u8>, crate::charts::ChartError> {
+    let (width, height) = (THUMBNAIL_WIDTH as usize, THUMBNAIL_HEIGHT as usize);
+    let background = (image.median / 65_535.0 * 255.0 * 8.0).clamp(8.0, 120.0);
+    let mut luminance = vec![background; width * height];
+    let sigma = (image.hfr * 0.6).max(0.6);
+    let radius = (sigma * 3.0).ceil() as i64;
+    for star in 0..(image.stars.max(0) / 10).min(200) as u64 {
+        let x = hashed_unit(seed, star, 1) * width as f64;
+        let y = hashed_unit(seed, star, 2) * height as f64;
+        let peak = 60.0 + 195.0 * hashed_unit(seed, star, 3).powi(3);
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (px, py) = (x as i64 + dx, y as i64 + dy);
+                if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+                    continue;
+                }
+                let distance = (dx * dx + dy * dy) as f64;
+                let pixel = &mut luminance[py as usize * width + px as usize];
+                *pixel += peak * (-distance / (2.0 * sigma * sigma)).exp();
+            }
+        }
+    }
+    let rgb: Vec<u8> = luminance
+        .iter()
+        .flat_map(|value| {
+            let value = value.min(255.0) as u8;
+            [value, value, value]
+        })
+        .collect();
+    crate::charts::encode_png(&rgb, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+}
+
+/// SplitMix64: tiny, seedable and good enough for simulated noise.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix(self.0)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal sample (Box–Muller).
+    fn gaussian(&mut self) -> f64 {
+        box_muller(self.next_f64(), self.next_f64())
+    }
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn hashed_unit(seed: u64, id: u64, salt: u64) -> f64 {
+    let hash = mix(seed ^ mix(id.wrapping_add(0x9E37_79B9_7F4A_7C15)) ^ mix(salt));
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn hashed_gaussian(seed: u64, id: u64, salt: u64) -> f64 {
+    box_muller(
+        hashed_unit(seed, id, salt),
+        hashed_unit(seed, id, salt + 0x100),
+    )
+}
+
+fn box_muller(u1: f64, u2: f64) -> f64 {
+    (-2.0 * u1.max(1e-12).ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coalescing::CoalescingConfig;
+    use crate::test_support::{SimulatedNight, short_plan};
+
+    async fn event_names(source: &SimulatedRigSource) -> Vec<String> {
+        source
+            .get_event_history()
+            .await
+            .unwrap()
+            .response
+            .into_iter()
+            .map(|event| event.event)
+            .collect()
+    }
+
+    /// Assert `expected` appears in `names` in order, other events between.
+    fn assert_in_order(names: &[String], expected: &[&str]) {
+        let mut remaining = names.iter();
+        for name in expected {
+            assert!(
+                remaining.any(|seen| seen == name),
+                "{name} missing or out of order in {names:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn plans_default_every_field_and_script_failures() {
+        let plan: NightPlan = serde_json::from_str(
+            r#"{"target_name":"NGC 7000","failures":[
+                {"at_minutes":30,"kind":"clouds","minutes":10},
+                {"at_minutes":45,"kind":"autofocus_failed"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(plan.target_name, "NGC 7000");
+        assert_eq!(plan.filters, NightPlan::default().filters);
+        assert_eq!(plan.failures[0].kind, FailureKind::Clouds { minutes: 10.0 });
+        assert_eq!(plan.failures[1].kind, FailureKind::AutofocusFailed);
+        assert!(plan.validate().is_ok());
+
+        let empty = NightPlan {
+            filters: Vec::new(),
+            ..NightPlan::default()
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn night_runs_from_cooling_to_a_parked_finish() {
+        let source = SimulatedRigSource::new(short_plan()).with_time_scale(60.0);
+        let camera = source.get_camera_info().await.unwrap().response;
+        assert!(camera.cooler_on);
+        assert!(!camera.at_target_temp);
+        let mount = source.get_mount_info().await.unwrap().response;
+        assert!(!mount.at_park && mount.tracking_enabled);
+
+        // Ten real seconds at 60x cover the ten-minute cool-down.
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert!(
+            source
+                .get_camera_info()
+                .await
+                .unwrap()
+                .response
+                .at_target_temp
+        );
+
+        tokio::time::advance(Duration::from_secs(120)).await;
+        assert!(source.finished());
+        let names = event_names(&source).await;
+        assert_in_order(
+            &names,
+            &[
+                event_types::SEQUENCE_STARTING,
+                event_types::MOUNT_UNPARKED,
+                event_types::MOUNT_CENTER,
+                event_types::TS_TARGETSTART,
+                event_types::GUIDER_START,
+                event_types::AUTOFOCUS_STARTING,
+                event_types::AUTOFOCUS_POINT_ADDED,
+                event_types::AUTOFOCUS_FINISHED,
+                event_types::IMAGE_SAVE,
+                event_types::GUIDER_DITHER,
+                event_types::FILTERWHEEL_CHANGED,
+                event_types::MOUNT_BEFORE_FLIP,
+                event_types::MOUNT_AFTER_FLIP,
+                event_types::MOUNT_CENTER,
+                event_types::AUTOFOCUS_FINISHED,
+                event_types::IMAGE_SAVE,
+                event_types::SEQUENCE_FINISHED,
+                event_types::MOUNT_PARKED,
+            ],
+        );
+        let images = source.get_all_image_history().await.unwrap().response;
+        assert_eq!(images.len(), 6);
+        assert_eq!(images[0].filter, "L");
+        assert_eq!(images[5].filter, "R");
+        assert!(
+            images
+                .iter()
+                .all(|image| image.hfr < 3.5 && image.stars > 100)
+        );
+
+        let autofocus = source.get_last_autofocus().await.unwrap();
+        assert!(autofocus.is_successful());
+        assert_eq!(autofocus.response.measure_points.len(), AUTOFOCUS_POINTS);
+        let thumbnail = source.get_thumbnail(0).await.unwrap();
+        assert!(thumbnail.data.starts_with(b"\x89PNG"));
+        let mount = source.get_mount_info().await.unwrap().response;
+        assert!(mount.at_park);
+        assert_eq!(mount.side_of_pier, "pierWest");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn same_seed_replays_the_same_night() {
+        let night = |seed| async move {
+            let source = SimulatedRigSource::new(short_plan())
+                .with_seed(seed)
+                .with_time_scale(60.0);
+            tokio::time::advance(Duration::from_secs(25)).await;
+            let images = source.get_all_image_history().await.unwrap().response;
+            let graph = source.get_guider_graph().await.unwrap().response;
+            (
+                images.iter().map(|image| image.hfr).collect::<Vec<_>>(),
+                graph.rms.unwrap().total,
+            )
+        };
+        let first = night(7).await;
+        assert!(!first.0.is_empty());
+        assert_eq!(first, night(7).await);
+        assert_ne!(first, night(8).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn guide_graph_marks_dithers_and_settles() {
+        let source = SimulatedRigSource::new(NightPlan {
+            dither_every: 1,
+            ..short_plan()
+        })
+        .with_time_scale(60.0);
+        // First frame finishes ~18 simulated minutes in.
+        tokio::time::advance(Duration::from_secs(18 * 60 / 60 + 1)).await;
+        let names = event_names(&source).await;
+        assert!(names.iter().any(|name| name == event_types::GUIDER_DITHER));
+        let graph = source.get_guider_graph().await.unwrap().response;
+        assert!(graph.has_graph_data());
+        assert!(
+            graph
+                .guide_steps
+                .iter()
+                .any(GuideStepsHistory::is_dither_step)
+        );
+        let peak = graph.rms.as_ref().unwrap().peak_ra * GUIDE_PIXEL_SCALE;
+        assert!(peak > 1.0, "dither offset should show in the peak: {peak}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn commands_change_the_simulated_rig() {
+        let source = SimulatedRigSource::new(short_plan()).with_time_scale(60.0);
+        let refused = source
+            .execute_command(RigCommand::StartSequence {
+                skip_validation: false,
+            })
+            .await
+            .unwrap();
+        assert!(!refused.success);
+
+        let stopped = source
+            .execute_command(RigCommand::StopSequence)
+            .await
+            .unwrap();
+        assert!(stopped.success);
+        let sequence = source.get_sequence().await.unwrap();
+        assert!(crate::sequence::extract_current_target(&sequence).is_none());
+
+        let bad_filter = source
+            .execute_command(RigCommand::ChangeFilter { filter_id: 9 })
+            .await
+            .unwrap();
+        assert!(!bad_filter.success);
+        let filter = source
+            .execute_command(RigCommand::ChangeFilter { filter_id: 1 })
+            .await
+            .unwrap();
+        assert!(filter.success);
+        let wheel = source.get_filterwheel_info().await.unwrap().response;
+        assert_eq!(wheel.selected_filter.unwrap().name, "R");
+
+        source
+            .execute_command(RigCommand::WarmCamera { minutes: 1.0 })
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(2)).await;
+        let camera = source.get_camera_info().await.unwrap().response;
+        assert!(!camera.cooler_on);
+        assert!(camera.temperature > 14.0);
+
+        let autofocus = source
+            .execute_command(RigCommand::StartAutofocus)
+            .await
+            .unwrap();
+        assert!(autofocus.success);
+        tokio::time::advance(Duration::from_secs(3)).await;
+        assert!(source.get_last_autofocus().await.unwrap().is_successful());
+
+        source.execute_command(RigCommand::ParkMount).await.unwrap();
+        assert!(source.get_mount_info().await.unwrap().response.at_park);
+        let guiding = source
+            .execute_command(RigCommand::StartGuiding { calibrate: false })
+            .await
+            .unwrap();
+        assert!(!guiding.success);
+
+        let slew = RigCommand::SlewToCoordinates {
+            ra_hours: 6.0,
+            dec_degrees: 22.5,
+            center: true,
+        };
+        let parked_slew = source.execute_command(slew.clone()).await.unwrap();
+        assert!(!parked_slew.success);
+        source
+            .execute_command(RigCommand::UnparkMount)
+            .await
+            .unwrap();
+        assert!(source.execute_command(slew).await.unwrap().success);
+        let mount = source.get_mount_info().await.unwrap().response;
+        assert!((mount.right_ascension - 6.0).abs() < 1e-9);
+        assert!((mount.declination - 22.5).abs() < 1e-9);
+        source
+            .execute_command(RigCommand::SetTracking {
+                enabled: false,
+                rate: None,
+            })
+            .await
+            .unwrap();
+        assert!(
+            !source
+                .get_mount_info()
+                .await
+                .unwrap()
+                .response
+                .tracking_enabled
+        );
+
+        let start = source.get_focuser_info().await.unwrap().response.position;
+        source
+            .execute_command(RigCommand::MoveFocuser {
+                target: FocuserTarget::RelativeSteps(-25),
+            })
+            .await
+            .unwrap();
+        let focuser = source.get_focuser_info().await.unwrap().response;
+        assert_eq!(focuser.position, start - 25);
+        source
+            .execute_command(RigCommand::SetFocuserTempComp { enabled: true })
+            .await
+            .unwrap();
+        let locked = source
+            .execute_command(RigCommand::MoveFocuser {
+                target: FocuserTarget::Position(start),
+            })
+            .await
+            .unwrap();
+        assert!(!locked.success);
+        source
+            .execute_command(RigCommand::MoveRotator {
+                position_angle: 45.0,
+                mechanical: false,
+            })
+            .await
+            .unwrap();
+        let rotator = source.get_rotator_info().await.unwrap().response;
+        assert!((rotator.position - 45.0).abs() < 1e-9);
+
+        let snapshot = source
+            .execute_command(RigCommand::CaptureSnapshot {
+                exposure_seconds: 5.0,
+                filter_id: Some(0),
+                gain: Some(200),
+                binning: None,
+            })
+            .await
+            .unwrap();
+        assert!(snapshot.success);
+        let images = source.get_all_image_history().await.unwrap().response;
+        let image = images.last().unwrap();
+        assert!(image.is_snapshot());
+        assert_eq!((image.filter.as_str(), image.gain), ("L", 200));
+        assert!(source.get_thumbnail(images.len() as u32 - 1).await.is_ok());
+
+        source
+            .execute_command(RigCommand::CloseDomeShutter)
+            .await
+            .unwrap();
+        source.execute_command(RigCommand::ParkDome).await.unwrap();
+        let dome = source.get_dome_info().await.unwrap().response;
+        assert_eq!(dome.summary(), "Shutter closed · parked");
+        source
+            .execute_command(RigCommand::CloseFlatCover)
+            .await
+            .unwrap();
+        let too_bright = source
+            .execute_command(RigCommand::SetFlatBrightness { brightness: 999 })
+            .await
+            .unwrap();
+        assert!(!too_bright.success);
+        source
+            .execute_command(RigCommand::SetFlatBrightness { brightness: 80 })
+            .await
+            .unwrap();
+        source
+            .execute_command(RigCommand::SetFlatLight { on: true })
+            .await
+            .unwrap();
+        let panel = source.get_flat_device_info().await.unwrap().response;
+        assert_eq!(panel.summary(), "Cover closed · Light on (80)");
+        let read_only = source
+            .execute_command(RigCommand::SetSwitch {
+                index: 4,
+                value: 1.0,
+            })
+            .await
+            .unwrap();
+        assert!(!read_only.success);
+        source
+            .execute_command(RigCommand::SetSwitch {
+                index: 2,
+                value: 255.0,
+            })
+            .await
+            .unwrap();
+        let hub = source.get_switch_info().await.unwrap().response;
+        assert_eq!(
+            hub.dew_heater_summary().as_deref(),
+            Some("Dew A 100% · Dew B 38%")
+        );
+        let names = event_names(&source).await;
+        assert_in_order(
+            &names,
+            &[
+                event_types::DOME_SHUTTER_CLOSED,
+                event_types::DOME_PARKED,
+                event_types::FLAT_COVER_CLOSED,
+            ],
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scripted_failures_reach_chat_through_the_updater() {
+        let plan = NightPlan {
+            failures: vec![
+                ScriptedFailure {
+                    at_minutes: 5.0,
+                    kind: FailureKind::PlateSolveFailed,
+                },
+                ScriptedFailure {
+                    at_minutes: 30.0,
+                    kind: FailureKind::GuideStarLost,
+                },
+                ScriptedFailure {
+                    at_minutes: 35.0,
+                    kind: FailureKind::Clouds { minutes: 12.0 },
+                },
+                ScriptedFailure {
+                    at_minutes: 50.0,
+                    kind: FailureKind::RigOffline { minutes: 4.0 },
+                },
+            ],
+            ..short_plan()
+        };
+        let night = SimulatedNight::new(plan);
+        let mut updater = night
+            .updater()
+            .with_image_cooldown(0)
+            // At 60x a burst window would span a large part of the night.
+            .with_coalescing(CoalescingConfig::disabled());
+        updater.initialize_baseline().await.unwrap();
+
+        // One poll cycle per simulated minute until the night is over.
+        for _ in 0..120 {
+            tokio::time::advance(Duration::from_secs(1)).await;
+            let events_ok = updater.poll_events().await;
+            let sequence_ok = updater.poll_sequence().await;
+            let images_ok = updater.poll_images().await;
+            updater
+                .record_reachability(events_ok || sequence_ok || images_ok)
+                .await;
+        }
+        assert!(night.source.finished());
+
+        for needle in [
+            "ERROR-PLATESOLVE",
+            "N.I.N.A. · Guider",
+            "Telescope offline",
+            "Telescope back online",
+            "Meridian",
+            "Sequence Finished",
+            "Mount Parked",
+            "Session Report",
+        ] {
+            assert!(
+                night.chat.posted(needle),
+                "no {needle} post in {:#?}",
+                night.chat.titles()
+            );
+        }
+    }
+}
@@ -7,18 +7,24 @@
 use crate::api_types::CommandResponse;
 use crate::autofocus::AutofocusResponse;
 use crate::camera::CameraInfoResponse;
-use crate::events::EventHistoryResponse;
+use crate::dome::DomeInfoResponse;
+use crate::events::{Event, EventHistoryResponse};
 use crate::filterwheel::FilterWheelInfoResponse;
+use crate::flat_device::FlatDeviceInfoResponse;
 use crate::focuser::FocuserInfoResponse;
 use crate::guider::{GuiderGraphResponse, GuiderInfoResponse};
-use crate::images::{ImageHistoryResponse, ThumbnailResponse};
+use crate::images::{ImageHistoryResponse, ImageMetadata, ThumbnailResponse};
 use crate::mount::MountInfoResponse;
 use crate::rotator::RotatorInfoResponse;
+use crate::safety::SafetyInfoResponse;
 use crate::sequence::SequenceResponse;
+use crate::switch::SwitchInfoResponse;
+use crate::weather::WeatherInfoResponse;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use thiserror::Error;
+use tokio::sync::broadcast;
 
 /// How a rig supplies N.I.N.A. data to Chatstronomy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -43,6 +49,22 @@ pub struct RigCapabilities {
     pub autofocus_details: bool,
     pub guider_graph: bool,
     pub commands: bool,
+    /// The rig pushes [`RigUpdate`]s once subscribed instead of waiting to be
+    /// polled. Absent on plugins that predate the push stream.
+    #[serde(default)]
+    pub event_stream: bool,
+    /// The rig answers weather and safety monitor queries. Absent on
+    /// plugins that predate them.
+    #[serde(default)]
+    pub observing_conditions: bool,
+    /// The command kinds this rig executes. Absent on plugins that predate
+    /// per-command negotiation, which are sent any command when `commands`
+    /// is set and refuse the ones they do not know.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_kinds: Option<CommandKindSet>,
+    /// Equipment state when the rig connected.
+    #[serde(default, skip_serializing_if = "DeviceHints::is_unknown")]
+    pub devices: DeviceHints,
 }
 
 impl RigCapabilities {
@@ -56,6 +78,10 @@ impl RigCapabilities {
             autofocus_details: false,
             guider_graph: false,
             commands: false,
+            event_stream: false,
+            observing_conditions: false,
+            command_kinds: None,
+            devices: DeviceHints::UNKNOWN,
         }
     }
 
@@ -69,8 +95,136 @@ impl RigCapabilities {
             autofocus_details: true,
             guider_graph: true,
             commands: true,
+            event_stream: true,
+            observing_conditions: true,
+            command_kinds: None,
+            devices: DeviceHints::UNKNOWN,
         }
     }
+
+    /// Whether a command of this kind may be sent: commands are enabled, the
+    /// rig negotiated the kind (or predates negotiation), and the device it
+    /// drives is not missing from the profile.
+    pub fn supports_command(&self, kind: RigCommandKind) -> bool {
+        self.commands
+            && self.command_kinds.is_none_or(|kinds| kinds.contains(kind))
+            && kind
+                .device()
+                .is_none_or(|device| self.devices.get(device) != DeviceState::Absent)
+    }
+}
+
+/// Equipment a command drives, for the per-device hints in [`DeviceHints`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RigDevice {
+    Mount,
+    Camera,
+    FilterWheel,
+    Focuser,
+    Rotator,
+    Guider,
+    Dome,
+    FlatDevice,
+    Switch,
+}
+
+impl RigDevice {
+    pub const ALL: [Self; 9] = [
+        Self::Mount,
+        Self::Camera,
+        Self::FilterWheel,
+        Self::Focuser,
+        Self::Rotator,
+        Self::Guider,
+        Self::Dome,
+        Self::FlatDevice,
+        Self::Switch,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Mount => "Mount",
+            Self::Camera => "Camera",
+            Self::FilterWheel => "Filter wheel",
+            Self::Focuser => "Focuser",
+            Self::Rotator => "Rotator",
+            Self::Guider => "Guider",
+            Self::Dome => "Dome",
+            Self::FlatDevice => "Flat panel",
+            Self::Switch => "Switch hub",
+        }
+    }
+}
+
+/// What the rig reported about one device when it connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceState {
+    /// Not reported; older plugins send no hints.
+    #[default]
+    Unknown,
+    /// The profile has no such device.
+    Absent,
+    /// Configured but not connected at the time of the hello.
+    Disconnected,
+    Connected,
+}
+
+/// Per-device state hints carried in the hello. Connection state can change
+/// afterwards, so only [`DeviceState::Absent`] rules a command out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DeviceHints {
+    #[serde(default)]
+    pub mount: DeviceState,
+    #[serde(default)]
+    pub camera: DeviceState,
+    #[serde(default)]
+    pub filter_wheel: DeviceState,
+    #[serde(default)]
+    pub focuser: DeviceState,
+    #[serde(default)]
+    pub rotator: DeviceState,
+    #[serde(default)]
+    pub guider: DeviceState,
+    #[serde(default)]
+    pub dome: DeviceState,
+    #[serde(default)]
+    pub flat_device: DeviceState,
+    #[serde(default)]
+    pub switch: DeviceState,
+}
+
+impl DeviceHints {
+    pub const UNKNOWN: Self = Self {
+        mount: DeviceState::Unknown,
+        camera: DeviceState::Unknown,
+        filter_wheel: DeviceState::Unknown,
+        focuser: DeviceState::Unknown,
+        rotator: DeviceState::Unknown,
+        guider: DeviceState::Unknown,
+        dome: DeviceState::Unknown,
+        flat_device: DeviceState::Unknown,
+        switch: DeviceState::Unknown,
+    };
+
+    pub fn get(&self, device: RigDevice) -> DeviceState {
+        match device {
+            RigDevice::Mount => self.mount,
+            RigDevice::Camera => self.camera,
+            RigDevice::FilterWheel => self.filter_wheel,
+            RigDevice::Focuser => self.focuser,
+            RigDevice::Rotator => self.rotator,
+            RigDevice::Guider => self.guider,
+            RigDevice::Dome => self.dome,
+            RigDevice::FlatDevice => self.flat_device,
+            RigDevice::Switch => self.switch,
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        *self == Self::UNKNOWN
+    }
 }
 
 #[derive(Debug, Error)]
@@ -97,17 +251,351 @@ pub type SharedRigSource = Arc<dyn RigSource>;
 pub enum RigCommand {
     UnparkMount,
     HomeMount,
-    ChangeFilter { filter_id: i32 },
-    StartGuiding { calibrate: bool },
+    ChangeFilter {
+        filter_id: i32,
+    },
+    StartGuiding {
+        calibrate: bool,
+    },
     StopGuiding,
-    CoolCamera { temperature: f64, minutes: f64 },
-    WarmCamera { minutes: f64 },
+    CoolCamera {
+        temperature: f64,
+        minutes: f64,
+    },
+    WarmCamera {
+        minutes: f64,
+    },
     StartAutofocus,
     CancelAutofocus,
     ParkMount,
     AbortExposure,
     StopSequence,
-    StartSequence { skip_validation: bool },
+    StartSequence {
+        skip_validation: bool,
+    },
+    /// Slew to J2000 coordinates; `center` plate-solves and re-centers after
+    /// the slew.
+    SlewToCoordinates {
+        ra_hours: f64,
+        dec_degrees: f64,
+        center: bool,
+    },
+    /// Turn sidereal (or `rate`) tracking on or off. Without a rate the
+    /// mount keeps its current tracking mode.
+    SetTracking {
+        enabled: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rate: Option<TrackingMode>,
+    },
+    /// Abort a running slew. Always accepted, so it can be used as a stop.
+    StopSlew,
+    /// Move the focuser to an absolute position or by a signed step count.
+    MoveFocuser {
+        #[serde(flatten)]
+        target: FocuserTarget,
+    },
+    SetFocuserTempComp {
+        enabled: bool,
+    },
+    /// Rotate to a sky position angle in degrees, or to a raw mechanical
+    /// angle when `mechanical` is set.
+    MoveRotator {
+        position_angle: f64,
+        mechanical: bool,
+    },
+    /// Take one snapshot exposure outside the sequence. Omitted settings
+    /// keep the camera's and filter wheel's current values.
+    CaptureSnapshot {
+        exposure_seconds: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filter_id: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gain: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        binning: Option<u8>,
+    },
+    OpenDomeShutter,
+    /// Close the dome shutter or roof. Rigs accept it whatever the dome is
+    /// doing, as it is the weather response.
+    CloseDomeShutter,
+    ParkDome,
+    /// Slave the dome to the mount (or stop following it).
+    SetDomeSlaving {
+        enabled: bool,
+    },
+    SetFlatLight {
+        on: bool,
+    },
+    /// Set the panel brightness, within the panel's reported range.
+    SetFlatBrightness {
+        brightness: i32,
+    },
+    OpenFlatCover,
+    CloseFlatCover,
+    /// Set switch hub port `index` to `value`, within the port's range;
+    /// on/off ports take 0 or 1.
+    SetSwitch {
+        index: u32,
+        value: f64,
+    },
+}
+
+/// Where a [`RigCommand::MoveFocuser`] goes; exactly one of `position` or
+/// `relative_steps` appears on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocuserTarget {
+    Position(i32),
+    RelativeSteps(i32),
+}
+
+impl RigCommand {
+    pub fn kind(&self) -> RigCommandKind {
+        match self {
+            Self::UnparkMount => RigCommandKind::UnparkMount,
+            Self::HomeMount => RigCommandKind::HomeMount,
+            Self::ChangeFilter { .. } => RigCommandKind::ChangeFilter,
+            Self::StartGuiding { .. } => RigCommandKind::StartGuiding,
+            Self::StopGuiding => RigCommandKind::StopGuiding,
+            Self::CoolCamera { .. } => RigCommandKind::CoolCamera,
+            Self::WarmCamera { .. } => RigCommandKind::WarmCamera,
+            Self::StartAutofocus => RigCommandKind::StartAutofocus,
+            Self::CancelAutofocus => RigCommandKind::CancelAutofocus,
+            Self::ParkMount => RigCommandKind::ParkMount,
+            Self::AbortExposure => RigCommandKind::AbortExposure,
+            Self::StopSequence => RigCommandKind::StopSequence,
+            Self::StartSequence { .. } => RigCommandKind::StartSequence,
+            Self::SlewToCoordinates { .. } => RigCommandKind::SlewToCoordinates,
+            Self::SetTracking { .. } => RigCommandKind::SetTracking,
+            Self::StopSlew => RigCommandKind::StopSlew,
+            Self::MoveFocuser { .. } => RigCommandKind::MoveFocuser,
+            Self::SetFocuserTempComp { .. } => RigCommandKind::SetFocuserTempComp,
+            Self::MoveRotator { .. } => RigCommandKind::MoveRotator,
+            Self::CaptureSnapshot { .. } => RigCommandKind::CaptureSnapshot,
+            Self::OpenDomeShutter => RigCommandKind::OpenDomeShutter,
+            Self::CloseDomeShutter => RigCommandKind::CloseDomeShutter,
+            Self::ParkDome => RigCommandKind::ParkDome,
+            Self::SetDomeSlaving { .. } => RigCommandKind::SetDomeSlaving,
+            Self::SetFlatLight { .. } => RigCommandKind::SetFlatLight,
+            Self::SetFlatBrightness { .. } => RigCommandKind::SetFlatBrightness,
+            Self::OpenFlatCover => RigCommandKind::OpenFlatCover,
+            Self::CloseFlatCover => RigCommandKind::CloseFlatCover,
+            Self::SetSwitch { .. } => RigCommandKind::SetSwitch,
+        }
+    }
+}
+
+/// The wire `kind` of a [`RigCommand`], without its parameters. Rigs list
+/// the kinds they execute in [`RigCapabilities::command_kinds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum RigCommandKind {
+    UnparkMount,
+    HomeMount,
+    ChangeFilter,
+    StartGuiding,
+    StopGuiding,
+    CoolCamera,
+    WarmCamera,
+    StartAutofocus,
+    CancelAutofocus,
+    ParkMount,
+    AbortExposure,
+    StopSequence,
+    StartSequence,
+    SlewToCoordinates,
+    SetTracking,
+    StopSlew,
+    MoveFocuser,
+    SetFocuserTempComp,
+    MoveRotator,
+    CaptureSnapshot,
+    OpenDomeShutter,
+    CloseDomeShutter,
+    ParkDome,
+    SetDomeSlaving,
+    SetFlatLight,
+    SetFlatBrightness,
+    OpenFlatCover,
+    CloseFlatCover,
+    SetSwitch,
+}
+
+impl RigCommandKind {
+    pub const ALL: [Self; 29] = [
+        Self::UnparkMount,
+        Self::HomeMount,
+        Self::ChangeFilter,
+        Self::StartGuiding,
+        Self::StopGuiding,
+        Self::CoolCamera,
+        Self::WarmCamera,
+        Self::StartAutofocus,
+        Self::CancelAutofocus,
+        Self::ParkMount,
+        Self::AbortExposure,
+        Self::StopSequence,
+        Self::StartSequence,
+        Self::SlewToCoordinates,
+        Self::SetTracking,
+        Self::StopSlew,
+        Self::MoveFocuser,
+        Self::SetFocuserTempComp,
+        Self::MoveRotator,
+        Self::CaptureSnapshot,
+        Self::OpenDomeShutter,
+        Self::CloseDomeShutter,
+        Self::ParkDome,
+        Self::SetDomeSlaving,
+        Self::SetFlatLight,
+        Self::SetFlatBrightness,
+        Self::OpenFlatCover,
+        Self::CloseFlatCover,
+        Self::SetSwitch,
+    ];
+
+    /// The device this command drives; `None` for sequence control.
+    pub fn device(self) -> Option<RigDevice> {
+        match self {
+            Self::UnparkMount
+            | Self::HomeMount
+            | Self::ParkMount
+            | Self::SlewToCoordinates
+            | Self::SetTracking
+            | Self::StopSlew => Some(RigDevice::Mount),
+            Self::CoolCamera | Self::WarmCamera | Self::AbortExposure | Self::CaptureSnapshot => {
+                Some(RigDevice::Camera)
+            }
+            Self::ChangeFilter => Some(RigDevice::FilterWheel),
+            Self::StartAutofocus
+            | Self::CancelAutofocus
+            | Self::MoveFocuser
+            | Self::SetFocuserTempComp => Some(RigDevice::Focuser),
+            Self::MoveRotator => Some(RigDevice::Rotator),
+            Self::StartGuiding | Self::StopGuiding => Some(RigDevice::Guider),
+            Self::OpenDomeShutter
+            | Self::CloseDomeShutter
+            | Self::ParkDome
+            | Self::SetDomeSlaving => Some(RigDevice::Dome),
+            Self::SetFlatLight
+            | Self::SetFlatBrightness
+            | Self::OpenFlatCover
+            | Self::CloseFlatCover => Some(RigDevice::FlatDevice),
+            Self::SetSwitch => Some(RigDevice::Switch),
+            Self::StopSequence | Self::StartSequence => None,
+        }
+    }
+
+    fn bit(self) -> u64 {
+        1 << self as u64
+    }
+}
+
+// One bit per kind in `CommandKindSet`.
+const _: () = assert!(RigCommandKind::ALL.len() <= u64::BITS as usize);
+
+/// A set of [`RigCommandKind`]s, sent as a list of wire names. Names this
+/// build does not know are skipped so newer plugins can still connect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CommandKindSet(u64);
+
+impl CommandKindSet {
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub fn all() -> Self {
+        RigCommandKind::ALL.into_iter().collect()
+    }
+
+    pub fn insert(&mut self, kind: RigCommandKind) {
+        self.0 |= kind.bit();
+    }
+
+    pub fn contains(&self, kind: RigCommandKind) -> bool {
+        self.0 & kind.bit() != 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RigCommandKind> + '_ {
+        RigCommandKind::ALL
+            .into_iter()
+            .filter(|kind| self.contains(*kind))
+    }
+}
+
+impl FromIterator<RigCommandKind> for CommandKindSet {
+    fn from_iter<I: IntoIterator<Item = RigCommandKind>>(iter: I) -> Self {
+        let mut set = Self::empty();
+        for kind in iter {
+            set.insert(kind);
+        }
+        set
+    }
+}
+
+impl Serialize for CommandKindSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for CommandKindSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::IntoDeserializer;
+        let names = Vec::<String>::deserialize(deserializer)?;
+        Ok(names
+            .into_iter()
+            .filter_map(|name| {
+                let name: serde::de::value::StringDeserializer<serde::de::value::Error> =
+                    name.into_deserializer();
+                RigCommandKind::deserialize(name).ok()
+            })
+            .collect())
+    }
+}
+
+/// Mount tracking rates a [`RigCommand::SetTracking`] can request, matching
+/// the modes N.I.N.A. reports in `TrackingModes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackingMode {
+    Sidereal,
+    Lunar,
+    Solar,
+    King,
+}
+
+impl TrackingMode {
+    /// The name N.I.N.A. uses for this mode.
+    pub fn nina_name(self) -> &'static str {
+        match self {
+            Self::Sidereal => "Sidereal",
+            Self::Lunar => "Lunar",
+            Self::Solar => "Solar",
+            Self::King => "King",
+        }
+    }
+}
+
+/// A change pushed by a rig that streams updates instead of being polled.
+///
+/// Each variant carries exactly what the matching poll would have returned
+/// for that item, so consumers apply pushes and polls through one path and
+/// deduplicate them against the same seen state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RigUpdate {
+    /// One new entry of the event history.
+    Event { event: Event },
+    /// One new image; `index` is its position in the image history and
+    /// addresses its thumbnail.
+    Image { index: u32, image: ImageMetadata },
+    /// The sequence tree, sent whenever an item changes status. Operation
+    /// deltas are derived from it exactly as from a polled snapshot, so a
+    /// lost push is repaired by the next; the contract README covers the
+    /// wire cost of sending the whole tree.
+    Sequence { sequence: SequenceResponse },
 }
 
 /// Source-neutral read and command surface used by Chatstronomy's runtime.
@@ -132,7 +620,19 @@ pub trait RigSource: Send + Sync {
     async fn get_guider_graph(&self) -> RigSourceResult<GuiderGraphResponse>;
     async fn get_rotator_info(&self) -> RigSourceResult<RotatorInfoResponse>;
     async fn get_focuser_info(&self) -> RigSourceResult<FocuserInfoResponse>;
+    async fn get_dome_info(&self) -> RigSourceResult<DomeInfoResponse>;
+    async fn get_flat_device_info(&self) -> RigSourceResult<FlatDeviceInfoResponse>;
+    async fn get_weather_info(&self) -> RigSourceResult<WeatherInfoResponse>;
+    async fn get_safety_info(&self) -> RigSourceResult<SafetyInfoResponse>;
+    async fn get_switch_info(&self) -> RigSourceResult<SwitchInfoResponse>;
     async fn execute_command(&self, command: RigCommand) -> RigSourceResult<CommandResponse>;
+
+    /// Subscribe to pushed updates. `None` means the source must be polled,
+    /// which remains the fallback for rigs without
+    /// [`RigCapabilities::event_stream`].
+    fn subscribe_updates(&self) -> Option<broadcast::Receiver<RigUpdate>> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +647,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn command_kinds_match_the_command_wire_names() {
+        let commands = [
+            RigCommand::ChangeFilter { filter_id: 1 },
+            RigCommand::StopSlew,
+            RigCommand::CaptureSnapshot {
+                exposure_seconds: 1.0,
+                filter_id: None,
+                gain: None,
+                binning: None,
+            },
+            RigCommand::CloseDomeShutter,
+            RigCommand::SetFlatBrightness { brightness: 40 },
+            RigCommand::SetSwitch {
+                index: 4,
+                value: 128.0,
+            },
+        ];
+        for command in commands {
+            assert_eq!(
+                serde_json::to_value(command.kind()).unwrap(),
+                serde_json::to_value(&command).unwrap()["kind"]
+            );
+        }
+        assert_eq!(
+            CommandKindSet::all().iter().count(),
+            RigCommandKind::ALL.len()
+        );
+    }
+
+    #[test]
+    fn negotiated_command_kinds_gate_commands_and_skip_unknown_names() {
+        let capabilities: RigCapabilities = serde_json::from_value(serde_json::json!({
+            "event_history": true,
+            "image_history": true,
+            "thumbnails": true,
+            "sequence": true,
+            "equipment_snapshots": true,
+            "autofocus_details": true,
+            "guider_graph": true,
+            "commands": true,
+            "command_kinds": ["park_mount", "move_rotator", "open_roof_of_the_future"],
+            "devices": {"mount": "connected", "rotator": "absent"}
+        }))
+        .unwrap();
+        assert!(capabilities.supports_command(RigCommandKind::ParkMount));
+        assert!(!capabilities.supports_command(RigCommandKind::SlewToCoordinates));
+        // Negotiated, but the profile has no rotator.
+        assert!(!capabilities.supports_command(RigCommandKind::MoveRotator));
+        assert_eq!(
+            capabilities.devices.get(RigDevice::Camera),
+            DeviceState::Unknown
+        );
+        assert_eq!(
+            serde_json::to_value(capabilities).unwrap()["command_kinds"],
+            serde_json::json!(["park_mount", "move_rotator"])
+        );
+
+        // Plugins that predate negotiation accept every kind.
+        let legacy = RigCapabilities::all();
+        assert!(legacy.supports_command(RigCommandKind::SlewToCoordinates));
+        assert!(!RigCapabilities::none().supports_command(RigCommandKind::ParkMount));
+        let json = serde_json::to_value(legacy).unwrap();
+        assert!(json.get("command_kinds").is_none() && json.get("devices").is_none());
+    }
+
     #[test]
     fn commands_have_stable_semantic_wire_names() {
         assert_eq!(
@@ -165,5 +731,100 @@ mod tests {
             serde_json::to_value(RigCommand::ParkMount).unwrap(),
             serde_json::json!({"kind": "park_mount"})
         );
+        assert_eq!(
+            serde_json::to_value(RigCommand::CloseDomeShutter).unwrap(),
+            serde_json::json!({"kind": "close_dome_shutter"})
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::SetDomeSlaving { enabled: true }).unwrap(),
+            serde_json::json!({"kind": "set_dome_slaving", "enabled": true})
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::SetFlatLight { on: false }).unwrap(),
+            serde_json::json!({"kind": "set_flat_light", "on": false})
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::SetSwitch {
+                index: 0,
+                value: 1.0,
+            })
+            .unwrap(),
+            serde_json::json!({"kind": "set_switch", "index": 0, "value": 1.0})
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::SlewToCoordinates {
+                ra_hours: 5.5,
+                dec_degrees: -5.25,
+                center: true,
+            })
+            .unwrap(),
+            serde_json::json!({
+                "kind": "slew_to_coordinates",
+                "ra_hours": 5.5,
+                "dec_degrees": -5.25,
+                "center": true,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::SetTracking {
+                enabled: true,
+                rate: Some(TrackingMode::Sidereal),
+            })
+            .unwrap(),
+            serde_json::json!({"kind": "set_tracking", "enabled": true, "rate": "sidereal"})
+        );
+        assert_eq!(
+            serde_json::from_value::<RigCommand>(
+                serde_json::json!({"kind": "set_tracking", "enabled": false})
+            )
+            .unwrap(),
+            RigCommand::SetTracking {
+                enabled: false,
+                rate: None,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::StopSlew).unwrap(),
+            serde_json::json!({"kind": "stop_slew"})
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::MoveFocuser {
+                target: FocuserTarget::RelativeSteps(-40),
+            })
+            .unwrap(),
+            serde_json::json!({"kind": "move_focuser", "relative_steps": -40})
+        );
+        assert_eq!(
+            serde_json::from_value::<RigCommand>(
+                serde_json::json!({"kind": "move_focuser", "position": 12000})
+            )
+            .unwrap(),
+            RigCommand::MoveFocuser {
+                target: FocuserTarget::Position(12000),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::MoveRotator {
+                position_angle: 92.5,
+                mechanical: false,
+            })
+            .unwrap(),
+            serde_json::json!({"kind": "move_rotator", "position_angle": 92.5, "mechanical": false})
+        );
+        assert_eq!(
+            serde_json::to_value(RigCommand::CaptureSnapshot {
+                exposure_seconds: 5.0,
+                filter_id: Some(0),
+                gain: None,
+                binning: Some(2),
+            })
+            .unwrap(),
+            serde_json::json!({
+                "kind": "capture_snapshot",
+                "exposure_seconds": 5.0,
+                "filter_id": 0,
+                "binning": 2,
+            })
+        );
     }
 }
@@ -0,0 +1,185 @@
+use crate::serde_helpers::de_f64_tolerant;
+use crate::source::RigCommand;
+use serde::{Deserialize, Serialize};
+
+/// Switch hub (power box) snapshot returned by a Direct N.I.N.A. rig.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SwitchInfoResponse {
+    pub response: SwitchInfo,
+    pub error: String,
+    pub status_code: i32,
+    pub success: bool,
+    #[serde(rename = "Type")]
+    pub response_type: String,
+}
+
+/// The switch hub's ports, split the way N.I.N.A. reports them. Unknown
+/// additive fields are intentionally ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SwitchInfo {
+    pub connected: bool,
+    #[serde(default)]
+    pub writable_switches: Vec<Switch>,
+    /// Sensors such as input voltage or current draw.
+    #[serde(default)]
+    pub readonly_switches: Vec<Switch>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+/// One switch port. `id` is the ASCOM switch index the rig addresses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Switch {
+    pub id: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, deserialize_with = "de_f64_tolerant")]
+    pub value: f64,
+    /// Range and step are only reported for writable switches.
+    #[serde(default, deserialize_with = "de_f64_tolerant")]
+    pub minimum: f64,
+    #[serde(default, deserialize_with = "de_f64_tolerant")]
+    pub maximum: f64,
+    #[serde(default, deserialize_with = "de_f64_tolerant")]
+    pub step_size: f64,
+}
+
+impl Switch {
+    /// An on/off port rather than a variable output.
+    pub fn is_boolean(&self) -> bool {
+        self.minimum == 0.0 && self.maximum == 1.0
+    }
+
+    /// Dew heaters are recognized by name, as ASCOM has no port type.
+    pub fn is_dew_heater(&self) -> bool {
+        self.name.to_ascii_lowercase().contains("dew")
+            || self.description.to_ascii_lowercase().contains("dew")
+    }
+
+    /// Output as a percentage of the port's range, for variable ports.
+    pub fn power_percent(&self) -> Option<f64> {
+        let range = self.maximum - self.minimum;
+        (range > 0.0 && !self.is_boolean() && self.value.is_finite())
+            .then(|| (self.value - self.minimum) / range * 100.0)
+    }
+
+    /// The value as chat shows it: on/off, a percentage, or the raw reading.
+    pub fn display_value(&self) -> String {
+        if self.is_boolean() {
+            if self.value >= 0.5 { "on" } else { "off" }.to_string()
+        } else if let Some(percent) = self.power_percent() {
+            format!("{percent:.0}%")
+        } else if self.value.is_finite() {
+            format!("{:.2}", self.value)
+        } else {
+            "unknown".to_string()
+        }
+    }
+}
+
+impl SwitchInfo {
+    /// The switch at ASCOM index `id`, and whether it can be written.
+    pub fn get(&self, id: u32) -> Option<(&Switch, bool)> {
+        self.writable_switches
+            .iter()
+            .map(|switch| (switch, true))
+            .chain(self.readonly_switches.iter().map(|switch| (switch, false)))
+            .find(|(switch, _)| switch.id == id)
+    }
+
+    /// Dew heater outputs for status embeds, or `None` without any.
+    pub fn dew_heater_summary(&self) -> Option<String> {
+        let heaters: Vec<String> = self
+            .writable_switches
+            .iter()
+            .filter(|switch| switch.is_dew_heater())
+            .map(|switch| format!("{} {}", switch.name, switch.display_value()))
+            .collect();
+        (!heaters.is_empty()).then(|| heaters.join(" · "))
+    }
+
+    /// One line per port, writable ones first.
+    pub fn summary(&self) -> String {
+        self.writable_switches
+            .iter()
+            .chain(&self.readonly_switches)
+            .map(|switch| format!("#{} {}: {}", switch.id, switch.name, switch.display_value()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl SwitchInfoResponse {
+    /// Check a switch command against the live hub.
+    pub fn check_command(&self, command: &RigCommand) -> Result<(), String> {
+        let RigCommand::SetSwitch { index, value } = command else {
+            return Ok(());
+        };
+        let hub = &self.response;
+        if !(self.success && hub.connected) {
+            return Err("the switch hub is not connected".to_string());
+        }
+        match hub.get(*index) {
+            None => Err(format!("the switch hub has no switch #{index}")),
+            Some((switch, false)) => Err(format!("switch #{index} ({}) is read-only", switch.name)),
+            Some((switch, true)) if !(switch.minimum..=switch.maximum).contains(value) => {
+                Err(format!(
+                    "{value} is outside {}–{} for {}",
+                    switch.minimum, switch.maximum, switch.name
+                ))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POWER_BOX: &str = r#"{"Response":{"WritableSwitches":[{"Maximum":1,"Minimum":0,"StepSize":1,"TargetValue":1,"Id":0,"Name":"Camera","Description":"12V port 1","Value":1},{"Maximum":255,"Minimum":0,"StepSize":1,"TargetValue":153,"Id":4,"Name":"Dew A","Description":"PWM","Value":153}],"ReadonlySwitches":[{"Id":8,"Name":"Input voltage","Description":"V","Value":12.61}],"SupportedActions":[],"Connected":true,"Name":"UPBv2","DisplayName":"Pegasus Ultimate Powerbox v2"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+
+    #[test]
+    fn parses_switch_hub_snapshot() {
+        let parsed: SwitchInfoResponse = serde_json::from_str(POWER_BOX).unwrap();
+        let hub = &parsed.response;
+
+        assert!(hub.connected);
+        assert_eq!(hub.dew_heater_summary().as_deref(), Some("Dew A 60%"));
+        assert_eq!(
+            hub.summary(),
+            "#0 Camera: on\n#4 Dew A: 60%\n#8 Input voltage: 12.61"
+        );
+        assert!(hub.get(8).is_some_and(|(_, writable)| !writable));
+    }
+
+    #[test]
+    fn switch_commands_are_checked_against_live_state() {
+        let mut parsed: SwitchInfoResponse = serde_json::from_str(POWER_BOX).unwrap();
+        let set = |index, value| RigCommand::SetSwitch { index, value };
+
+        assert_eq!(parsed.check_command(&set(0, 0.0)), Ok(()));
+        assert!(
+            parsed
+                .check_command(&set(4, 300.0))
+                .unwrap_err()
+                .contains("0–255")
+        );
+        assert!(
+            parsed
+                .check_command(&set(8, 1.0))
+                .unwrap_err()
+                .contains("read-only")
+        );
+        assert!(parsed.check_command(&set(9, 1.0)).is_err());
+        parsed.response.connected = false;
+        assert!(parsed.check_command(&set(0, 1.0)).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Fixtures shared by tests in several modules.
+
+use crate::chat::{
+    ChatAction, ChatAttachment, ChatMessage, ChatService, ChatServiceManager, ChatTarget,
+};
+use crate::chat_updater::ChatUpdater;
+use crate::error::ChatError;
+use crate::events::{Event, EventDetails, event_types};
+use crate::images::{ImageHistoryResponse, ImageMetadata};
+use crate::simulator::{NightPlan, SimulatedRigSource};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// A five-minute light frame; `rms` is N.I.N.A.'s guiding RMS text.
+pub(crate) fn light(date: &str, filter: &str, hfr: f64, stars: i32, rms: &str) -> ImageMetadata {
+    ImageMetadata {
+        chat_enabled: true,
+        exposure_time: 300.0,
+        image_type: "LIGHT".to_string(),
+        filter: filter.to_string(),
+        rms_text: rms.to_string(),
+        temperature: -10.0,
+        camera_name: "ZWO ASI2600MM Pro".to_string(),
+        gain: 100,
+        offset: 50,
+        date: date.to_string(),
+        telescope_name: "Askar107PHQ".to_string(),
+        focal_length: 749,
+        st_dev: 120.0,
+        mean: 900.0,
+        median: 880.0,
+        stars,
+        hfr,
+        is_bayered: false,
+    }
+}
+
+fn event(time: &str, name: &str, details: Option<EventDetails>) -> Event {
+    Event {
+        time: time.to_string(),
+        event: name.to_string(),
+        chat_enabled: true,
+        details,
+    }
+}
+
+/// Four lights on the night of 2025-08-07 after the example history's
+/// flats from the evening before.
+pub(crate) fn sample_night() -> ImageHistoryResponse {
+    let mut images: ImageHistoryResponse =
+        serde_json::from_str(&std::fs::read_to_string("example_image-history.json").unwrap())
+            .unwrap();
+    // The example's flats were taken the evening before this night.
+    images.response.truncate(3);
+    images.response.extend([
+        light(
+            "2025-08-07T22:10:00-07:00",
+            "Ha",
+            2.1,
+            900,
+            "Tot: 0.41 (0.80\")",
+        ),
+        light(
+            "2025-08-07T22:20:00-07:00",
+            "Ha",
+            2.3,
+            880,
+            "Tot: 0.52 (1.02\")",
+        ),
+        light(
+            "2025-08-08T01:05:00-07:00",
+            "OIII",
+            2.6,
+            640,
+            "Tot: 0.47 (0.91\")",
+        ),
+        light(
+            "2025-08-08T01:15:00-07:00",
+            "OIII",
+            2.4,
+            700,
+            "Tot: 0.00 (0.00\")",
+        ),
+    ]);
+    images
+}
+
+/// Two targets, autofocus, a dither, a flip and a few failures across that
+/// night, plus an autofocus error from the night before.
+pub(crate) fn sample_events() -> Vec<Event> {
+    let target = |name: &str| {
+        Some(EventDetails::TargetStart {
+            target_name: name.to_string(),
+            project_name: None,
+            rotation: None,
+            target_end_time: None,
+            coordinates: None,
+        })
+    };
+    vec![
+        event("2025-08-06T21:00:00-07:00", event_types::ERROR_AF, None),
+        event(
+            "2025-08-07T22:00:00-07:00",
+            event_types::TS_TARGETSTART,
+            target("NGC 7000"),
+        ),
+        event(
+            "2025-08-07T22:05:00-07:00",
+            event_types::AUTOFOCUS_FINISHED,
+            None,
+        ),
+        event(
+            "2025-08-07T22:15:00-07:00",
+            event_types::GUIDER_DITHER,
+            None,
+        ),
+        event(
+            "2025-08-07T23:00:00-07:00",
+            event_types::SAFETY_CHANGED,
+            None,
+        ),
+        event(
+            "2025-08-08T00:30:00-07:00",
+            event_types::MOUNT_AFTER_FLIP,
+            None,
+        ),
+        event(
+            "2025-08-08T00:40:00-07:00",
+            event_types::ERROR_PLATESOLVE,
+            None,
+        ),
+        event(
+            "2025-08-08T01:00:00-07:00",
+            event_types::TS_NEWTARGETSTART,
+            target("M27"),
+        ),
+        event(
+            "2025-08-08T01:02:00-07:00",
+            event_types::FILTERWHEEL_CHANGED,
+            None,
+        ),
+    ]
+}
+
+/// Records every title posted, in order, the actions attached to them and
+/// the names of the session threads opened. Clones share the record.
+#[derive(Clone, Default)]
+pub(crate) struct CapturingService {
+    pub titles: Arc<Mutex<Vec<String>>>,
+    pub actions: Arc<Mutex<Vec<ChatAction>>>,
+    pub threads: Arc<Mutex<Vec<String>>>,
+}
+
+impl CapturingService {
+    pub fn titles(&self) -> Vec<String> {
+        self.titles.lock().unwrap().clone()
+    }
+
+    /// Titles containing `needle`.
+    pub fn count(&self, needle: &str) -> usize {
+        self.titles
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|title| title.contains(needle))
+            .count()
+    }
+
+    pub fn posted(&self, needle: &str) -> bool {
+        self.count(needle) > 0
+    }
+}
+
+#[async_trait]
+impl ChatService for CapturingService {
+    async fn send_message(
+        &self,
+        message: &ChatMessage,
+        _target: &ChatTarget,
+    ) -> Result<(), ChatError> {
+        self.titles.lock().unwrap().push(message.title.clone());
+        self.actions
+            .lock()
+            .unwrap()
+            .extend(message.actions.iter().cloned());
+        Ok(())
+    }
+
+    async fn send_message_with_image(
+        &self,
+        message: &ChatMessage,
+        target: &ChatTarget,
+        _image_data: &[u8],
+        _filename: &str,
+    ) -> Result<(), ChatError> {
+        self.send_message(message, target).await
+    }
+
+    async fn send_message_with_attachments(
+        &self,
+        message: &ChatMessage,
+        target: &ChatTarget,
+        _attachments: &[ChatAttachment],
+    ) -> Result<(), ChatError> {
+        self.send_message(message, target).await
+    }
+
+    async fn start_session_thread(
+        &self,
+        _target: &ChatTarget,
+        name: &str,
+        pointer: &ChatMessage,
+    ) -> Result<(), ChatError> {
+        self.titles.lock().unwrap().push(pointer.title.clone());
+        self.threads.lock().unwrap().push(name.to_string());
+        Ok(())
+    }
+
+    fn service_name(&self) -> &'static str {
+        "capture"
+    }
+
+    fn can_route(&self, _target: &ChatTarget) -> bool {
+        true
+    }
+}
+
+/// A short night: two filters of three frames, flipping after the first
+/// filter.
+pub(crate) fn short_plan() -> NightPlan {
+    NightPlan {
+        filters: vec!["L".to_string(), "R".to_string()],
+        frames_per_filter: 3,
+        exposure_seconds: 120.0,
+        meridian_flip_after_minutes: 20.0,
+        ..NightPlan::default()
+    }
+}
+
+/// A simulated rig whose updaters post to a [`CapturingService`], for
+/// scenarios that run a whole night through the updater.
+pub(crate) struct SimulatedNight {
+    pub source: Arc<SimulatedRigSource>,
+    pub manager: Arc<ChatServiceManager>,
+    pub chat: CapturingService,
+}
+
+impl SimulatedNight {
+    /// `plan` at 60x: each real second is a simulated minute.
+    pub fn new(plan: NightPlan) -> Self {
+        Self::with_source(SimulatedRigSource::new(plan).with_time_scale(60.0))
+    }
+
+    pub fn with_source(source: SimulatedRigSource) -> Self {
+        let chat = CapturingService::default();
+        let mut manager = ChatServiceManager::new();
+        manager.add_service(Box::new(chat.clone()));
+        Self {
+            source: Arc::new(source),
+            manager: Arc::new(manager),
+            chat,
+        }
+    }
+
+    /// A fresh updater named "sim" for the night's rig.
+    pub fn updater(&self) -> ChatUpdater {
+        ChatUpdater::new(
+            self.source.clone(),
+            "sim".to_string(),
+            ChatTarget::default(),
+            self.manager.clone(),
+        )
+    }
+}
@@ -0,0 +1,48 @@
+//! Timestamps as N.I.N.A. writes them.
+//!
+//! Event and image dates usually carry an offset, but values N.I.N.A. holds
+//! as `DateTimeKind.Unspecified` serialize without one; those are read as
+//! observatory-local time, which is this host's local time.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone};
+
+pub(crate) fn parse_nina_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed);
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.fixed_offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nina_timestamps_parse_with_and_without_an_offset() {
+        assert!(parse_nina_timestamp("2026-08-17T04:00:00-07:00").is_some());
+        // DateTimeKind.Unspecified serializes without an offset; these used to
+        // be dropped, leaving the sequence wait state unset.
+        assert!(parse_nina_timestamp("2026-08-17T04:00:00").is_some());
+        assert!(parse_nina_timestamp("2026-08-17T04:00:00.1234567").is_some());
+        assert!(parse_nina_timestamp("not a timestamp").is_none());
+    }
+
+    #[test]
+    fn nina_timestamp_accepts_offset_and_observatory_local_values() {
+        let offset = parse_nina_timestamp("2026-08-16T20:00:00-07:00").expect("offset time");
+        assert_eq!(offset.offset().local_minus_utc(), -7 * 60 * 60);
+
+        let local = parse_nina_timestamp("2026-08-16T20:00:00.1234567").expect("local time");
+        assert_eq!(
+            local.naive_local(),
+            NaiveDateTime::parse_from_str("2026-08-16T20:00:00.1234567", "%Y-%m-%dT%H:%M:%S%.f")
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use crate::serde_helpers::de_f64_tolerant;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Observing-conditions snapshot returned by a Direct N.I.N.A. rig.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WeatherInfoResponse {
+    pub response: WeatherInfo,
+    pub error: String,
+    pub status_code: i32,
+    pub success: bool,
+    #[serde(rename = "Type")]
+    pub response_type: String,
+}
+
+/// The sensor readings Chatstronomy reports and alerts on. A reading the
+/// station does not provide is NaN, whether N.I.N.A. sends `"NaN"` or omits
+/// the field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WeatherInfo {
+    pub connected: bool,
+    /// Percent of the sky covered.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub cloud_cover: f64,
+    /// °C.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub temperature: f64,
+    /// °C.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub sky_temperature: f64,
+    /// Percent relative humidity.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub humidity: f64,
+    /// °C.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub dew_point: f64,
+    /// m/s.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub wind_speed: f64,
+    /// m/s.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub wind_gust: f64,
+    /// Degrees from north.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub wind_direction: f64,
+    /// mm/h.
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub rain_rate: f64,
+    /// mag/arcsec².
+    #[serde(default = "unknown", deserialize_with = "de_f64_tolerant")]
+    pub sky_quality: f64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+fn unknown() -> f64 {
+    f64::NAN
+}
+
+impl WeatherInfo {
+    /// How far the air is above its dew point, when both are measured.
+    pub fn dew_point_spread(&self) -> Option<f64> {
+        let spread = self.temperature - self.dew_point;
+        spread.is_finite().then_some(spread)
+    }
+
+    /// One-line readings for status embeds; unmeasured values are left out.
+    pub fn summary(&self) -> String {
+        let readings = [
+            (self.temperature, "🌡️ {} °C", 1),
+            (self.dew_point, "dew {} °C", 1),
+            (self.humidity, "{}% RH", 0),
+            (self.wind_speed, "wind {} m/s", 1),
+            (self.wind_gust, "gusts {} m/s", 1),
+            (self.cloud_cover, "clouds {}%", 0),
+            (self.sky_temperature, "sky {} °C", 1),
+            (self.rain_rate, "rain {} mm/h", 1),
+            (self.sky_quality, "SQM {}", 2),
+        ];
+        let parts: Vec<String> = readings
+            .into_iter()
+            .filter(|(value, _, _)| value.is_finite())
+            .map(|(value, template, decimals)| {
+                template.replace("{}", &format!("{value:.decimals$}"))
+            })
+            .collect();
+        if parts.is_empty() {
+            "No readings".to_string()
+        } else {
+            parts.join(" · ")
+        }
+    }
+
+    /// The conditions over `config`'s thresholds. Conditions already in
+    /// `active` stay raised until they recover past a margin, so a reading
+    /// hovering at a threshold does not alert every poll.
+    pub fn alerts(
+        &self,
+        config: &WeatherAlertConfig,
+        active: &BTreeSet<ConditionAlert>,
+    ) -> BTreeSet<ConditionAlert> {
+        let mut raised = BTreeSet::new();
+        let mut check = |alert: ConditionAlert, over: bool, recovered: bool| {
+            if over || (active.contains(&alert) && !recovered) {
+                raised.insert(alert);
+            }
+        };
+        if let Some(spread) = self.dew_point_spread() {
+            let margin = config.dew_point_margin_celsius;
+            check(
+                ConditionAlert::DewPoint,
+                spread <= margin,
+                spread > margin + DEW_POINT_HYSTERESIS_CELSIUS,
+            );
+        }
+        for (alert, value, limit) in [
+            (
+                ConditionAlert::Wind,
+                self.wind_speed,
+                config.wind_speed_limit,
+            ),
+            (ConditionAlert::Gust, self.wind_gust, config.wind_gust_limit),
+            (
+                ConditionAlert::Clouds,
+                self.cloud_cover,
+                config.cloud_cover_limit,
+            ),
+        ] {
+            if let Some(limit) = limit
+                && value.is_finite()
+            {
+                check(alert, value > limit, value <= limit * RECOVERY_FRACTION);
+            }
+        }
+        if config.rain && self.rain_rate.is_finite() {
+            check(
+                ConditionAlert::Rain,
+                self.rain_rate > 0.0,
+                self.rain_rate <= 0.0,
+            );
+        }
+        raised
+    }
+}
+
+/// How far above the dew-point margin the spread must recover before the
+/// dew alert clears.
+const DEW_POINT_HYSTERESIS_CELSIUS: f64 = 1.0;
+/// Wind and cloud alerts clear once the reading drops to this fraction of
+/// the limit.
+const RECOVERY_FRACTION: f64 = 0.9;
+
+/// A weather condition the chat updater warns about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConditionAlert {
+    DewPoint,
+    Wind,
+    Gust,
+    Clouds,
+    Rain,
+}
+
+impl ConditionAlert {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::DewPoint => "💧 Dew point close",
+            Self::Wind => "💨 Wind over limit",
+            Self::Gust => "💨 Gusts over limit",
+            Self::Clouds => "☁️ Clouds over limit",
+            Self::Rain => "🌧️ Rain detected",
+        }
+    }
+
+    /// The reading behind this alert, for the chat message.
+    pub fn detail(self, weather: &WeatherInfo, config: &WeatherAlertConfig) -> String {
+        let limit = |limit: Option<f64>| limit.unwrap_or(f64::NAN);
+        match self {
+            Self::DewPoint => format!(
+                "{:.1} °C air, {:.1} °C dew point ({:.1} °C apart, alert within {:.1} °C)",
+                weather.temperature,
+                weather.dew_point,
+                weather.temperature - weather.dew_point,
+                config.dew_point_margin_celsius
+            ),
+            Self::Wind => format!(
+                "{:.1} m/s (limit {:.1} m/s)",
+                weather.wind_speed,
+                limit(config.wind_speed_limit)
+            ),
+            Self::Gust => format!(
+                "{:.1} m/s (limit {:.1} m/s)",
+                weather.wind_gust,
+                limit(config.wind_gust_limit)
+            ),
+            Self::Clouds => format!(
+                "{:.0}% (limit {:.0}%)",
+                weather.cloud_cover,
+                limit(config.cloud_cover_limit)
+            ),
+            Self::Rain => format!("{:.1} mm/h", weather.rain_rate),
+        }
+    }
+}
+
+/// Per-telescope thresholds for early weather warnings, meant to fire before
+/// N.I.N.A.'s own safety trigger does. Limits left out are not checked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherAlertConfig {
+    /// Warn when the air is within this many °C of its dew point.
+    #[serde(default = "default_dew_point_margin_celsius")]
+    pub dew_point_margin_celsius: f64,
+    /// m/s.
+    #[serde(default)]
+    pub wind_speed_limit: Option<f64>,
+    /// m/s.
+    #[serde(default)]
+    pub wind_gust_limit: Option<f64>,
+    /// Percent.
+    #[serde(default)]
+    pub cloud_cover_limit: Option<f64>,
+    /// Warn on any measured rain.
+    #[serde(default = "default_true")]
+    pub rain: bool,
+}
+
+fn default_dew_point_margin_celsius() -> f64 {
+    2.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for WeatherAlertConfig {
+    fn default() -> Self {
+        Self {
+            dew_point_margin_celsius: default_dew_point_margin_celsius(),
+            wind_speed_limit: None,
+            wind_gust_limit: None,
+            cloud_cover_limit: None,
+            rain: true,
+        }
+    }
+}
+
+impl WeatherAlertConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.dew_point_margin_celsius.is_finite() && self.dew_point_margin_celsius >= 0.0) {
+            return Err("weather dew point margin must be a non-negative number".to_string());
+        }
+        for (name, limit) in [
+            ("wind speed", self.wind_speed_limit),
+            ("wind gust", self.wind_gust_limit),
+            ("cloud cover", self.cloud_cover_limit),
+        ] {
+            if limit.is_some_and(|limit| !(limit.is_finite() && limit > 0.0)) {
+                return Err(format!("weather {name} limit must be a positive number"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATION_JSON: &str = r#"{"Response":{"AveragePeriod":0,"CloudCover":12,"DewPoint":7.4,"Humidity":71,"Pressure":1012.5,"RainRate":0,"SkyBrightness":"NaN","SkyQuality":20.91,"SkyTemperature":-17.2,"StarFWHM":"NaN","Temperature":10.1,"WindDirection":220,"WindGust":6.2,"WindSpeed":3.4,"SupportedActions":[],"Connected":true,"Name":"AAG CloudWatcher","DisplayName":"AAG CloudWatcher"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+
+    #[test]
+    fn parses_weather_snapshot() {
+        let parsed: WeatherInfoResponse = serde_json::from_str(STATION_JSON).unwrap();
+        let weather = parsed.response;
+
+        assert!(weather.connected);
+        assert!((weather.dew_point_spread().unwrap() - 2.7).abs() < 1e-9);
+        assert_eq!(
+            weather.summary(),
+            "🌡️ 10.1 °C · dew 7.4 °C · 71% RH · wind 3.4 m/s · gusts 6.2 m/s · clouds 12% · sky -17.2 °C · rain 0.0 mm/h · SQM 20.91"
+        );
+    }
+
+    #[test]
+    fn missing_sensors_are_unknown() {
+        let json = r#"{"Response":{"Connected":true,"Temperature":4.0,"Humidity":"NaN"},"Error":"","StatusCode":200,"Success":true,"Type":"API"}"#;
+        let weather = serde_json::from_str::<WeatherInfoResponse>(json)
+            .unwrap()
+            .response;
+
+        assert!(weather.humidity.is_nan() && weather.wind_speed.is_nan());
+        assert_eq!(weather.dew_point_spread(), None);
+        assert_eq!(weather.summary(), "🌡️ 4.0 °C");
+        assert!(
+            weather
+                .alerts(&WeatherAlertConfig::default(), &BTreeSet::new())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn alerts_raise_at_thresholds_and_clear_with_hysteresis() {
+        let mut weather = serde_json::from_str::<WeatherInfoResponse>(STATION_JSON)
+            .unwrap()
+            .response;
+        let config = WeatherAlertConfig {
+            wind_speed_limit: Some(5.0),
+            ..WeatherAlertConfig::default()
+        };
+        assert!(weather.alerts(&config, &BTreeSet::new()).is_empty());
+
+        weather.dew_point = 8.5;
+        weather.wind_speed = 5.5;
+        let active = weather.alerts(&config, &BTreeSet::new());
+        assert_eq!(
+            active,
+            BTreeSet::from([ConditionAlert::DewPoint, ConditionAlert::Wind])
+        );
+
+        // Just back under the thresholds: still raised.
+        weather.dew_point = 7.6;
+        weather.wind_speed = 4.8;
+        assert_eq!(weather.alerts(&config, &active), active);
+
+        weather.dew_point = 6.0;
+        weather.wind_speed = 4.0;
+        assert!(weather.alerts(&config, &active).is_empty());
+
+        weather.rain_rate = 0.4;
+        assert_eq!(
+            weather.alerts(&config, &BTreeSet::new()),
+            BTreeSet::from([ConditionAlert::Rain])
+        );
+    }
+
+    #[test]
+    fn alert_config_defaults_and_validates() {
+        let config: WeatherAlertConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, WeatherAlertConfig::default());
+        assert!(config.validate().is_ok());
+        let bad = WeatherAlertConfig {
+            wind_gust_limit: Some(0.0),
+            ..WeatherAlertConfig::default()
+        };
+        assert!(bad.validate().unwrap_err().contains("wind gust"));
+    }
+}
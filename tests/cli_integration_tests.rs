@@ -36,12 +36,12 @@ mod tests {
     fn test_basic_commands_available() {
         let stdout = help_text();
 
-        // `plugin-runtime` speaks over a Windows named pipe, so the subcommand
-        // only exists there. Assert it is absent elsewhere rather than simply
-        // skipping the check.
-        #[cfg(windows)]
+        // `plugin-runtime` speaks over a Windows named pipe or a Unix domain
+        // socket, so the subcommand only exists on those platforms. Assert it
+        // is absent elsewhere rather than simply skipping the check.
+        #[cfg(any(windows, unix))]
         assert!(stdout.contains("plugin-runtime"));
-        #[cfg(not(windows))]
+        #[cfg(not(any(windows, unix)))]
         assert!(!stdout.contains("plugin-runtime"));
 
         #[cfg(feature = "hub")]